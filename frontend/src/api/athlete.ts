@@ -1,5 +1,13 @@
 import { apiFetch } from './client';
-import type { ProfileResponse } from './types';
+import type {
+  FtpaceEstimate,
+  ProfileResponse,
+  RacePrediction,
+  RaceResult,
+  ThresholdSource,
+  ThresholdsResponse,
+  ZoneRefresh,
+} from './types';
 
 export interface CreateProfileInput {
   name: string;
@@ -31,6 +39,34 @@ export interface UpdateProfileInput {
   sports_background?: string;
 }
 
+export interface RecordThresholdsInput {
+  lthr?: number;
+  ftpace_m_per_s?: number;
+  source: ThresholdSource;
+  notes?: string;
+}
+
+export interface RaceResultInput {
+  race_name?: string;
+  distance_m: number;
+  time_seconds: number;
+  race_date: string;
+}
+
+/** A stored race result; `proposed_thresholds` can be passed to `recordThresholds` as is. */
+export interface RaceResultResponse {
+  race_result: RaceResult;
+  proposed_thresholds: RecordThresholdsInput;
+  current_ftpace_m_per_s: number | null;
+  predictions: RacePrediction[];
+  goal_prediction: RacePrediction | null;
+}
+
+export interface FtpaceEstimateResponse {
+  estimate: FtpaceEstimate | null;
+  current_ftpace_m_per_s: number | null;
+}
+
 export function createProfile(data: CreateProfileInput): Promise<ProfileResponse> {
   return apiFetch('/athlete/profile', {
     method: 'POST',
@@ -42,9 +78,33 @@ export function getProfile(): Promise<ProfileResponse> {
   return apiFetch('/athlete/profile');
 }
 
-export function updateProfile(data: UpdateProfileInput): Promise<ProfileResponse> {
+export function updateProfile(data: UpdateProfileInput): Promise<ProfileResponse & ZoneRefresh> {
   return apiFetch('/athlete/profile', {
     method: 'PUT',
     body: JSON.stringify(data),
   });
 }
+
+export function getThresholds(): Promise<ThresholdsResponse> {
+  return apiFetch('/athlete/thresholds');
+}
+
+export function recordThresholds(
+  data: RecordThresholdsInput,
+): Promise<ThresholdsResponse & ZoneRefresh> {
+  return apiFetch('/athlete/thresholds', {
+    method: 'POST',
+    body: JSON.stringify(data),
+  });
+}
+
+export function getFtpaceEstimate(): Promise<FtpaceEstimateResponse> {
+  return apiFetch('/athlete/ftpace-estimate');
+}
+
+export function createRaceResult(data: RaceResultInput): Promise<RaceResultResponse> {
+  return apiFetch('/athlete/race-results', {
+    method: 'POST',
+    body: JSON.stringify(data),
+  });
+}
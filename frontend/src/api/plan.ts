@@ -11,7 +11,14 @@ export function generatePlan(raceGoalId: number): Promise<MacrocycleSkeleton> {
 export function confirmPlan(skeleton: MacrocycleSkeleton): Promise<GeneratedPlan> {
   return apiFetch('/plan/confirm', {
     method: 'POST',
-    body: JSON.stringify(skeleton),
+    body: JSON.stringify({ draft_id: skeleton.id }),
+  });
+}
+
+export function reviseDraft(draftId: number, feedback: string): Promise<MacrocycleSkeleton> {
+  return apiFetch(`/plan/drafts/${draftId}/revise`, {
+    method: 'POST',
+    body: JSON.stringify({ feedback }),
   });
 }
 
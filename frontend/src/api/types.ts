@@ -51,6 +51,33 @@ export interface AthleteProfile {
   updated_at: string;
 }
 
+export type ThresholdSource = 'race' | 'time_trial' | 'estimate' | 'workout_derived' | 'manual';
+
+export interface LthrEntry {
+  id: number;
+  lthr: number;
+  source: ThresholdSource;
+  recorded_at: string;
+  notes: string | null;
+}
+
+export interface FtpaceEntry {
+  id: number;
+  pace_m_per_s: number;
+  source: ThresholdSource;
+  recorded_at: string;
+  notes: string | null;
+}
+
+export interface ThresholdsResponse {
+  lthr: number;
+  ftpace_m_per_s: number | null;
+  hr_zones: HrZones;
+  pace_zones: PaceZones | null;
+  lthr_history: LthrEntry[];
+  ftpace_history: FtpaceEntry[];
+}
+
 export interface ProfileResponse {
   profile: AthleteProfile;
   hr_zones: HrZones;
@@ -58,6 +85,11 @@ export interface ProfileResponse {
   race_goal: RaceGoal | null;
 }
 
+/** Upcoming workouts resolved again after LTHR or FTPace changed. */
+export interface ZoneRefresh {
+  updated_workouts: PlannedWorkout[];
+}
+
 export interface RaceGoal {
   id: number;
   race_name: string | null;
@@ -66,7 +98,37 @@ export interface RaceGoal {
   target_time_seconds: number | null;
 }
 
+export interface RaceResult {
+  id: number;
+  user_id: number;
+  race_name: string | null;
+  distance_m: number;
+  time_seconds: number;
+  race_date: string;
+  created_at: string;
+}
+
+export interface RacePrediction {
+  distance_m: number;
+  time_seconds: number;
+}
+
+export type EstimateConfidence = 'low' | 'medium' | 'high';
+
+/** FTPace read off the pace-to-HR line of recent runs at LTHR. */
+export interface FtpaceEstimate {
+  ftpace_m_per_s: number;
+  confidence: EstimateConfidence;
+  segments: number;
+  workouts: number;
+  r_squared: number;
+  highest_hr_fraction: number;
+}
+
 export interface MacrocycleSkeleton {
+  id: number;
+  version: number;
+  expires_at: string;
   target_ctl: number;
   coach_message: string;
   mesocycles: MesocycleSkeleton[];
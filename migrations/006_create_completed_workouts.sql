@@ -0,0 +1,44 @@
+-- Completed workouts (parsed from uploaded FIT files) and their time series
+
+CREATE TABLE completed_workouts (
+    id INTEGER PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    planned_workout_id INTEGER REFERENCES planned_workouts(id) ON DELETE SET NULL,
+    fit_file_path TEXT,
+    source TEXT NOT NULL CHECK (source IN ('fit_upload', 'coros_api', 'manual')),
+    hr_data_sufficient INTEGER NOT NULL DEFAULT 1,
+
+    -- Parsed summary
+    started_at TEXT NOT NULL,
+    duration_seconds INTEGER NOT NULL,
+    distance_m REAL NOT NULL,
+    avg_hr INTEGER,
+    max_hr INTEGER,
+    avg_pace_m_per_s REAL,
+    max_pace_m_per_s REAL,
+    avg_cadence REAL,
+    elevation_gain_m REAL,
+    elevation_loss_m REAL,
+
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- One row per activity start: re-uploading the same file is a conflict
+CREATE UNIQUE INDEX idx_completed_workouts_user_date ON completed_workouts(user_id, started_at);
+
+CREATE TABLE workout_records (
+    id INTEGER PRIMARY KEY,
+    workout_id INTEGER NOT NULL REFERENCES completed_workouts(id) ON DELETE CASCADE,
+    timestamp_ms INTEGER NOT NULL,
+    heart_rate INTEGER,
+    speed_m_per_s REAL,
+    latitude REAL,
+    longitude REAL,
+    altitude_m REAL,
+    cadence INTEGER,
+    power_watts INTEGER,
+    distance_m REAL,
+    temperature_c INTEGER
+);
+
+CREATE INDEX idx_workout_records_workout ON workout_records(workout_id);
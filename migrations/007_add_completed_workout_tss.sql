@@ -0,0 +1,3 @@
+-- Training stress score used for ATL/CTL/TSB. NULL until scored; load
+-- tracking falls back to the linked planned workout's expected_tss.
+ALTER TABLE completed_workouts ADD COLUMN tss REAL;
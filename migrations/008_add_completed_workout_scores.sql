@@ -0,0 +1,6 @@
+-- Pace/HR load scores for completed workouts. `tss` (added in 007) holds
+-- whichever of rtss/hrtss is used for training load.
+ALTER TABLE completed_workouts ADD COLUMN ngp_m_per_s REAL;
+ALTER TABLE completed_workouts ADD COLUMN intensity_factor REAL;
+ALTER TABLE completed_workouts ADD COLUMN rtss REAL;
+ALTER TABLE completed_workouts ADD COLUMN hrtss REAL;
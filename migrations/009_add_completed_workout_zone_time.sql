@@ -0,0 +1,16 @@
+-- HR-based load and time-in-zone distribution for completed workouts.
+-- HR columns stay NULL/0 when HR data is insufficient.
+ALTER TABLE completed_workouts ADD COLUMN trimp REAL;
+ALTER TABLE completed_workouts ADD COLUMN hr_zone_1_seconds INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE completed_workouts ADD COLUMN hr_zone_2_seconds INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE completed_workouts ADD COLUMN hr_zone_3_seconds INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE completed_workouts ADD COLUMN hr_zone_4_seconds INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE completed_workouts ADD COLUMN hr_zone_5_seconds INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE completed_workouts ADD COLUMN hr_zone_6_seconds INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE completed_workouts ADD COLUMN hr_zone_7_seconds INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE completed_workouts ADD COLUMN pace_zone_1_seconds INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE completed_workouts ADD COLUMN pace_zone_2_seconds INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE completed_workouts ADD COLUMN pace_zone_3_seconds INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE completed_workouts ADD COLUMN pace_zone_4_seconds INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE completed_workouts ADD COLUMN pace_zone_5_seconds INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE completed_workouts ADD COLUMN pace_zone_6_seconds INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,8 @@
+-- Aerobic/anaerobic effect scores (0-5) and the deterministic classification.
+-- NULL when HR data is insufficient.
+ALTER TABLE completed_workouts ADD COLUMN aerobic_effect REAL;
+ALTER TABLE completed_workouts ADD COLUMN anaerobic_effect REAL;
+ALTER TABLE completed_workouts ADD COLUMN classification TEXT CHECK (classification IN (
+    'aerobic_capacity', 'aerobic_utilization',
+    'anaerobic_capacity', 'anaerobic_utilization', 'mixed'
+));
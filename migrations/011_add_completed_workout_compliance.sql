@@ -0,0 +1,5 @@
+-- Compliance verdict against the matched planned workout.
+ALTER TABLE completed_workouts ADD COLUMN compliance TEXT CHECK (compliance IN (
+    'on_target', 'harder_than_prescribed', 'easier_than_prescribed',
+    'different_type', 'unplanned'
+));
@@ -0,0 +1,5 @@
+-- Coach Jan's post-workout analysis (analyze_workout tool output).
+ALTER TABLE completed_workouts ADD COLUMN coach_summary TEXT;
+ALTER TABLE completed_workouts ADD COLUMN coach_commentary TEXT;
+ALTER TABLE completed_workouts ADD COLUMN coach_message TEXT;
+ALTER TABLE completed_workouts ADD COLUMN analyzed_at TEXT;
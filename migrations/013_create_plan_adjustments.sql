@@ -0,0 +1,18 @@
+-- Plan adjustments proposed by Coach Jan. Applied only when the athlete
+-- accepts them; `adjustment` holds the operation as JSON.
+CREATE TABLE plan_adjustments (
+    id INTEGER PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    completed_workout_id INTEGER REFERENCES completed_workouts(id) ON DELETE SET NULL,
+    adjustment_type TEXT NOT NULL CHECK (adjustment_type IN (
+        'swap_workout', 'reduce_intensity', 'increase_intensity',
+        'add_recovery_day', 'extend_mesocycle', 'skip_workout'
+    )),
+    adjustment TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'accepted', 'rejected', 'superseded')),
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    resolved_at TEXT
+);
+
+CREATE INDEX idx_plan_adjustments_user_status ON plan_adjustments(user_id, status);
@@ -0,0 +1,13 @@
+-- Conversation with Coach Jan: free-form chat and coach messages posted by
+-- other flows (e.g. workout analysis), in one timeline per athlete.
+CREATE TABLE chat_messages (
+    id INTEGER PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role TEXT NOT NULL CHECK (role IN ('athlete', 'coach', 'system')),
+    content TEXT NOT NULL,
+    context_type TEXT,
+    related_workout_id INTEGER REFERENCES completed_workouts(id) ON DELETE SET NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_chat_messages_user_date ON chat_messages(user_id, created_at);
@@ -0,0 +1,17 @@
+-- Macrocycle skeletons generated by Claude, held until the athlete confirms
+-- them. `skeleton` holds the MacrocycleSkeleton as JSON; `version` counts
+-- the edits made to it. Unconfirmed drafts expire at `expires_at`.
+CREATE TABLE plan_drafts (
+    id INTEGER PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    race_goal_id INTEGER NOT NULL REFERENCES race_goals(id) ON DELETE CASCADE,
+    skeleton TEXT NOT NULL,
+    version INTEGER NOT NULL DEFAULT 1,
+    status TEXT NOT NULL DEFAULT 'draft' CHECK (status IN ('draft', 'confirmed')),
+    macrocycle_id INTEGER REFERENCES macrocycles(id) ON DELETE SET NULL,
+    expires_at TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_plan_drafts_user_status ON plan_drafts(user_id, status);
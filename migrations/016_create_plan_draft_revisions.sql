@@ -0,0 +1,13 @@
+-- Every version of a plan draft's skeleton, oldest first. `source` says how
+-- the version came about; `feedback` holds the athlete's request for
+-- versions Claude revised.
+CREATE TABLE plan_draft_revisions (
+    id INTEGER PRIMARY KEY,
+    draft_id INTEGER NOT NULL REFERENCES plan_drafts(id) ON DELETE CASCADE,
+    version INTEGER NOT NULL,
+    skeleton TEXT NOT NULL,
+    source TEXT NOT NULL CHECK (source IN ('generated', 'edited', 'revised')),
+    feedback TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE (draft_id, version)
+);
@@ -0,0 +1,30 @@
+-- Both threshold histories accept the same sources. SQLite can't change a
+-- CHECK constraint in place, so the tables are rebuilt.
+CREATE TABLE ftpace_history_new (
+    id INTEGER PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    pace_m_per_s REAL NOT NULL,
+    source TEXT NOT NULL CHECK (source IN ('race', 'time_trial', 'estimate', 'workout_derived', 'manual')),
+    recorded_at TEXT NOT NULL,
+    notes TEXT
+);
+INSERT INTO ftpace_history_new (id, user_id, pace_m_per_s, source, recorded_at, notes)
+    SELECT id, user_id, pace_m_per_s, source, recorded_at, notes FROM ftpace_history;
+DROP TABLE ftpace_history;
+ALTER TABLE ftpace_history_new RENAME TO ftpace_history;
+
+CREATE TABLE lthr_history_new (
+    id INTEGER PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    lthr INTEGER NOT NULL,
+    source TEXT NOT NULL CHECK (source IN ('race', 'time_trial', 'estimate', 'workout_derived', 'manual')),
+    recorded_at TEXT NOT NULL,
+    notes TEXT
+);
+INSERT INTO lthr_history_new (id, user_id, lthr, source, recorded_at, notes)
+    SELECT id, user_id, lthr, source, recorded_at, notes FROM lthr_history;
+DROP TABLE lthr_history;
+ALTER TABLE lthr_history_new RENAME TO lthr_history;
+
+CREATE INDEX idx_ftpace_history_user ON ftpace_history(user_id, recorded_at);
+CREATE INDEX idx_lthr_history_user ON lthr_history(user_id, recorded_at);
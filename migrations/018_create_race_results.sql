@@ -0,0 +1,13 @@
+-- Race results the athlete reports, used to estimate FTPace and predict
+-- the goal race
+CREATE TABLE race_results (
+    id INTEGER PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    race_name TEXT,
+    distance_m REAL NOT NULL,
+    time_seconds INTEGER NOT NULL,
+    race_date TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_race_results_user ON race_results(user_id, race_date);
@@ -0,0 +1,8 @@
+-- Classification check against the matched planned workout: the
+-- classification its type expects and whether the workout matched it.
+-- NULL when the workout wasn't matched to a planned running session.
+ALTER TABLE completed_workouts ADD COLUMN expected_classification TEXT CHECK (expected_classification IN (
+    'aerobic_capacity', 'aerobic_utilization',
+    'anaerobic_capacity', 'anaerobic_utilization', 'mixed'
+));
+ALTER TABLE completed_workouts ADD COLUMN classification_matches INTEGER;
@@ -0,0 +1,35 @@
+-- A plan adjustment names the §3.7 trigger that opened it and holds a list of
+-- operations instead of a single one. SQLite can't change a CHECK constraint
+-- in place, so the table is rebuilt. Existing single-operation proposals are
+-- wrapped in an operations list, with the trigger the operation answers.
+CREATE TABLE plan_adjustments_new (
+    id INTEGER PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    completed_workout_id INTEGER REFERENCES completed_workouts(id) ON DELETE SET NULL,
+    trigger_reason TEXT NOT NULL CHECK (trigger_reason IN (
+        'sustained_harder_than_prescribed', 'sustained_easier_than_prescribed',
+        'extended_absence', 'high_atl_ctl_ratio'
+    )),
+    adjustment TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'accepted', 'rejected', 'superseded')),
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    resolved_at TEXT
+);
+INSERT INTO plan_adjustments_new
+    (id, user_id, completed_workout_id, trigger_reason, adjustment, reason, status, created_at, resolved_at)
+    SELECT id, user_id, completed_workout_id, trigger_reason,
+           json_object('trigger_reason', trigger_reason, 'operations', json_array(json(adjustment))),
+           reason, status, created_at, resolved_at
+    FROM (
+        SELECT *, CASE adjustment_type
+            WHEN 'increase_intensity' THEN 'sustained_easier_than_prescribed'
+            WHEN 'extend_mesocycle' THEN 'extended_absence'
+            ELSE 'sustained_harder_than_prescribed'
+        END AS trigger_reason
+        FROM plan_adjustments
+    );
+DROP TABLE plan_adjustments;
+ALTER TABLE plan_adjustments_new RENAME TO plan_adjustments;
+
+CREATE INDEX idx_plan_adjustments_user_status ON plan_adjustments(user_id, status);
@@ -0,0 +1,3 @@
+-- Set by the daily scheduler when a mesocycle becomes due for its transition
+-- evaluation. The athlete starts the transition; the scheduler only flags it.
+ALTER TABLE mesocycles ADD COLUMN transition_due_at TEXT;
@@ -0,0 +1,43 @@
+-- A draft is claimed ('confirming') before its plan is generated, so two
+-- confirmations of the same draft cannot both create a plan. SQLite can't
+-- change a CHECK constraint in place, so plan_drafts is rebuilt. Its
+-- revisions are set aside first: dropping the old table would otherwise
+-- cascade into them.
+CREATE TABLE plan_draft_revisions_backup AS SELECT * FROM plan_draft_revisions;
+DROP TABLE plan_draft_revisions;
+
+CREATE TABLE plan_drafts_new (
+    id INTEGER PRIMARY KEY,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    race_goal_id INTEGER NOT NULL REFERENCES race_goals(id) ON DELETE CASCADE,
+    skeleton TEXT NOT NULL,
+    version INTEGER NOT NULL DEFAULT 1,
+    status TEXT NOT NULL DEFAULT 'draft' CHECK (status IN ('draft', 'confirming', 'confirmed')),
+    macrocycle_id INTEGER REFERENCES macrocycles(id) ON DELETE SET NULL,
+    expires_at TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+INSERT INTO plan_drafts_new
+    (id, user_id, race_goal_id, skeleton, version, status, macrocycle_id, expires_at, created_at, updated_at)
+    SELECT id, user_id, race_goal_id, skeleton, version, status, macrocycle_id, expires_at, created_at, updated_at
+    FROM plan_drafts;
+DROP TABLE plan_drafts;
+ALTER TABLE plan_drafts_new RENAME TO plan_drafts;
+
+CREATE INDEX idx_plan_drafts_user_status ON plan_drafts(user_id, status);
+
+CREATE TABLE plan_draft_revisions (
+    id INTEGER PRIMARY KEY,
+    draft_id INTEGER NOT NULL REFERENCES plan_drafts(id) ON DELETE CASCADE,
+    version INTEGER NOT NULL,
+    skeleton TEXT NOT NULL,
+    source TEXT NOT NULL CHECK (source IN ('generated', 'edited', 'revised')),
+    feedback TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE (draft_id, version)
+);
+INSERT INTO plan_draft_revisions (id, draft_id, version, skeleton, source, feedback, created_at)
+    SELECT id, draft_id, version, skeleton, source, feedback, created_at
+    FROM plan_draft_revisions_backup;
+DROP TABLE plan_draft_revisions_backup;
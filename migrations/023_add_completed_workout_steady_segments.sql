@@ -0,0 +1,5 @@
+-- The steady (HR, speed) segments the FTPace estimate is fitted through,
+-- as JSON, stored at upload so estimating doesn't reload per-second records.
+-- NULL for workouts uploaded before this column; they are filled in the
+-- first time an estimate reads them.
+ALTER TABLE completed_workouts ADD COLUMN steady_segments TEXT;
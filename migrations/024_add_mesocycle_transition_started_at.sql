@@ -0,0 +1,4 @@
+-- Set while a transition evaluates a mesocycle and generates the next block,
+-- so a second transition of the same mesocycle is refused. Cleared when the
+-- transition fails or completes the mesocycle.
+ALTER TABLE mesocycles ADD COLUMN transition_started_at TEXT;
@@ -0,0 +1,3 @@
+-- Whether completed_at was set by linking an uploaded workout rather than by
+-- the athlete, so unlinking only clears what the link set.
+ALTER TABLE planned_workouts ADD COLUMN completed_by_link INTEGER NOT NULL DEFAULT 0;
@@ -2,6 +2,16 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::time::Duration;
 
+use crate::ai::progress::{Progress, ProgressSender};
+use crate::ai::stream::{SseParser, StreamAccumulator, StreamEvent};
+
+/// Timeout for a complete non-streaming request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+/// Timeout for a complete streamed request. Long plan generations stream for
+/// well over a minute, so only the gap between chunks is held to a minute.
+const STREAM_TIMEOUT: Duration = Duration::from_secs(600);
+const STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
 #[derive(Debug, Clone, Copy)]
 pub enum Model {
     Sonnet,
@@ -30,6 +40,8 @@ pub struct ClaudeRequest {
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub tools: Vec<Tool>,
     pub max_tokens: u32,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub stream: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,6 +58,13 @@ impl Message {
         }
     }
 
+    pub fn assistant(text: &str) -> Self {
+        Self {
+            role: "assistant".to_string(),
+            content: vec![ContentBlock::Text { text: text.to_string() }],
+        }
+    }
+
     pub fn assistant_tool_use(id: &str, name: &str, input: Value) -> Self {
         Self {
             role: "assistant".to_string(),
@@ -94,7 +113,7 @@ pub struct ClaudeResponse {
     pub usage: Usage,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct Usage {
     pub input_tokens: u32,
     pub output_tokens: u32,
@@ -130,6 +149,8 @@ pub enum ClaudeError {
     InvalidResponse(String),
     #[error("API error ({status}): {message}")]
     ApiError { status: u16, message: String },
+    #[error("Stream error: {0}")]
+    Stream(String),
     #[error("HTTP error: {0}")]
     Http(#[from] reqwest::Error),
 }
@@ -140,6 +161,7 @@ pub struct ClaudeClient {
     http: reqwest::Client,
     api_key: String,
     base_url: String,
+    progress: Option<ProgressSender>,
 }
 
 impl ClaudeClient {
@@ -149,10 +171,35 @@ impl ClaudeClient {
 
     pub fn new_with_base_url(api_key: String, base_url: String) -> Self {
         let http = reqwest::Client::builder()
-            .timeout(Duration::from_secs(60))
+            .timeout(REQUEST_TIMEOUT)
             .build()
             .expect("Failed to build HTTP client");
-        Self { http, api_key, base_url }
+        Self {
+            http,
+            api_key,
+            base_url,
+            progress: None,
+        }
+    }
+
+    /// A client sharing this one's connection pool whose [`send`](Self::send)
+    /// streams every response and reports the deltas to `progress`.
+    pub fn with_progress(&self, progress: ProgressSender) -> Self {
+        Self {
+            http: self.http.clone(),
+            api_key: self.api_key.clone(),
+            base_url: self.base_url.clone(),
+            progress: Some(progress),
+        }
+    }
+
+    /// Report a stage of a multi-call operation, if progress is being tracked.
+    pub fn report_stage(&self, message: &str) {
+        if let Some(progress) = &self.progress {
+            let _ = progress.send(Progress::Stage {
+                message: message.to_string(),
+            });
+        }
     }
 
     pub async fn send(
@@ -162,6 +209,45 @@ impl ClaudeClient {
         messages: Vec<Message>,
         tools: Vec<Tool>,
         max_tokens: u32,
+    ) -> Result<ClaudeResponse, ClaudeError> {
+        if let Some(progress) = &self.progress {
+            return self
+                .send_stream(model, system, messages, tools, max_tokens, |event| {
+                    if let Some(update) = Progress::from_stream_event(event) {
+                        let _ = progress.send(update);
+                    }
+                })
+                .await;
+        }
+
+        let request = ClaudeRequest {
+            model: model.as_str().to_string(),
+            system: system.map(String::from),
+            messages,
+            tools,
+            max_tokens,
+            stream: false,
+        };
+
+        let resp = self.post(&request).await?;
+        let response: ClaudeResponse = resp
+            .json()
+            .await
+            .map_err(|e| ClaudeError::InvalidResponse(e.to_string()))?;
+        Ok(response)
+    }
+
+    /// Like [`send`](Self::send), but requests a server-sent event stream and
+    /// calls `on_event` with each delta as it arrives. Returns the assembled
+    /// response once `message_stop` has been received.
+    pub async fn send_stream(
+        &self,
+        model: Model,
+        system: Option<&str>,
+        messages: Vec<Message>,
+        tools: Vec<Tool>,
+        max_tokens: u32,
+        mut on_event: impl FnMut(&StreamEvent),
     ) -> Result<ClaudeResponse, ClaudeError> {
         let request = ClaudeRequest {
             model: model.as_str().to_string(),
@@ -169,6 +255,46 @@ impl ClaudeClient {
             messages,
             tools,
             max_tokens,
+            stream: true,
+        };
+
+        let mut resp = self.post(&request).await?;
+        let mut parser = SseParser::default();
+        let mut message = StreamAccumulator::default();
+
+        while !message.is_complete() {
+            let chunk = match tokio::time::timeout(STREAM_IDLE_TIMEOUT, resp.chunk()).await {
+                Err(_) => return Err(ClaudeError::Timeout),
+                Ok(Err(e)) if e.is_timeout() => return Err(ClaudeError::Timeout),
+                Ok(Err(e)) => return Err(ClaudeError::Http(e)),
+                Ok(Ok(Some(chunk))) => chunk,
+                Ok(Ok(None)) => {
+                    if let Some(event) = parser.finish()
+                        && let Some(event) = message.apply(&event)?
+                    {
+                        on_event(&event);
+                    }
+                    break;
+                }
+            };
+
+            for event in parser.push(&chunk) {
+                if let Some(event) = message.apply(&event)? {
+                    on_event(&event);
+                }
+            }
+        }
+
+        message.finish()
+    }
+
+    /// POST the request, retrying on 429 with exponential backoff. Returns
+    /// the response once its status is successful.
+    async fn post(&self, request: &ClaudeRequest) -> Result<reqwest::Response, ClaudeError> {
+        let timeout = if request.stream {
+            STREAM_TIMEOUT
+        } else {
+            REQUEST_TIMEOUT
         };
 
         let mut retries = 0;
@@ -181,7 +307,8 @@ impl ClaudeClient {
                 .header("x-api-key", &self.api_key)
                 .header("anthropic-version", "2023-06-01")
                 .header("content-type", "application/json")
-                .json(&request)
+                .timeout(timeout)
+                .json(request)
                 .send()
                 .await;
 
@@ -204,12 +331,7 @@ impl ClaudeClient {
                             message: body,
                         });
                     }
-
-                    let response: ClaudeResponse = resp
-                        .json()
-                        .await
-                        .map_err(|e| ClaudeError::InvalidResponse(e.to_string()))?;
-                    return Ok(response);
+                    return Ok(resp);
                 }
                 Err(e) if e.is_timeout() => return Err(ClaudeError::Timeout),
                 Err(e) => return Err(ClaudeError::Http(e)),
@@ -280,6 +402,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn stream_flag_is_only_sent_when_streaming() {
+        let mut request = ClaudeRequest {
+            model: Model::Haiku.as_str().to_string(),
+            system: None,
+            messages: vec![Message::user("Hi")],
+            tools: vec![],
+            max_tokens: 16,
+            stream: false,
+        };
+        let json = serde_json::to_value(&request).unwrap();
+        assert!(json.get("stream").is_none());
+
+        request.stream = true;
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["stream"], true);
+    }
+
     #[test]
     fn claude_error_display() {
         let err = ClaudeError::RateLimit;
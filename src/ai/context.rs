@@ -1,28 +1,19 @@
 use std::collections::HashMap;
 
-use crate::db::plans::PlannedWorkout;
-use crate::db::profiles::{AthleteProfile, RaceGoal};
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use crate::db::chat::ChatMessage;
+use crate::db::metrics::DailyMetrics;
+use crate::db::plans::{Mesocycle, MesocycleContext, PlannedWorkout};
+use crate::db::profiles::{AthleteProfile, RaceGoal, RaceResult};
+use crate::db::workouts::CompletedWorkout;
+use crate::domain::classification::ClassificationCheck;
+use crate::domain::compliance::{ComplianceAssessment, Direction, TriggerReason};
+use crate::domain::equivalency::{format_distance, format_race_time, predict};
+use crate::domain::types::{HrZones, PaceZones};
 use crate::domain::workouts::WorkoutType;
-
-/// Format a distance in meters as a human-readable race distance string.
-fn format_distance(meters: f64) -> String {
-    // Common race distances
-    let m = meters.round() as i64;
-    match m {
-        5000 => "5K".to_string(),
-        10000 => "10K".to_string(),
-        15000 => "15K".to_string(),
-        21097 | 21098 | 21100 => "Half Marathon (21.1 km)".to_string(),
-        42195 | 42200 => "Marathon (42.2 km)".to_string(),
-        _ => {
-            if meters >= 1000.0 {
-                format!("{:.1} km", meters / 1000.0)
-            } else {
-                format!("{:.0} m", meters)
-            }
-        }
-    }
-}
+use crate::domain::zones::{format_pace, format_pace_range};
 
 /// Build context for macrocycle skeleton generation.
 /// This is sent as the user message when asking Claude to create the overall plan structure.
@@ -32,6 +23,7 @@ pub fn build_macrocycle_context(
     ctl: f64,
     weeks_until_race: i64,
     workout_history: Option<&str>,
+    recent_race: Option<&RaceResult>,
 ) -> String {
     let experience = &profile.experience_level;
     let (load_weeks, recovery_weeks) = match experience.as_str() {
@@ -42,15 +34,27 @@ pub fn build_macrocycle_context(
     };
 
     let weekly_km = profile.current_weekly_volume_km;
-    let ftpace_display = profile.ftpace_m_per_s.map(|p| {
-        let min_per_km = 1000.0 / p / 60.0;
-        let mins = min_per_km as u32;
-        let secs = ((min_per_km - mins as f64) * 60.0) as u32;
-        format!("{}:{:02}/km", mins, secs)
-    }).unwrap_or_else(|| "not set".to_string());
+    let ftpace_display = profile
+        .ftpace_m_per_s
+        .map(format_pace)
+        .unwrap_or_else(|| "not set".to_string());
 
     let distance_display = format_distance(race_goal.distance_m);
 
+    // What the athlete's latest race says they could run at the goal distance
+    let prediction = recent_race
+        .map(|r| {
+            let predicted = predict(r.distance_m, r.time_seconds as f64, race_goal.distance_m);
+            format!(
+                "\n- Predicted finish: {} (from {} in {} on {})",
+                format_race_time(predicted.time_seconds),
+                format_distance(r.distance_m),
+                format_race_time(r.time_seconds),
+                r.race_date,
+            )
+        })
+        .unwrap_or_default();
+
     let mut result = format!(
         r#"Create a periodized macrocycle for this athlete:
 
@@ -66,7 +70,7 @@ pub fn build_macrocycle_context(
 Race goal:
 - Race: {race_name}
 - Distance: {race_distance}
-- Date: {race_date} ({weeks_until_race} weeks away)
+- Date: {race_date} ({weeks_until_race} weeks away){prediction}
 
 Mesocycle structure for {experience} athletes: {load_weeks} load weeks + {recovery_weeks} recovery week(s)
 
@@ -89,6 +93,7 @@ The final mesocycle should be a taper of 1-2 weeks."#,
         race_distance = distance_display,
         race_date = race_goal.race_date,
         weeks_until_race = weeks_until_race,
+        prediction = prediction,
         load_weeks = load_weeks,
         recovery_weeks = recovery_weeks,
     );
@@ -335,6 +340,487 @@ pub fn format_workout_history_summary(
     result
 }
 
+/// App-computed flags sent with a workout analysis. Claude reasons from these
+/// rather than re-deriving them.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AnalysisFlags {
+    pub consecutive_off_target_count: u32,
+    pub off_target_direction: Option<Direction>,
+    /// Whether the §3.7 stability rules allow proposing a plan change.
+    pub adjustment_eligible: bool,
+    /// The §3.7 triggers that fired; a proposal must name one of them.
+    pub adjustment_triggers: Vec<TriggerReason>,
+    pub days_since_last_workout: Option<i64>,
+    /// e.g. "week 2 of 4 (load)"
+    pub current_mesocycle_week: Option<String>,
+    pub days_until_race: Option<i64>,
+}
+
+/// Everything Claude sees when analyzing a completed workout.
+pub struct WorkoutAnalysisInput<'a> {
+    pub profile: &'a AthleteProfile,
+    pub workout: &'a CompletedWorkout,
+    pub planned: Option<&'a (PlannedWorkout, MesocycleContext)>,
+    pub classification_check: Option<&'a ClassificationCheck>,
+    pub compliance: Option<&'a ComplianceAssessment>,
+    pub load_before: Option<&'a DailyMetrics>,
+    pub load_after: Option<&'a DailyMetrics>,
+    pub flags: &'a AnalysisFlags,
+    /// Open planned sessions after the workout, for adjustment proposals.
+    pub upcoming: &'a [PlannedWorkout],
+}
+
+/// Build context for post-workout analysis: what the athlete did, what was
+/// prescribed, how it compares, and where their training load now stands.
+pub fn build_workout_analysis_context(input: &WorkoutAnalysisInput) -> String {
+    let w = input.workout;
+    let mut result = format!(
+        "Analyze this completed workout for {name} (LTHR {lthr} bpm, FTPace {ftpace}).\n\n",
+        name = input.profile.name,
+        lthr = input.profile.lthr,
+        ftpace = input
+            .profile
+            .ftpace_m_per_s
+            .map(format_pace)
+            .unwrap_or_else(|| "not set".to_string()),
+    );
+
+    // What the athlete did
+    result.push_str(&format!(
+        "Completed workout ({date}):\n- Duration: {min} min, distance: {km:.2} km\n",
+        date = w.started_at.get(..10).unwrap_or(&w.started_at),
+        min = (w.duration_seconds as f64 / 60.0).round() as i64,
+        km = w.distance_m / 1000.0,
+    ));
+    if let Some(avg_hr) = w.avg_hr {
+        result.push_str(&format!("- Avg HR: {avg_hr} bpm"));
+        if let Some(max_hr) = w.max_hr {
+            result.push_str(&format!(", max HR: {max_hr} bpm"));
+        }
+        result.push('\n');
+    }
+    if w.hr_data_sufficient == 0 {
+        result.push_str("- HR data insufficient; HR zones and effects unavailable\n");
+    }
+    if let Some(pace) = w.avg_pace_m_per_s.filter(|p| *p > 0.0) {
+        result.push_str(&format!("- Avg pace: {}", format_pace(pace)));
+        if let Some(ngp) = w.ngp_m_per_s.filter(|p| *p > 0.0) {
+            result.push_str(&format!(", NGP: {}", format_pace(ngp)));
+        }
+        result.push('\n');
+    }
+    if let Some(tss) = w.tss {
+        let method = if w.rtss.is_some() { "rTSS" } else { "hrTSS" };
+        result.push_str(&format!("- TSS: {tss:.0} ({method})"));
+        if let Some(intensity_factor) = w.intensity_factor {
+            result.push_str(&format!(", IF: {intensity_factor:.2}"));
+        }
+        result.push('\n');
+    }
+    if let Some(trimp) = w.trimp {
+        result.push_str(&format!("- TRIMP: {trimp:.0}\n"));
+    }
+    if let (Some(aerobic), Some(anaerobic)) = (w.aerobic_effect, w.anaerobic_effect) {
+        result.push_str(&format!(
+            "- Aerobic effect: {aerobic:.1}/5, anaerobic effect: {anaerobic:.1}/5\n"
+        ));
+    }
+    if let Some(classification) = &w.classification {
+        result.push_str(&format!("- Classification: {classification}\n"));
+    }
+    result.push_str(&format!(
+        "- Time in HR zones: {}\n",
+        format_zone_minutes(&w.hr_zone_seconds())
+    ));
+    result.push_str(&format!(
+        "- Time in pace zones: {}\n",
+        format_zone_minutes(&w.pace_zone_seconds())
+    ));
+
+    // What was prescribed
+    match input.planned {
+        Some((planned, meso)) => {
+            result.push_str(&format!(
+                "\nPrescribed workout ({phase} phase, {focus} focus):\n- Type: {workout_type}\n",
+                phase = meso.phase,
+                focus = meso.focus,
+                workout_type = planned.workout_type,
+            ));
+            if let Some(duration) = planned.duration_min {
+                result.push_str(&format!("- Duration: {duration} min\n"));
+            }
+            if let Some(km) = planned.target_distance_km {
+                result.push_str(&format!("- Distance: {km:.1} km\n"));
+            }
+            if let Some(tss) = planned.expected_tss {
+                result.push_str(&format!("- Expected TSS: {tss:.0}\n"));
+            }
+            if let Some(zones) = &planned.target_hr_zones {
+                result.push_str(&format!("- Target HR zones: {zones}\n"));
+            }
+            if let Some(zones) = &planned.target_pace_zones {
+                result.push_str(&format!("- Target pace zones: {zones}\n"));
+            }
+            if let Some(notes) = &planned.coach_notes {
+                result.push_str(&format!("- Coach notes: {notes}\n"));
+            }
+        }
+        None => result.push_str("\nNo workout was planned for this day (unplanned session).\n"),
+    }
+
+    // How it compares
+    if let Some(check) = input.classification_check {
+        result.push_str(&format!(
+            "\nClassification check: expected {expected}, actual {actual} ({verdict})\n",
+            expected = check.expected.map_or("n/a", |c| c.as_str()),
+            actual = check.actual.map_or("n/a", |c| c.as_str()),
+            verdict = if check.matches { "matches" } else { "does not match" },
+        ));
+    }
+    if let Some(compliance) = input.compliance {
+        result.push_str(&format!("\nCompliance: {}\n", compliance.verdict.as_str()));
+        let ratios = [
+            ("Time in target zones", compliance.time_in_target_zones),
+            ("Time above target zones", compliance.time_above_target_zones),
+            ("Actual/expected TSS", compliance.tss_ratio),
+            ("Actual/planned duration", compliance.duration_ratio),
+            ("Actual/target distance", compliance.distance_ratio),
+        ];
+        for (label, ratio) in ratios {
+            if let Some(ratio) = ratio {
+                result.push_str(&format!("- {label}: {:.0}%\n", ratio * 100.0));
+            }
+        }
+    }
+
+    // Training load
+    result.push_str("\nTraining load:\n");
+    for (label, metrics) in [("Before", input.load_before), ("After", input.load_after)] {
+        match metrics {
+            Some(m) => result.push_str(&format!(
+                "- {label}: CTL {:.1}, ATL {:.1}, TSB {:.1}\n",
+                m.ctl, m.atl, m.tsb
+            )),
+            None => result.push_str(&format!("- {label}: unknown\n")),
+        }
+    }
+
+    // Flags
+    let flags = input.flags;
+    result.push_str(&format!(
+        "\nFlags:\n- consecutive_off_target_count: {count}{direction}\n",
+        count = flags.consecutive_off_target_count,
+        direction = match flags.off_target_direction {
+            Some(Direction::Harder) => " (harder)",
+            Some(Direction::Easier) => " (easier)",
+            None => "",
+        },
+    ));
+    result.push_str(&format!(
+        "- adjustment_eligible: {}\n",
+        flags.adjustment_eligible
+    ));
+    if !flags.adjustment_triggers.is_empty() {
+        let triggers: Vec<&str> = flags
+            .adjustment_triggers
+            .iter()
+            .map(|t| t.as_str())
+            .collect();
+        result.push_str(&format!("- adjustment_triggers: {}\n", triggers.join(", ")));
+    }
+    if let Some(days) = flags.days_since_last_workout {
+        result.push_str(&format!("- days_since_last_workout: {days}\n"));
+    }
+    if let Some(week) = &flags.current_mesocycle_week {
+        result.push_str(&format!("- current_mesocycle_week: {week}\n"));
+    }
+    if let Some(days) = flags.days_until_race {
+        result.push_str(&format!("- days_until_race: {days}\n"));
+    }
+
+    if flags.adjustment_eligible && !input.upcoming.is_empty() {
+        result.push_str("\nUpcoming sessions (id, date, type, mesocycle id):\n");
+        for planned in input.upcoming {
+            result.push_str(&format!(
+                "- #{} {} {}{} (mesocycle {})\n",
+                planned.id,
+                planned.scheduled_date,
+                planned.workout_type,
+                planned
+                    .duration_min
+                    .map(|min| format!(" {min} min"))
+                    .unwrap_or_default(),
+                planned.mesocycle_id,
+            ));
+        }
+    }
+
+    result.push_str(
+        r#"
+Use the analyze_workout tool. Explain what this session trained and how it fits the
+plan, in plain language. Base the compliance commentary on the compliance verdict above.
+Only suggest changing the plan if adjustment_eligible is true — a single off day is normal.
+A plan_adjustment is a proposal: the athlete decides whether to accept it. Its
+trigger_reason must be one of the adjustment_triggers above."#,
+    );
+
+    result
+}
+
+/// Everything Claude sees when evaluating a finished mesocycle.
+pub struct MesocycleEvaluationInput<'a> {
+    pub profile: &'a AthleteProfile,
+    pub mesocycle: &'a Mesocycle,
+    /// The block's sessions, from `format_workout_history_detailed`.
+    pub history: &'a str,
+    pub ctl_at_start: Option<f64>,
+    pub current_ctl: f64,
+    pub target_ctl: Option<f64>,
+    /// The mesocycle that starts next, if this was not the last one.
+    pub next: Option<&'a Mesocycle>,
+}
+
+/// Build context for the end-of-mesocycle evaluation.
+pub fn build_mesocycle_evaluation_context(input: &MesocycleEvaluationInput) -> String {
+    let meso = input.mesocycle;
+    let mut result = format!(
+        "Evaluate mesocycle {seq} for {name}: {phase} phase, {focus} focus, \
+         {load}+{recovery} weeks ({start} to {end}).\n\n{history}\n",
+        seq = meso.sequence_number,
+        name = input.profile.name,
+        phase = meso.phase,
+        focus = meso.focus,
+        load = meso.load_weeks,
+        recovery = meso.recovery_weeks,
+        start = meso.start_date,
+        end = meso.end_date,
+        history = input.history.trim_end(),
+    );
+
+    result.push_str("\nFitness:\n");
+    match input.ctl_at_start {
+        Some(ctl) => result.push_str(&format!(
+            "- CTL at start: {ctl:.1}, now: {:.1}\n",
+            input.current_ctl
+        )),
+        None => result.push_str(&format!("- CTL now: {:.1}\n", input.current_ctl)),
+    }
+    if let Some(target) = input.target_ctl {
+        result.push_str(&format!("- Race-day target CTL: {target:.0}\n"));
+    }
+    if let Some(km) = meso.target_volume_km {
+        result.push_str(&format!("- Planned volume: {km:.0} km\n"));
+    }
+
+    match input.next {
+        Some(next) => result.push_str(&format!(
+            "\nNext mesocycle: {phase} phase, {focus} focus, {load}+{recovery} weeks from {start}.\n",
+            phase = next.phase,
+            focus = next.focus,
+            load = next.load_weeks,
+            recovery = next.recovery_weeks,
+            start = next.start_date,
+        )),
+        None => result.push_str("\nThis was the last mesocycle before the race.\n"),
+    }
+
+    result.push_str(
+        r#"
+Use the evaluate_mesocycle tool. Be specific about missed or off-target sessions and RPE
+trends. next_focus is passed to the planner for the next mesocycle's workouts, and
+volume_adjustment scales its planned weekly volume."#,
+    );
+
+    result
+}
+
+/// Most chat messages sent as history with each chat call.
+pub const CHAT_HISTORY_MESSAGES: usize = 20;
+
+/// Approximate token budget for chat history.
+pub const CHAT_HISTORY_TOKENS: usize = 4000;
+
+/// Rough token count for budgeting (about 4 characters per token).
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// The newest messages (oldest first) within both `CHAT_HISTORY_MESSAGES`
+/// and `CHAT_HISTORY_TOKENS`. `messages` must be oldest first.
+pub fn chat_history_window(messages: &[ChatMessage]) -> &[ChatMessage] {
+    let mut tokens = 0;
+    let mut start = messages.len();
+    for (i, message) in messages.iter().enumerate().rev() {
+        tokens += estimate_tokens(&message.content);
+        if messages.len() - i > CHAT_HISTORY_MESSAGES || tokens > CHAT_HISTORY_TOKENS {
+            break;
+        }
+        start = i;
+    }
+    &messages[start..]
+}
+
+/// Everything Claude knows about the athlete during a chat.
+pub struct ChatContextInput<'a> {
+    pub profile: &'a AthleteProfile,
+    pub race_goal: Option<&'a RaceGoal>,
+    pub hr_zones: &'a HrZones,
+    pub pace_zones: Option<&'a PaceZones>,
+    pub today: NaiveDate,
+    /// e.g. "week 2 of 4 (load), mesocycle 1 capacity / aerobic_capacity"
+    pub current_week: Option<&'a str>,
+    pub todays_workouts: &'a [PlannedWorkout],
+    pub upcoming: &'a [PlannedWorkout],
+    /// Newest first.
+    pub recent_workouts: &'a [CompletedWorkout],
+    pub metrics: Option<&'a DailyMetrics>,
+}
+
+/// Build the athlete context for a chat call: profile, zones, where they are
+/// in the plan, today's and upcoming sessions, recent workouts and load.
+pub fn build_chat_context(input: &ChatContextInput) -> String {
+    let p = input.profile;
+    let mut result = format!(
+        "Today is {today}.\n\nAthlete: {name}, {age} years, {experience}, {km:.0} km/week\n",
+        today = input.today.format("%A %Y-%m-%d"),
+        name = p.name,
+        age = p.age,
+        experience = p.experience_level,
+        km = p.current_weekly_volume_km,
+    );
+    if let Some(background) = &p.sports_background {
+        result.push_str(&format!("Sports background: {background}\n"));
+    }
+    if let Some(goal) = input.race_goal {
+        result.push_str(&format!(
+            "Race goal: {name}{distance} on {date}\n",
+            name = goal
+                .race_name
+                .as_deref()
+                .map(|n| format!("{n}, "))
+                .unwrap_or_default(),
+            distance = format_distance(goal.distance_m),
+            date = goal.race_date,
+        ));
+    }
+
+    // Zones
+    result.push_str(&format!(
+        "\nHR zones (LTHR {} bpm, resting {} bpm, max {} bpm):\n",
+        p.lthr, p.resting_hr, p.max_hr
+    ));
+    for z in &input.hr_zones.zones {
+        let range = match z.max_bpm {
+            Some(max) => format!("{}-{max} bpm", z.min_bpm),
+            None => format!("{}+ bpm", z.min_bpm),
+        };
+        result.push_str(&format!("- Z{} {}: {range}\n", z.zone, z.name));
+    }
+    match (input.pace_zones, p.ftpace_m_per_s) {
+        (Some(zones), Some(ftpace)) => {
+            result.push_str(&format!("\nPace zones (FTPace {}):\n", format_pace(ftpace)));
+            for z in &zones.zones {
+                result.push_str(&format!(
+                    "- Z{} {}: {}\n",
+                    z.zone,
+                    z.name,
+                    format_pace_range(z)
+                ));
+            }
+        }
+        _ => result.push_str("\nPace zones: not set (no FTPace)\n"),
+    }
+
+    // Plan
+    result.push_str("\nPlan:\n");
+    match input.current_week {
+        Some(week) => result.push_str(&format!("- Current: {week}\n")),
+        None => result.push_str("- No active training plan covers today\n"),
+    }
+    if input.todays_workouts.is_empty() {
+        result.push_str("- Today: nothing scheduled\n");
+    }
+    for w in input.todays_workouts {
+        result.push_str(&format!(
+            "- Today: {}{}\n",
+            describe_planned(w),
+            if w.is_completed != 0 { " (done)" } else { "" }
+        ));
+    }
+    for w in input.upcoming {
+        result.push_str(&format!(
+            "- {} {}: {}\n",
+            weekday_abbr(&w.scheduled_date),
+            w.scheduled_date,
+            describe_planned(w)
+        ));
+    }
+
+    // Recent workouts
+    if !input.recent_workouts.is_empty() {
+        result.push_str("\nRecent workouts:\n");
+        for w in input.recent_workouts {
+            result.push_str(&format!(
+                "- {date}: {km:.1} km in {min} min",
+                date = w.started_at.get(..10).unwrap_or(&w.started_at),
+                km = w.distance_m / 1000.0,
+                min = (w.duration_seconds as f64 / 60.0).round() as i64,
+            ));
+            if let Some(tss) = w.tss {
+                result.push_str(&format!(", TSS {tss:.0}"));
+            }
+            if let Some(classification) = &w.classification {
+                result.push_str(&format!(", {classification}"));
+            }
+            if let Some(compliance) = &w.compliance {
+                result.push_str(&format!(", compliance {compliance}"));
+            }
+            result.push('\n');
+        }
+    }
+
+    // Training load
+    match input.metrics {
+        Some(m) => result.push_str(&format!(
+            "\nTraining load ({}): CTL {:.1}, ATL {:.1}, TSB {:.1}\n",
+            m.date, m.ctl, m.atl, m.tsb
+        )),
+        None => result.push_str("\nTraining load: unknown\n"),
+    }
+
+    result
+}
+
+/// One-line summary of a planned session, e.g. "tempo_run 50 min (Z3-Z4)".
+fn describe_planned(w: &PlannedWorkout) -> String {
+    let mut result = w.workout_type.clone();
+    if let Some(min) = w.duration_min {
+        result.push_str(&format!(" {min} min"));
+    }
+    if let Some(km) = w.target_distance_km {
+        result.push_str(&format!(" {km:.1} km"));
+    }
+    if let Some(zones) = &w.target_hr_zones {
+        result.push_str(&format!(" (HR {zones})"));
+    }
+    result
+}
+
+/// Format seconds per zone as "Z1 5 min, Z2 35 min", skipping empty zones.
+fn format_zone_minutes(seconds: &[i64]) -> String {
+    let zones: Vec<String> = seconds
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| **s > 0)
+        .map(|(i, s)| format!("Z{} {} min", i + 1, (*s as f64 / 60.0).round() as i64))
+        .collect();
+    if zones.is_empty() {
+        "n/a".to_string()
+    } else {
+        zones.join(", ")
+    }
+}
+
 /// Helper to get 3-letter weekday abbreviation from a YYYY-MM-DD date string.
 fn weekday_abbr(date_str: &str) -> &'static str {
     use chrono::NaiveDate;
@@ -395,7 +881,7 @@ mod tests {
     fn macrocycle_context_includes_all_fields() {
         let profile = test_profile();
         let goal = test_race_goal();
-        let ctx = build_macrocycle_context(&profile, &goal, 35.0, 16, None);
+        let ctx = build_macrocycle_context(&profile, &goal, 35.0, 16, None, None);
 
         assert!(ctx.contains("Test Runner"));
         assert!(ctx.contains("35"));
@@ -416,7 +902,7 @@ mod tests {
         let mut profile = test_profile();
         profile.ftpace_m_per_s = None;
         let goal = test_race_goal();
-        let ctx = build_macrocycle_context(&profile, &goal, 35.0, 16, None);
+        let ctx = build_macrocycle_context(&profile, &goal, 35.0, 16, None, None);
 
         assert!(ctx.contains("not set"));
         assert!(ctx.contains("0.00 m/s"));
@@ -427,7 +913,7 @@ mod tests {
         let profile = test_profile();
         let mut goal = test_race_goal();
         goal.race_name = None;
-        let ctx = build_macrocycle_context(&profile, &goal, 35.0, 16, None);
+        let ctx = build_macrocycle_context(&profile, &goal, 35.0, 16, None, None);
 
         assert!(ctx.contains("Goal Race"));
     }
@@ -492,7 +978,7 @@ mod tests {
         let mut profile = test_profile();
         profile.experience_level = "beginner".to_string();
         let goal = test_race_goal();
-        let ctx = build_macrocycle_context(&profile, &goal, 20.0, 20, None);
+        let ctx = build_macrocycle_context(&profile, &goal, 20.0, 20, None, None);
         assert!(ctx.contains("2 load weeks"));
     }
 
@@ -501,29 +987,15 @@ mod tests {
         let mut profile = test_profile();
         profile.experience_level = "advanced".to_string();
         let goal = test_race_goal();
-        let ctx = build_macrocycle_context(&profile, &goal, 50.0, 12, None);
+        let ctx = build_macrocycle_context(&profile, &goal, 50.0, 12, None, None);
         assert!(ctx.contains("3 load weeks"));
     }
 
-    #[test]
-    fn format_distance_common_races() {
-        assert_eq!(format_distance(5000.0), "5K");
-        assert_eq!(format_distance(10000.0), "10K");
-        assert_eq!(format_distance(21097.0), "Half Marathon (21.1 km)");
-        assert_eq!(format_distance(42195.0), "Marathon (42.2 km)");
-    }
-
-    #[test]
-    fn format_distance_custom() {
-        assert_eq!(format_distance(8000.0), "8.0 km");
-        assert_eq!(format_distance(800.0), "800 m");
-    }
-
     #[test]
     fn ftpace_display_calculation() {
         let profile = test_profile(); // ftpace_m_per_s = 3.5
         let goal = test_race_goal();
-        let ctx = build_macrocycle_context(&profile, &goal, 35.0, 16, None);
+        let ctx = build_macrocycle_context(&profile, &goal, 35.0, 16, None, None);
         // 3.5 m/s => 1000/3.5/60 = 4.7619... min/km => 4:45/km
         assert!(ctx.contains("4:45/km"));
         assert!(ctx.contains("3.50 m/s"));
@@ -539,7 +1011,8 @@ mod tests {
         let goal = test_race_goal();
         let ctx = build_macrocycle_context(
             &profile, &goal, 35.0, 16,
-            Some("Previous mesocycle (capacity / aerobic_capacity, 4 weeks):\nCompletion: 22/28 (79%)")
+            Some("Previous mesocycle (capacity / aerobic_capacity, 4 weeks):\nCompletion: 22/28 (79%)"),
+            None,
         );
         assert!(ctx.contains("Previous training history:"));
         assert!(ctx.contains("Completion: 22/28 (79%)"));
@@ -549,8 +1022,29 @@ mod tests {
     fn macrocycle_context_without_history() {
         let profile = test_profile();
         let goal = test_race_goal();
-        let ctx = build_macrocycle_context(&profile, &goal, 35.0, 16, None);
+        let ctx = build_macrocycle_context(&profile, &goal, 35.0, 16, None, None);
         assert!(ctx.contains("No previous training history available"));
+        assert!(!ctx.contains("Predicted finish"));
+    }
+
+    #[test]
+    fn macrocycle_context_predicts_goal_time_from_recent_race() {
+        let profile = test_profile();
+        let goal = test_race_goal();
+        let race = RaceResult {
+            id: 1,
+            user_id: 1,
+            race_name: None,
+            distance_m: 10000.0,
+            time_seconds: 2400,
+            race_date: "2026-02-15".to_string(),
+            created_at: "2026-02-16".to_string(),
+        };
+        let ctx = build_macrocycle_context(&profile, &goal, 35.0, 16, None, Some(&race));
+        assert!(
+            ctx.contains("- Predicted finish: 1:28:15 (from 10K in 40:00 on 2026-02-15)"),
+            "{ctx}"
+        );
     }
 
     // -----------------------------------------------------------------------
@@ -664,4 +1158,279 @@ mod tests {
         let result = format_workout_history_summary(&[], "capacity", "aerobic_capacity", 4);
         assert!(result.is_empty());
     }
+
+    // -----------------------------------------------------------------------
+    // Workout analysis context
+    // -----------------------------------------------------------------------
+
+    fn test_completed_workout() -> CompletedWorkout {
+        CompletedWorkout {
+            id: 7,
+            user_id: 1,
+            planned_workout_id: Some(3),
+            fit_file_path: None,
+            source: "fit_upload".to_string(),
+            hr_data_sufficient: 1,
+            started_at: "2026-03-03T07:00:00+00:00".to_string(),
+            duration_seconds: 2700,
+            distance_m: 8400.0,
+            avg_hr: Some(141),
+            max_hr: Some(152),
+            avg_pace_m_per_s: Some(3.1),
+            max_pace_m_per_s: Some(3.4),
+            avg_cadence: None,
+            elevation_gain_m: None,
+            elevation_loss_m: None,
+            ngp_m_per_s: Some(3.1),
+            intensity_factor: Some(0.69),
+            rtss: Some(36.0),
+            hrtss: Some(40.0),
+            tss: Some(36.0),
+            trimp: Some(55.0),
+            hr_zone_1_seconds: 300,
+            hr_zone_2_seconds: 2400,
+            hr_zone_3_seconds: 0,
+            hr_zone_4_seconds: 0,
+            hr_zone_5_seconds: 0,
+            hr_zone_6_seconds: 0,
+            hr_zone_7_seconds: 0,
+            pace_zone_1_seconds: 2700,
+            pace_zone_2_seconds: 0,
+            pace_zone_3_seconds: 0,
+            pace_zone_4_seconds: 0,
+            pace_zone_5_seconds: 0,
+            pace_zone_6_seconds: 0,
+            aerobic_effect: Some(2.2),
+            anaerobic_effect: Some(0.0),
+            classification: Some("aerobic_capacity".to_string()),
+            expected_classification: None,
+            classification_matches: None,
+            compliance: Some("unplanned".to_string()),
+            coach_summary: None,
+            coach_commentary: None,
+            coach_message: None,
+            analyzed_at: None,
+            created_at: "2026-03-03".to_string(),
+        }
+    }
+
+    #[test]
+    fn workout_analysis_context_includes_scores_load_and_flags() {
+        let profile = test_profile();
+        let workout = test_completed_workout();
+        let load_after = DailyMetrics {
+            id: 1,
+            user_id: 1,
+            date: "2026-03-03".to_string(),
+            total_tss: 36.0,
+            atl: 28.4,
+            ctl: 30.2,
+            tsb: 1.8,
+        };
+        let flags = AnalysisFlags {
+            consecutive_off_target_count: 2,
+            off_target_direction: Some(Direction::Harder),
+            adjustment_eligible: false,
+            adjustment_triggers: vec![],
+            days_since_last_workout: Some(2),
+            current_mesocycle_week: Some("week 1 of 4 (load)".to_string()),
+            days_until_race: Some(90),
+        };
+        let ctx = build_workout_analysis_context(&WorkoutAnalysisInput {
+            profile: &profile,
+            workout: &workout,
+            planned: None,
+            classification_check: None,
+            compliance: None,
+            load_before: None,
+            load_after: Some(&load_after),
+            flags: &flags,
+            upcoming: &[],
+        });
+
+        assert!(ctx.contains("Test Runner"));
+        assert!(ctx.contains("Completed workout (2026-03-03)"));
+        assert!(ctx.contains("Duration: 45 min, distance: 8.40 km"));
+        assert!(ctx.contains("Avg pace: 5:22/km"));
+        assert!(ctx.contains("TSS: 36 (rTSS), IF: 0.69"));
+        assert!(ctx.contains("Aerobic effect: 2.2/5, anaerobic effect: 0.0/5"));
+        assert!(ctx.contains("Classification: aerobic_capacity"));
+        assert!(ctx.contains("Time in HR zones: Z1 5 min, Z2 40 min"));
+        assert!(ctx.contains("unplanned session"));
+        assert!(ctx.contains("- Before: unknown"));
+        assert!(ctx.contains("- After: CTL 30.2, ATL 28.4, TSB 1.8"));
+        assert!(ctx.contains("consecutive_off_target_count: 2 (harder)"));
+        assert!(ctx.contains("adjustment_eligible: false"));
+        assert!(!ctx.contains("adjustment_triggers:"));
+        assert!(ctx.contains("current_mesocycle_week: week 1 of 4 (load)"));
+        assert!(ctx.contains("days_until_race: 90"));
+        assert!(ctx.contains("analyze_workout"));
+    }
+
+    #[test]
+    fn mesocycle_evaluation_context_includes_history_fitness_and_next_block() {
+        let profile = test_profile();
+        let meso = |seq: i64, phase: &str, start: &str, end: &str| Mesocycle {
+            id: seq,
+            macrocycle_id: 1,
+            sequence_number: seq,
+            phase: phase.to_string(),
+            focus: "aerobic_capacity".to_string(),
+            load_weeks: 3,
+            recovery_weeks: 1,
+            target_volume_km: Some(160.0),
+            start_date: start.to_string(),
+            end_date: end.to_string(),
+            status: "active".to_string(),
+            evaluation_summary: None,
+            transition_due_at: None,
+            created_at: "2026-01-01".to_string(),
+        };
+        let finished = meso(1, "capacity", "2026-03-02", "2026-03-29");
+        let next = meso(2, "utilization", "2026-03-30", "2026-04-26");
+
+        let ctx = build_mesocycle_evaluation_context(&MesocycleEvaluationInput {
+            profile: &profile,
+            mesocycle: &finished,
+            history: "Previous mesocycle (capacity / aerobic_capacity, 4 weeks):\n",
+            ctl_at_start: Some(30.0),
+            current_ctl: 38.5,
+            target_ctl: Some(55.0),
+            next: Some(&next),
+        });
+        assert!(ctx.contains("Evaluate mesocycle 1 for Test Runner: capacity phase"));
+        assert!(ctx.contains("3+1 weeks (2026-03-02 to 2026-03-29)"));
+        assert!(ctx.contains("Previous mesocycle (capacity / aerobic_capacity, 4 weeks)"));
+        assert!(ctx.contains("CTL at start: 30.0, now: 38.5"));
+        assert!(ctx.contains("Race-day target CTL: 55"));
+        assert!(ctx.contains("Next mesocycle: utilization phase"));
+        assert!(ctx.contains("evaluate_mesocycle"));
+
+        let ctx = build_mesocycle_evaluation_context(&MesocycleEvaluationInput {
+            profile: &profile,
+            mesocycle: &next,
+            history: "",
+            ctl_at_start: None,
+            current_ctl: 50.0,
+            target_ctl: None,
+            next: None,
+        });
+        assert!(ctx.contains("CTL now: 50.0"));
+        assert!(ctx.contains("last mesocycle before the race"));
+    }
+
+    // -----------------------------------------------------------------------
+    // Chat context tests
+    // -----------------------------------------------------------------------
+
+    fn chat_message(id: i64, role: &str, content: &str) -> ChatMessage {
+        ChatMessage {
+            id,
+            user_id: 1,
+            role: role.to_string(),
+            content: content.to_string(),
+            context_type: Some("freeform".to_string()),
+            related_workout_id: None,
+            created_at: "2026-03-03 12:00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn chat_history_window_keeps_newest_within_budgets() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcde"), 2);
+
+        // Message count: only the newest 20 of 25 short messages.
+        let messages: Vec<_> = (1..=25).map(|i| chat_message(i, "athlete", "hi")).collect();
+        let window = chat_history_window(&messages);
+        assert_eq!(window.len(), CHAT_HISTORY_MESSAGES);
+        assert_eq!(window[0].id, 6);
+        assert_eq!(window.last().unwrap().id, 25);
+
+        // Token budget: 1,500-token messages, so only two fit in 4,000.
+        let long = "x".repeat(6000);
+        let messages: Vec<_> = (1..=4).map(|i| chat_message(i, "coach", &long)).collect();
+        let window = chat_history_window(&messages);
+        assert_eq!(window.iter().map(|m| m.id).collect::<Vec<_>>(), [3, 4]);
+
+        // A newest message over the whole budget leaves no history.
+        let messages = vec![
+            chat_message(1, "athlete", "hi"),
+            chat_message(2, "coach", &"x".repeat(20_000)),
+        ];
+        assert!(chat_history_window(&messages).is_empty());
+    }
+
+    #[test]
+    fn chat_context_includes_profile_zones_plan_workouts_and_load() {
+        use crate::domain::zones::{calculate_hr_zones, calculate_pace_zones};
+
+        let profile = test_profile();
+        let race_goal = test_race_goal();
+        let hr_zones = calculate_hr_zones(170);
+        let pace_zones = calculate_pace_zones(3.5);
+        let today = make_test_workout("2026-03-03", "tempo_run", 0, None, None, Some("short"));
+        let upcoming = [make_test_workout(
+            "2026-03-04",
+            "easy_run",
+            0,
+            None,
+            None,
+            None,
+        )];
+        let recent = [test_completed_workout()];
+        let metrics = DailyMetrics {
+            id: 1,
+            user_id: 1,
+            date: "2026-03-03".to_string(),
+            total_tss: 0.0,
+            atl: 40.0,
+            ctl: 35.5,
+            tsb: -4.5,
+        };
+
+        let ctx = build_chat_context(&ChatContextInput {
+            profile: &profile,
+            race_goal: Some(&race_goal),
+            hr_zones: &hr_zones,
+            pace_zones: Some(&pace_zones),
+            today: NaiveDate::from_ymd_opt(2026, 3, 3).unwrap(),
+            current_week: Some("week 1 of 4 (load), mesocycle 1 capacity / aerobic_capacity"),
+            todays_workouts: std::slice::from_ref(&today),
+            upcoming: &upcoming,
+            recent_workouts: &recent,
+            metrics: Some(&metrics),
+        });
+        assert!(ctx.contains("Today is Tuesday 2026-03-03."));
+        assert!(ctx.contains("Athlete: Test Runner, 35 years, intermediate, 40 km/week"));
+        assert!(ctx.contains("Race goal: Spring Half, Half Marathon (21.1 km) on 2026-06-01"));
+        assert!(ctx.contains("HR zones (LTHR 170 bpm"));
+        assert!(ctx.contains("- Z1 "));
+        assert!(ctx.contains("Pace zones (FTPace 4:45/km)"));
+        assert!(ctx.contains("- Current: week 1 of 4 (load)"));
+        assert!(ctx.contains("- Today: tempo_run 45 min"));
+        assert!(ctx.contains("- Wed 2026-03-04: easy_run 45 min"));
+        assert!(ctx.contains("Recent workouts:"));
+        assert!(ctx.contains("Training load (2026-03-03): CTL 35.5, ATL 40.0, TSB -4.5"));
+
+        let mut no_ftpace = test_profile();
+        no_ftpace.ftpace_m_per_s = None;
+        let ctx = build_chat_context(&ChatContextInput {
+            profile: &no_ftpace,
+            race_goal: None,
+            hr_zones: &hr_zones,
+            pace_zones: None,
+            today: NaiveDate::from_ymd_opt(2026, 3, 3).unwrap(),
+            current_week: None,
+            todays_workouts: &[],
+            upcoming: &[],
+            recent_workouts: &[],
+            metrics: None,
+        });
+        assert!(ctx.contains("Pace zones: not set"));
+        assert!(ctx.contains("No active training plan covers today"));
+        assert!(ctx.contains("- Today: nothing scheduled"));
+        assert!(!ctx.contains("Recent workouts:"));
+        assert!(ctx.contains("Training load: unknown"));
+    }
 }
@@ -4,50 +4,64 @@ use serde_json::Value;
 use sqlx::sqlite::SqlitePool;
 use tracing::{info, warn};
 
-use crate::ai::client::{ClaudeClient, ClaudeError, Message, Model};
+use crate::ai::client::{ClaudeClient, ClaudeError, ClaudeResponse, ContentBlock, Message, Model};
 use crate::ai::context::{
-    build_macrocycle_context, build_mesocycle_context, format_workout_history_detailed,
-    format_workout_history_summary,
+    AnalysisFlags, CHAT_HISTORY_MESSAGES, ChatContextInput, MesocycleEvaluationInput,
+    WorkoutAnalysisInput, build_chat_context, build_macrocycle_context, build_mesocycle_context,
+    build_mesocycle_evaluation_context, build_workout_analysis_context, chat_history_window,
+    format_workout_history_detailed, format_workout_history_summary,
 };
-use crate::ai::prompts::coach_jan_system_prompt;
+use crate::ai::prompts::{coach_jan_chat_system_prompt, coach_jan_system_prompt};
 use crate::ai::tools::{
-    add_coach_notes_tool, generate_macrocycle_skeleton_tool, generate_mesocycle_plan_tool,
+    add_coach_notes_tool, analyze_workout_tool, evaluate_mesocycle_tool,
+    generate_macrocycle_skeleton_tool, generate_mesocycle_plan_tool,
 };
+use crate::db::chat::{self as chat_db, ChatMessage, CreateChatMessage};
+use crate::db::drafts::DraftRevision;
 use crate::db::plans::{
     self, CreateMacrocycle, CreateMesocycle, CreatePlannedWorkout, Macrocycle, Mesocycle,
     PlannedWorkout,
 };
-use crate::db::profiles::{AthleteProfile, RaceGoal};
+use crate::db::profiles::{self, AthleteProfile, RaceGoal};
+use crate::db::workouts::{self, CompletedWorkout};
+use crate::db::{adjustments, metrics};
+use crate::domain::adjustments::PlanAdjustment;
+use crate::domain::compliance::{adjustment_triggers, assess_compliance};
+use crate::domain::repair::{Repair, repair_week_plans};
+use crate::domain::scoring::format_zone_list;
+pub use crate::domain::skeleton::{MacrocycleSkeleton, MesocycleSkeleton};
+use crate::domain::skeleton::validate_skeleton;
+use crate::domain::time_trial::schedule_time_trial;
 use crate::domain::validation::{
-    validate_week_plan, PlannedDay, ValidationContext, WeekPlan, WeekType,
+    PlannedDay, ValidationContext, WeekPlan, WeekType, WeekValidationError, validate_plan_weeks,
 };
 use crate::domain::workouts::{DurationCategory, WorkoutRegistry, WorkoutType};
 use crate::domain::zones::{calculate_hr_zones, calculate_pace_zones};
+use crate::error::AppError;
 
 const DEFAULT_STRENGTH_DURATION_MIN: u16 = 45;
 const DEFAULT_STRENGTH_TSS: f64 = 30.0;
 
+/// Upcoming sessions shown to Claude when it may propose an adjustment.
+const UPCOMING_SESSIONS: i64 = 14;
+
+/// Upper bound on a chat reply.
+const CHAT_MAX_TOKENS: u32 = 1024;
+
+/// Completed workouts and upcoming sessions listed in chat context.
+const CHAT_RECENT_WORKOUTS: i64 = 5;
+const CHAT_UPCOMING_SESSIONS: i64 = 3;
+
+/// Times a mesocycle plan that fails validation is sent back to Claude.
+const MAX_PLAN_RETRIES: u32 = 2;
+
+/// Weeks of uploads averaged for the first mesocycle's volume baseline.
+const RECENT_VOLUME_WEEKS: i64 = 4;
+
 // ---------------------------------------------------------------------------
 // Data structures
 // ---------------------------------------------------------------------------
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MacrocycleSkeleton {
-    pub target_ctl: f64,
-    pub coach_message: String,
-    pub mesocycles: Vec<MesocycleSkeleton>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MesocycleSkeleton {
-    pub sequence_number: i64,
-    pub phase: String,
-    pub focus: String,
-    pub load_weeks: i64,
-    pub recovery_weeks: i64,
-    pub target_volume_km: f64,
-}
-
 #[derive(Debug, Clone, Serialize)]
 pub struct GeneratedPlan {
     pub macrocycle: Macrocycle,
@@ -119,27 +133,13 @@ pub async fn generate_skeleton(
     race_goal: &RaceGoal,
     ctl: f64,
 ) -> Result<MacrocycleSkeleton, PlanError> {
-    let today = chrono::Utc::now().date_naive();
-    let race_date = NaiveDate::parse_from_str(&race_goal.race_date, "%Y-%m-%d").map_err(|e| {
-        PlanError::InvalidResponse(format!("Invalid race date '{}': {}", race_goal.race_date, e))
-    })?;
-    let weeks_until_race = (race_date - today).num_weeks();
-
-    // Fetch workout history from previous mesocycle (if any active plan exists)
-    let workout_history = fetch_workout_history_summary(pool, profile.user_id).await?;
-    let context = build_macrocycle_context(
-        profile,
-        race_goal,
-        ctl,
-        weeks_until_race,
-        workout_history.as_deref(),
-    );
+    let context = skeleton_context(pool, profile, race_goal, ctl).await?;
     let messages = vec![Message::user(&context)];
     let tools = vec![generate_macrocycle_skeleton_tool()];
 
     info!(
-        "Generating macrocycle skeleton for user_id={}, race_date={}, weeks_until_race={}",
-        profile.user_id, race_goal.race_date, weeks_until_race
+        "Generating macrocycle skeleton for user_id={}, race_date={}",
+        profile.user_id, race_goal.race_date
     );
 
     let response = client
@@ -152,20 +152,7 @@ pub async fn generate_skeleton(
         )
         .await?;
 
-    let (_id, name, input) = response.tool_use().ok_or_else(|| {
-        PlanError::InvalidResponse("No tool_use in Claude response".to_string())
-    })?;
-
-    if name != "generate_macrocycle_skeleton" {
-        return Err(PlanError::InvalidResponse(format!(
-            "Expected generate_macrocycle_skeleton tool, got {}",
-            name
-        )));
-    }
-
-    let skeleton: MacrocycleSkeleton = serde_json::from_value(input.clone()).map_err(|e| {
-        PlanError::InvalidResponse(format!("Failed to parse skeleton: {}", e))
-    })?;
+    let skeleton = skeleton_from_response(&response)?;
 
     info!(
         "Generated skeleton with {} mesocycles, target_ctl={}",
@@ -176,12 +163,197 @@ pub async fn generate_skeleton(
     Ok(skeleton)
 }
 
+/// Revise a draft skeleton from the athlete's free-text `feedback`.
+///
+/// Claude sees the draft's whole history as one conversation: the original
+/// context, each version as its own `generate_macrocycle_skeleton` call, and
+/// the requests that led from one version to the next. `revisions` must hold
+/// every version of the draft, oldest first. A revision that fails
+/// `validate_skeleton` is sent back once with its errors; if the retry fails
+/// too, the revision is rejected with `PlanError::ValidationFailed`.
+pub async fn revise_skeleton(
+    client: &ClaudeClient,
+    pool: &SqlitePool,
+    profile: &AthleteProfile,
+    race_goal: &RaceGoal,
+    ctl: f64,
+    revisions: &[DraftRevision],
+    feedback: &str,
+) -> Result<MacrocycleSkeleton, PlanError> {
+    let context = skeleton_context(pool, profile, race_goal, ctl).await?;
+    let mut messages = skeleton_revision_messages(&context, revisions, feedback)?;
+    let race_date = parse_race_date(race_goal)?;
+    let today = chrono::Utc::now().date_naive();
+
+    info!(
+        "Revising macrocycle skeleton for user_id={}, revisions so far={}",
+        profile.user_id,
+        revisions.len()
+    );
+
+    let mut retried = false;
+    loop {
+        let response = client
+            .send(
+                Model::Sonnet,
+                Some(&coach_jan_system_prompt()),
+                messages.clone(),
+                vec![generate_macrocycle_skeleton_tool()],
+                4096,
+            )
+            .await?;
+        let skeleton = skeleton_from_response(&response)?;
+
+        let errors = validate_skeleton(
+            &skeleton,
+            today,
+            race_date,
+            profile.current_weekly_volume_km,
+        );
+        if errors.is_empty() {
+            return Ok(skeleton);
+        }
+        let issues: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        if retried {
+            return Err(PlanError::ValidationFailed(format!(
+                "Revised skeleton is still invalid after a retry: {}",
+                issues.join("; ")
+            )));
+        }
+
+        warn!(
+            "Revised skeleton has {} validation errors, retrying: {}",
+            issues.len(),
+            issues.join("; ")
+        );
+        let (id, name, input) = response.tool_use().ok_or_else(|| {
+            PlanError::InvalidResponse("No tool_use in Claude response".to_string())
+        })?;
+        messages.push(Message::assistant_tool_use(id, name, input.clone()));
+        messages.push(Message::user_tool_result(
+            id,
+            &skeleton_validation_feedback(&issues),
+        ));
+        retried = true;
+    }
+}
+
+/// The tool result answering a skeleton that failed validation.
+fn skeleton_validation_feedback(issues: &[String]) -> String {
+    let issues: Vec<String> = issues.iter().map(|issue| format!("- {issue}")).collect();
+    format!(
+        "The skeleton failed validation:\n{}\n\n\
+         Call generate_macrocycle_skeleton again with the complete corrected skeleton. \
+         Fix every issue listed and keep the athlete's requested changes.",
+        issues.join("\n")
+    )
+}
+
+/// Build the revision conversation. Each version is replayed as an assistant
+/// tool call whose result is the request behind the next version; the last
+/// one is answered with the athlete's new `feedback`.
+pub(crate) fn skeleton_revision_messages(
+    context: &str,
+    revisions: &[DraftRevision],
+    feedback: &str,
+) -> Result<Vec<Message>, PlanError> {
+    if revisions.is_empty() {
+        return Err(PlanError::InvalidResponse(
+            "No skeleton to revise".to_string(),
+        ));
+    }
+
+    let mut messages = vec![Message::user(context)];
+    for (i, revision) in revisions.iter().enumerate() {
+        let tool_use_id = format!("skeleton_v{}", revision.version);
+        let input = serde_json::to_value(&revision.skeleton).map_err(|e| {
+            PlanError::InvalidResponse(format!("Failed to serialize skeleton: {}", e))
+        })?;
+        messages.push(Message::assistant_tool_use(
+            &tool_use_id,
+            "generate_macrocycle_skeleton",
+            input,
+        ));
+
+        let result = match revisions.get(i + 1) {
+            Some(next) if next.source == "edited" => {
+                "The athlete edited this skeleton by hand. Their version follows.".to_string()
+            }
+            Some(next) => revision_request(next.feedback.as_deref().unwrap_or_default()),
+            None => revision_request(feedback),
+        };
+        messages.push(Message::user_tool_result(&tool_use_id, &result));
+    }
+
+    Ok(messages)
+}
+
+fn revision_request(feedback: &str) -> String {
+    format!(
+        "The athlete asked for changes to this skeleton:\n\n{}\n\n\
+         Call generate_macrocycle_skeleton again with the complete revised skeleton. \
+         Change only what the request needs, keep the plan ending just before race day, \
+         and use coach_message to explain what changed and why.",
+        feedback
+    )
+}
+
+/// The macrocycle context for generating or revising a skeleton today.
+async fn skeleton_context(
+    pool: &SqlitePool,
+    profile: &AthleteProfile,
+    race_goal: &RaceGoal,
+    ctl: f64,
+) -> Result<String, PlanError> {
+    let today = chrono::Utc::now().date_naive();
+    let race_date = parse_race_date(race_goal)?;
+    let weeks_until_race = (race_date - today).num_weeks();
+
+    // Fetch workout history from previous mesocycle (if any active plan exists)
+    let workout_history = fetch_workout_history_summary(pool, profile.user_id).await?;
+    let recent_race = profiles::get_latest_race_result(pool, profile.user_id).await?;
+    Ok(build_macrocycle_context(
+        profile,
+        race_goal,
+        ctl,
+        weeks_until_race,
+        workout_history.as_deref(),
+        recent_race.as_ref(),
+    ))
+}
+
+fn parse_race_date(race_goal: &RaceGoal) -> Result<NaiveDate, PlanError> {
+    NaiveDate::parse_from_str(&race_goal.race_date, "%Y-%m-%d").map_err(|e| {
+        PlanError::InvalidResponse(format!(
+            "Invalid race date '{}': {}",
+            race_goal.race_date, e
+        ))
+    })
+}
+
+/// The skeleton from Claude's `generate_macrocycle_skeleton` call.
+fn skeleton_from_response(response: &ClaudeResponse) -> Result<MacrocycleSkeleton, PlanError> {
+    let (_id, name, input) = response
+        .tool_use()
+        .ok_or_else(|| PlanError::InvalidResponse("No tool_use in Claude response".to_string()))?;
+
+    if name != "generate_macrocycle_skeleton" {
+        return Err(PlanError::InvalidResponse(format!(
+            "Expected generate_macrocycle_skeleton tool, got {}",
+            name
+        )));
+    }
+
+    parse_skeleton(input)
+}
+
 // ---------------------------------------------------------------------------
 // Orchestration: confirm_and_generate_plan
 // ---------------------------------------------------------------------------
 
-/// Persist skeleton to DB, generate first mesocycle workouts via Claude,
-/// add coach notes, validate, and persist workouts.
+/// Generate and validate the first mesocycle's workouts via Claude, then
+/// store the macrocycle, its mesocycles and those workouts in one
+/// transaction. Nothing is stored if generation fails.
 pub async fn confirm_and_generate_plan(
     client: &ClaudeClient,
     pool: &SqlitePool,
@@ -191,7 +363,7 @@ pub async fn confirm_and_generate_plan(
     race_goal: &RaceGoal,
     ctl: f64,
 ) -> Result<GeneratedPlan, PlanError> {
-    // --- Step 1: Persist macrocycle + mesocycles to DB ---
+    // --- Step 1: Lay out the mesocycles ---
     let today = chrono::Utc::now().date_naive();
 
     // Calculate mesocycle dates
@@ -203,7 +375,47 @@ pub async fn confirm_and_generate_plan(
         .map(|(_, end)| end.to_string())
         .unwrap_or_else(|| today.to_string());
 
-    let macrocycle = plans::create_macrocycle(
+    // The macrocycle ID is assigned when the plan is stored
+    let mesocycles: Vec<CreateMesocycle> = skeleton
+        .mesocycles
+        .iter()
+        .zip(&meso_dates)
+        .map(|(meso_skel, (start, end))| CreateMesocycle {
+            macrocycle_id: 0,
+            sequence_number: meso_skel.sequence_number,
+            phase: meso_skel.phase.clone(),
+            focus: meso_skel.focus.clone(),
+            load_weeks: meso_skel.load_weeks,
+            recovery_weeks: meso_skel.recovery_weeks,
+            target_volume_km: Some(meso_skel.target_volume_km),
+            start_date: start.to_string(),
+            end_date: end.to_string(),
+        })
+        .collect();
+    let first_meso = mesocycles
+        .first()
+        .ok_or_else(|| PlanError::InvalidResponse("Skeleton has no mesocycles".to_string()))?;
+
+    // --- Step 2: Generate and validate the first mesocycle ---
+    // A new plan has no earlier block to learn from, so its first block
+    // starts from the athlete's recent volume
+    let baseline = first_block_volume_baseline(pool, user_id, profile, today).await?;
+    let generated = generate_mesocycle(
+        client,
+        pool,
+        user_id,
+        profile,
+        first_meso,
+        ctl,
+        None,
+        baseline,
+        VolumeAdjustment::Maintain,
+    )
+    .await?;
+
+    // --- Step 3: Store the plan ---
+    client.report_stage("Saving workouts");
+    let (macrocycle, mesocycles, workouts) = plans::create_plan(
         pool,
         &CreateMacrocycle {
             user_id,
@@ -213,257 +425,1029 @@ pub async fn confirm_and_generate_plan(
             target_ctl: Some(skeleton.target_ctl),
             coach_message: Some(skeleton.coach_message.clone()),
         },
+        mesocycles,
+        generated.workouts,
+    )
+    .await?;
+
+    info!(
+        "Created macrocycle id={} with {} mesocycles and {} planned workouts",
+        macrocycle.id,
+        mesocycles.len(),
+        workouts.len()
+    );
+
+    Ok(GeneratedPlan {
+        macrocycle,
+        mesocycles,
+        workouts,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Helper: generate a mesocycle's workouts
+// ---------------------------------------------------------------------------
+
+/// A mesocycle's generated and validated workouts, not yet stored. Their
+/// `mesocycle_id` is set when they are.
+struct GeneratedMesocycle {
+    /// The planned weekly volume after the volume adjustment.
+    target_volume_km: f64,
+    workouts: Vec<CreatePlannedWorkout>,
+}
+
+/// Generate a mesocycle's day-by-day workouts with Claude, fill them from the
+/// registry, add coach notes and validate (retrying on severe errors).
+/// `previous_week_volume_km` is what the first load week's volume is
+/// checked against. `volume_adjustment` scales the mesocycle's planned
+/// weekly volume. Nothing is stored.
+#[allow(clippy::too_many_arguments)]
+async fn generate_mesocycle(
+    client: &ClaudeClient,
+    pool: &SqlitePool,
+    user_id: i64,
+    profile: &AthleteProfile,
+    meso: &CreateMesocycle,
+    ctl: f64,
+    workout_history: Option<&str>,
+    previous_week_volume_km: Option<f64>,
+    volume_adjustment: VolumeAdjustment,
+) -> Result<GeneratedMesocycle, PlanError> {
+    // --- Step 1: Generate the day-by-day plan ---
+    client.report_stage(&format!(
+        "Generating workouts for the {} mesocycle",
+        meso.phase
+    ));
+    let target_volume_km = meso.target_volume_km.unwrap_or_default() * volume_adjustment.factor();
+    let call = generate_mesocycle_workouts(
+        client,
+        profile,
+        &meso.phase,
+        &meso.focus,
+        meso.load_weeks,
+        meso.recovery_weeks,
+        &meso.start_date,
+        &meso.end_date,
+        target_volume_km,
+        ctl,
+        workout_history,
+    )
+    .await?;
+
+    // --- Step 2: Fill details from workout registry and validate ---
+    let hr_zones = calculate_hr_zones(profile.lthr as u16);
+    let pace_zones = profile.ftpace_m_per_s.map(calculate_pace_zones);
+    let validation_ctx = ValidationContext {
+        athlete_ctl: ctl,
+        previous_week_volume_km,
+    };
+    let mut attempt = check_mesocycle_plan(call, &hr_zones, pace_zones.as_ref(), &validation_ctx)?;
+
+    // --- Step 3: Retry with the violations as feedback ---
+    // Only what the repair pass could not fix goes back to Claude. Any error
+    // left is worth a retry. Severe errors must be gone before the plan
+    // is kept; if retries leave only soft errors, the attempt with the fewest
+    // is kept.
+    let mut fallback: Option<MesocycleAttempt> = None;
+    let mut retries = 0;
+    while !attempt.errors.is_empty() && retries < MAX_PLAN_RETRIES {
+        retries += 1;
+        warn!(
+            "Mesocycle plan has {} validation errors, retry {}/{}: {}",
+            attempt.errors.len(),
+            retries,
+            MAX_PLAN_RETRIES,
+            describe_errors(&attempt.errors)
+        );
+        client.report_stage(&format!(
+            "Plan failed validation, regenerating (attempt {retries}/{MAX_PLAN_RETRIES})"
+        ));
+
+        let feedback = validation_feedback(&attempt.errors);
+        let call = retry_mesocycle_workouts(client, &attempt.call, &feedback).await?;
+        let next = check_mesocycle_plan(call, &hr_zones, pace_zones.as_ref(), &validation_ctx)?;
+
+        if !attempt.has_severe_errors()
+            && fallback
+                .as_ref()
+                .is_none_or(|f| attempt.errors.len() < f.errors.len())
+        {
+            fallback = Some(attempt);
+        }
+        attempt = next;
+    }
+
+    if let Some(fallback) = fallback
+        && (attempt.has_severe_errors() || fallback.errors.len() < attempt.errors.len())
+    {
+        attempt = fallback;
+    }
+    if attempt.has_severe_errors() {
+        return Err(PlanError::ValidationFailed(format!(
+            "Plan has severe validation errors after {} retries: {}",
+            MAX_PLAN_RETRIES,
+            describe_errors(&attempt.errors)
+        )));
+    }
+    if !attempt.errors.is_empty() {
+        warn!(
+            "Keeping mesocycle plan with {} soft validation errors: {}",
+            attempt.errors.len(),
+            describe_errors(&attempt.errors)
+        );
+    }
+
+    // --- Step 4: Schedule a time trial in the recovery week ---
+    let last_time_trial = plans::get_last_time_trial_date(pool, user_id, &meso.start_date)
+        .await?
+        .and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok());
+    let week_plans = build_week_plans(&attempt.call.plan.weeks, &attempt.filled);
+    let time_trial = schedule_time_trial(&week_plans, last_time_trial);
+    if let Some(placement) = &time_trial {
+        info!("Scheduling a time trial on {}", placement.date);
+        if let Some(day) = plan_day_mut(&mut attempt.call.plan, &placement.date) {
+            day.workout_type = WorkoutType::TimeTrial.as_str().to_string();
+            day.duration_category = Some(DurationCategory::Long.as_str().to_string());
+        }
+        attempt.filled =
+            fill_workouts_from_registry(&attempt.call.plan.weeks, &hr_zones, pace_zones.as_ref())?;
+    }
+
+    // --- Step 5: Add coach notes ---
+    client.report_stage("Writing coach notes");
+    let coach_notes = generate_coach_notes(
+        client,
+        profile,
+        &meso.phase,
+        &attempt.call.plan,
+        workout_history,
+    )
+    .await?;
+
+    // Build a lookup map date -> coach_note
+    let mut notes_map: std::collections::HashMap<String, String> = coach_notes
+        .workout_notes
+        .into_iter()
+        .map(|n| (n.date, n.coach_note))
+        .collect();
+
+    // Tell the athlete about every change the repair pass and time trial
+    // scheduling made
+    let changes = attempt
+        .repairs
+        .iter()
+        .map(|r| (r.date(), r.to_string()))
+        .chain(time_trial.iter().map(|t| (t.date.as_str(), t.to_string())));
+    for (date, change) in changes {
+        let note = notes_map.entry(date.to_string()).or_default();
+        if !note.is_empty() {
+            note.push(' ');
+        }
+        note.push_str(&change);
+    }
+
+    let workouts = planned_workout_inputs(
+        user_id,
+        &attempt.call.plan.weeks,
+        &attempt.filled,
+        &notes_map,
+    );
+    info!(
+        "Generated {} planned workouts for mesocycle {}",
+        workouts.len(),
+        meso.sequence_number
+    );
+
+    Ok(GeneratedMesocycle {
+        target_volume_km,
+        workouts,
+    })
+}
+
+/// A generated mesocycle plan after the repair pass, filled from the
+/// registry, with the validation errors left.
+struct MesocycleAttempt {
+    call: MesocyclePlanCall,
+    filled: Vec<FilledWorkout>,
+    errors: Vec<WeekValidationError>,
+    repairs: Vec<Repair>,
+}
+
+impl MesocycleAttempt {
+    fn has_severe_errors(&self) -> bool {
+        self.errors.iter().any(|e| e.error.is_severe())
+    }
+}
+
+/// Fill and validate Claude's plan, repairing what the repair pass can fix
+/// before anything goes back to Claude.
+fn check_mesocycle_plan(
+    mut call: MesocyclePlanCall,
+    hr_zones: &crate::domain::types::HrZones,
+    pace_zones: Option<&crate::domain::types::PaceZones>,
+    ctx: &ValidationContext,
+) -> Result<MesocycleAttempt, PlanError> {
+    let mut filled = fill_workouts_from_registry(&call.plan.weeks, hr_zones, pace_zones)?;
+    let mut week_plans = build_week_plans(&call.plan.weeks, &filled);
+    let mut errors = validate_plan_weeks(&week_plans, ctx);
+
+    let mut repairs = Vec::new();
+    if !errors.is_empty() {
+        repairs = repair_week_plans(&mut week_plans, ctx);
+        if !repairs.is_empty() {
+            info!(
+                "Repaired {} validation issues without Claude: {:?}",
+                repairs.len(),
+                repairs
+            );
+            apply_repairs(&mut call.plan, &repairs);
+            filled = fill_workouts_from_registry(&call.plan.weeks, hr_zones, pace_zones)?;
+            week_plans = build_week_plans(&call.plan.weeks, &filled);
+            errors = validate_plan_weeks(&week_plans, ctx);
+        }
+    }
+
+    Ok(MesocycleAttempt {
+        call,
+        filled,
+        errors,
+        repairs,
+    })
+}
+
+/// Make the repair pass's changes in Claude's plan, which is what gets
+/// persisted.
+fn apply_repairs(plan: &mut ClaudeMesocyclePlan, repairs: &[Repair]) {
+    for repair in repairs {
+        match repair {
+            Repair::DowngradeToEasy { date, .. } => {
+                if let Some(day) = plan_day_mut(plan, date) {
+                    day.workout_type = WorkoutType::EasyRun.as_str().to_string();
+                    day.duration_category
+                        .get_or_insert_with(|| DurationCategory::Short.as_str().to_string());
+                }
+            }
+            Repair::AddRestDay { date, .. } => {
+                if let Some(day) = plan_day_mut(plan, date) {
+                    day.workout_type = WorkoutType::Rest.as_str().to_string();
+                    day.duration_category = None;
+                    day.target_distance_km = None;
+                }
+            }
+            Repair::CapVolume {
+                week_number,
+                from_km,
+                to_km,
+                ..
+            } => {
+                let Some(week) = plan
+                    .weeks
+                    .iter_mut()
+                    .find(|w| w.week_number == i64::from(*week_number))
+                else {
+                    continue;
+                };
+                week.target_volume_km = *to_km;
+                // Keep the days' distances in proportion to the new target
+                let scale = to_km / from_km;
+                for km in week
+                    .days
+                    .iter_mut()
+                    .filter_map(|d| d.target_distance_km.as_mut())
+                {
+                    *km = (*km * scale * 10.0).round() / 10.0;
+                }
+            }
+        }
+    }
+}
+
+fn plan_day_mut<'a>(plan: &'a mut ClaudeMesocyclePlan, date: &str) -> Option<&'a mut ClaudeDay> {
+    plan.weeks
+        .iter_mut()
+        .flat_map(|w| w.days.iter_mut())
+        .find(|d| d.date == date)
+}
+
+fn describe_errors(errors: &[WeekValidationError]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// The tool result answering a plan that failed validation: every violation
+/// with its week and dates, and what to send back.
+fn validation_feedback(errors: &[WeekValidationError]) -> String {
+    let issues: Vec<String> = errors.iter().map(|e| format!("- {e}")).collect();
+    format!(
+        "The plan failed validation:\n{}\n\n\
+         Call generate_mesocycle_plan again with the complete corrected plan. \
+         Fix every issue listed, keep the same dates and week structure, \
+         and leave everything else as it was.",
+        issues.join("\n")
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Orchestration: analyze_workout
+// ---------------------------------------------------------------------------
+
+/// Coach Jan's analysis of a completed workout, from the analyze_workout tool.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkoutAnalysis {
+    pub summary: String,
+    pub training_effect_commentary: String,
+    pub compliance_commentary: String,
+    pub load_commentary: String,
+    pub coach_message: String,
+    /// A plan change Coach Jan proposes, kept raw so a malformed proposal
+    /// does not discard the analysis.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plan_adjustment: Option<Value>,
+}
+
+/// A plan change proposed in a workout analysis.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProposedAdjustment {
+    pub explanation: String,
+    #[serde(flatten)]
+    pub adjustment: PlanAdjustment,
+}
+
+impl WorkoutAnalysis {
+    /// The commentary paragraphs as stored on the workout.
+    pub fn commentary(&self) -> String {
+        [
+            self.training_effect_commentary.as_str(),
+            self.compliance_commentary.as_str(),
+            self.load_commentary.as_str(),
+        ]
+        .join("\n\n")
+    }
+}
+
+/// Send a completed workout to Claude for analysis and store the returned
+/// summary, commentary and coach message on it. Claude gets the workout's
+/// scores and classification, the prescribed session, ATL/CTL/TSB before and
+/// after, and the app-computed compliance flags.
+///
+/// A plan adjustment in the response is stored as a pending proposal when the
+/// flags make the athlete eligible; it is never applied here.
+pub async fn analyze_workout(
+    client: &ClaudeClient,
+    pool: &SqlitePool,
+    workout: &CompletedWorkout,
+) -> Result<WorkoutAnalysis, PlanError> {
+    let user_id = workout.user_id;
+    let profile = profiles::get_profile_by_user_id(pool, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("No athlete profile found".to_string()))?;
+    let date = workout_date(&workout.started_at)?;
+    let date_str = date.format("%Y-%m-%d").to_string();
+
+    // Prescribed session and how the workout compares with it
+    let planned = match workout.planned_workout_id {
+        Some(id) => plans::get_workout_with_context(pool, id, user_id).await?,
+        None => None,
+    };
+    let execution = workout.execution();
+    let (compliance, classification_check) = match &planned {
+        Some((planned, _)) => (
+            planned
+                .prescription()
+                .map(|p| assess_compliance(Some(&p), &execution)),
+            workout.classification_check(&planned.workout_type),
+        ),
+        None => (Some(assess_compliance(None, &execution)), None),
+    };
+
+    // Training load before and after the workout's day
+    let load_before = metrics::get_metrics_before(pool, user_id, &date_str).await?;
+    let load_after = metrics::get_daily_metrics_for_date(pool, user_id, &date_str).await?;
+
+    // App-computed flags
+    let streak = workouts::get_off_target_streak(pool, user_id, &workout.started_at).await?;
+    let days_since_last_workout =
+        match workouts::get_previous_started_at(pool, user_id, &workout.started_at).await? {
+            Some(previous) => Some((date - workout_date(&previous)?).num_days()),
+            None => None,
+        };
+    let atl_ctl_ratio = load_after
+        .as_ref()
+        .filter(|m| m.ctl > 0.0)
+        .map(|m| m.atl / m.ctl);
+    let (current_mesocycle_week, days_until_race) =
+        match plans::get_current_plan(pool, user_id).await? {
+            Some((macrocycle, mesocycles)) => (
+                describe_mesocycle_week(&mesocycles, date),
+                NaiveDate::parse_from_str(&macrocycle.end_date, "%Y-%m-%d")
+                    .ok()
+                    .map(|race| (race - date).num_days())
+                    .filter(|days| *days >= 0),
+            ),
+            None => (None, None),
+        };
+    let triggers = adjustment_triggers(&streak, days_since_last_workout, atl_ctl_ratio);
+    let flags = AnalysisFlags {
+        consecutive_off_target_count: streak.consecutive_off_target_count,
+        off_target_direction: streak.off_target_direction,
+        adjustment_eligible: !triggers.is_empty(),
+        adjustment_triggers: triggers,
+        days_since_last_workout,
+        current_mesocycle_week,
+        days_until_race,
+    };
+    let upcoming = if flags.adjustment_eligible {
+        plans::get_upcoming_workouts(pool, user_id, &date_str, UPCOMING_SESSIONS).await?
+    } else {
+        Vec::new()
+    };
+
+    let prompt = build_workout_analysis_context(&WorkoutAnalysisInput {
+        profile: &profile,
+        workout,
+        planned: planned.as_ref(),
+        classification_check: classification_check.as_ref(),
+        compliance: compliance.as_ref(),
+        load_before: load_before.as_ref(),
+        load_after: load_after.as_ref(),
+        flags: &flags,
+        upcoming: &upcoming,
+    });
+
+    let response = client
+        .send(
+            Model::Sonnet,
+            Some(&coach_jan_system_prompt()),
+            vec![Message::user(&prompt)],
+            vec![analyze_workout_tool()],
+            2048,
+        )
+        .await?;
+
+    let (_id, name, input) = response.tool_use().ok_or_else(|| {
+        PlanError::InvalidResponse("No tool_use in workout analysis response".to_string())
+    })?;
+
+    if name != "analyze_workout" {
+        return Err(PlanError::InvalidResponse(format!(
+            "Expected analyze_workout tool, got {}",
+            name
+        )));
+    }
+
+    let analysis: WorkoutAnalysis = serde_json::from_value(input.clone()).map_err(|e| {
+        PlanError::InvalidResponse(format!("Failed to parse workout analysis: {}", e))
+    })?;
+
+    workouts::save_workout_analysis(
+        pool,
+        user_id,
+        workout.id,
+        &analysis.summary,
+        &analysis.commentary(),
+        &analysis.coach_message,
+    )
+    .await?;
+
+    if let Some(proposal) = &analysis.plan_adjustment {
+        if !flags.adjustment_eligible {
+            warn!(
+                "Ignoring plan adjustment for ineligible workout id={}",
+                workout.id
+            );
+        } else {
+            match serde_json::from_value::<ProposedAdjustment>(proposal.clone()) {
+                Ok(p)
+                    if !flags
+                        .adjustment_triggers
+                        .contains(&p.adjustment.trigger_reason) =>
+                {
+                    warn!(
+                        "Ignoring plan adjustment with trigger {} that did not fire for workout id={}",
+                        p.adjustment.trigger_reason.as_str(),
+                        workout.id
+                    );
+                }
+                Ok(p) if p.adjustment.operations.is_empty() => {
+                    warn!(
+                        "Ignoring plan adjustment without operations for workout id={}",
+                        workout.id
+                    );
+                }
+                Ok(p) => {
+                    adjustments::create_adjustment(
+                        pool,
+                        user_id,
+                        Some(workout.id),
+                        &p.adjustment,
+                        &p.explanation,
+                    )
+                    .await?;
+                }
+                Err(e) => warn!("Ignoring malformed plan adjustment: {}", e),
+            }
+        }
+    }
+
+    info!("Analyzed workout id={} for user_id={}", workout.id, user_id);
+
+    Ok(analysis)
+}
+
+/// Local calendar date of an RFC 3339 workout start time.
+fn workout_date(started_at: &str) -> Result<NaiveDate, PlanError> {
+    chrono::DateTime::parse_from_rfc3339(started_at)
+        .map(|dt| dt.date_naive())
+        .map_err(|e| {
+            PlanError::Database(AppError::Internal(format!(
+                "Invalid workout start time '{started_at}': {e}"
+            )))
+        })
+}
+
+/// Where `date` falls in the plan, e.g. "week 2 of 4 (load)". `None` if no
+/// mesocycle covers it.
+fn describe_mesocycle_week(mesocycles: &[Mesocycle], date: NaiveDate) -> Option<String> {
+    let meso = mesocycles.iter().find(|m| {
+        let start = NaiveDate::parse_from_str(&m.start_date, "%Y-%m-%d");
+        let end = NaiveDate::parse_from_str(&m.end_date, "%Y-%m-%d");
+        matches!((start, end), (Ok(start), Ok(end)) if start <= date && date <= end)
+    })?;
+    let start = NaiveDate::parse_from_str(&meso.start_date, "%Y-%m-%d").ok()?;
+    let week = (date - start).num_days() / 7 + 1;
+    let week_type = if week <= meso.load_weeks { "load" } else { "recovery" };
+    Some(format!(
+        "week {week} of {} ({week_type}), mesocycle {} {} / {}",
+        meso.load_weeks + meso.recovery_weeks,
+        meso.sequence_number,
+        meso.phase,
+        meso.focus
+    ))
+}
+
+// ---------------------------------------------------------------------------
+// Orchestration: chat
+// ---------------------------------------------------------------------------
+
+/// The athlete's chat message and Coach Jan's reply, as stored.
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatExchange {
+    pub message: ChatMessage,
+    pub reply: ChatMessage,
+}
+
+/// Store the athlete's message, send it to Claude with their current context
+/// and recent chat history, and store Coach Jan's reply.
+///
+/// Chat calls offer Claude no tools, so nothing said in chat can change the
+/// plan; plan changes only come from analysis proposals the athlete accepts.
+pub async fn chat(
+    client: &ClaudeClient,
+    pool: &SqlitePool,
+    user_id: i64,
+    message: &str,
+    today: NaiveDate,
+) -> Result<ChatExchange, PlanError> {
+    let profile = profiles::get_profile_by_user_id(pool, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("No athlete profile found".to_string()))?;
+    let race_goal = profiles::get_active_race_goal(pool, user_id).await?;
+    let hr_zones = calculate_hr_zones(profile.lthr as u16);
+    let pace_zones = profile.ftpace_m_per_s.map(calculate_pace_zones);
+
+    let today_str = today.format("%Y-%m-%d").to_string();
+    let tomorrow = today
+        .succ_opt()
+        .unwrap_or(today)
+        .format("%Y-%m-%d")
+        .to_string();
+    let current_week = match plans::get_current_plan(pool, user_id).await? {
+        Some((_, mesocycles)) => describe_mesocycle_week(&mesocycles, today),
+        None => None,
+    };
+    let todays_workouts = plans::get_workouts_for_date(pool, user_id, &today_str).await?;
+    let upcoming =
+        plans::get_upcoming_workouts(pool, user_id, &today_str, CHAT_UPCOMING_SESSIONS).await?;
+    let recent_workouts =
+        workouts::list_recent_workouts(pool, user_id, CHAT_RECENT_WORKOUTS).await?;
+    let load = metrics::get_metrics_before(pool, user_id, &tomorrow).await?;
+
+    let context = build_chat_context(&ChatContextInput {
+        profile: &profile,
+        race_goal: race_goal.as_ref(),
+        hr_zones: &hr_zones,
+        pace_zones: pace_zones.as_ref(),
+        today,
+        current_week: current_week.as_deref(),
+        todays_workouts: &todays_workouts,
+        upcoming: &upcoming,
+        recent_workouts: &recent_workouts,
+        metrics: load.as_ref(),
+    });
+
+    let history =
+        chat_db::list_chat_messages(pool, user_id, CHAT_HISTORY_MESSAGES as i64, None).await?;
+
+    let response = client
+        .send(
+            Model::Haiku,
+            Some(&coach_jan_chat_system_prompt(&context)),
+            chat_messages(chat_history_window(&history), message),
+            vec![],
+            CHAT_MAX_TOKENS,
+        )
+        .await?;
+
+    let text = response
+        .text()
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .ok_or_else(|| PlanError::InvalidResponse("No text in chat response".to_string()))?;
+
+    // Both messages are stored only once Claude has replied, so a failed
+    // call leaves no unanswered message in the timeline.
+    let (athlete_message, reply) = chat_db::create_chat_exchange(
+        pool,
+        &CreateChatMessage {
+            user_id,
+            role: "athlete".to_string(),
+            content: message.to_string(),
+            context_type: Some("freeform".to_string()),
+            related_workout_id: None,
+        },
+        &CreateChatMessage {
+            user_id,
+            role: "coach".to_string(),
+            content: text.to_string(),
+            context_type: Some("freeform".to_string()),
+            related_workout_id: None,
+        },
     )
     .await?;
 
-    info!("Created macrocycle id={}", macrocycle.id);
-
-    let mut db_mesocycles = Vec::new();
-    for (i, meso_skel) in skeleton.mesocycles.iter().enumerate() {
-        let (start, end) = meso_dates[i];
-        let db_meso = plans::create_mesocycle(
-            pool,
-            &CreateMesocycle {
-                macrocycle_id: macrocycle.id,
-                sequence_number: meso_skel.sequence_number,
-                phase: meso_skel.phase.clone(),
-                focus: meso_skel.focus.clone(),
-                load_weeks: meso_skel.load_weeks,
-                recovery_weeks: meso_skel.recovery_weeks,
-                target_volume_km: Some(meso_skel.target_volume_km),
-                start_date: start.to_string(),
-                end_date: end.to_string(),
-            },
-        )
-        .await?;
-        db_mesocycles.push(db_meso);
+    info!("Chat reply for user_id={}", user_id);
+
+    Ok(ChatExchange {
+        message: athlete_message,
+        reply,
+    })
+}
+
+/// Turn stored chat history plus the new athlete message into Claude
+/// messages. System messages are skipped, consecutive messages from the same
+/// side are merged, and leading coach messages are dropped so the
+/// conversation starts with the athlete, as the API requires.
+fn chat_messages(history: &[ChatMessage], message: &str) -> Vec<Message> {
+    let mut messages: Vec<Message> = Vec::new();
+    let turns = history
+        .iter()
+        .filter_map(|m| match m.role.as_str() {
+            "athlete" => Some(("user", m.content.as_str())),
+            "coach" => Some(("assistant", m.content.as_str())),
+            _ => None,
+        })
+        .chain(std::iter::once(("user", message)));
+
+    for (role, text) in turns {
+        if messages.is_empty() && role != "user" {
+            continue;
+        }
+        match messages.last_mut() {
+            Some(last) if last.role == role => {
+                last.content.push(ContentBlock::Text {
+                    text: text.to_string(),
+                });
+            }
+            _ if role == "user" => messages.push(Message::user(text)),
+            _ => messages.push(Message::assistant(text)),
+        }
+    }
+
+    messages
+}
+
+// ---------------------------------------------------------------------------
+// Orchestration: transition_mesocycle
+// ---------------------------------------------------------------------------
+
+/// Where CTL stands against the trajectory to the race-day target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CtlVsTarget {
+    OnTrack,
+    Behind,
+    Ahead,
+}
+
+impl CtlVsTarget {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::OnTrack => "on_track",
+            Self::Behind => "behind",
+            Self::Ahead => "ahead",
+        }
     }
+}
 
-    info!(
-        "Created {} mesocycles for macrocycle id={}",
-        db_mesocycles.len(),
-        macrocycle.id
-    );
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NextPhase {
+    Capacity,
+    Utilization,
+    Taper,
+}
 
-    // --- Step 2: Fetch workout history from previous mesocycle ---
-    let first_meso = &db_mesocycles[0];
-    let first_skel = &skeleton.mesocycles[0];
+impl NextPhase {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Capacity => "capacity",
+            Self::Utilization => "utilization",
+            Self::Taper => "taper",
+        }
+    }
+}
 
-    let workout_history = fetch_workout_history_detailed(pool, user_id, first_meso.id).await?;
-    let history_ref = workout_history.as_deref();
+/// How the next mesocycle's weekly volume changes from the skeleton's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VolumeAdjustment {
+    #[serde(rename = "increase_10pct")]
+    Increase10Pct,
+    Maintain,
+    Decrease,
+}
 
-    // --- Step 3: Generate first mesocycle day-by-day plan ---
-    let mesocycle_plan = generate_mesocycle_workouts(
-        client,
-        profile,
-        &first_meso.phase,
-        &first_meso.focus,
-        first_skel.load_weeks,
-        first_skel.recovery_weeks,
-        &first_meso.start_date,
-        &first_meso.end_date,
-        first_skel.target_volume_km,
-        ctl,
-        history_ref,
-    )
-    .await?;
+impl VolumeAdjustment {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Increase10Pct => "increase_10pct",
+            Self::Maintain => "maintain",
+            Self::Decrease => "decrease",
+        }
+    }
 
-    // --- Step 4: Fill details from workout registry ---
-    let hr_zones = calculate_hr_zones(profile.lthr as u16);
-    let pace_zones = profile
-        .ftpace_m_per_s
-        .map(calculate_pace_zones);
+    /// Multiplier for the planned weekly volume. A decrease mirrors the 10%
+    /// step of an increase.
+    pub fn factor(&self) -> f64 {
+        match self {
+            Self::Increase10Pct => 1.1,
+            Self::Maintain => 1.0,
+            Self::Decrease => 0.9,
+        }
+    }
+}
 
-    let filled_workouts = fill_workouts_from_registry(
-        &mesocycle_plan.weeks,
-        &hr_zones,
-        pace_zones.as_ref(),
-    )?;
+/// Coach Jan's review of a finished mesocycle, from the evaluate_mesocycle
+/// tool (PRODUCT_DESIGN §7).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MesocycleEvaluation {
+    pub assessment: String,
+    pub ctl_vs_target: CtlVsTarget,
+    pub next_phase_recommendation: NextPhase,
+    pub next_focus: String,
+    pub volume_adjustment: VolumeAdjustment,
+    /// Whether the athlete advances to the next strength level.
+    pub strength_level_progression: bool,
+    pub coach_message: String,
+}
 
-    // --- Step 5: Add coach notes ---
-    let coach_notes = generate_coach_notes(
-        client,
-        profile,
-        &first_meso.phase,
-        &mesocycle_plan,
-        history_ref,
-    )
-    .await?;
+impl MesocycleEvaluation {
+    /// The evaluation as stored in `mesocycles.evaluation_summary`.
+    pub fn evaluation_summary(&self) -> String {
+        format!(
+            "{}\n\nCTL vs target: {}\n\nNext phase: {}\n\nNext block: {}\n\n\
+             Volume: {}\n\nStrength level: {}",
+            self.assessment,
+            self.ctl_vs_target.as_str(),
+            self.next_phase_recommendation.as_str(),
+            self.next_focus,
+            self.volume_adjustment.as_str(),
+            if self.strength_level_progression {
+                "advance"
+            } else {
+                "hold"
+            }
+        )
+    }
+}
 
-    // Build a lookup map date -> coach_note
-    let notes_map: std::collections::HashMap<String, String> = coach_notes
-        .workout_notes
-        .into_iter()
-        .map(|n| (n.date, n.coach_note))
-        .collect();
+#[derive(Debug, Clone, Serialize)]
+pub struct MesocycleTransition {
+    pub completed: Mesocycle,
+    pub evaluation: MesocycleEvaluation,
+    /// The newly active mesocycle; `None` after the last one.
+    pub next: Option<Mesocycle>,
+    pub workouts: Vec<PlannedWorkout>,
+}
 
-    // --- Step 5: Validate ---
-    let week_plans = build_week_plans(&mesocycle_plan.weeks, &filled_workouts);
-    let validation_ctx = ValidationContext {
-        athlete_ctl: ctl,
-        previous_week_volume_km: None,
-    };
+/// Close out a mesocycle: evaluate it with Claude, generate and validate the
+/// next mesocycle's workouts with the evaluation as context, then store the
+/// evaluation and the next block, mark the block completed and activate the
+/// next one in one transaction.
+///
+/// The mesocycle is claimed while this runs, so a second transition is
+/// refused with a conflict. A failed transition releases the claim and
+/// stores nothing, so it can be retried.
+pub async fn transition_mesocycle(
+    client: &ClaudeClient,
+    pool: &SqlitePool,
+    user_id: i64,
+    mesocycle_id: i64,
+    ctl: f64,
+    today: NaiveDate,
+) -> Result<MesocycleTransition, PlanError> {
+    let meso = plans::get_mesocycle(pool, mesocycle_id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Mesocycle not found".to_string()))?;
+    if meso.status == "completed" {
+        return Err(AppError::Conflict("Mesocycle is already completed".to_string()).into());
+    }
+    let recovery_start = NaiveDate::parse_from_str(&meso.start_date, "%Y-%m-%d")
+        .map(|start| start + chrono::Duration::weeks(meso.load_weeks))
+        .map_err(|e| AppError::Internal(format!("Invalid mesocycle start date: {e}")))?;
+    if today < recovery_start {
+        return Err(AppError::Conflict(format!(
+            "Mesocycle's load weeks run until {recovery_start}"
+        ))
+        .into());
+    }
 
-    let mut all_errors = Vec::new();
-    for week in &week_plans {
-        let errors = validate_week_plan(week, &validation_ctx);
-        all_errors.extend(errors);
+    let workouts = plans::get_planned_workouts(pool, meso.id).await?;
+    if workouts.is_empty() {
+        return Err(AppError::Conflict("Mesocycle has no workouts to evaluate".to_string()).into());
+    }
+    let next = plans::get_next_mesocycle(pool, &meso).await?;
+    if let Some(next) = &next
+        && !plans::get_planned_workouts(pool, next.id).await?.is_empty()
+    {
+        return Err(AppError::Conflict("Next mesocycle already has workouts".to_string()).into());
     }
 
-    // --- Step 6: Retry on validation failure (max 2 retries) ---
-    // For now, we log warnings but persist anyway if validation errors are soft.
-    // Hard failures (e.g., too many intensity sessions) get retried by calling
-    // Claude again with feedback about what was wrong.
-    if !all_errors.is_empty() {
-        warn!(
-            "Validation found {} issues in first mesocycle plan: {:?}",
-            all_errors.len(),
-            all_errors
+    if !plans::claim_mesocycle_transition(pool, meso.id).await? {
+        return Err(
+            AppError::Conflict("Mesocycle is already being transitioned".to_string()).into(),
         );
+    }
+    let result = evaluate_and_plan_next(client, pool, user_id, &meso, &workouts, next, ctl).await;
+    if result.is_err() {
+        plans::release_mesocycle_transition(pool, meso.id).await?;
+    }
+    result
+}
 
-        // Attempt retries for severe issues
-        let severe_count = all_errors
-            .iter()
-            .filter(|e| {
-                matches!(
-                    e,
-                    crate::domain::validation::ValidationError::TooManyIntensitySessions { .. }
-                        | crate::domain::validation::ValidationError::NoRestDay
-                )
-            })
-            .count();
-
-        if severe_count > 0 {
-            info!(
-                "Attempting retry due to {} severe validation errors",
-                severe_count
-            );
+/// The claimed part of `transition_mesocycle`: evaluate `meso`, generate the
+/// next block and store both.
+async fn evaluate_and_plan_next(
+    client: &ClaudeClient,
+    pool: &SqlitePool,
+    user_id: i64,
+    meso: &Mesocycle,
+    workouts: &[PlannedWorkout],
+    next: Option<Mesocycle>,
+    ctl: f64,
+) -> Result<MesocycleTransition, PlanError> {
+    let profile = profiles::get_profile_by_user_id(pool, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("No athlete profile found".to_string()))?;
+    let target_ctl = plans::get_current_macrocycle(pool, user_id)
+        .await?
+        .filter(|mc| mc.id == meso.macrocycle_id)
+        .and_then(|mc| mc.target_ctl);
+    let ctl_at_start = metrics::get_metrics_before(pool, user_id, &meso.start_date)
+        .await?
+        .map(|m| m.ctl);
+
+    // The block as the next one sees it: its previous mesocycle's workouts
+    let history = match &next {
+        Some(next) => fetch_workout_history_detailed(pool, user_id, next.id).await?,
+        None => None,
+    }
+    .unwrap_or_else(|| {
+        format_workout_history_detailed(
+            workouts,
+            &meso.phase,
+            &meso.focus,
+            meso.load_weeks + meso.recovery_weeks,
+        )
+    });
+
+    // --- Step 1: Evaluate the finished block ---
+    let prompt = build_mesocycle_evaluation_context(&MesocycleEvaluationInput {
+        profile: &profile,
+        mesocycle: meso,
+        history: &history,
+        ctl_at_start,
+        current_ctl: ctl,
+        target_ctl,
+        next: next.as_ref(),
+    });
 
-            // Retry up to 2 times
-            let mut retry_result = None;
-            for attempt in 1..=2 {
-                info!("Retry attempt {}/2", attempt);
-
-                let retry_plan = generate_mesocycle_workouts(
-                    client,
-                    profile,
-                    &first_meso.phase,
-                    &first_meso.focus,
-                    first_skel.load_weeks,
-                    first_skel.recovery_weeks,
-                    &first_meso.start_date,
-                    &first_meso.end_date,
-                    first_skel.target_volume_km,
-                    ctl,
-                    history_ref,
-                )
-                .await?;
-
-                let retry_filled = fill_workouts_from_registry(
-                    &retry_plan.weeks,
-                    &hr_zones,
-                    pace_zones.as_ref(),
-                )?;
-
-                let retry_week_plans = build_week_plans(&retry_plan.weeks, &retry_filled);
-                let mut retry_errors = Vec::new();
-                for week in &retry_week_plans {
-                    retry_errors.extend(validate_week_plan(week, &validation_ctx));
-                }
+    let response = client
+        .send(
+            Model::Sonnet,
+            Some(&coach_jan_system_prompt()),
+            vec![Message::user(&prompt)],
+            vec![evaluate_mesocycle_tool()],
+            2048,
+        )
+        .await?;
 
-                let retry_severe = retry_errors
-                    .iter()
-                    .filter(|e| {
-                        matches!(
-                            e,
-                            crate::domain::validation::ValidationError::TooManyIntensitySessions {
-                                ..
-                            } | crate::domain::validation::ValidationError::NoRestDay
-                        )
-                    })
-                    .count();
-
-                if retry_severe == 0 {
-                    info!("Retry attempt {} resolved severe validation errors", attempt);
-                    retry_result = Some((retry_plan, retry_filled));
-                    break;
-                }
+    let (_id, name, input) = response.tool_use().ok_or_else(|| {
+        PlanError::InvalidResponse("No tool_use in mesocycle evaluation response".to_string())
+    })?;
 
-                warn!(
-                    "Retry attempt {} still has {} severe errors",
-                    attempt, retry_severe
-                );
-            }
+    if name != "evaluate_mesocycle" {
+        return Err(PlanError::InvalidResponse(format!(
+            "Expected evaluate_mesocycle tool, got {}",
+            name
+        )));
+    }
 
-            if let Some((retry_plan, retry_filled)) = retry_result {
-                // Use retried results, regenerate coach notes
-                let retry_notes = generate_coach_notes(
-                    client,
-                    profile,
-                    &first_meso.phase,
-                    &retry_plan,
-                    history_ref,
-                )
-                .await?;
-
-                let retry_notes_map: std::collections::HashMap<String, String> = retry_notes
-                    .workout_notes
-                    .into_iter()
-                    .map(|n| (n.date, n.coach_note))
-                    .collect();
-
-                // Persist with retried data
-                let workouts = persist_workouts(
-                    pool,
-                    first_meso.id,
-                    user_id,
-                    &retry_plan.weeks,
-                    &retry_filled,
-                    &retry_notes_map,
-                )
-                .await?;
-
-                return Ok(GeneratedPlan {
-                    macrocycle,
-                    mesocycles: db_mesocycles,
-                    workouts,
-                });
-            }
+    let evaluation: MesocycleEvaluation = serde_json::from_value(input.clone()).map_err(|e| {
+        PlanError::InvalidResponse(format!("Failed to parse mesocycle evaluation: {}", e))
+    })?;
 
-            // If retries didn't resolve, fail the operation
-            let error_descriptions: Vec<String> =
-                all_errors.iter().map(|e| format!("{:?}", e)).collect();
-            return Err(PlanError::ValidationFailed(format!(
-                "Plan has severe validation errors after 2 retries: {}",
-                error_descriptions.join("; ")
-            )));
+    // --- Step 2: Generate the next block with the evaluation as context ---
+    let next_block = match &next {
+        Some(next) => {
+            let next_history = format!(
+                "{}\nEvaluation: {}\nFocus for this block: {}",
+                history.trim_end(),
+                evaluation.assessment,
+                evaluation.next_focus
+            );
+            let baseline = volume_baseline(pool, user_id, &profile, next).await?;
+            let generated = generate_mesocycle(
+                client,
+                pool,
+                user_id,
+                &profile,
+                &mesocycle_outline(next),
+                ctl,
+                Some(&next_history),
+                baseline,
+                evaluation.volume_adjustment,
+            )
+            .await?;
+            let target_volume_km = (evaluation.volume_adjustment != VolumeAdjustment::Maintain
+                && next.target_volume_km.is_some())
+            .then_some(generated.target_volume_km);
+            client.report_stage("Saving workouts");
+            Some(plans::NextBlock {
+                mesocycle_id: next.id,
+                workouts: generated.workouts,
+                target_volume_km,
+            })
         }
-    }
+        None => None,
+    };
 
-    // --- Step 7: Persist planned workouts to DB ---
-    let workouts = persist_workouts(
-        pool,
-        first_meso.id,
-        user_id,
-        &mesocycle_plan.weeks,
-        &filled_workouts,
-        &notes_map,
-    )
-    .await?;
+    // --- Step 3: Close out the block and store the next one ---
+    let next_workouts =
+        plans::complete_mesocycle(pool, meso.id, &evaluation.evaluation_summary(), next_block)
+            .await?;
 
     info!(
-        "Persisted {} planned workouts for mesocycle id={}",
-        workouts.len(),
-        first_meso.id
+        "Completed mesocycle id={} for user_id={}; {} workouts planned for the next",
+        meso.id,
+        user_id,
+        next_workouts.len()
     );
 
-    Ok(GeneratedPlan {
-        macrocycle,
-        mesocycles: db_mesocycles,
-        workouts,
+    let completed = plans::get_mesocycle(pool, meso.id, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Mesocycle not found".to_string()))?;
+    let next = match next {
+        Some(next) => plans::get_mesocycle(pool, next.id, user_id).await?,
+        None => None,
+    };
+
+    Ok(MesocycleTransition {
+        completed,
+        evaluation,
+        next,
+        workouts: next_workouts,
     })
 }
 
+/// A stored mesocycle as the input to generating its workouts.
+fn mesocycle_outline(meso: &Mesocycle) -> CreateMesocycle {
+    CreateMesocycle {
+        macrocycle_id: meso.macrocycle_id,
+        sequence_number: meso.sequence_number,
+        phase: meso.phase.clone(),
+        focus: meso.focus.clone(),
+        load_weeks: meso.load_weeks,
+        recovery_weeks: meso.recovery_weeks,
+        target_volume_km: meso.target_volume_km,
+        start_date: meso.start_date.clone(),
+        end_date: meso.end_date.clone(),
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Helper: calculate mesocycle dates from skeleton
 // ---------------------------------------------------------------------------
@@ -500,7 +1484,7 @@ async fn generate_mesocycle_workouts(
     target_volume_km: f64,
     ctl: f64,
     workout_history: Option<&str>,
-) -> Result<ClaudeMesocyclePlan, PlanError> {
+) -> Result<MesocyclePlanCall, PlanError> {
     let context = build_mesocycle_context(
         profile,
         phase,
@@ -514,20 +1498,45 @@ async fn generate_mesocycle_workouts(
         workout_history,
     );
 
-    let messages = vec![Message::user(&context)];
+    request_mesocycle_plan(client, vec![Message::user(&context)]).await
+}
+
+/// A generated mesocycle plan and the conversation that produced it, ending
+/// with Claude's `generate_mesocycle_plan` call.
+struct MesocyclePlanCall {
+    plan: ClaudeMesocyclePlan,
+    messages: Vec<Message>,
+    tool_use_id: String,
+}
+
+/// Ask Claude to correct `previous`, answering its tool call with `feedback`.
+async fn retry_mesocycle_workouts(
+    client: &ClaudeClient,
+    previous: &MesocyclePlanCall,
+    feedback: &str,
+) -> Result<MesocyclePlanCall, PlanError> {
+    let mut messages = previous.messages.clone();
+    messages.push(Message::user_tool_result(&previous.tool_use_id, feedback));
+    request_mesocycle_plan(client, messages).await
+}
+
+async fn request_mesocycle_plan(
+    client: &ClaudeClient,
+    mut messages: Vec<Message>,
+) -> Result<MesocyclePlanCall, PlanError> {
     let tools = vec![generate_mesocycle_plan_tool()];
 
     let response = client
         .send(
             Model::Sonnet,
             Some(&coach_jan_system_prompt()),
-            messages,
+            messages.clone(),
             tools,
             8192,
         )
         .await?;
 
-    let (_id, name, input) = response.tool_use().ok_or_else(|| {
+    let (id, name, input) = response.tool_use().ok_or_else(|| {
         PlanError::InvalidResponse("No tool_use in mesocycle response".to_string())
     })?;
 
@@ -542,7 +1551,12 @@ async fn generate_mesocycle_workouts(
         PlanError::InvalidResponse(format!("Failed to parse mesocycle plan: {}", e))
     })?;
 
-    Ok(plan)
+    messages.push(Message::assistant_tool_use(id, name, input.clone()));
+    Ok(MesocyclePlanCall {
+        plan,
+        messages,
+        tool_use_id: id.to_string(),
+    })
 }
 
 // ---------------------------------------------------------------------------
@@ -765,6 +1779,7 @@ fn build_week_plans(weeks: &[ClaudeWeek], filled: &[FilledWorkout]) -> Vec<WeekP
                     workout_type: f.workout_type,
                     duration_category: f.duration_category,
                     expected_tss: f.expected_tss,
+                    target_distance_km: f.target_distance_km,
                 });
                 filled_idx += 1;
             }
@@ -788,81 +1803,39 @@ fn build_week_plans(weeks: &[ClaudeWeek], filled: &[FilledWorkout]) -> Vec<WeekP
 }
 
 // ---------------------------------------------------------------------------
-// Helper: persist workouts to DB
+// Helper: planned workouts to store
 // ---------------------------------------------------------------------------
 
-async fn persist_workouts(
-    pool: &SqlitePool,
-    mesocycle_id: i64,
+/// The planned workouts to store for Claude's weeks and their filled details,
+/// with each day's coach note.
+fn planned_workout_inputs(
     user_id: i64,
     weeks: &[ClaudeWeek],
     filled: &[FilledWorkout],
     notes_map: &std::collections::HashMap<String, String>,
-) -> Result<Vec<PlannedWorkout>, PlanError> {
-    let mut persisted = Vec::new();
-    let mut filled_idx = 0;
-
-    for week in weeks {
-        for _day in &week.days {
-            if filled_idx >= filled.len() {
-                break;
-            }
-            let f = &filled[filled_idx];
-            filled_idx += 1;
-
-            let coach_notes = notes_map.get(&f.date).cloned();
-
-            let hr_zones_str = if f.target_hr_zones.is_empty() {
-                None
-            } else {
-                Some(
-                    f.target_hr_zones
-                        .iter()
-                        .map(|z| format!("Z{}", z))
-                        .collect::<Vec<_>>()
-                        .join(", "),
-                )
-            };
-
-            let pace_zones_str = if f.target_pace_zones.is_empty() {
-                None
-            } else {
-                Some(
-                    f.target_pace_zones
-                        .iter()
-                        .map(|z| format!("Z{}", z))
-                        .collect::<Vec<_>>()
-                        .join(", "),
-                )
-            };
-
-            let workout = plans::create_planned_workout(
-                pool,
-                &CreatePlannedWorkout {
-                    mesocycle_id,
-                    user_id,
-                    scheduled_date: f.date.clone(),
-                    workout_type: f.workout_type.as_str().to_string(),
-                    duration_min: f.duration_min.map(|d| d as i64),
-                    duration_category: f
-                        .duration_category
-                        .as_ref()
-                        .map(|dc| dc.as_str().to_string()),
-                    target_hr_zones: hr_zones_str,
-                    target_pace_zones: pace_zones_str,
-                    expected_tss: Some(f.expected_tss),
-                    description: f.description.clone(),
-                    coach_notes,
-                    target_distance_km: f.target_distance_km,
-                },
-            )
-            .await?;
-
-            persisted.push(workout);
-        }
-    }
-
-    Ok(persisted)
+) -> Vec<CreatePlannedWorkout> {
+    let days = weeks.iter().map(|w| w.days.len()).sum();
+    filled
+        .iter()
+        .take(days)
+        .map(|f| CreatePlannedWorkout {
+            mesocycle_id: 0,
+            user_id,
+            scheduled_date: f.date.clone(),
+            workout_type: f.workout_type.as_str().to_string(),
+            duration_min: f.duration_min.map(|d| d as i64),
+            duration_category: f
+                .duration_category
+                .as_ref()
+                .map(|dc| dc.as_str().to_string()),
+            target_hr_zones: format_zone_list(&f.target_hr_zones),
+            target_pace_zones: format_zone_list(&f.target_pace_zones),
+            expected_tss: Some(f.expected_tss),
+            description: f.description.clone(),
+            coach_notes: notes_map.get(&f.date).cloned(),
+            target_distance_km: f.target_distance_km,
+        })
+        .collect()
 }
 
 // ---------------------------------------------------------------------------
@@ -929,6 +1902,63 @@ async fn fetch_workout_history_detailed(
     }
 }
 
+// ---------------------------------------------------------------------------
+// Helper: volume baseline for validation
+// ---------------------------------------------------------------------------
+
+/// The weekly volume a mesocycle's first load week is compared with. After an
+/// earlier mesocycle that is its last load week. The first mesocycle starts
+/// from the athlete's recent volume: measured from uploads when they cover the
+/// last few weeks, taken from the profile otherwise.
+async fn volume_baseline(
+    pool: &SqlitePool,
+    user_id: i64,
+    profile: &AthleteProfile,
+    meso: &Mesocycle,
+) -> Result<Option<f64>, PlanError> {
+    let mesocycles = plans::get_mesocycles(pool, meso.macrocycle_id).await?;
+    if let Some(previous) = mesocycles
+        .iter()
+        .find(|m| m.sequence_number == meso.sequence_number - 1)
+    {
+        let workouts = plans::get_previous_mesocycle_workouts(pool, user_id, meso.id).await?;
+        return Ok(last_load_week_volume_km(previous, &workouts).or(previous.target_volume_km));
+    }
+
+    let start = NaiveDate::parse_from_str(&meso.start_date, "%Y-%m-%d")
+        .map_err(|e| AppError::Internal(format!("Invalid mesocycle start date: {e}")))?;
+    first_block_volume_baseline(pool, user_id, profile, start).await
+}
+
+/// The baseline for a plan's first mesocycle, starting on `start`.
+async fn first_block_volume_baseline(
+    pool: &SqlitePool,
+    user_id: i64,
+    profile: &AthleteProfile,
+    start: NaiveDate,
+) -> Result<Option<f64>, PlanError> {
+    let recent = workouts::get_recent_weekly_volume_km(pool, user_id, start, RECENT_VOLUME_WEEKS)
+        .await?
+        .filter(|km| *km > 0.0);
+    Ok(recent
+        .or((profile.current_weekly_volume_km > 0.0).then_some(profile.current_weekly_volume_km)))
+}
+
+/// Planned distance of a mesocycle's last load week, if any of its workouts
+/// have one.
+fn last_load_week_volume_km(meso: &Mesocycle, workouts: &[PlannedWorkout]) -> Option<f64> {
+    let start = NaiveDate::parse_from_str(&meso.start_date, "%Y-%m-%d").ok()?
+        + chrono::Duration::weeks(meso.load_weeks - 1);
+    let end = start + chrono::Duration::days(7);
+    let (start, end) = (start.to_string(), end.to_string());
+    let distances: Vec<f64> = workouts
+        .iter()
+        .filter(|w| w.scheduled_date >= start && w.scheduled_date < end)
+        .filter_map(|w| w.target_distance_km)
+        .collect();
+    (!distances.is_empty()).then(|| distances.iter().sum())
+}
+
 /// Fetch summary workout history for macrocycle skeleton generation.
 /// Uses the most recent mesocycle from the current active macrocycle.
 async fn fetch_workout_history_summary(
@@ -979,6 +2009,7 @@ async fn fetch_workout_history_summary(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::validation::validate_week_plan;
     use crate::domain::zones::calculate_hr_zones;
     use serde_json::json;
 
@@ -1078,6 +2109,66 @@ mod tests {
         }
     }
 
+    fn revision(version: i64, source: &str, feedback: Option<&str>) -> DraftRevision {
+        DraftRevision {
+            id: version,
+            draft_id: 1,
+            version,
+            skeleton: parse_skeleton(&sample_skeleton_json()).unwrap(),
+            source: source.to_string(),
+            feedback: feedback.map(str::to_string),
+            created_at: "2026-03-01 10:00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn skeleton_revision_replays_history_as_tool_calls() {
+        let revisions = vec![
+            revision(1, "generated", None),
+            revision(2, "edited", None),
+            revision(3, "revised", Some("Shorter first block")),
+        ];
+        let messages =
+            skeleton_revision_messages("context", &revisions, "I can't train on Tuesdays").unwrap();
+
+        // context, then a tool call and its result per version
+        assert_eq!(messages.len(), 7);
+        let roles: Vec<_> = messages.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles[0], "user");
+        for pair in roles[1..].chunks(2) {
+            assert_eq!(pair, ["assistant", "user"]);
+        }
+        match &messages[5].content[0] {
+            ContentBlock::ToolUse { id, name, input } => {
+                assert_eq!(id, "skeleton_v3");
+                assert_eq!(name, "generate_macrocycle_skeleton");
+                assert_eq!(input["mesocycles"][0]["phase"], "capacity");
+            }
+            other => panic!("Expected tool_use, got: {:?}", other),
+        }
+        let results: Vec<_> = messages[2..]
+            .iter()
+            .step_by(2)
+            .map(|m| match &m.content[0] {
+                ContentBlock::ToolResult {
+                    tool_use_id,
+                    content,
+                } => (tool_use_id.as_str(), content.as_str()),
+                other => panic!("Expected tool_result, got: {:?}", other),
+            })
+            .collect();
+        assert_eq!(results[0].0, "skeleton_v1");
+        assert!(results[0].1.contains("edited this skeleton by hand"));
+        assert!(results[1].1.contains("Shorter first block"));
+        assert_eq!(results[2].0, "skeleton_v3");
+        assert!(results[2].1.contains("I can't train on Tuesdays"));
+    }
+
+    #[test]
+    fn skeleton_revision_needs_a_skeleton() {
+        assert!(skeleton_revision_messages("context", &[], "feedback").is_err());
+    }
+
     #[test]
     fn parse_mesocycle_plan_from_json() {
         let input = sample_mesocycle_plan_json();
@@ -1418,6 +2509,40 @@ mod tests {
         }
     }
 
+    #[test]
+    fn repairs_are_applied_to_claude_plan() {
+        let mut plan: ClaudeMesocyclePlan =
+            serde_json::from_value(sample_mesocycle_plan_json()).unwrap();
+        plan.weeks[0].days[6].target_distance_km = Some(20.0);
+        let repairs = vec![
+            Repair::DowngradeToEasy {
+                week_number: 1,
+                date: "2026-03-03".to_string(),
+                from: WorkoutType::Vo2maxIntervals,
+            },
+            Repair::AddRestDay {
+                week_number: 1,
+                date: "2026-03-07".to_string(),
+                from: WorkoutType::RecoveryRun,
+            },
+            Repair::CapVolume {
+                week_number: 1,
+                date: "2026-03-02".to_string(),
+                from_km: 40.0,
+                to_km: 33.0,
+            },
+        ];
+        apply_repairs(&mut plan, &repairs);
+
+        let days = &plan.weeks[0].days;
+        assert_eq!(days[1].workout_type, "easy_run");
+        assert_eq!(days[1].duration_category.as_deref(), Some("short"));
+        assert_eq!(days[5].workout_type, "rest");
+        assert_eq!(days[5].duration_category, None);
+        assert_eq!(plan.weeks[0].target_volume_km, 33.0);
+        assert_eq!(days[6].target_distance_km, Some(16.5));
+    }
+
     // -----------------------------------------------------------------------
     // PlanError display tests
     // -----------------------------------------------------------------------
@@ -1543,4 +2668,185 @@ mod tests {
 
         assert_eq!(week_plans[0].week_type, WeekType::Recovery);
     }
+
+    // -----------------------------------------------------------------------
+    // Workout analysis
+    // -----------------------------------------------------------------------
+
+    fn test_mesocycle(sequence_number: i64, start_date: &str, end_date: &str) -> Mesocycle {
+        Mesocycle {
+            id: sequence_number,
+            macrocycle_id: 1,
+            sequence_number,
+            phase: "capacity".to_string(),
+            focus: "aerobic_capacity".to_string(),
+            load_weeks: 3,
+            recovery_weeks: 1,
+            target_volume_km: Some(45.0),
+            start_date: start_date.to_string(),
+            end_date: end_date.to_string(),
+            status: "active".to_string(),
+            evaluation_summary: None,
+            transition_due_at: None,
+            created_at: "2026-01-01".to_string(),
+        }
+    }
+
+    #[test]
+    fn mesocycle_week_is_described_from_dates() {
+        let mesocycles = vec![
+            test_mesocycle(1, "2026-03-02", "2026-03-29"),
+            test_mesocycle(2, "2026-03-30", "2026-04-26"),
+        ];
+        let date = |s| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+
+        assert_eq!(
+            describe_mesocycle_week(&mesocycles, date("2026-03-03")).as_deref(),
+            Some("week 1 of 4 (load), mesocycle 1 capacity / aerobic_capacity")
+        );
+        assert_eq!(
+            describe_mesocycle_week(&mesocycles, date("2026-03-25")).as_deref(),
+            Some("week 4 of 4 (recovery), mesocycle 1 capacity / aerobic_capacity")
+        );
+        assert!(describe_mesocycle_week(&mesocycles, date("2026-04-01"))
+            .unwrap()
+            .starts_with("week 1 of 4 (load), mesocycle 2"));
+        assert_eq!(describe_mesocycle_week(&mesocycles, date("2026-05-01")), None);
+    }
+
+    #[test]
+    fn baseline_is_the_last_load_weeks_planned_distance() {
+        let planned = |id, date: &str, km| PlannedWorkout {
+            id,
+            mesocycle_id: 1,
+            user_id: 1,
+            scheduled_date: date.to_string(),
+            workout_type: "easy_run".to_string(),
+            duration_min: Some(45),
+            duration_category: Some("medium".to_string()),
+            target_hr_zones: None,
+            target_pace_zones: None,
+            expected_tss: Some(45.0),
+            description: None,
+            coach_notes: None,
+            target_distance_km: km,
+            is_completed: 0,
+            completed_workout_id: None,
+            rpe: None,
+            athlete_notes: None,
+            actual_duration_min: None,
+            completed_at: None,
+            created_at: "2026-01-01".to_string(),
+        };
+        let meso = test_mesocycle(1, "2026-03-02", "2026-03-29");
+
+        // Week 3 (2026-03-16 to 2026-03-22) is the last load week
+        let workouts = vec![
+            planned(1, "2026-03-15", Some(30.0)),
+            planned(2, "2026-03-16", Some(10.0)),
+            planned(3, "2026-03-18", None),
+            planned(4, "2026-03-22", Some(22.5)),
+            planned(5, "2026-03-23", Some(8.0)),
+        ];
+        assert_eq!(last_load_week_volume_km(&meso, &workouts), Some(32.5));
+        assert_eq!(last_load_week_volume_km(&meso, &workouts[2..3]), None);
+    }
+
+    #[test]
+    fn mesocycle_evaluation_summary_joins_sections() {
+        let evaluation: MesocycleEvaluation = serde_json::from_value(json!({
+            "assessment": "Consistent block.",
+            "ctl_vs_target": "behind",
+            "next_phase_recommendation": "utilization",
+            "next_focus": "Steadier tempos.",
+            "volume_adjustment": "increase_10pct",
+            "strength_level_progression": true,
+            "coach_message": "We're on track."
+        }))
+        .unwrap();
+        assert_eq!(evaluation.ctl_vs_target, CtlVsTarget::Behind);
+        assert_eq!(
+            evaluation.volume_adjustment,
+            VolumeAdjustment::Increase10Pct
+        );
+        assert_eq!(evaluation.volume_adjustment.factor(), 1.1);
+        assert_eq!(
+            evaluation.evaluation_summary(),
+            "Consistent block.\n\nCTL vs target: behind\n\nNext phase: utilization\n\n\
+             Next block: Steadier tempos.\n\nVolume: increase_10pct\n\nStrength level: advance"
+        );
+
+        let unknown = serde_json::from_value::<MesocycleEvaluation>(json!({
+            "assessment": "Consistent block.",
+            "ctl_vs_target": "on_track",
+            "next_phase_recommendation": "base",
+            "next_focus": "More of the same.",
+            "volume_adjustment": "maintain",
+            "strength_level_progression": false,
+            "coach_message": "We're on track."
+        }));
+        assert!(unknown.is_err());
+    }
+
+    #[test]
+    fn workout_analysis_parses_and_joins_commentary() {
+        let analysis: WorkoutAnalysis = serde_json::from_value(json!({
+            "summary": "Easy 45 minutes.",
+            "training_effect_commentary": "Aerobic base.",
+            "compliance_commentary": "On target.",
+            "load_commentary": "TSB positive.",
+            "coach_message": "Nice work."
+        }))
+        .unwrap();
+        assert_eq!(analysis.commentary(), "Aerobic base.\n\nOn target.\n\nTSB positive.");
+    }
+
+    // -----------------------------------------------------------------------
+    // Chat message conversion
+    // -----------------------------------------------------------------------
+
+    fn chat_message(role: &str, content: &str) -> ChatMessage {
+        ChatMessage {
+            id: 1,
+            user_id: 1,
+            role: role.to_string(),
+            content: content.to_string(),
+            context_type: None,
+            related_workout_id: None,
+            created_at: "2026-03-03 12:00:00".to_string(),
+        }
+    }
+
+    fn texts(message: &Message) -> Vec<&str> {
+        message
+            .content
+            .iter()
+            .filter_map(|block| match block {
+                ContentBlock::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn chat_messages_alternate_and_start_with_the_athlete() {
+        let history = [
+            chat_message("coach", "Nice tempo run yesterday."),
+            chat_message("athlete", "How hard should Thursday be?"),
+            chat_message("coach", "Easy, Zone 2."),
+            chat_message("system", "Plan updated"),
+            chat_message("athlete", "Thanks!"),
+        ];
+        let messages = chat_messages(&history, "And Saturday?");
+
+        let roles: Vec<_> = messages.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, ["user", "assistant", "user"]);
+        assert_eq!(texts(&messages[0]), ["How hard should Thursday be?"]);
+        assert_eq!(texts(&messages[1]), ["Easy, Zone 2."]);
+        assert_eq!(texts(&messages[2]), ["Thanks!", "And Saturday?"]);
+
+        let messages = chat_messages(&[], "Hello coach");
+        assert_eq!(messages.len(), 1);
+        assert_eq!(texts(&messages[0]), ["Hello coach"]);
+    }
 }
@@ -1,5 +1,7 @@
 pub mod client;
 pub mod context;
 pub mod handlers;
+pub mod progress;
 pub mod prompts;
+pub mod stream;
 pub mod tools;
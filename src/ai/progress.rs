@@ -0,0 +1,106 @@
+//! Progress reporting for long-running Claude calls.
+//!
+//! A [`ClaudeClient`](crate::ai::client::ClaudeClient) created with
+//! `with_progress` streams its responses and forwards the deltas here;
+//! orchestration code adds stage messages between calls. The API layer relays
+//! everything to the browser as server-sent events.
+
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::mpsc;
+
+use crate::ai::stream::StreamEvent;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Progress {
+    /// A step of a multi-call operation has started.
+    Stage { message: String },
+    /// Streamed reply text.
+    Text { text: String },
+    /// A tool call has started; its input follows as `tool_input` fragments.
+    ToolStart { name: String },
+    /// A fragment of the tool input JSON being generated.
+    ToolInput { partial_json: String },
+    /// The operation finished; `result` is the endpoint's usual JSON body.
+    Done { result: Value },
+    /// The operation failed.
+    Error { status: u16, message: String },
+}
+
+impl Progress {
+    /// The SSE event name this update is sent under.
+    pub fn event_name(&self) -> &'static str {
+        match self {
+            Self::Stage { .. } => "stage",
+            Self::Text { .. } => "text",
+            Self::ToolStart { .. } => "tool_start",
+            Self::ToolInput { .. } => "tool_input",
+            Self::Done { .. } => "done",
+            Self::Error { .. } => "error",
+        }
+    }
+
+    /// The update to relay for a stream event, if any.
+    pub fn from_stream_event(event: &StreamEvent) -> Option<Self> {
+        match event {
+            StreamEvent::TextDelta(text) => Some(Self::Text { text: text.clone() }),
+            StreamEvent::ToolUseStart { name, .. } => Some(Self::ToolStart { name: name.clone() }),
+            StreamEvent::InputJsonDelta(partial_json) if !partial_json.is_empty() => {
+                Some(Self::ToolInput {
+                    partial_json: partial_json.clone(),
+                })
+            }
+            StreamEvent::InputJsonDelta(_) | StreamEvent::MessageStop => None,
+        }
+    }
+}
+
+/// Sending half of a progress channel. Sends never block; updates sent after
+/// the browser disconnected are dropped while the operation runs to the end.
+pub type ProgressSender = mpsc::UnboundedSender<Progress>;
+
+pub type ProgressReceiver = mpsc::UnboundedReceiver<Progress>;
+
+pub fn channel() -> (ProgressSender, ProgressReceiver) {
+    mpsc::unbounded_channel()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progress_serializes_with_type_tag() {
+        let json = serde_json::to_value(Progress::ToolInput {
+            partial_json: "{\"weeks\"".to_string(),
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "type": "tool_input", "partial_json": "{\"weeks\"" })
+        );
+        assert_eq!(
+            Progress::Stage {
+                message: "x".to_string()
+            }
+            .event_name(),
+            "stage"
+        );
+    }
+
+    #[test]
+    fn stream_events_map_to_progress() {
+        assert_eq!(
+            Progress::from_stream_event(&StreamEvent::TextDelta("Hi".to_string())),
+            Some(Progress::Text {
+                text: "Hi".to_string()
+            })
+        );
+        assert_eq!(
+            Progress::from_stream_event(&StreamEvent::InputJsonDelta(String::new())),
+            None
+        );
+        assert_eq!(Progress::from_stream_event(&StreamEvent::MessageStop), None);
+    }
+}
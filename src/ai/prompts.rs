@@ -61,6 +61,16 @@ When generating plans, you will receive the athlete's:
 Use ALL of this data to make decisions. A beginner with a soccer background (high anaerobic capacity) needs different training than a beginner from swimming (good aerobic base). An athlete with CTL of 20 cannot handle the same volume as one with CTL of 50.
 "#;
 
+const CHAT_GUIDELINES: &str = r#"
+
+## Chat Guidelines
+You are chatting with the athlete. Reply in plain conversational text (Markdown is fine), usually a few short paragraphs.
+- Stay on running: training, physiology, recovery, racing, and the athlete's plan. Steer other topics back to running.
+- You cannot change the training plan from chat. If the athlete asks to move, skip, swap or change sessions, discuss the options, but never claim to have changed anything. Plan changes are proposed after workout analysis and the athlete accepts them in the plan view.
+- Ground answers in the athlete context below. Do not invent sessions, zones or numbers that are not there.
+- For pain or injury symptoms, advise easing off and seeing a medical professional.
+"#;
+
 pub fn coach_jan_system_prompt() -> String {
     let mut workout_section = String::from("## Workout Type Reference\n\n");
 
@@ -123,6 +133,17 @@ pub fn coach_jan_system_prompt() -> String {
     )
 }
 
+/// System prompt for free-form chat: the Coach Jan persona, chat guardrails
+/// and the athlete's current context.
+pub fn coach_jan_chat_system_prompt(athlete_context: &str) -> String {
+    format!(
+        "{}{}\n## Athlete Context\n{}",
+        coach_jan_system_prompt(),
+        CHAT_GUIDELINES,
+        athlete_context,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,4 +187,12 @@ mod tests {
         assert!(prompt.contains("LTHR"));
         assert!(prompt.contains("FTPace"));
     }
+
+    #[test]
+    fn chat_prompt_contains_guardrails_and_context() {
+        let prompt = coach_jan_chat_system_prompt("Athlete: Test Runner");
+        assert!(prompt.starts_with(&coach_jan_system_prompt()));
+        assert!(prompt.contains("You cannot change the training plan from chat"));
+        assert!(prompt.ends_with("## Athlete Context\nAthlete: Test Runner"));
+    }
 }
@@ -0,0 +1,510 @@
+//! Parsing of the Messages API server-sent event stream.
+//!
+//! With `"stream": true` the API answers with `text/event-stream`: a
+//! `message_start`, then per content block a `content_block_start`, a run of
+//! `content_block_delta`s and a `content_block_stop`, then `message_delta`
+//! and `message_stop`. [`SseParser`] splits the raw bytes into events and
+//! [`StreamAccumulator`] folds them back into a [`ClaudeResponse`].
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::ai::client::{ClaudeError, ClaudeResponse, ContentBlock, Usage};
+
+/// Incremental output surfaced while a streamed response is being received.
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamEvent {
+    /// A piece of a text block.
+    TextDelta(String),
+    /// A tool_use block has started; its input follows as JSON fragments.
+    ToolUseStart { id: String, name: String },
+    /// A fragment of the current tool_use block's input JSON.
+    InputJsonDelta(String),
+    /// The message is complete.
+    MessageStop,
+}
+
+// ---------------------------------------------------------------------------
+// SSE framing
+// ---------------------------------------------------------------------------
+
+/// One dispatched server-sent event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SseEvent {
+    pub event: String,
+    pub data: String,
+}
+
+/// Splits a byte stream into server-sent events. Chunks may end anywhere,
+/// including inside a line or a multi-byte character.
+#[derive(Debug, Default)]
+pub struct SseParser {
+    buffer: Vec<u8>,
+    event: Option<String>,
+    data: Vec<String>,
+}
+
+impl SseParser {
+    /// Feed the next chunk and return the events it completed.
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(chunk);
+
+        let mut events = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            if let Some(event) = self.line(line.trim_end_matches(['\n', '\r'])) {
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    /// Flush an event left unterminated when the stream ended.
+    pub fn finish(&mut self) -> Option<SseEvent> {
+        if !self.buffer.is_empty() {
+            let line = String::from_utf8_lossy(&std::mem::take(&mut self.buffer)).into_owned();
+            self.line(line.trim_end_matches('\r'));
+        }
+        self.dispatch()
+    }
+
+    fn line(&mut self, line: &str) -> Option<SseEvent> {
+        if line.is_empty() {
+            return self.dispatch();
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+        match field {
+            "event" => self.event = Some(value.to_string()),
+            "data" => self.data.push(value.to_string()),
+            _ => {}
+        }
+        None
+    }
+
+    fn dispatch(&mut self) -> Option<SseEvent> {
+        let event = self.event.take();
+        if self.data.is_empty() {
+            return None;
+        }
+        let data = std::mem::take(&mut self.data).join("\n");
+        Some(SseEvent {
+            event: event.unwrap_or_else(|| "message".to_string()),
+            data,
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Event payloads
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Payload {
+    MessageStart {
+        message: MessageStart,
+    },
+    ContentBlockStart {
+        index: usize,
+        content_block: BlockStart,
+    },
+    ContentBlockDelta {
+        index: usize,
+        delta: ContentDelta,
+    },
+    ContentBlockStop,
+    MessageDelta {
+        delta: MessageDelta,
+        #[serde(default)]
+        usage: Option<DeltaUsage>,
+    },
+    MessageStop,
+    Ping,
+    Error {
+        error: ErrorBody,
+    },
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageStart {
+    id: String,
+    model: String,
+    #[serde(default)]
+    usage: Option<DeltaUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum BlockStart {
+    Text {
+        #[serde(default)]
+        text: String,
+    },
+    ToolUse {
+        id: String,
+        name: String,
+    },
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentDelta {
+    TextDelta {
+        text: String,
+    },
+    InputJsonDelta {
+        partial_json: String,
+    },
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageDelta {
+    stop_reason: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct DeltaUsage {
+    input_tokens: Option<u32>,
+    output_tokens: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ErrorBody {
+    #[serde(rename = "type")]
+    kind: String,
+    message: String,
+}
+
+// ---------------------------------------------------------------------------
+// Accumulator
+// ---------------------------------------------------------------------------
+
+#[derive(Debug)]
+enum PartialBlock {
+    Text(String),
+    ToolUse {
+        id: String,
+        name: String,
+        json: String,
+    },
+    /// A block type we do not use (e.g. thinking); dropped from the result.
+    Skipped,
+}
+
+/// Folds stream events into the complete response.
+#[derive(Debug, Default)]
+pub struct StreamAccumulator {
+    id: String,
+    model: String,
+    stop_reason: Option<String>,
+    usage: Usage,
+    blocks: Vec<PartialBlock>,
+    stopped: bool,
+}
+
+impl StreamAccumulator {
+    /// Apply one event. Returns the incremental output it carried, if any.
+    pub fn apply(&mut self, event: &SseEvent) -> Result<Option<StreamEvent>, ClaudeError> {
+        let payload: Payload = serde_json::from_str(&event.data).map_err(|e| {
+            ClaudeError::Stream(format!("Malformed '{}' event: {}", event.event, e))
+        })?;
+
+        match payload {
+            Payload::MessageStart { message } => {
+                self.id = message.id;
+                self.model = message.model;
+                self.apply_usage(message.usage);
+                Ok(None)
+            }
+            Payload::ContentBlockStart {
+                index,
+                content_block,
+            } => {
+                let (block, event) = match content_block {
+                    BlockStart::Text { text } => {
+                        let event =
+                            (!text.is_empty()).then(|| StreamEvent::TextDelta(text.clone()));
+                        (PartialBlock::Text(text), event)
+                    }
+                    BlockStart::ToolUse { id, name } => {
+                        let event = StreamEvent::ToolUseStart {
+                            id: id.clone(),
+                            name: name.clone(),
+                        };
+                        let block = PartialBlock::ToolUse {
+                            id,
+                            name,
+                            json: String::new(),
+                        };
+                        (block, Some(event))
+                    }
+                    BlockStart::Unknown => (PartialBlock::Skipped, None),
+                };
+                if self.blocks.len() <= index {
+                    self.blocks.resize_with(index + 1, || PartialBlock::Skipped);
+                }
+                self.blocks[index] = block;
+                Ok(event)
+            }
+            Payload::ContentBlockDelta { index, delta } => {
+                let block = self.blocks.get_mut(index).ok_or_else(|| {
+                    ClaudeError::Stream(format!("Delta for unknown content block {index}"))
+                })?;
+                match (block, delta) {
+                    (PartialBlock::Text(text), ContentDelta::TextDelta { text: delta }) => {
+                        text.push_str(&delta);
+                        Ok(Some(StreamEvent::TextDelta(delta)))
+                    }
+                    (
+                        PartialBlock::ToolUse { json, .. },
+                        ContentDelta::InputJsonDelta { partial_json },
+                    ) => {
+                        json.push_str(&partial_json);
+                        Ok(Some(StreamEvent::InputJsonDelta(partial_json)))
+                    }
+                    _ => Ok(None),
+                }
+            }
+            Payload::ContentBlockStop | Payload::Ping | Payload::Unknown => Ok(None),
+            Payload::MessageDelta { delta, usage } => {
+                if delta.stop_reason.is_some() {
+                    self.stop_reason = delta.stop_reason;
+                }
+                self.apply_usage(usage);
+                Ok(None)
+            }
+            Payload::MessageStop => {
+                self.stopped = true;
+                Ok(Some(StreamEvent::MessageStop))
+            }
+            Payload::Error { error } => Err(ClaudeError::Stream(format!(
+                "{}: {}",
+                error.kind, error.message
+            ))),
+        }
+    }
+
+    /// Whether `message_stop` has been received.
+    pub fn is_complete(&self) -> bool {
+        self.stopped
+    }
+
+    /// The assembled response. Fails if the stream ended before
+    /// `message_stop` or a tool input is not valid JSON.
+    pub fn finish(self) -> Result<ClaudeResponse, ClaudeError> {
+        if !self.stopped {
+            return Err(ClaudeError::Stream(
+                "Stream ended before message_stop".to_string(),
+            ));
+        }
+
+        let mut content = Vec::with_capacity(self.blocks.len());
+        for block in self.blocks {
+            match block {
+                PartialBlock::Text(text) => content.push(ContentBlock::Text { text }),
+                PartialBlock::ToolUse { id, name, json } => {
+                    // A tool called without arguments streams no input at all.
+                    let input = if json.trim().is_empty() {
+                        Value::Object(Default::default())
+                    } else {
+                        serde_json::from_str(&json).map_err(|e| {
+                            ClaudeError::InvalidResponse(format!(
+                                "Invalid input JSON for tool {name}: {e}"
+                            ))
+                        })?
+                    };
+                    content.push(ContentBlock::ToolUse { id, name, input });
+                }
+                PartialBlock::Skipped => {}
+            }
+        }
+
+        Ok(ClaudeResponse {
+            id: self.id,
+            content,
+            model: self.model,
+            stop_reason: self.stop_reason.unwrap_or_default(),
+            usage: self.usage,
+        })
+    }
+
+    fn apply_usage(&mut self, usage: Option<DeltaUsage>) {
+        let usage = usage.unwrap_or_default();
+        if let Some(input) = usage.input_tokens {
+            self.usage.input_tokens = input;
+        }
+        if let Some(output) = usage.output_tokens {
+            self.usage.output_tokens = output;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEXT_STREAM: &str = include_str!("../../tests/fixtures/claude_stream_text.sse");
+    const TOOL_STREAM: &str = include_str!("../../tests/fixtures/claude_stream_tool_use.sse");
+
+    /// Replay `raw` through the parser in chunks of `size` bytes.
+    fn replay(raw: &str, size: usize) -> (Result<ClaudeResponse, ClaudeError>, Vec<StreamEvent>) {
+        let mut parser = SseParser::default();
+        let mut accumulator = StreamAccumulator::default();
+        let mut events = Vec::new();
+
+        let mut sse = Vec::new();
+        for chunk in raw.as_bytes().chunks(size) {
+            sse.extend(parser.push(chunk));
+        }
+        sse.extend(parser.finish());
+
+        for event in &sse {
+            match accumulator.apply(event) {
+                Ok(Some(e)) => events.push(e),
+                Ok(None) => {}
+                Err(e) => return (Err(e), events),
+            }
+        }
+        (accumulator.finish(), events)
+    }
+
+    #[test]
+    fn parser_handles_fields_comments_and_crlf() {
+        let mut parser = SseParser::default();
+        let events =
+            parser.push(b": comment\r\nevent: ping\r\ndata: {\"a\":\r\ndata: 1}\r\n\r\ndata:x\n\n");
+        assert_eq!(
+            events,
+            vec![
+                SseEvent {
+                    event: "ping".to_string(),
+                    data: "{\"a\":\n1}".to_string(),
+                },
+                SseEvent {
+                    event: "message".to_string(),
+                    data: "x".to_string(),
+                },
+            ]
+        );
+        assert_eq!(parser.finish(), None);
+    }
+
+    #[test]
+    fn parser_flushes_an_unterminated_event() {
+        let mut parser = SseParser::default();
+        assert!(parser.push(b"event: message_stop\ndata: {}").is_empty());
+        assert_eq!(
+            parser.finish(),
+            Some(SseEvent {
+                event: "message_stop".to_string(),
+                data: "{}".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn text_stream_is_assembled_regardless_of_chunking() {
+        for size in [1, 3, 7, 64, TEXT_STREAM.len()] {
+            let (response, events) = replay(TEXT_STREAM, size);
+            let response = response.expect("text stream should assemble");
+
+            assert_eq!(response.id, "msg_stream_text");
+            assert_eq!(response.stop_reason, "end_turn");
+            assert_eq!(response.usage.input_tokens, 412);
+            assert_eq!(response.usage.output_tokens, 27);
+            assert_eq!(
+                response.text(),
+                Some("Easy runs build your aerobic base — keep them truly easy.")
+            );
+
+            let text: String = events
+                .iter()
+                .filter_map(|e| match e {
+                    StreamEvent::TextDelta(t) => Some(t.as_str()),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(text, response.text().unwrap());
+            assert_eq!(events.last(), Some(&StreamEvent::MessageStop));
+        }
+    }
+
+    #[test]
+    fn tool_use_stream_is_assembled_regardless_of_chunking() {
+        for size in [1, 5, 50, TOOL_STREAM.len()] {
+            let (response, events) = replay(TOOL_STREAM, size);
+            let response = response.expect("tool stream should assemble");
+
+            assert_eq!(response.stop_reason, "tool_use");
+            let (id, name, input) = response.tool_use().expect("tool_use block");
+            assert_eq!(id, "toolu_stream_1");
+            assert_eq!(name, "add_coach_notes");
+            assert_eq!(input["workout_notes"][0]["date"], "2026-03-02");
+            assert_eq!(input["workout_notes"].as_array().unwrap().len(), 2);
+            // The leading text block is kept alongside the tool call.
+            assert_eq!(response.text(), Some("Adding the notes now."));
+
+            assert!(events.contains(&StreamEvent::ToolUseStart {
+                id: "toolu_stream_1".to_string(),
+                name: "add_coach_notes".to_string(),
+            }));
+            let json: String = events
+                .iter()
+                .filter_map(|e| match e {
+                    StreamEvent::InputJsonDelta(j) => Some(j.as_str()),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), *input);
+        }
+    }
+
+    #[test]
+    fn truncated_stream_is_an_error() {
+        let cut = TEXT_STREAM.find("event: message_stop").unwrap();
+        let (response, _) = replay(&TEXT_STREAM[..cut], 16);
+        assert!(matches!(response, Err(ClaudeError::Stream(_))));
+    }
+
+    #[test]
+    fn error_event_is_surfaced() {
+        let raw = "event: message_start\ndata: {\"type\":\"message_start\",\"message\":{\"id\":\"m\",\"model\":\"x\"}}\n\n\
+                   event: error\ndata: {\"type\":\"error\",\"error\":{\"type\":\"overloaded_error\",\"message\":\"Overloaded\"}}\n\n";
+        let (response, _) = replay(raw, 10);
+        match response {
+            Err(ClaudeError::Stream(message)) => {
+                assert_eq!(message, "overloaded_error: Overloaded")
+            }
+            other => panic!("expected stream error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn tool_without_input_gets_an_empty_object() {
+        let raw = concat!(
+            "event: message_start\ndata: {\"type\":\"message_start\",\"message\":{\"id\":\"m\",\"model\":\"x\",\"usage\":{\"input_tokens\":1,\"output_tokens\":1}}}\n\n",
+            "event: content_block_start\ndata: {\"type\":\"content_block_start\",\"index\":0,\"content_block\":{\"type\":\"tool_use\",\"id\":\"t\",\"name\":\"noop\",\"input\":{}}}\n\n",
+            "event: content_block_stop\ndata: {\"type\":\"content_block_stop\",\"index\":0}\n\n",
+            "event: message_delta\ndata: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"tool_use\"},\"usage\":{\"output_tokens\":3}}\n\n",
+            "event: message_stop\ndata: {\"type\":\"message_stop\"}\n\n",
+        );
+        let (response, _) = replay(raw, 32);
+        let response = response.unwrap();
+        assert_eq!(response.tool_use().unwrap().2, &serde_json::json!({}));
+        assert_eq!(response.usage.output_tokens, 3);
+    }
+}
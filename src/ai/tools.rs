@@ -1,5 +1,7 @@
 use serde_json::json;
 use crate::ai::client::Tool;
+use crate::domain::adjustments::{AdjustmentOperation, MAX_EXTENSION_WEEKS};
+use crate::domain::compliance::TriggerReason;
 
 /// Tool schema for generating a macrocycle skeleton.
 /// Claude uses this to output the high-level periodization plan.
@@ -156,6 +158,157 @@ pub fn add_coach_notes_tool() -> Tool {
     }
 }
 
+/// Tool schema for post-workout analysis.
+/// Claude uses this to explain a completed workout against its prescription.
+pub fn analyze_workout_tool() -> Tool {
+    Tool {
+        name: "analyze_workout".to_string(),
+        description: "Analyze a completed workout: what it trained, how it compared with the prescribed session, and what it did to the athlete's training load.".to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "summary": {
+                    "type": "string",
+                    "description": "1-2 sentence summary of the session"
+                },
+                "training_effect_commentary": {
+                    "type": "string",
+                    "description": "What the session trained, based on the classification and effect scores"
+                },
+                "compliance_commentary": {
+                    "type": "string",
+                    "description": "How the session compared with the prescribed workout"
+                },
+                "load_commentary": {
+                    "type": "string",
+                    "description": "What the session did to CTL, ATL and TSB"
+                },
+                "coach_message": {
+                    "type": "string",
+                    "description": "Coach Jan's message to the athlete, using \"we\" language"
+                },
+                "plan_adjustment": {
+                    "type": "object",
+                    "description": "Optional change to the upcoming plan. Only when adjustment_triggers is not empty; the athlete must accept it before anything changes.",
+                    "properties": {
+                        "trigger_reason": {
+                            "type": "string",
+                            "enum": TriggerReason::all(),
+                            "description": "The adjustment trigger this answers; must be one of adjustment_triggers"
+                        },
+                        "operations": {
+                            "type": "array",
+                            "minItems": 1,
+                            "description": "Changes to apply, in order",
+                            "items": {
+                                "type": "object",
+                                "properties": {
+                                    "type": {
+                                        "type": "string",
+                                        "enum": AdjustmentOperation::all_types()
+                                    },
+                                    "workout_id": {
+                                        "type": "integer",
+                                        "description": "Upcoming session to change (swap_workout, reduce_intensity, increase_intensity, skip_workout)"
+                                    },
+                                    "new_workout_type": {
+                                        "type": "string",
+                                        "description": "Replacement workout type (swap_workout, reduce_intensity, increase_intensity)"
+                                    },
+                                    "duration_category": {
+                                        "type": "string",
+                                        "enum": ["short", "medium", "long"]
+                                    },
+                                    "date": {
+                                        "type": "string",
+                                        "description": "YYYY-MM-DD of the day to turn into rest (add_recovery_day)"
+                                    },
+                                    "mesocycle_id": {
+                                        "type": "integer",
+                                        "description": "Mesocycle to extend (extend_mesocycle)"
+                                    },
+                                    "weeks": {
+                                        "type": "integer",
+                                        "minimum": 1,
+                                        "maximum": MAX_EXTENSION_WEEKS,
+                                        "description": "Load weeks to add (extend_mesocycle)"
+                                    }
+                                },
+                                "required": ["type"]
+                            }
+                        },
+                        "explanation": {
+                            "type": "string",
+                            "description": "Why the plan should change, addressed to the athlete"
+                        }
+                    },
+                    "required": ["trigger_reason", "operations", "explanation"]
+                }
+            },
+            "required": [
+                "summary",
+                "training_effect_commentary",
+                "compliance_commentary",
+                "load_commentary",
+                "coach_message"
+            ]
+        }),
+    }
+}
+
+/// Tool schema for evaluating a finished mesocycle.
+/// Claude uses this to review the block before the next one is planned.
+pub fn evaluate_mesocycle_tool() -> Tool {
+    Tool {
+        name: "evaluate_mesocycle".to_string(),
+        description: "Evaluate a finished mesocycle: how consistently it was executed, what it did to fitness, and what the next mesocycle should take from it.".to_string(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "assessment": {
+                    "type": "string",
+                    "description": "2-3 sentence evaluation of the block: completion, off-target sessions, RPE trends and what it did to CTL"
+                },
+                "ctl_vs_target": {
+                    "type": "string",
+                    "enum": ["on_track", "behind", "ahead"],
+                    "description": "CTL against the trajectory to the race-day target"
+                },
+                "next_phase_recommendation": {
+                    "type": "string",
+                    "enum": ["capacity", "utilization", "taper"]
+                },
+                "next_focus": {
+                    "type": "string",
+                    "description": "What the next mesocycle's workouts should emphasize or avoid, given this block"
+                },
+                "volume_adjustment": {
+                    "type": "string",
+                    "enum": ["increase_10pct", "maintain", "decrease"],
+                    "description": "Change to the next mesocycle's planned weekly volume"
+                },
+                "strength_level_progression": {
+                    "type": "boolean",
+                    "description": "Whether the athlete advances to the next strength level"
+                },
+                "coach_message": {
+                    "type": "string",
+                    "description": "Coach Jan's message to the athlete, using \"we\" language"
+                }
+            },
+            "required": [
+                "assessment",
+                "ctl_vs_target",
+                "next_phase_recommendation",
+                "next_focus",
+                "volume_adjustment",
+                "strength_level_progression",
+                "coach_message"
+            ]
+        }),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -166,6 +319,8 @@ mod tests {
             generate_macrocycle_skeleton_tool(),
             generate_mesocycle_plan_tool(),
             add_coach_notes_tool(),
+            analyze_workout_tool(),
+            evaluate_mesocycle_tool(),
         ];
         for tool in &tools {
             // Verify the schema is valid JSON by re-serializing
@@ -179,6 +334,8 @@ mod tests {
         assert_eq!(generate_macrocycle_skeleton_tool().name, "generate_macrocycle_skeleton");
         assert_eq!(generate_mesocycle_plan_tool().name, "generate_mesocycle_plan");
         assert_eq!(add_coach_notes_tool().name, "add_coach_notes");
+        assert_eq!(analyze_workout_tool().name, "analyze_workout");
+        assert_eq!(evaluate_mesocycle_tool().name, "evaluate_mesocycle");
     }
 
     #[test]
@@ -1,19 +1,28 @@
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
-use axum::routing::get;
+use axum::routing::{get, post};
 use axum::{Json, Router};
-use chrono::Utc;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
 use sqlx::sqlite::SqlitePool;
 
 use crate::api::middleware::AuthUser;
+use crate::db::plans::{self, PlannedWorkout};
 use crate::db::profiles::{
-    self, AthleteProfile, CreateProfile, CreateRaceGoal, UpdateProfile,
+    self, AthleteProfile, CreateProfile, CreateRaceGoal, CreateRaceResult, FtpaceEntry, LthrEntry,
+    RaceResult, UpdateProfile,
 };
+use crate::db::workouts as workouts_db;
 use crate::domain::bootstrap::bootstrap_ctl;
-use crate::domain::types::{ExperienceLevel, HrZones, PaceZones};
-use crate::domain::zones::{calculate_hr_zones, calculate_pace_zones};
+use crate::domain::equivalency::{
+    self, MIN_DISTANCE_M, RacePrediction, format_distance, format_race_time,
+};
+use crate::domain::ftpace_estimate::{
+    FtpaceEstimate, LOOKBACK_DAYS, MAX_WORKOUTS, estimate_ftpace, should_apply, steady_segments,
+};
+use crate::domain::types::{ExperienceLevel, HrZones, PaceZones, ThresholdSource};
+use crate::domain::zones::{calculate_hr_zones, calculate_pace_zones, format_pace};
 use crate::error::{AppError, AppResult};
 use crate::AppState;
 
@@ -54,6 +63,26 @@ pub struct UpdateProfileRequest {
     pub sports_background: Option<String>,
 }
 
+#[derive(Deserialize, Serialize)]
+pub struct ThresholdsRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lthr: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ftpace_m_per_s: Option<f64>,
+    pub source: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct RaceResultRequest {
+    pub race_name: Option<String>,
+    pub distance_m: f64,
+    pub time_seconds: i64,
+    /// YYYY-MM-DD, not in the future.
+    pub race_date: String,
+}
+
 #[derive(Serialize)]
 pub struct ProfileData {
     pub id: i64,
@@ -89,6 +118,57 @@ pub struct ProfileResponse {
     pub race_goal: Option<RaceGoalData>,
 }
 
+#[derive(Serialize)]
+pub struct ThresholdsResponse {
+    pub lthr: i64,
+    pub ftpace_m_per_s: Option<f64>,
+    pub hr_zones: HrZones,
+    pub pace_zones: Option<PaceZones>,
+    /// Oldest first.
+    pub lthr_history: Vec<LthrEntry>,
+    /// Oldest first.
+    pub ftpace_history: Vec<FtpaceEntry>,
+}
+
+/// Response to a threshold change: the new thresholds plus the upcoming
+/// workouts whose descriptions were updated for the new zones.
+#[derive(Serialize)]
+pub struct RecordThresholdsResponse {
+    #[serde(flatten)]
+    pub thresholds: ThresholdsResponse,
+    pub updated_workouts: Vec<PlannedWorkout>,
+}
+
+/// A stored race result with what it says about the athlete.
+#[derive(Serialize)]
+pub struct RaceResultResponse {
+    pub race_result: RaceResult,
+    /// The FTPace the result is equivalent to, ready to accept through
+    /// `POST /api/athlete/thresholds`.
+    pub proposed_thresholds: ThresholdsRequest,
+    pub current_ftpace_m_per_s: Option<f64>,
+    /// Equivalent times at the standard race distances.
+    pub predictions: Vec<RacePrediction>,
+    /// Equivalent time at the active race goal's distance.
+    pub goal_prediction: Option<RacePrediction>,
+}
+
+/// FTPace estimated from the athlete's recent workouts.
+#[derive(Serialize)]
+pub struct FtpaceEstimateResponse {
+    /// `None` until recent runs have enough steady segments.
+    pub estimate: Option<FtpaceEstimate>,
+    pub current_ftpace_m_per_s: Option<f64>,
+}
+
+#[derive(Serialize)]
+pub struct UpdateProfileResponse {
+    #[serde(flatten)]
+    pub profile: ProfileResponse,
+    /// Upcoming workouts resolved again because LTHR or FTPace changed.
+    pub updated_workouts: Vec<PlannedWorkout>,
+}
+
 // ---------------------------------------------------------------------------
 // Helpers
 // ---------------------------------------------------------------------------
@@ -139,6 +219,201 @@ async fn build_profile_response(
     })
 }
 
+async fn build_thresholds_response(
+    pool: &SqlitePool,
+    profile: &AthleteProfile,
+) -> AppResult<ThresholdsResponse> {
+    Ok(ThresholdsResponse {
+        lthr: profile.lthr,
+        ftpace_m_per_s: profile.ftpace_m_per_s,
+        hr_zones: calculate_hr_zones(profile.lthr as u16),
+        pace_zones: profile.ftpace_m_per_s.map(calculate_pace_zones),
+        lthr_history: profiles::list_lthr_history(pool, profile.user_id).await?,
+        ftpace_history: profiles::list_ftpace_history(pool, profile.user_id).await?,
+    })
+}
+
+/// Resolve the athlete's upcoming workouts again with the profile's current
+/// zones. Past and completed workouts keep what they had.
+async fn refresh_upcoming_workouts(
+    pool: &SqlitePool,
+    profile: &AthleteProfile,
+) -> AppResult<Vec<PlannedWorkout>> {
+    let today = Utc::now().format("%Y-%m-%d").to_string();
+    let hr_zones = calculate_hr_zones(profile.lthr as u16);
+    let pace_zones = profile.ftpace_m_per_s.map(calculate_pace_zones);
+    plans::refresh_workout_zones(
+        pool,
+        profile.user_id,
+        &today,
+        &hr_zones,
+        pace_zones.as_ref(),
+    )
+    .await
+}
+
+/// Make new thresholds the profile's current values, record them in the
+/// history and re-resolve upcoming workouts with the new zones. Returns the
+/// updated profile and the workouts that changed.
+pub(crate) async fn apply_thresholds(
+    pool: &SqlitePool,
+    user_id: i64,
+    lthr: Option<i64>,
+    ftpace_m_per_s: Option<f64>,
+    source: ThresholdSource,
+    notes: Option<&str>,
+) -> AppResult<(AthleteProfile, Vec<PlannedWorkout>)> {
+    // 1. Update the profile's current values and record every value given,
+    //    even one that didn't change: a new test confirming the old value is
+    //    still part of the progression
+    let updated =
+        profiles::record_thresholds(pool, user_id, lthr, ftpace_m_per_s, source.as_str(), notes)
+            .await?;
+
+    // 2. Re-resolve upcoming workouts with the new zones
+    let updated_workouts = refresh_upcoming_workouts(pool, &updated).await?;
+
+    Ok((updated, updated_workouts))
+}
+
+/// Estimate FTPace from the steady segments of the athlete's runs over the
+/// last six weeks. Segments are stored with each upload; a workout uploaded
+/// before that has its segments found from its records once and stored.
+pub(crate) async fn estimate_recent_ftpace(
+    pool: &SqlitePool,
+    profile: &AthleteProfile,
+) -> AppResult<Option<FtpaceEstimate>> {
+    let since = (Utc::now().date_naive() - Duration::days(LOOKBACK_DAYS)).to_string();
+    let workouts =
+        workouts_db::list_recent_steady_segments(pool, profile.user_id, &since, MAX_WORKOUTS)
+            .await?;
+
+    let mut segments = Vec::new();
+    for (workout_id, stored) in workouts {
+        let workout_segments = match stored {
+            Some(stored) => stored,
+            None => {
+                let records = workouts_db::list_workout_records(pool, workout_id).await?;
+                let found = steady_segments(&records);
+                workouts_db::set_steady_segments(pool, workout_id, &found).await?;
+                found
+            }
+        };
+        segments.push(workout_segments);
+    }
+
+    Ok(estimate_ftpace(&segments, profile.lthr))
+}
+
+/// Make a workout-derived estimate the athlete's FTPace (source
+/// `workout_derived`) if `should_apply` allows it. Returns the upcoming
+/// workouts that changed, or `None` when the estimate was not applied.
+pub(crate) async fn apply_ftpace_estimate(
+    pool: &SqlitePool,
+    profile: &AthleteProfile,
+    estimate: &FtpaceEstimate,
+) -> AppResult<Option<Vec<PlannedWorkout>>> {
+    // A recent race, time trial or manual entry set FTPace; don't estimate
+    // over it
+    let history = profiles::list_ftpace_history(pool, profile.user_id).await?;
+    let days_since_measured = history
+        .iter()
+        .rev()
+        .find(|e| {
+            matches!(
+                ThresholdSource::from_str(&e.source),
+                Some(ThresholdSource::Race | ThresholdSource::TimeTrial | ThresholdSource::Manual)
+            )
+        })
+        .and_then(|e| DateTime::parse_from_rfc3339(&e.recorded_at).ok())
+        .map(|at| (Utc::now().date_naive() - at.date_naive()).num_days());
+    if !should_apply(estimate, profile.ftpace_m_per_s, days_since_measured) {
+        return Ok(None);
+    }
+
+    let notes = format!(
+        "{} at LTHR {} from {} steady segments in {} workouts",
+        format_pace(estimate.ftpace_m_per_s),
+        profile.lthr,
+        estimate.segments,
+        estimate.workouts,
+    );
+    let (_, updated_workouts) = apply_thresholds(
+        pool,
+        profile.user_id,
+        None,
+        Some(estimate.ftpace_m_per_s),
+        ThresholdSource::WorkoutDerived,
+        Some(&notes),
+    )
+    .await?;
+
+    Ok(Some(updated_workouts))
+}
+
+/// Validate a new threshold entry against the athlete's profile and return
+/// its source.
+fn validate_thresholds_request(
+    req: &ThresholdsRequest,
+    profile: &AthleteProfile,
+) -> Result<ThresholdSource, AppError> {
+    let source = ThresholdSource::from_str(&req.source).ok_or_else(|| {
+        AppError::BadRequest(
+            "Source must be one of: race, time_trial, estimate, workout_derived, manual"
+                .to_string(),
+        )
+    })?;
+    if req.lthr.is_none() && req.ftpace_m_per_s.is_none() {
+        return Err(AppError::BadRequest(
+            "Provide lthr, ftpace_m_per_s or both".to_string(),
+        ));
+    }
+    if let Some(lthr) = req.lthr
+        && (lthr <= profile.resting_hr || lthr >= profile.max_hr)
+    {
+        return Err(AppError::BadRequest(
+            "LTHR must be between resting HR and max HR".to_string(),
+        ));
+    }
+    if let Some(ftpace) = req.ftpace_m_per_s
+        && !(ftpace > 0.0 && ftpace.is_finite())
+    {
+        return Err(AppError::BadRequest(
+            "FTPace must be greater than 0".to_string(),
+        ));
+    }
+    Ok(source)
+}
+
+/// Validate a race result against the range the equivalency model covers.
+fn validate_race_result_request(req: &RaceResultRequest, today: NaiveDate) -> Result<(), AppError> {
+    if !(req.distance_m >= MIN_DISTANCE_M && req.distance_m.is_finite()) {
+        return Err(AppError::BadRequest(format!(
+            "Race distance must be at least {MIN_DISTANCE_M:.0} m"
+        )));
+    }
+    if req.time_seconds <= 0 {
+        return Err(AppError::BadRequest(
+            "Race time must be greater than 0".to_string(),
+        ));
+    }
+    // Faster than any world record: a typo in the time or distance
+    if req.distance_m / req.time_seconds as f64 > 7.0 {
+        return Err(AppError::BadRequest(
+            "Race time is too fast for the distance".to_string(),
+        ));
+    }
+    match NaiveDate::parse_from_str(&req.race_date, "%Y-%m-%d") {
+        Ok(date) if date <= today => Ok(()),
+        Ok(_) => Err(AppError::BadRequest(
+            "Race date must not be in the future".to_string(),
+        )),
+        Err(_) => Err(AppError::BadRequest(
+            "Race date must be in YYYY-MM-DD format".to_string(),
+        )),
+    }
+}
+
 /// Validate the create profile request fields.
 fn validate_create_request(req: &CreateProfileRequest) -> Result<(), AppError> {
     if req.age <= 0 || req.age >= 120 {
@@ -238,13 +513,11 @@ async fn create_athlete_profile(
 
     // 3. Record FTPace history if provided
     if let Some(ftpace) = body.ftpace_m_per_s {
-        profiles::create_ftpace_entry(&state.db, auth.user_id, ftpace, "estimate")
-            .await?;
+        profiles::create_ftpace_entry(&state.db, auth.user_id, ftpace, "estimate", None).await?;
     }
 
     // 4. Record LTHR history
-    profiles::create_lthr_entry(&state.db, auth.user_id, body.lthr, "estimate")
-        .await?;
+    profiles::create_lthr_entry(&state.db, auth.user_id, body.lthr, "estimate", None).await?;
 
     // 5. Bootstrap CTL/ATL and create initial daily_metrics
     let (ctl, atl) = bootstrap_ctl(body.current_weekly_volume_km, &experience_level);
@@ -312,8 +585,7 @@ async fn update_athlete_profile(
     // 2. If LTHR changed, record LTHR history entry
     if let Some(new_lthr) = update.lthr {
         if new_lthr != current.lthr {
-            profiles::create_lthr_entry(&state.db, auth.user_id, new_lthr, "manual")
-                .await?;
+            profiles::create_lthr_entry(&state.db, auth.user_id, new_lthr, "manual", None).await?;
         }
     }
 
@@ -329,17 +601,159 @@ async fn update_athlete_profile(
                 auth.user_id,
                 new_ftpace,
                 "manual",
+                None,
             )
             .await?;
         }
     }
 
-    // 4. Build response with updated zones and race goal
-    let response = build_profile_response(&state.db, &updated).await?;
+    // 4. Re-resolve upcoming workouts if the zones moved
+    let updated_workouts =
+        if updated.lthr != current.lthr || updated.ftpace_m_per_s != current.ftpace_m_per_s {
+            refresh_upcoming_workouts(&state.db, &updated).await?
+        } else {
+            Vec::new()
+        };
+
+    // 5. Build response with updated zones and race goal
+    let response = UpdateProfileResponse {
+        profile: build_profile_response(&state.db, &updated).await?,
+        updated_workouts,
+    };
+
+    Ok(Json(response))
+}
+
+/// GET /api/athlete/thresholds
+async fn get_thresholds(
+    state: axum::extract::State<AppState>,
+    auth: AuthUser,
+) -> AppResult<impl IntoResponse> {
+    let profile = profiles::get_profile_by_user_id(&state.db, auth.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("No athlete profile found".to_string()))?;
+
+    let response = build_thresholds_response(&state.db, &profile).await?;
 
     Ok(Json(response))
 }
 
+/// POST /api/athlete/thresholds
+///
+/// Records a new LTHR and/or FTPace with where it came from and makes it the
+/// profile's current value, so zones follow it.
+async fn record_thresholds(
+    state: axum::extract::State<AppState>,
+    auth: AuthUser,
+    Json(body): Json<ThresholdsRequest>,
+) -> AppResult<impl IntoResponse> {
+    let current = profiles::get_profile_by_user_id(&state.db, auth.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("No athlete profile found".to_string()))?;
+    let source = validate_thresholds_request(&body, &current)?;
+
+    let (updated, updated_workouts) = apply_thresholds(
+        &state.db,
+        auth.user_id,
+        body.lthr,
+        body.ftpace_m_per_s,
+        source,
+        body.notes.as_deref(),
+    )
+    .await?;
+
+    // Respond with the recalculated zones, the full history and the
+    // workouts that changed
+    let response = RecordThresholdsResponse {
+        thresholds: build_thresholds_response(&state.db, &updated).await?,
+        updated_workouts,
+    };
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// GET /api/athlete/ftpace-estimate
+///
+/// FTPace estimated from the pace-to-HR relationship of the athlete's recent
+/// runs, with its confidence. Uploads apply it when confidence is high.
+async fn get_ftpace_estimate(
+    state: axum::extract::State<AppState>,
+    auth: AuthUser,
+) -> AppResult<impl IntoResponse> {
+    let profile = profiles::get_profile_by_user_id(&state.db, auth.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("No athlete profile found".to_string()))?;
+
+    let response = FtpaceEstimateResponse {
+        estimate: estimate_recent_ftpace(&state.db, &profile).await?,
+        current_ftpace_m_per_s: profile.ftpace_m_per_s,
+    };
+
+    Ok(Json(response))
+}
+
+/// POST /api/athlete/race-results
+///
+/// Stores a race result and proposes the FTPace it is equivalent to. The
+/// proposal is not applied: the athlete accepts it through the thresholds
+/// endpoint. Later plans are generated with the goal time it predicts.
+async fn create_race_result(
+    state: axum::extract::State<AppState>,
+    auth: AuthUser,
+    Json(body): Json<RaceResultRequest>,
+) -> AppResult<impl IntoResponse> {
+    let profile = profiles::get_profile_by_user_id(&state.db, auth.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("No athlete profile found".to_string()))?;
+    validate_race_result_request(&body, Utc::now().date_naive())?;
+
+    let race_result = profiles::create_race_result(
+        &state.db,
+        &CreateRaceResult {
+            user_id: auth.user_id,
+            race_name: body.race_name,
+            distance_m: body.distance_m,
+            time_seconds: body.time_seconds,
+            race_date: body.race_date,
+        },
+    )
+    .await?;
+
+    let time_seconds = race_result.time_seconds as f64;
+    let goal_prediction = profiles::get_active_race_goal(&state.db, auth.user_id)
+        .await?
+        .map(|goal| equivalency::predict(race_result.distance_m, time_seconds, goal.distance_m));
+    let notes = format!(
+        "{}{} in {} on {}",
+        race_result
+            .race_name
+            .as_deref()
+            .map(|n| format!("{n}: "))
+            .unwrap_or_default(),
+        format_distance(race_result.distance_m),
+        format_race_time(race_result.time_seconds),
+        race_result.race_date,
+    );
+
+    let response = RaceResultResponse {
+        proposed_thresholds: ThresholdsRequest {
+            lthr: None,
+            ftpace_m_per_s: Some(equivalency::ftpace_from_race(
+                race_result.distance_m,
+                time_seconds,
+            )),
+            source: ThresholdSource::Race.as_str().to_string(),
+            notes: Some(notes),
+        },
+        current_ftpace_m_per_s: profile.ftpace_m_per_s,
+        predictions: equivalency::predict_standard_distances(race_result.distance_m, time_seconds),
+        goal_prediction,
+        race_result,
+    };
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
 // ---------------------------------------------------------------------------
 // Router
 // ---------------------------------------------------------------------------
@@ -352,6 +766,9 @@ pub fn router() -> Router<AppState> {
                 .post(create_athlete_profile)
                 .put(update_athlete_profile),
         )
+        .route("/thresholds", get(get_thresholds).post(record_thresholds))
+        .route("/ftpace-estimate", get(get_ftpace_estimate))
+        .route("/race-results", post(create_race_result))
 }
 
 // ---------------------------------------------------------------------------
@@ -468,6 +885,60 @@ mod tests {
         assert!(validate_create_request(&req).is_err());
     }
 
+    #[test]
+    fn test_validate_thresholds_request() {
+        let profile = AthleteProfile {
+            id: 1,
+            user_id: 1,
+            name: "Test".into(),
+            age: 30,
+            weight_kg: 70.0,
+            resting_hr: 50,
+            max_hr: 185,
+            lthr: 170,
+            ftpace_m_per_s: None,
+            current_weekly_volume_km: 40.0,
+            experience_level: "intermediate".into(),
+            sports_background: None,
+            created_at: "2026-01-01T00:00:00Z".into(),
+            updated_at: "2026-01-01T00:00:00Z".into(),
+        };
+        let req = |lthr, ftpace_m_per_s, source: &str| ThresholdsRequest {
+            lthr,
+            ftpace_m_per_s,
+            source: source.into(),
+            notes: None,
+        };
+
+        assert_eq!(
+            validate_thresholds_request(&req(Some(172), Some(4.1), "time_trial"), &profile).ok(),
+            Some(ThresholdSource::TimeTrial)
+        );
+        assert!(validate_thresholds_request(&req(Some(172), None, "guess"), &profile).is_err());
+        assert!(validate_thresholds_request(&req(None, None, "race"), &profile).is_err());
+        assert!(validate_thresholds_request(&req(Some(190), None, "race"), &profile).is_err());
+        assert!(validate_thresholds_request(&req(None, Some(0.0), "race"), &profile).is_err());
+    }
+
+    #[test]
+    fn test_validate_race_result_request() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        let req = |distance_m, time_seconds, race_date: &str| RaceResultRequest {
+            race_name: None,
+            distance_m,
+            time_seconds,
+            race_date: race_date.into(),
+        };
+
+        assert!(validate_race_result_request(&req(10000.0, 2460, "2026-10-17"), today).is_ok());
+        assert!(validate_race_result_request(&req(800.0, 130, "2026-10-01"), today).is_err());
+        assert!(validate_race_result_request(&req(10000.0, 0, "2026-10-01"), today).is_err());
+        // 10K in 10 minutes
+        assert!(validate_race_result_request(&req(10000.0, 600, "2026-10-01"), today).is_err());
+        assert!(validate_race_result_request(&req(10000.0, 2460, "2026-10-18"), today).is_err());
+        assert!(validate_race_result_request(&req(10000.0, 2460, "17/10/2026"), today).is_err());
+    }
+
     #[test]
     fn test_profile_response_zones_with_ftpace() {
         let profile = AthleteProfile {
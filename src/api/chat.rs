@@ -0,0 +1,150 @@
+use axum::extract::Query;
+use axum::response::IntoResponse;
+use axum::{Json, Router};
+use chrono::Utc;
+use serde::Deserialize;
+
+use crate::AppState;
+use crate::ai::handlers::{self, PlanError};
+use crate::api::middleware::AuthUser;
+use crate::api::sse::stream_progress;
+use crate::db::chat as chat_db;
+use crate::error::{AppError, AppResult};
+
+/// Longest chat message accepted from the athlete, in characters.
+const MAX_MESSAGE_CHARS: usize = 4000;
+
+const DEFAULT_HISTORY_LIMIT: i64 = 50;
+const MAX_HISTORY_LIMIT: i64 = 200;
+
+// ---------------------------------------------------------------------------
+// Request types
+// ---------------------------------------------------------------------------
+
+#[derive(Deserialize)]
+pub struct ChatRequest {
+    pub message: String,
+}
+
+#[derive(Deserialize)]
+pub struct HistoryQuery {
+    pub limit: Option<i64>,
+    /// Only messages older than this message ID.
+    pub before: Option<i64>,
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// The trimmed message, if it is non-empty and within the length limit.
+fn validate_message(message: &str) -> AppResult<&str> {
+    let message = message.trim();
+    if message.is_empty() {
+        return Err(AppError::BadRequest(
+            "Message must not be empty".to_string(),
+        ));
+    }
+    if message.chars().count() > MAX_MESSAGE_CHARS {
+        return Err(AppError::BadRequest(format!(
+            "Message must be at most {MAX_MESSAGE_CHARS} characters"
+        )));
+    }
+    Ok(message)
+}
+
+fn chat_error(e: PlanError) -> AppError {
+    match e {
+        PlanError::Database(e) => e,
+        e => AppError::Internal(format!("Chat failed: {}", e)),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Handlers
+// ---------------------------------------------------------------------------
+
+/// POST /api/chat
+///
+/// Send Coach Jan a message. Returns the stored message and the coach's reply.
+async fn send_message(
+    state: axum::extract::State<AppState>,
+    auth: AuthUser,
+    Json(body): Json<ChatRequest>,
+) -> AppResult<impl IntoResponse> {
+    let message = validate_message(&body.message)?;
+
+    let client = state
+        .claude_client
+        .as_ref()
+        .ok_or_else(|| AppError::Internal("Claude API key not configured".to_string()))?;
+
+    let exchange = handlers::chat(
+        client,
+        &state.db,
+        auth.user_id,
+        message,
+        Utc::now().date_naive(),
+    )
+    .await
+    .map_err(chat_error)?;
+
+    Ok(Json(exchange))
+}
+
+/// POST /api/chat/stream
+///
+/// Like `POST /api/chat`, but answers with server-sent events: `text` events
+/// as the reply is written, then `done` with the exchange (or `error`).
+async fn stream_message(
+    state: axum::extract::State<AppState>,
+    auth: AuthUser,
+    Json(body): Json<ChatRequest>,
+) -> AppResult<impl IntoResponse> {
+    let message = validate_message(&body.message)?.to_string();
+
+    let client = state
+        .claude_client
+        .as_ref()
+        .ok_or_else(|| AppError::Internal("Claude API key not configured".to_string()))?;
+
+    let pool = state.db.clone();
+    let user_id = auth.user_id;
+    Ok(stream_progress(client, move |client| async move {
+        handlers::chat(&client, &pool, user_id, &message, Utc::now().date_naive())
+            .await
+            .map_err(chat_error)
+    }))
+}
+
+/// GET /api/chat?limit=&before=
+///
+/// The chat timeline, oldest first: the newest `limit` messages, or with
+/// `before` the page of messages preceding that message ID.
+async fn get_history(
+    state: axum::extract::State<AppState>,
+    auth: AuthUser,
+    Query(query): Query<HistoryQuery>,
+) -> AppResult<impl IntoResponse> {
+    let limit = query.limit.unwrap_or(DEFAULT_HISTORY_LIMIT);
+    if !(1..=MAX_HISTORY_LIMIT).contains(&limit) {
+        return Err(AppError::BadRequest(format!(
+            "'limit' must be between 1 and {MAX_HISTORY_LIMIT}"
+        )));
+    }
+
+    let messages =
+        chat_db::list_chat_messages(&state.db, auth.user_id, limit, query.before).await?;
+
+    Ok(Json(serde_json::json!({ "messages": messages })))
+}
+
+// ---------------------------------------------------------------------------
+// Router
+// ---------------------------------------------------------------------------
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", axum::routing::get(get_history).post(send_message))
+        .route("/stream", axum::routing::post(stream_message))
+}
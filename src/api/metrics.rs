@@ -0,0 +1,114 @@
+use axum::extract::Query;
+use axum::response::IntoResponse;
+use axum::{Json, Router};
+use chrono::{NaiveDate, Utc};
+use serde::Deserialize;
+
+use crate::api::middleware::AuthUser;
+use crate::db::{metrics as metrics_db, plans as plans_db};
+use crate::domain::load_tracking::{DailyLoad, assess_race_day};
+use crate::error::{AppError, AppResult};
+use crate::AppState;
+
+// ---------------------------------------------------------------------------
+// Request types
+// ---------------------------------------------------------------------------
+
+#[derive(Deserialize)]
+pub struct PmcQuery {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+fn parse_query_date(name: &str, value: Option<&str>) -> AppResult<Option<NaiveDate>> {
+    value
+        .map(|v| {
+            NaiveDate::parse_from_str(v, "%Y-%m-%d").map_err(|_| {
+                AppError::BadRequest(format!("'{name}' must be a date in YYYY-MM-DD format"))
+            })
+        })
+        .transpose()
+}
+
+fn in_range(day: &DailyLoad, from: Option<NaiveDate>, to: Option<NaiveDate>) -> bool {
+    from.is_none_or(|f| day.date >= f) && to.is_none_or(|t| day.date <= t)
+}
+
+// ---------------------------------------------------------------------------
+// Handlers
+// ---------------------------------------------------------------------------
+
+/// GET /api/metrics/pmc?from=&to=
+///
+/// Returns the actual CTL/ATL/TSB series up to today and a projected series
+/// through the active macrocycle's end date, based on the expected TSS of the
+/// remaining planned workouts. `race_day` compares the projection on the
+/// macrocycle end date with the race-day TSB range and the plan's target CTL.
+/// `from`/`to` filter both series; the race-day projection is always included.
+async fn get_pmc(
+    state: axum::extract::State<AppState>,
+    auth: AuthUser,
+    Query(query): Query<PmcQuery>,
+) -> AppResult<impl IntoResponse> {
+    let from = parse_query_date("from", query.from.as_deref())?;
+    let to = parse_query_date("to", query.to.as_deref())?;
+    if let (Some(f), Some(t)) = (from, to)
+        && f > t
+    {
+        return Err(AppError::BadRequest("'from' must not be after 'to'".to_string()));
+    }
+
+    // Bring the stored series up to date (rest days since the last update).
+    let today = Utc::now().date_naive();
+    metrics_db::recompute_daily_metrics(&state.db, auth.user_id, today, today).await?;
+
+    let actual = metrics_db::get_daily_metrics_range(
+        &state.db,
+        auth.user_id,
+        &from.map_or_else(|| "0000-01-01".to_string(), |d| d.to_string()),
+        &to.map_or_else(|| "9999-12-31".to_string(), |d| d.to_string()),
+    )
+    .await?
+    .iter()
+    .map(|row| row.to_load())
+    .collect::<AppResult<Vec<_>>>()?;
+
+    let (projected, race_day) =
+        match plans_db::get_current_macrocycle(&state.db, auth.user_id).await? {
+            Some(mc) => {
+                let end = NaiveDate::parse_from_str(&mc.end_date, "%Y-%m-%d").map_err(|e| {
+                    AppError::Internal(format!("Invalid macrocycle end_date: {e}"))
+                })?;
+                let projection =
+                    metrics_db::project_daily_metrics(&state.db, auth.user_id, today, end).await?;
+                let race_day = projection
+                    .last()
+                    .map(|day| assess_race_day(day, mc.target_ctl));
+                (projection, race_day)
+            }
+            None => (vec![], None),
+        };
+
+    let projected: Vec<DailyLoad> = projected
+        .into_iter()
+        .filter(|day| in_range(day, from, to))
+        .collect();
+
+    Ok(Json(serde_json::json!({
+        "actual": actual,
+        "projected": projected,
+        "race_day": race_day,
+    })))
+}
+
+// ---------------------------------------------------------------------------
+// Router
+// ---------------------------------------------------------------------------
+
+pub fn router() -> Router<AppState> {
+    Router::new().route("/pmc", axum::routing::get(get_pmc))
+}
@@ -1,4 +1,8 @@
 pub mod auth;
 pub mod athletes;
+pub mod chat;
 pub mod middleware;
 pub mod plans;
+pub mod sse;
+pub mod workouts;
+pub mod metrics;
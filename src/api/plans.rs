@@ -1,15 +1,25 @@
 use axum::http::StatusCode;
 use axum::response::IntoResponse;
 use axum::{Json, Router};
+use chrono::{NaiveDate, Utc};
 use serde::Deserialize;
 use sqlx::SqlitePool;
 
-use crate::ai::handlers::{self, MacrocycleSkeleton};
+use crate::ai::client::ClaudeClient;
+use crate::ai::handlers::{self, GeneratedPlan, PlanError};
 use crate::api::middleware::AuthUser;
-use crate::db::{plans as plans_db, profiles};
+use crate::api::sse::stream_progress;
+use crate::db::adjustments::{self as adjustments_db, AdjustmentContext};
+use crate::db::drafts::{self as drafts_db, PlanDraft};
+use crate::db::profiles::{AthleteProfile, RaceGoal};
+use crate::db::{metrics as metrics_db, plans as plans_db, profiles};
+use crate::domain::skeleton::{SkeletonPatch, validate_skeleton};
+use crate::domain::zones::{calculate_hr_zones, calculate_pace_zones};
 use crate::error::{AppError, AppResult};
 use crate::AppState;
 
+const MAX_FEEDBACK_CHARS: usize = 2000;
+
 // ---------------------------------------------------------------------------
 // Request types
 // ---------------------------------------------------------------------------
@@ -19,6 +29,16 @@ pub struct GenerateRequest {
     pub race_goal_id: i64,
 }
 
+#[derive(Deserialize)]
+pub struct ConfirmRequest {
+    pub draft_id: i64,
+}
+
+#[derive(Deserialize)]
+pub struct ReviseRequest {
+    pub feedback: String,
+}
+
 #[derive(Deserialize)]
 pub struct CompleteWorkoutRequest {
     pub rpe: Option<i64>,
@@ -26,6 +46,11 @@ pub struct CompleteWorkoutRequest {
     pub actual_duration_min: Option<i64>,
 }
 
+#[derive(Deserialize)]
+pub struct AdjustmentsQuery {
+    pub status: Option<String>,
+}
+
 // ---------------------------------------------------------------------------
 // Helpers
 // ---------------------------------------------------------------------------
@@ -42,6 +67,41 @@ async fn get_current_ctl(pool: &SqlitePool, user_id: i64) -> AppResult<f64> {
     Ok(ctl.unwrap_or(0.0))
 }
 
+/// The user's draft, if it is still open for editing and confirmation.
+async fn get_open_draft(pool: &SqlitePool, user_id: i64, draft_id: i64) -> AppResult<PlanDraft> {
+    drafts_db::get_open_draft(pool, user_id, draft_id, Utc::now())
+        .await?
+        .ok_or_else(|| AppError::NotFound("Plan draft not found or expired".to_string()))
+}
+
+fn draft_changed() -> AppError {
+    AppError::Conflict("The draft was changed or confirmed in the meantime".to_string())
+}
+
+/// Profile and active race goal for working on `draft`, which must have been
+/// generated for that race goal.
+async fn draft_inputs(
+    pool: &SqlitePool,
+    user_id: i64,
+    draft: &PlanDraft,
+) -> AppResult<(AthleteProfile, RaceGoal)> {
+    let profile = profiles::get_profile_by_user_id(pool, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("No athlete profile found".to_string()))?;
+
+    let race_goal = profiles::get_active_race_goal(pool, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("No active race goal found".to_string()))?;
+
+    if race_goal.id != draft.race_goal_id {
+        return Err(AppError::Conflict(
+            "The active race goal has changed since this draft was generated".to_string(),
+        ));
+    }
+
+    Ok((profile, race_goal))
+}
+
 // ---------------------------------------------------------------------------
 // Handlers
 // ---------------------------------------------------------------------------
@@ -81,47 +141,276 @@ async fn generate_plan(
         .await
         .map_err(|e| AppError::Internal(format!("Plan generation failed: {}", e)))?;
 
-    Ok((StatusCode::CREATED, Json(skeleton)))
+    let draft =
+        drafts_db::create_draft(&state.db, auth.user_id, race_goal.id, &skeleton, Utc::now())
+            .await?;
+
+    Ok((StatusCode::CREATED, Json(draft)))
 }
 
-/// POST /api/plan/confirm
+/// GET /api/plan/drafts/:id
 ///
-/// Takes a macrocycle skeleton, persists it, generates the first mesocycle's
-/// workouts via Claude, validates, and returns the full plan.
-async fn confirm_plan(
+/// Returns an open skeleton draft.
+async fn get_draft(
     state: axum::extract::State<AppState>,
     auth: AuthUser,
-    Json(skeleton): Json<MacrocycleSkeleton>,
+    axum::extract::Path(draft_id): axum::extract::Path<i64>,
 ) -> AppResult<impl IntoResponse> {
-    let client = state.claude_client.as_ref().ok_or_else(|| {
-        AppError::Internal("Claude API key not configured".to_string())
+    let draft = get_open_draft(&state.db, auth.user_id, draft_id).await?;
+    Ok(Json(draft))
+}
+
+/// PATCH /api/plan/drafts/:id
+///
+/// Applies the athlete's edits to a draft's mesocycles. The edited skeleton
+/// must pass `validate_skeleton` for a plan starting today; otherwise nothing
+/// is stored and the violations are returned.
+async fn update_draft(
+    state: axum::extract::State<AppState>,
+    auth: AuthUser,
+    axum::extract::Path(draft_id): axum::extract::Path<i64>,
+    Json(patch): Json<SkeletonPatch>,
+) -> AppResult<impl IntoResponse> {
+    if patch.mesocycles.is_empty() {
+        return Err(AppError::BadRequest("No changes given".to_string()));
+    }
+
+    let draft = get_open_draft(&state.db, auth.user_id, draft_id).await?;
+    let (profile, race_goal) = draft_inputs(&state.db, auth.user_id, &draft).await?;
+    let race_date = NaiveDate::parse_from_str(&race_goal.race_date, "%Y-%m-%d").map_err(|e| {
+        AppError::Internal(format!("Invalid race date '{}': {e}", race_goal.race_date))
     })?;
 
-    let profile = profiles::get_profile_by_user_id(&state.db, auth.user_id)
-        .await?
-        .ok_or_else(|| AppError::NotFound("No athlete profile found".to_string()))?;
+    let mut skeleton = draft.skeleton.clone();
+    skeleton
+        .apply_patch(&patch)
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
 
-    let race_goal = profiles::get_active_race_goal(&state.db, auth.user_id)
-        .await?
-        .ok_or_else(|| AppError::NotFound("No active race goal found".to_string()))?;
+    let errors = validate_skeleton(
+        &skeleton,
+        Utc::now().date_naive(),
+        race_date,
+        profile.current_weekly_volume_km,
+    );
+    if !errors.is_empty() {
+        let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        return Err(AppError::BadRequest(messages.join("; ")));
+    }
+
+    let updated = drafts_db::update_draft_skeleton(
+        &state.db,
+        draft.id,
+        draft.version,
+        &skeleton,
+        "edited",
+        None,
+    )
+    .await?
+    .ok_or_else(draft_changed)?;
+
+    Ok(Json(updated))
+}
+
+/// POST /api/plan/drafts/:id/revise
+///
+/// Sends the athlete's free-text feedback ("I can't train on Tuesdays") to
+/// Claude along with the draft's history and stores the revised skeleton as
+/// the draft's next version.
+async fn revise_draft(
+    state: axum::extract::State<AppState>,
+    auth: AuthUser,
+    axum::extract::Path(draft_id): axum::extract::Path<i64>,
+    Json(body): Json<ReviseRequest>,
+) -> AppResult<impl IntoResponse> {
+    let feedback = body.feedback.trim();
+    if feedback.is_empty() {
+        return Err(AppError::BadRequest(
+            "Feedback must not be empty".to_string(),
+        ));
+    }
+    if feedback.chars().count() > MAX_FEEDBACK_CHARS {
+        return Err(AppError::BadRequest(format!(
+            "Feedback must be at most {MAX_FEEDBACK_CHARS} characters"
+        )));
+    }
+
+    let client = state
+        .claude_client
+        .as_ref()
+        .ok_or_else(|| AppError::Internal("Claude API key not configured".to_string()))?;
 
+    let draft = get_open_draft(&state.db, auth.user_id, draft_id).await?;
+    let (profile, race_goal) = draft_inputs(&state.db, auth.user_id, &draft).await?;
     let ctl = get_current_ctl(&state.db, auth.user_id).await?;
+    let revisions = drafts_db::list_revisions(&state.db, draft.id).await?;
 
-    let plan = handlers::confirm_and_generate_plan(
-        client,
+    let skeleton = handlers::revise_skeleton(
+        client, &state.db, &profile, &race_goal, ctl, &revisions, feedback,
+    )
+    .await
+    .map_err(|e| match e {
+        PlanError::ValidationFailed(message) => AppError::Unprocessable(format!(
+            "Could not produce a revision that passes validation: {message}"
+        )),
+        e => AppError::Internal(format!("Plan revision failed: {}", e)),
+    })?;
+
+    let updated = drafts_db::update_draft_skeleton(
         &state.db,
-        auth.user_id,
+        draft.id,
+        draft.version,
         &skeleton,
-        &profile,
-        &race_goal,
+        "revised",
+        Some(feedback),
+    )
+    .await?
+    .ok_or_else(draft_changed)?;
+
+    Ok(Json(updated))
+}
+
+/// GET /api/plan/drafts/:id/revisions
+///
+/// Every version of an open draft, oldest first, with the edit or feedback
+/// that produced it.
+async fn list_draft_revisions(
+    state: axum::extract::State<AppState>,
+    auth: AuthUser,
+    axum::extract::Path(draft_id): axum::extract::Path<i64>,
+) -> AppResult<impl IntoResponse> {
+    let draft = get_open_draft(&state.db, auth.user_id, draft_id).await?;
+    let revisions = drafts_db::list_revisions(&state.db, draft.id).await?;
+    Ok(Json(revisions))
+}
+
+/// Everything needed to confirm a draft, checked before any Claude call. The
+/// draft is claimed once the inputs are complete.
+struct ConfirmInputs {
+    draft: PlanDraft,
+    profile: AthleteProfile,
+    race_goal: RaceGoal,
+    ctl: f64,
+}
+
+async fn confirm_inputs(
+    pool: &SqlitePool,
+    user_id: i64,
+    draft_id: i64,
+) -> AppResult<ConfirmInputs> {
+    let draft = get_open_draft(pool, user_id, draft_id).await?;
+    let (profile, race_goal) = draft_inputs(pool, user_id, &draft).await?;
+    let ctl = get_current_ctl(pool, user_id).await?;
+
+    // The stored skeleton may have been generated days ago; it must still
+    // fit a plan that starts today.
+    let race_date = NaiveDate::parse_from_str(&race_goal.race_date, "%Y-%m-%d").map_err(|e| {
+        AppError::Internal(format!("Invalid race date '{}': {e}", race_goal.race_date))
+    })?;
+    let errors = validate_skeleton(
+        &draft.skeleton,
+        Utc::now().date_naive(),
+        race_date,
+        profile.current_weekly_volume_km,
+    );
+    if !errors.is_empty() {
+        let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        return Err(AppError::BadRequest(messages.join("; ")));
+    }
+
+    if !drafts_db::claim_draft(pool, draft.id, draft.version).await? {
+        return Err(draft_changed());
+    }
+
+    Ok(ConfirmInputs {
+        draft,
+        profile,
+        race_goal,
         ctl,
+    })
+}
+
+/// Generate the plan for a claimed draft and close the draft. If generation
+/// fails, the draft is reopened so the athlete can try again.
+async fn confirm_draft(
+    client: &ClaudeClient,
+    pool: &SqlitePool,
+    user_id: i64,
+    inputs: &ConfirmInputs,
+) -> AppResult<GeneratedPlan> {
+    let plan = match handlers::confirm_and_generate_plan(
+        client,
+        pool,
+        user_id,
+        &inputs.draft.skeleton,
+        &inputs.profile,
+        &inputs.race_goal,
+        inputs.ctl,
     )
     .await
-    .map_err(|e| AppError::Internal(format!("Plan confirmation failed: {}", e)))?;
+    {
+        Ok(plan) => plan,
+        Err(e) => {
+            drafts_db::release_draft(pool, inputs.draft.id).await?;
+            return Err(AppError::Internal(format!(
+                "Plan confirmation failed: {}",
+                e
+            )));
+        }
+    };
+
+    if !drafts_db::mark_draft_confirmed(pool, inputs.draft.id, plan.macrocycle.id).await? {
+        return Err(AppError::Internal(format!(
+            "Plan draft {} was not claimed when its plan was saved",
+            inputs.draft.id
+        )));
+    }
+
+    Ok(plan)
+}
+
+/// POST /api/plan/confirm
+///
+/// Takes a draft ID, persists the draft's skeleton, generates the first
+/// mesocycle's workouts via Claude, validates, and returns the full plan.
+async fn confirm_plan(
+    state: axum::extract::State<AppState>,
+    auth: AuthUser,
+    Json(body): Json<ConfirmRequest>,
+) -> AppResult<impl IntoResponse> {
+    let client = state.claude_client.as_ref().ok_or_else(|| {
+        AppError::Internal("Claude API key not configured".to_string())
+    })?;
+
+    let inputs = confirm_inputs(&state.db, auth.user_id, body.draft_id).await?;
+    let plan = confirm_draft(client, &state.db, auth.user_id, &inputs).await?;
 
     Ok((StatusCode::CREATED, Json(plan)))
 }
 
+/// POST /api/plan/confirm/stream
+///
+/// Like `POST /api/plan/confirm`, but answers with server-sent events:
+/// `stage` events between Claude calls, `tool_start`/`tool_input` events as
+/// the workouts are generated, then `done` with the full plan (or `error`).
+async fn confirm_plan_stream(
+    state: axum::extract::State<AppState>,
+    auth: AuthUser,
+    Json(body): Json<ConfirmRequest>,
+) -> AppResult<impl IntoResponse> {
+    let client = state
+        .claude_client
+        .as_ref()
+        .ok_or_else(|| AppError::Internal("Claude API key not configured".to_string()))?;
+
+    let inputs = confirm_inputs(&state.db, auth.user_id, body.draft_id).await?;
+
+    let pool = state.db.clone();
+    let user_id = auth.user_id;
+    Ok(stream_progress(client, move |client| async move {
+        confirm_draft(&client, &pool, user_id, &inputs).await
+    }))
+}
+
 /// GET /api/plan
 ///
 /// Returns the current active macrocycle with all mesocycles and their workouts.
@@ -193,9 +482,128 @@ async fn complete_workout(
     )
     .await?;
 
+    metrics_db::recompute_after_workout_change(
+        &state.db,
+        auth.user_id,
+        &workout.scheduled_date,
+        chrono::Utc::now().date_naive(),
+    )
+    .await?;
+
     Ok(Json(workout))
 }
 
+/// POST /api/plan/mesocycles/:id/transition
+///
+/// Evaluates a finished mesocycle with Claude, stores the evaluation, marks it
+/// completed and generates the next mesocycle's workouts.
+async fn transition_mesocycle(
+    state: axum::extract::State<AppState>,
+    auth: AuthUser,
+    axum::extract::Path(mesocycle_id): axum::extract::Path<i64>,
+) -> AppResult<impl IntoResponse> {
+    let client = state.claude_client.as_ref().ok_or_else(|| {
+        AppError::Internal("Claude API key not configured".to_string())
+    })?;
+
+    let ctl = get_current_ctl(&state.db, auth.user_id).await?;
+
+    let transition = handlers::transition_mesocycle(
+        client,
+        &state.db,
+        auth.user_id,
+        mesocycle_id,
+        ctl,
+        chrono::Utc::now().date_naive(),
+    )
+    .await
+    .map_err(|e| match e {
+        PlanError::Database(e) => e,
+        e => AppError::Internal(format!("Mesocycle transition failed: {}", e)),
+    })?;
+
+    Ok(Json(transition))
+}
+
+/// The athlete's current zones, fitness and date, for applying adjustments.
+async fn adjustment_context(pool: &SqlitePool, user_id: i64) -> AppResult<AdjustmentContext> {
+    let profile = profiles::get_profile_by_user_id(pool, user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("No athlete profile found".to_string()))?;
+
+    Ok(AdjustmentContext {
+        hr_zones: calculate_hr_zones(profile.lthr as u16),
+        pace_zones: profile.ftpace_m_per_s.map(calculate_pace_zones),
+        athlete_ctl: get_current_ctl(pool, user_id).await?,
+        today: chrono::Utc::now().date_naive(),
+    })
+}
+
+/// GET /api/plan/adjustments?status=pending
+///
+/// Lists plan adjustment proposals, newest first. Pending proposals include
+/// the changes accepting them would make, or why they can no longer apply.
+async fn list_adjustments(
+    state: axum::extract::State<AppState>,
+    auth: AuthUser,
+    axum::extract::Query(query): axum::extract::Query<AdjustmentsQuery>,
+) -> AppResult<impl IntoResponse> {
+    let proposals =
+        adjustments_db::list_adjustments(&state.db, auth.user_id, query.status.as_deref()).await?;
+    let ctx = if proposals.iter().any(|p| p.status == "pending") {
+        Some(adjustment_context(&state.db, auth.user_id).await?)
+    } else {
+        None
+    };
+
+    let mut items = Vec::with_capacity(proposals.len());
+    for proposal in proposals {
+        let mut item = serde_json::json!(proposal);
+        if let Some(ctx) = &ctx
+            && proposal.status == "pending"
+        {
+            match adjustments_db::preview_adjustment(&state.db, &proposal, ctx).await {
+                Ok(changes) => item["changes"] = serde_json::json!(changes),
+                Err(e @ (AppError::Database(_) | AppError::Internal(_))) => return Err(e),
+                Err(e) => item["error"] = serde_json::json!(e.to_string()),
+            }
+        }
+        items.push(item);
+    }
+
+    Ok(Json(items))
+}
+
+/// POST /api/plan/adjustments/:id/accept
+///
+/// Applies a pending proposal to the plan. Nothing changes if the adjusted
+/// weeks would fail validation.
+async fn accept_adjustment(
+    state: axum::extract::State<AppState>,
+    auth: AuthUser,
+    axum::extract::Path(adjustment_id): axum::extract::Path<i64>,
+) -> AppResult<impl IntoResponse> {
+    let ctx = adjustment_context(&state.db, auth.user_id).await?;
+    let (proposal, changes) =
+        adjustments_db::accept_adjustment(&state.db, adjustment_id, auth.user_id, &ctx).await?;
+
+    Ok(Json(serde_json::json!({
+        "adjustment": proposal,
+        "changes": changes
+    })))
+}
+
+/// POST /api/plan/adjustments/:id/reject
+async fn reject_adjustment(
+    state: axum::extract::State<AppState>,
+    auth: AuthUser,
+    axum::extract::Path(adjustment_id): axum::extract::Path<i64>,
+) -> AppResult<impl IntoResponse> {
+    let proposal =
+        adjustments_db::reject_adjustment(&state.db, adjustment_id, auth.user_id).await?;
+    Ok(Json(proposal))
+}
+
 // ---------------------------------------------------------------------------
 // Router
 // ---------------------------------------------------------------------------
@@ -204,9 +612,32 @@ pub fn router() -> Router<AppState> {
     Router::new()
         .route("/generate", axum::routing::post(generate_plan))
         .route("/confirm", axum::routing::post(confirm_plan))
+        .route("/confirm/stream", axum::routing::post(confirm_plan_stream))
+        .route(
+            "/drafts/{id}",
+            axum::routing::get(get_draft).patch(update_draft),
+        )
+        .route("/drafts/{id}/revise", axum::routing::post(revise_draft))
+        .route(
+            "/drafts/{id}/revisions",
+            axum::routing::get(list_draft_revisions),
+        )
         .route("/workouts/{id}/complete", axum::routing::post(complete_workout))
         .route("/", axum::routing::get(get_plan))
         .route("/workout/{id}", axum::routing::get(get_workout))
+        .route(
+            "/mesocycles/{id}/transition",
+            axum::routing::post(transition_mesocycle),
+        )
+        .route("/adjustments", axum::routing::get(list_adjustments))
+        .route(
+            "/adjustments/{id}/accept",
+            axum::routing::post(accept_adjustment),
+        )
+        .route(
+            "/adjustments/{id}/reject",
+            axum::routing::post(reject_adjustment),
+        )
 }
 
 // ---------------------------------------------------------------------------
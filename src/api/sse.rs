@@ -0,0 +1,61 @@
+use std::future::Future;
+
+use axum::response::sse::{Event, KeepAlive, Sse};
+use serde::Serialize;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tokio_stream::{Stream, StreamExt};
+
+use crate::ai::client::ClaudeClient;
+use crate::ai::progress::{self, Progress};
+use crate::error::AppResult;
+
+/// Run a Claude-backed operation in the background and stream its progress
+/// as server-sent events.
+///
+/// `task` gets a client that streams its responses into the event stream.
+/// The stream ends with a `done` event carrying the operation's JSON result,
+/// or an `error` event with the status and message the plain endpoint would
+/// have returned. The operation runs to completion even if the browser
+/// disconnects.
+pub fn stream_progress<F, Fut, T>(
+    client: &ClaudeClient,
+    task: F,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>> + use<F, Fut, T>>
+where
+    F: FnOnce(ClaudeClient) -> Fut,
+    Fut: Future<Output = AppResult<T>> + Send + 'static,
+    T: Serialize,
+{
+    let (tx, rx) = progress::channel();
+    let task = task(client.with_progress(tx.clone()));
+
+    tokio::spawn(async move {
+        let last = match task.await {
+            Ok(result) => match serde_json::to_value(&result) {
+                Ok(result) => Progress::Done { result },
+                Err(e) => {
+                    tracing::error!("Failed to serialize streamed result: {e}");
+                    Progress::Error {
+                        status: 500,
+                        message: "Internal server error".to_string(),
+                    }
+                }
+            },
+            Err(e) => {
+                let (status, message) = e.status_and_message();
+                Progress::Error {
+                    status: status.as_u16(),
+                    message,
+                }
+            }
+        };
+        let _ = tx.send(last);
+    });
+
+    let events = UnboundedReceiverStream::new(rx).map(|update| {
+        Event::default()
+            .event(update.event_name())
+            .json_data(&update)
+    });
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
@@ -0,0 +1,473 @@
+use std::path::PathBuf;
+
+use axum::extract::{DefaultBodyLimit, Multipart, Query};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::{Json, Router};
+use chrono::Utc;
+use serde::Deserialize;
+
+use crate::ai::handlers;
+use crate::api::athletes::{ThresholdsRequest, apply_ftpace_estimate, estimate_recent_ftpace};
+use crate::api::middleware::AuthUser;
+use crate::db::profiles::AthleteProfile;
+use crate::db::{
+    adjustments as adjustments_db, metrics as metrics_db, plans as plans_db, profiles,
+    workouts as workouts_db,
+};
+use crate::domain::classification::{Classification, check_against_plan, classify_workout};
+use crate::domain::compliance::{Execution, assess_compliance};
+use crate::domain::effects::{
+    EffectCoefficients, calculate_aerobic_effect, calculate_anaerobic_effect,
+};
+use crate::domain::ftpace_estimate::steady_segments;
+use crate::domain::scoring::{
+    LoadScores, ZoneTime, calculate_trimp, hr_time_in_zone, pace_time_in_zone, score_workout,
+};
+use crate::domain::summary::summarize_activity;
+use crate::domain::time_trial::derive_thresholds;
+use crate::domain::types::ThresholdSource;
+use crate::domain::workouts::WorkoutType;
+use crate::domain::zones::{calculate_hr_zones, calculate_pace_zones, format_pace};
+use crate::error::{AppError, AppResult};
+use crate::fit::{FitRecord, parse_fit};
+use crate::AppState;
+
+/// Largest FIT upload accepted. Multi-hour activities recorded at 1 Hz are a
+/// few MB, so this leaves plenty of headroom.
+const MAX_UPLOAD_BYTES: usize = 50 * 1024 * 1024;
+
+// ---------------------------------------------------------------------------
+// Request types
+// ---------------------------------------------------------------------------
+
+#[derive(Deserialize)]
+pub struct AnalysisQuery {
+    #[serde(default)]
+    pub regenerate: bool,
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+/// HR-based load, zone time, effect scores and classification for an upload.
+/// HR metrics are skipped when HR data is insufficient; pace zones need an
+/// FTPace.
+#[derive(Default)]
+struct IntensityAnalysis {
+    trimp: Option<f64>,
+    hr_zone_seconds: [i64; 7],
+    pace_zone_seconds: [i64; 6],
+    aerobic_effect: Option<f64>,
+    anaerobic_effect: Option<f64>,
+    classification: Option<Classification>,
+}
+
+fn analyze_intensity(
+    records: &[FitRecord],
+    profile: &AthleteProfile,
+    hr_data_sufficient: bool,
+    ctl: f64,
+) -> IntensityAnalysis {
+    fn to_array<const N: usize>(time: &ZoneTime) -> [i64; N] {
+        let mut out = [0; N];
+        for (slot, seconds) in out.iter_mut().zip(&time.seconds) {
+            *slot = *seconds;
+        }
+        out
+    }
+
+    let mut analysis = IntensityAnalysis::default();
+    let lthr = u16::try_from(profile.lthr).unwrap_or(0);
+
+    if hr_data_sufficient {
+        let resting_hr = u16::try_from(profile.resting_hr).unwrap_or(0);
+        let max_hr = u16::try_from(profile.max_hr).unwrap_or(0);
+        let coefficients = EffectCoefficients::default();
+
+        analysis.hr_zone_seconds = to_array(&hr_time_in_zone(records, &calculate_hr_zones(lthr)));
+        analysis.trimp = calculate_trimp(records, resting_hr, max_hr);
+        let aerobic =
+            calculate_aerobic_effect(records, lthr, resting_hr, max_hr, ctl, &coefficients);
+        let anaerobic = calculate_anaerobic_effect(
+            records,
+            lthr,
+            resting_hr,
+            max_hr,
+            profile.ftpace_m_per_s,
+            ctl,
+            &coefficients,
+        );
+        analysis.aerobic_effect = Some(aerobic);
+        analysis.anaerobic_effect = Some(anaerobic);
+        analysis.classification = classify_workout(&analysis.hr_zone_seconds, aerobic, anaerobic);
+    }
+    if let Some(ftpace) = profile.ftpace_m_per_s {
+        analysis.pace_zone_seconds =
+            to_array(&pace_time_in_zone(records, &calculate_pace_zones(ftpace)));
+    }
+
+    analysis
+}
+
+// ---------------------------------------------------------------------------
+// Handlers
+// ---------------------------------------------------------------------------
+
+/// POST /api/workouts/upload
+///
+/// Accepts a multipart form with a `file` field containing a `.fit` activity.
+/// Parses and scores it (NGP, rTSS or hrTSS, TRIMP, time in zone, effects,
+/// classification), stores the raw file and the per-second records, and
+/// matches the activity to the planned workout scheduled on the same day (if
+/// any). `classification_check` compares the classification with the planned
+/// workout type; `compliance` is the verdict against the whole prescription
+/// and `off_target_streak` the athlete's current run of off-target sessions.
+///
+/// A planned time trial measures FTPace and LTHR from its best 30 minutes;
+/// `time_trial` has what was measured and `proposed_thresholds` the values
+/// (source `time_trial`), ready to accept through
+/// `POST /api/athlete/thresholds`. They are not applied on upload.
+/// `ftpace_estimate` is FTPace estimated from the pace-to-HR line of recent
+/// runs; it is applied (source `workout_derived`) when confidence is high and
+/// no race, time trial or manual entry set FTPace in the last four weeks, and
+/// `updated_workouts` has the upcoming workouts resolved again for it.
+async fn upload_workout(
+    state: axum::extract::State<AppState>,
+    auth: AuthUser,
+    mut multipart: Multipart,
+) -> AppResult<impl IntoResponse> {
+    let mut file_bytes = None;
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Invalid multipart body: {e}")))?
+    {
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        let file_name = field.file_name().unwrap_or_default().to_lowercase();
+        if !file_name.ends_with(".fit") {
+            return Err(AppError::BadRequest(
+                "Uploaded file must be a .fit file".to_string(),
+            ));
+        }
+
+        let bytes = field
+            .bytes()
+            .await
+            .map_err(|e| AppError::BadRequest(format!("Failed to read upload: {e}")))?;
+        file_bytes = Some(bytes);
+    }
+
+    let bytes = file_bytes
+        .ok_or_else(|| AppError::BadRequest("Missing 'file' field".to_string()))?;
+
+    let activity = parse_fit(&bytes)
+        .map_err(|e| AppError::BadRequest(format!("Invalid FIT file: {e}")))?;
+    let summary = summarize_activity(&activity).ok_or_else(|| {
+        AppError::BadRequest("FIT file contains no activity data".to_string())
+    })?;
+    let records = activity.per_second_records();
+
+    // Load scores need the athlete's thresholds; without a profile the
+    // workout is stored unscored.
+    let profile = profiles::get_profile_by_user_id(&state.db, auth.user_id).await?;
+    let scores = match &profile {
+        Some(profile) => score_workout(
+            &records,
+            summary.duration_seconds as f64,
+            profile.ftpace_m_per_s,
+            u16::try_from(profile.lthr).unwrap_or(0),
+            summary.hr_data_sufficient,
+        ),
+        None => LoadScores::default(),
+    };
+    let date = summary.started_at.format("%Y-%m-%d").to_string();
+    let intensity = match &profile {
+        Some(profile) => {
+            // Effects are normalized by fitness going into the workout
+            let ctl = metrics_db::get_ctl_before(&state.db, auth.user_id, &date).await?;
+            analyze_intensity(&records, profile, summary.hr_data_sufficient, ctl)
+        }
+        None => IntensityAnalysis::default(),
+    };
+
+    // Compliance against the planned workout on the same day (if any)
+    let planned =
+        plans_db::find_unlinked_planned_workout_for_date(&state.db, auth.user_id, &date).await?;
+    let execution = Execution {
+        duration_seconds: summary.duration_seconds,
+        distance_m: summary.distance_m,
+        tss: scores.tss,
+        hr_zone_seconds: summary
+            .hr_data_sufficient
+            .then_some(intensity.hr_zone_seconds),
+        pace_zone_seconds: intensity.pace_zone_seconds,
+        classification: intensity.classification,
+    };
+    let unplanned_compliance = assess_compliance(None, &execution);
+    let mut compliance = match &planned {
+        Some(planned) => planned.prescription().map(|p| assess_compliance(Some(&p), &execution)),
+        None => Some(unplanned_compliance.clone()),
+    };
+
+    // Did the athlete train the system the session prescribed?
+    let mut classification_check = planned
+        .as_ref()
+        .and_then(|planned| WorkoutType::from_str(&planned.workout_type))
+        .map(|planned| check_against_plan(intensity.classification, planned));
+
+    let input = workouts_db::CreateCompletedWorkout {
+        user_id: auth.user_id,
+        source: "fit_upload".to_string(),
+        hr_data_sufficient: summary.hr_data_sufficient,
+        started_at: summary.started_at.to_rfc3339(),
+        duration_seconds: summary.duration_seconds,
+        distance_m: summary.distance_m,
+        avg_hr: summary.avg_hr,
+        max_hr: summary.max_hr,
+        avg_pace_m_per_s: summary.avg_pace_m_per_s,
+        max_pace_m_per_s: summary.max_pace_m_per_s,
+        avg_cadence: summary.avg_cadence,
+        elevation_gain_m: summary.elevation_gain_m,
+        elevation_loss_m: summary.elevation_loss_m,
+        ngp_m_per_s: scores.ngp_m_per_s,
+        intensity_factor: scores.intensity_factor,
+        rtss: scores.rtss,
+        hrtss: scores.hrtss,
+        tss: scores.tss,
+        trimp: intensity.trimp,
+        hr_zone_seconds: intensity.hr_zone_seconds,
+        pace_zone_seconds: intensity.pace_zone_seconds,
+        aerobic_effect: intensity.aerobic_effect,
+        anaerobic_effect: intensity.anaerobic_effect,
+        classification: intensity.classification.map(|c| c.as_str().to_string()),
+        classification_check: classification_check.clone(),
+        compliance: compliance.as_ref().map(|c| c.verdict.as_str().to_string()),
+    };
+
+    // Store the workout, the raw file as
+    // data/fit_files/{athlete_id}/{YYYY-MM-DD}_{workout_id}.fit, the
+    // per-second records, the steady segments for FTPace estimates and the
+    // link to the planned workout together: if any of them fails, none is
+    // kept
+    let dir = PathBuf::from(&state.config.fit_files_dir).join(auth.user_id.to_string());
+    let mut written = None;
+    let segments = steady_segments(&records);
+    let link = planned.as_ref().map(|planned| workouts_db::PlanLink {
+        planned_workout_id: planned.id,
+        actual_duration_min: (summary.duration_seconds as f64 / 60.0).round() as i64,
+        unplanned_compliance: unplanned_compliance.verdict.as_str().to_string(),
+    });
+    let stored = workouts_db::create_uploaded_workout(
+        &state.db,
+        &input,
+        &records,
+        &segments,
+        link.as_ref(),
+        async |w| {
+            let path = dir.join(format!("{date}_{}.fit", w.id));
+            written = Some(path.clone());
+            let write_result = async {
+                tokio::fs::create_dir_all(&dir).await?;
+                tokio::fs::write(&path, &bytes).await
+            }
+            .await;
+            write_result
+                .map_err(|e| AppError::Internal(format!("Failed to store FIT file: {e}")))?;
+            Ok(path.to_string_lossy().into_owned())
+        },
+    )
+    .await;
+    let (workout, planned_workout) = match stored {
+        Ok(stored) => stored,
+        Err(e) => {
+            if let Some(path) = &written
+                && let Err(e) = tokio::fs::remove_file(path).await
+                && e.kind() != std::io::ErrorKind::NotFound
+            {
+                tracing::warn!("Failed to remove FIT file {}: {e}", path.display());
+            }
+            return Err(e);
+        }
+    };
+
+    // Another upload may have linked the planned workout since it was looked
+    // up; this one then counts as unplanned
+    if let Some(planned) = &planned
+        && planned_workout.is_none()
+    {
+        tracing::info!(
+            "Planned workout {} was linked by another upload; storing workout {} as unplanned",
+            planned.id,
+            workout.id
+        );
+        compliance = Some(unplanned_compliance);
+        classification_check = None;
+    }
+
+    // A completed time trial proposes new thresholds for the athlete to accept
+    let mut time_trial = None;
+    let mut proposed_thresholds = None;
+    if let (Some(profile), Some(planned)) = (&profile, &planned_workout)
+        && planned.workout_type == WorkoutType::TimeTrial.as_str()
+        && let Some(mut measured) = derive_thresholds(&records)
+    {
+        // An average outside resting..max HR is bad sensor data
+        measured.lthr = measured
+            .lthr
+            .filter(|&lthr| lthr > profile.resting_hr && lthr < profile.max_hr);
+        let notes = format!(
+            "30-min time trial on {date} at {}",
+            format_pace(measured.segment_avg_speed_m_per_s)
+        );
+        proposed_thresholds = Some(ThresholdsRequest {
+            lthr: measured.lthr,
+            ftpace_m_per_s: Some(measured.ftpace_m_per_s),
+            source: ThresholdSource::TimeTrial.as_str().to_string(),
+            notes: Some(notes),
+        });
+        time_trial = Some(measured);
+    }
+
+    // Every run with HR refines the pace-to-HR line; a confident estimate
+    // becomes the athlete's FTPace
+    let mut ftpace_estimate = None;
+    let mut updated_workouts = Vec::new();
+    let mut ftpace_estimate_applied = false;
+    if let Some(profile) = &profile
+        && summary.hr_data_sufficient
+        && let Some(estimate) = estimate_recent_ftpace(&state.db, profile).await?
+    {
+        if let Some(changed) = apply_ftpace_estimate(&state.db, profile, &estimate).await? {
+            updated_workouts = changed;
+            ftpace_estimate_applied = true;
+        }
+        ftpace_estimate = Some(estimate);
+    }
+
+    metrics_db::recompute_after_workout_change(
+        &state.db,
+        auth.user_id,
+        &date,
+        Utc::now().date_naive(),
+    )
+    .await?;
+
+    let workout = workouts_db::get_completed_workout(&state.db, workout.id, auth.user_id)
+        .await?
+        .ok_or_else(|| AppError::Internal("Uploaded workout disappeared".to_string()))?;
+    let off_target_streak =
+        workouts_db::get_off_target_streak(&state.db, auth.user_id, &workout.started_at).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(serde_json::json!({
+            "workout": workout,
+            "planned_workout": planned_workout,
+            "classification_check": classification_check,
+            "compliance": compliance,
+            "off_target_streak": off_target_streak,
+            "time_trial": time_trial,
+            "proposed_thresholds": proposed_thresholds,
+            "ftpace_estimate": ftpace_estimate,
+            "ftpace_estimate_applied": ftpace_estimate_applied,
+            "updated_workouts": updated_workouts,
+            "records_count": records.len(),
+        })),
+    ))
+}
+
+/// DELETE /api/workouts/:id
+///
+/// Deletes an uploaded workout, its records and stored FIT file, detaches it
+/// from the matched planned workout (returning that to not-completed unless
+/// the athlete completed it by hand), and recomputes training load.
+async fn delete_workout(
+    state: axum::extract::State<AppState>,
+    auth: AuthUser,
+    axum::extract::Path(workout_id): axum::extract::Path<i64>,
+) -> AppResult<impl IntoResponse> {
+    let workout = workouts_db::get_completed_workout(&state.db, workout_id, auth.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Workout not found".to_string()))?;
+
+    workouts_db::delete_completed_workout(&state.db, workout.id, auth.user_id).await?;
+
+    if let Some(path) = &workout.fit_file_path
+        && let Err(e) = tokio::fs::remove_file(path).await
+    {
+        tracing::warn!("Failed to remove FIT file {path}: {e}");
+    }
+
+    metrics_db::recompute_after_workout_change(
+        &state.db,
+        auth.user_id,
+        &workout.started_at,
+        Utc::now().date_naive(),
+    )
+    .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /api/workouts/:id/analysis
+///
+/// Coach Jan's analysis of a workout. Generated with Claude on first request
+/// and stored on the workout; later requests return the stored analysis
+/// unless `?regenerate=true`. Includes any plan adjustments it prompted.
+async fn get_workout_analysis(
+    state: axum::extract::State<AppState>,
+    auth: AuthUser,
+    axum::extract::Path(workout_id): axum::extract::Path<i64>,
+    Query(query): Query<AnalysisQuery>,
+) -> AppResult<impl IntoResponse> {
+    let mut workout = workouts_db::get_completed_workout(&state.db, workout_id, auth.user_id)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Workout not found".to_string()))?;
+
+    if workout.analyzed_at.is_none() || query.regenerate {
+        let client = state.claude_client.as_ref().ok_or_else(|| {
+            AppError::Internal("Claude API key not configured".to_string())
+        })?;
+
+        handlers::analyze_workout(client, &state.db, &workout)
+            .await
+            .map_err(|e| AppError::Internal(format!("Workout analysis failed: {}", e)))?;
+
+        workout = workouts_db::get_completed_workout(&state.db, workout_id, auth.user_id)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Workout not found".to_string()))?;
+    }
+
+    let adjustments =
+        adjustments_db::list_adjustments_for_workout(&state.db, auth.user_id, workout.id).await?;
+
+    Ok(Json(serde_json::json!({
+        "workout_id": workout.id,
+        "summary": workout.coach_summary,
+        "commentary": workout.coach_commentary,
+        "coach_message": workout.coach_message,
+        "classification": workout.classification,
+        "compliance": workout.compliance,
+        "analyzed_at": workout.analyzed_at,
+        "plan_adjustments": adjustments,
+    })))
+}
+
+// ---------------------------------------------------------------------------
+// Router
+// ---------------------------------------------------------------------------
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/upload",
+            axum::routing::post(upload_workout).layer(DefaultBodyLimit::max(MAX_UPLOAD_BYTES)),
+        )
+        .route("/{id}", axum::routing::delete(delete_workout))
+        .route("/{id}/analysis", axum::routing::get(get_workout_analysis))
+}
@@ -6,6 +6,8 @@ pub struct Config {
     pub host: String,
     pub port: u16,
     pub anthropic_api_key: Option<String>,
+    /// Directory where uploaded FIT files are stored, one subdirectory per athlete.
+    pub fit_files_dir: String,
 }
 
 impl Config {
@@ -19,6 +21,8 @@ impl Config {
                 .and_then(|p| p.parse().ok())
                 .unwrap_or(3000),
             anthropic_api_key: env::var("ANTHROPIC_API_KEY").ok(),
+            fit_files_dir: env::var("FIT_FILES_DIR")
+                .unwrap_or_else(|_| "data/fit_files".to_string()),
         }
     }
 
@@ -39,6 +43,7 @@ mod tests {
             env::remove_var("HOST");
             env::remove_var("PORT");
             env::remove_var("ANTHROPIC_API_KEY");
+            env::remove_var("FIT_FILES_DIR");
         }
 
         let config = Config::from_env();
@@ -46,6 +51,7 @@ mod tests {
         assert_eq!(config.host, "0.0.0.0");
         assert_eq!(config.port, 3000);
         assert!(config.anthropic_api_key.is_none());
+        assert_eq!(config.fit_files_dir, "data/fit_files");
     }
 
     #[test]
@@ -55,6 +61,7 @@ mod tests {
             host: "127.0.0.1".to_string(),
             port: 8080,
             anthropic_api_key: None,
+            fit_files_dir: String::new(),
         };
         assert_eq!(config.listen_addr(), "127.0.0.1:8080");
     }
@@ -0,0 +1,1401 @@
+use chrono::{Duration, NaiveDate, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use sqlx::sqlite::{SqliteConnection, SqlitePool};
+
+use crate::db::plans::{Mesocycle, PlannedWorkout};
+use crate::domain::adjustments::{
+    AdjustmentOperation, MAX_EXTENSION_WEEKS, MesocycleSpan, PlanAdjustment, PlanChange,
+    ScheduledSession, SessionFields, SessionSummary, mesocycle_weeks, new_violations,
+    resolve_session,
+};
+use crate::domain::scoring::format_zone_list;
+use crate::domain::types::{HrZones, PaceZones};
+use crate::domain::validation::WeekPlan;
+use crate::domain::workouts::{DurationCategory, WorkoutRegistry, WorkoutType};
+use crate::error::{AppError, AppResult};
+
+const PLANNED_WORKOUT_COLUMNS: &str = r#"id, mesocycle_id, user_id, scheduled_date, workout_type,
+    duration_min, duration_category, target_hr_zones, target_pace_zones, expected_tss,
+    description, coach_notes, target_distance_km, is_completed, completed_workout_id, rpe,
+    athlete_notes, actual_duration_min, completed_at, created_at"#;
+
+const MESOCYCLE_COLUMNS: &str = r#"m.id, m.macrocycle_id, m.sequence_number, m.phase, m.focus,
+    m.load_weeks, m.recovery_weeks, m.target_volume_km, m.start_date, m.end_date, m.status,
+    m.evaluation_summary, m.transition_due_at, m.created_at"#;
+
+// ---------------------------------------------------------------------------
+// Proposals
+// ---------------------------------------------------------------------------
+
+#[derive(FromRow)]
+struct ProposalRow {
+    id: i64,
+    user_id: i64,
+    completed_workout_id: Option<i64>,
+    adjustment: String,
+    reason: String,
+    status: String,
+    created_at: String,
+    resolved_at: Option<String>,
+}
+
+/// A plan adjustment proposed by Coach Jan, waiting for (or resolved by) the
+/// athlete.
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanAdjustmentProposal {
+    pub id: i64,
+    pub user_id: i64,
+    /// The analyzed workout that prompted the proposal, if any.
+    pub completed_workout_id: Option<i64>,
+    pub adjustment: PlanAdjustment,
+    pub reason: String,
+    /// pending, accepted, rejected or superseded
+    pub status: String,
+    pub created_at: String,
+    pub resolved_at: Option<String>,
+}
+
+impl TryFrom<ProposalRow> for PlanAdjustmentProposal {
+    type Error = AppError;
+
+    fn try_from(row: ProposalRow) -> AppResult<Self> {
+        let adjustment = serde_json::from_str(&row.adjustment).map_err(|e| {
+            AppError::Internal(format!("Invalid stored plan adjustment {}: {e}", row.id))
+        })?;
+        Ok(Self {
+            id: row.id,
+            user_id: row.user_id,
+            completed_workout_id: row.completed_workout_id,
+            adjustment,
+            reason: row.reason,
+            status: row.status,
+            created_at: row.created_at,
+            resolved_at: row.resolved_at,
+        })
+    }
+}
+
+const PROPOSAL_COLUMNS: &str =
+    "id, user_id, completed_workout_id, adjustment, reason, status, created_at, resolved_at";
+
+/// Store a new pending proposal. Earlier pending proposals prompted by the
+/// same workout are superseded (e.g. when its analysis is regenerated).
+pub async fn create_adjustment(
+    pool: &SqlitePool,
+    user_id: i64,
+    completed_workout_id: Option<i64>,
+    adjustment: &PlanAdjustment,
+    reason: &str,
+) -> AppResult<PlanAdjustmentProposal> {
+    let now = Utc::now().to_rfc3339();
+    let json = serde_json::to_string(adjustment)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize plan adjustment: {e}")))?;
+    let mut tx = pool.begin().await?;
+
+    if let Some(workout_id) = completed_workout_id {
+        sqlx::query(
+            r#"UPDATE plan_adjustments SET status = 'superseded', resolved_at = ?
+               WHERE user_id = ? AND completed_workout_id = ? AND status = 'pending'"#,
+        )
+        .bind(&now)
+        .bind(user_id)
+        .bind(workout_id)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    let row = sqlx::query_as::<_, ProposalRow>(&format!(
+        r#"INSERT INTO plan_adjustments
+            (user_id, completed_workout_id, trigger_reason, adjustment, reason, created_at)
+           VALUES (?, ?, ?, ?, ?, ?)
+           RETURNING {PROPOSAL_COLUMNS}"#
+    ))
+    .bind(user_id)
+    .bind(completed_workout_id)
+    .bind(adjustment.trigger_reason.as_str())
+    .bind(&json)
+    .bind(reason)
+    .bind(&now)
+    .fetch_one(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    row.try_into()
+}
+
+/// The user's proposals, newest first, optionally filtered by status.
+pub async fn list_adjustments(
+    pool: &SqlitePool,
+    user_id: i64,
+    status: Option<&str>,
+) -> AppResult<Vec<PlanAdjustmentProposal>> {
+    let rows = sqlx::query_as::<_, ProposalRow>(&format!(
+        r#"SELECT {PROPOSAL_COLUMNS} FROM plan_adjustments
+           WHERE user_id = ? AND (? IS NULL OR status = ?)
+           ORDER BY created_at DESC, id DESC"#
+    ))
+    .bind(user_id)
+    .bind(status)
+    .bind(status)
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter().map(TryInto::try_into).collect()
+}
+
+/// Proposals prompted by a completed workout, newest first.
+pub async fn list_adjustments_for_workout(
+    pool: &SqlitePool,
+    user_id: i64,
+    completed_workout_id: i64,
+) -> AppResult<Vec<PlanAdjustmentProposal>> {
+    let rows = sqlx::query_as::<_, ProposalRow>(&format!(
+        r#"SELECT {PROPOSAL_COLUMNS} FROM plan_adjustments
+           WHERE user_id = ? AND completed_workout_id = ?
+           ORDER BY created_at DESC, id DESC"#
+    ))
+    .bind(user_id)
+    .bind(completed_workout_id)
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter().map(TryInto::try_into).collect()
+}
+
+/// Get a proposal by ID, scoped to the user.
+pub async fn get_adjustment(
+    pool: &SqlitePool,
+    adjustment_id: i64,
+    user_id: i64,
+) -> AppResult<Option<PlanAdjustmentProposal>> {
+    let row = sqlx::query_as::<_, ProposalRow>(&format!(
+        "SELECT {PROPOSAL_COLUMNS} FROM plan_adjustments WHERE id = ? AND user_id = ?"
+    ))
+    .bind(adjustment_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    row.map(TryInto::try_into).transpose()
+}
+
+/// Resolve a pending proposal inside a transaction. `NotFound` if it does not
+/// exist, `Conflict` if it is no longer pending.
+async fn resolve_pending(
+    conn: &mut SqliteConnection,
+    adjustment_id: i64,
+    user_id: i64,
+    status: &str,
+) -> AppResult<PlanAdjustmentProposal> {
+    let row = sqlx::query_as::<_, ProposalRow>(&format!(
+        r#"UPDATE plan_adjustments SET status = ?, resolved_at = ?
+           WHERE id = ? AND user_id = ? AND status = 'pending'
+           RETURNING {PROPOSAL_COLUMNS}"#
+    ))
+    .bind(status)
+    .bind(Utc::now().to_rfc3339())
+    .bind(adjustment_id)
+    .bind(user_id)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    match row {
+        Some(row) => row.try_into(),
+        None => {
+            let status: Option<String> = sqlx::query_scalar(
+                "SELECT status FROM plan_adjustments WHERE id = ? AND user_id = ?",
+            )
+            .bind(adjustment_id)
+            .bind(user_id)
+            .fetch_optional(&mut *conn)
+            .await?;
+            match status {
+                Some(status) => Err(AppError::Conflict(format!(
+                    "Plan adjustment is already {status}"
+                ))),
+                None => Err(AppError::NotFound("Plan adjustment not found".to_string())),
+            }
+        }
+    }
+}
+
+/// Reject a pending proposal. The plan is left untouched.
+pub async fn reject_adjustment(
+    pool: &SqlitePool,
+    adjustment_id: i64,
+    user_id: i64,
+) -> AppResult<PlanAdjustmentProposal> {
+    let mut tx = pool.begin().await?;
+    let proposal = resolve_pending(&mut tx, adjustment_id, user_id, "rejected").await?;
+    tx.commit().await?;
+    Ok(proposal)
+}
+
+// ---------------------------------------------------------------------------
+// Applying adjustments
+// ---------------------------------------------------------------------------
+
+/// What applying an adjustment needs to know about the athlete.
+pub struct AdjustmentContext {
+    pub hr_zones: HrZones,
+    pub pace_zones: Option<PaceZones>,
+    pub athlete_ctl: f64,
+    /// Sessions before this date are history and cannot change.
+    pub today: NaiveDate,
+}
+
+/// Accept a pending proposal: apply it to the plan, re-validate the affected
+/// weeks and mark it accepted, all in one transaction. Nothing is written if
+/// the change would break the plan.
+pub async fn accept_adjustment(
+    pool: &SqlitePool,
+    adjustment_id: i64,
+    user_id: i64,
+    ctx: &AdjustmentContext,
+) -> AppResult<(PlanAdjustmentProposal, Vec<PlanChange>)> {
+    let mut tx = pool.begin().await?;
+    let proposal = resolve_pending(&mut tx, adjustment_id, user_id, "accepted").await?;
+    let changes = apply(&mut tx, user_id, &proposal.adjustment, ctx).await?;
+    tx.commit().await?;
+    Ok((proposal, changes))
+}
+
+/// The changes a proposal would make, without making them.
+pub async fn preview_adjustment(
+    pool: &SqlitePool,
+    proposal: &PlanAdjustmentProposal,
+    ctx: &AdjustmentContext,
+) -> AppResult<Vec<PlanChange>> {
+    let mut tx = pool.begin().await?;
+    let changes = apply(&mut tx, proposal.user_id, &proposal.adjustment, ctx).await;
+    tx.rollback().await?;
+    changes
+}
+
+/// Apply the operations in order. Each one is re-validated against the plan
+/// as the operations before it left it.
+async fn apply(
+    conn: &mut SqliteConnection,
+    user_id: i64,
+    adjustment: &PlanAdjustment,
+    ctx: &AdjustmentContext,
+) -> AppResult<Vec<PlanChange>> {
+    if adjustment.operations.is_empty() {
+        return Err(AppError::BadRequest(
+            "Plan adjustment has no operations".to_string(),
+        ));
+    }
+    let mut changes = Vec::new();
+    for operation in &adjustment.operations {
+        changes.extend(apply_operation(conn, user_id, operation, ctx).await?);
+    }
+    Ok(changes)
+}
+
+async fn apply_operation(
+    conn: &mut SqliteConnection,
+    user_id: i64,
+    operation: &AdjustmentOperation,
+    ctx: &AdjustmentContext,
+) -> AppResult<Vec<PlanChange>> {
+    match operation {
+        AdjustmentOperation::SwapWorkout {
+            workout_id,
+            new_workout_type,
+            duration_category,
+        } => {
+            let workout = get_open_workout(conn, user_id, *workout_id, ctx.today).await?;
+            replace_session(
+                conn,
+                &workout,
+                *new_workout_type,
+                *duration_category,
+                None,
+                ctx,
+            )
+            .await
+        }
+        AdjustmentOperation::ReduceIntensity {
+            workout_id,
+            new_workout_type,
+            duration_category,
+        } => {
+            let workout = get_open_workout(conn, user_id, *workout_id, ctx.today).await?;
+            replace_session(
+                conn,
+                &workout,
+                *new_workout_type,
+                *duration_category,
+                Some(Intensity::Lower),
+                ctx,
+            )
+            .await
+        }
+        AdjustmentOperation::IncreaseIntensity {
+            workout_id,
+            new_workout_type,
+            duration_category,
+        } => {
+            let workout = get_open_workout(conn, user_id, *workout_id, ctx.today).await?;
+            replace_session(
+                conn,
+                &workout,
+                *new_workout_type,
+                *duration_category,
+                Some(Intensity::Higher),
+                ctx,
+            )
+            .await
+        }
+        AdjustmentOperation::AddRecoveryDay { date } => {
+            let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|_| AppError::BadRequest(format!("Invalid date '{date}'")))?;
+            let workout_id: i64 = sqlx::query_scalar(
+                r#"SELECT id FROM planned_workouts
+                   WHERE user_id = ? AND scheduled_date = ? AND is_completed = 0
+                   ORDER BY id LIMIT 1"#,
+            )
+            .bind(user_id)
+            .bind(date.format("%Y-%m-%d").to_string())
+            .fetch_optional(&mut *conn)
+            .await?
+            .ok_or_else(|| AppError::NotFound(format!("No open planned workout on {date}")))?;
+            let workout = get_open_workout(conn, user_id, workout_id, ctx.today).await?;
+            if workout.workout_type == WorkoutType::Rest.as_str() {
+                return Err(AppError::BadRequest(format!(
+                    "{date} is already a rest day"
+                )));
+            }
+            replace_session(conn, &workout, WorkoutType::Rest, None, None, ctx).await
+        }
+        AdjustmentOperation::SkipWorkout { workout_id } => {
+            let workout = get_open_workout(conn, user_id, *workout_id, ctx.today).await?;
+            let meso = get_mesocycle(conn, user_id, workout.mesocycle_id).await?;
+            let before = load_weeks(conn, &meso).await?;
+
+            sqlx::query("DELETE FROM planned_workouts WHERE id = ?")
+                .bind(workout.id)
+                .execute(&mut *conn)
+                .await?;
+
+            check_weeks(conn, &meso, &before, ctx).await?;
+            Ok(vec![PlanChange::WorkoutRemoved {
+                workout_id: workout.id,
+                before: summarize(&workout),
+            }])
+        }
+        AdjustmentOperation::ExtendMesocycle {
+            mesocycle_id,
+            weeks,
+        } => extend_mesocycle(conn, user_id, *mesocycle_id, *weeks, ctx).await,
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Intensity {
+    Lower,
+    Higher,
+}
+
+/// Replace a session with another type, resolved against the athlete's zones.
+/// `required` enforces the direction of a reduce/increase_intensity change.
+async fn replace_session(
+    conn: &mut SqliteConnection,
+    workout: &PlannedWorkout,
+    new_type: WorkoutType,
+    duration_category: Option<DurationCategory>,
+    required: Option<Intensity>,
+    ctx: &AdjustmentContext,
+) -> AppResult<Vec<PlanChange>> {
+    let registry = WorkoutRegistry::new();
+    let duration_category = duration_category
+        .or_else(|| {
+            workout
+                .duration_category
+                .as_deref()
+                .and_then(DurationCategory::from_str)
+        })
+        .unwrap_or(DurationCategory::Medium);
+    let resolve = |wt| {
+        resolve_session(
+            &registry,
+            wt,
+            duration_category,
+            &ctx.hr_zones,
+            ctx.pace_zones.as_ref(),
+        )
+    };
+
+    let fields = resolve(new_type).ok_or_else(|| {
+        AppError::BadRequest(format!(
+            "{} cannot be scheduled by a plan adjustment",
+            new_type.as_str()
+        ))
+    })?;
+
+    if let Some(required) = required {
+        let current = WorkoutType::from_str(&workout.workout_type)
+            .and_then(resolve)
+            .ok_or_else(|| {
+                AppError::BadRequest(format!(
+                    "Cannot compare intensity with {}",
+                    workout.workout_type
+                ))
+            })?;
+        let (new, old) = (fields.tss_per_minute(), current.tss_per_minute());
+        let ok = match required {
+            Intensity::Lower => new < old,
+            Intensity::Higher => new > old,
+        };
+        if !ok {
+            return Err(AppError::BadRequest(format!(
+                "{} is not {} intense than {}",
+                new_type.as_str(),
+                if required == Intensity::Lower {
+                    "less"
+                } else {
+                    "more"
+                },
+                workout.workout_type
+            )));
+        }
+    }
+
+    let meso = get_mesocycle(conn, workout.user_id, workout.mesocycle_id).await?;
+    let before = load_weeks(conn, &meso).await?;
+
+    let updated = update_session(conn, workout.id, &fields).await?;
+
+    check_weeks(conn, &meso, &before, ctx).await?;
+    Ok(vec![PlanChange::WorkoutUpdated {
+        workout_id: workout.id,
+        before: summarize(workout),
+        after: summarize(&updated),
+    }])
+}
+
+/// Repeat the last load week `weeks` times ahead of the recovery week. The
+/// next mesocycle starts that much later and loses as many load weeks, so the
+/// race date does not move.
+async fn extend_mesocycle(
+    conn: &mut SqliteConnection,
+    user_id: i64,
+    mesocycle_id: i64,
+    weeks: i64,
+    ctx: &AdjustmentContext,
+) -> AppResult<Vec<PlanChange>> {
+    if !(1..=MAX_EXTENSION_WEEKS).contains(&weeks) {
+        return Err(AppError::BadRequest(format!(
+            "A mesocycle can be extended by 1 to {MAX_EXTENSION_WEEKS} weeks"
+        )));
+    }
+
+    let meso = get_mesocycle(conn, user_id, mesocycle_id).await?;
+    let start = parse_date(&meso.start_date)?;
+    let recovery_start = start + Duration::weeks(meso.load_weeks);
+    if recovery_start <= ctx.today {
+        return Err(AppError::Conflict(
+            "The mesocycle's load weeks are already over".to_string(),
+        ));
+    }
+
+    let next = sqlx::query_as::<_, Mesocycle>(&format!(
+        r#"SELECT {MESOCYCLE_COLUMNS} FROM mesocycles m
+           WHERE m.macrocycle_id = ? AND m.sequence_number = ?"#
+    ))
+    .bind(meso.macrocycle_id)
+    .bind(meso.sequence_number + 1)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::Conflict("The last mesocycle cannot be extended".to_string()))?;
+    if next.load_weeks <= weeks {
+        return Err(AppError::Conflict(format!(
+            "The next mesocycle has only {} load week(s)",
+            next.load_weeks
+        )));
+    }
+    let next_workouts: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM planned_workouts WHERE mesocycle_id = ?")
+            .bind(next.id)
+            .fetch_one(&mut *conn)
+            .await?;
+    if next_workouts > 0 {
+        return Err(AppError::Conflict(
+            "The next mesocycle is already scheduled".to_string(),
+        ));
+    }
+
+    let before = load_weeks(conn, &meso).await?;
+    let shift = Duration::weeks(weeks);
+    let mut changes = Vec::new();
+
+    // Move the recovery week out of the way
+    let recovery = list_workouts_from(conn, meso.id, recovery_start).await?;
+    for workout in &recovery {
+        if workout.is_completed != 0 {
+            return Err(AppError::Conflict(
+                "The recovery week already has completed workouts".to_string(),
+            ));
+        }
+        let to = (parse_date(&workout.scheduled_date)? + shift)
+            .format("%Y-%m-%d")
+            .to_string();
+        sqlx::query("UPDATE planned_workouts SET scheduled_date = ? WHERE id = ?")
+            .bind(&to)
+            .bind(workout.id)
+            .execute(&mut *conn)
+            .await?;
+        changes.push(PlanChange::WorkoutMoved {
+            workout_id: workout.id,
+            from: workout.scheduled_date.clone(),
+            to,
+        });
+    }
+
+    // Repeat the last load week in the gap
+    let last_load_week: Vec<PlannedWorkout> =
+        list_workouts_from(conn, meso.id, recovery_start - Duration::weeks(1))
+            .await?
+            .into_iter()
+            .filter(|w| recovery.iter().all(|r| r.id != w.id))
+            .collect();
+    for repeat in 1..=weeks {
+        for workout in &last_load_week {
+            let date = parse_date(&workout.scheduled_date)? + Duration::weeks(repeat);
+            let copy = copy_session(conn, workout, date).await?;
+            changes.push(PlanChange::WorkoutAdded {
+                after: summarize(&copy),
+            });
+        }
+    }
+
+    let extended = update_span(
+        conn,
+        &meso,
+        &meso.start_date,
+        &(parse_date(&meso.end_date)? + shift)
+            .format("%Y-%m-%d")
+            .to_string(),
+        meso.load_weeks + weeks,
+    )
+    .await?;
+    let shortened = update_span(
+        conn,
+        &next,
+        &(parse_date(&next.start_date)? + shift)
+            .format("%Y-%m-%d")
+            .to_string(),
+        &next.end_date,
+        next.load_weeks - weeks,
+    )
+    .await?;
+    changes.insert(0, mesocycle_change(&next, &shortened));
+    changes.insert(0, mesocycle_change(&meso, &extended));
+
+    check_weeks(conn, &extended, &before, ctx).await?;
+    Ok(changes)
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+fn parse_date(date: &str) -> AppResult<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|e| AppError::Internal(format!("Invalid date '{date}' in plan: {e}")))
+}
+
+fn summarize(workout: &PlannedWorkout) -> SessionSummary {
+    SessionSummary {
+        scheduled_date: workout.scheduled_date.clone(),
+        workout_type: workout.workout_type.clone(),
+        duration_min: workout.duration_min,
+        expected_tss: workout.expected_tss,
+    }
+}
+
+fn span(meso: &Mesocycle) -> MesocycleSpan {
+    MesocycleSpan {
+        start_date: meso.start_date.clone(),
+        end_date: meso.end_date.clone(),
+        load_weeks: meso.load_weeks,
+    }
+}
+
+fn mesocycle_change(before: &Mesocycle, after: &Mesocycle) -> PlanChange {
+    PlanChange::MesocycleChanged {
+        mesocycle_id: before.id,
+        before: span(before),
+        after: span(after),
+    }
+}
+
+/// A planned workout that an adjustment may change: owned by the user, not
+/// completed, and not in the past.
+async fn get_open_workout(
+    conn: &mut SqliteConnection,
+    user_id: i64,
+    workout_id: i64,
+    today: NaiveDate,
+) -> AppResult<PlannedWorkout> {
+    let workout = sqlx::query_as::<_, PlannedWorkout>(&format!(
+        "SELECT {PLANNED_WORKOUT_COLUMNS} FROM planned_workouts WHERE id = ? AND user_id = ?"
+    ))
+    .bind(workout_id)
+    .bind(user_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Planned workout not found".to_string()))?;
+
+    if workout.is_completed != 0 {
+        return Err(AppError::Conflict(
+            "Completed workouts cannot be adjusted".to_string(),
+        ));
+    }
+    if parse_date(&workout.scheduled_date)? < today {
+        return Err(AppError::Conflict(
+            "Past workouts cannot be adjusted".to_string(),
+        ));
+    }
+    Ok(workout)
+}
+
+async fn get_mesocycle(
+    conn: &mut SqliteConnection,
+    user_id: i64,
+    mesocycle_id: i64,
+) -> AppResult<Mesocycle> {
+    sqlx::query_as::<_, Mesocycle>(&format!(
+        r#"SELECT {MESOCYCLE_COLUMNS} FROM mesocycles m
+           JOIN macrocycles mc ON m.macrocycle_id = mc.id
+           WHERE m.id = ? AND mc.user_id = ?"#
+    ))
+    .bind(mesocycle_id)
+    .bind(user_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::NotFound("Mesocycle not found".to_string()))
+}
+
+/// A mesocycle's workouts scheduled on or after `from`, by date.
+async fn list_workouts_from(
+    conn: &mut SqliteConnection,
+    mesocycle_id: i64,
+    from: NaiveDate,
+) -> AppResult<Vec<PlannedWorkout>> {
+    let workouts = sqlx::query_as::<_, PlannedWorkout>(&format!(
+        r#"SELECT {PLANNED_WORKOUT_COLUMNS} FROM planned_workouts
+           WHERE mesocycle_id = ? AND scheduled_date >= ?
+           ORDER BY scheduled_date, id"#
+    ))
+    .bind(mesocycle_id)
+    .bind(from.format("%Y-%m-%d").to_string())
+    .fetch_all(&mut *conn)
+    .await?;
+
+    Ok(workouts)
+}
+
+/// The mesocycle's weeks as currently stored, for validation.
+async fn load_weeks(conn: &mut SqliteConnection, meso: &Mesocycle) -> AppResult<Vec<WeekPlan>> {
+    let start = parse_date(&meso.start_date)?;
+    let sessions: Vec<ScheduledSession> = list_workouts_from(conn, meso.id, start)
+        .await?
+        .iter()
+        .filter_map(|w| {
+            Some(ScheduledSession {
+                date: NaiveDate::parse_from_str(&w.scheduled_date, "%Y-%m-%d").ok()?,
+                workout_type: WorkoutType::from_str(&w.workout_type)?,
+                duration_category: w
+                    .duration_category
+                    .as_deref()
+                    .and_then(DurationCategory::from_str),
+                expected_tss: w.expected_tss.unwrap_or(0.0),
+                target_distance_km: w.target_distance_km,
+            })
+        })
+        .collect();
+
+    Ok(mesocycle_weeks(
+        start,
+        meso.load_weeks,
+        meso.load_weeks + meso.recovery_weeks,
+        &sessions,
+    ))
+}
+
+/// Fail with the new validation errors, if the change introduced any.
+async fn check_weeks(
+    conn: &mut SqliteConnection,
+    meso: &Mesocycle,
+    before: &[WeekPlan],
+    ctx: &AdjustmentContext,
+) -> AppResult<()> {
+    let after = load_weeks(conn, meso).await?;
+    let violations = new_violations(before, &after, ctx.athlete_ctl);
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(AppError::BadRequest(format!(
+            "Plan adjustment fails validation: {}",
+            violations.join("; ")
+        )))
+    }
+}
+
+async fn update_session(
+    conn: &mut SqliteConnection,
+    workout_id: i64,
+    fields: &SessionFields,
+) -> AppResult<PlannedWorkout> {
+    let workout = sqlx::query_as::<_, PlannedWorkout>(&format!(
+        r#"UPDATE planned_workouts
+           SET workout_type = ?, duration_min = ?, duration_category = ?,
+               target_hr_zones = ?, target_pace_zones = ?, expected_tss = ?, description = ?
+           WHERE id = ?
+           RETURNING {PLANNED_WORKOUT_COLUMNS}"#
+    ))
+    .bind(fields.workout_type.as_str())
+    .bind(fields.duration_min)
+    .bind(fields.duration_category.map(|dc| dc.as_str()))
+    .bind(format_zone_list(&fields.target_hr_zones))
+    .bind(format_zone_list(&fields.target_pace_zones))
+    .bind(fields.expected_tss)
+    .bind(&fields.description)
+    .bind(workout_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(workout)
+}
+
+async fn copy_session(
+    conn: &mut SqliteConnection,
+    workout: &PlannedWorkout,
+    date: NaiveDate,
+) -> AppResult<PlannedWorkout> {
+    let copy = sqlx::query_as::<_, PlannedWorkout>(&format!(
+        r#"INSERT INTO planned_workouts
+            (mesocycle_id, user_id, scheduled_date, workout_type, duration_min, duration_category,
+             target_hr_zones, target_pace_zones, expected_tss, description, coach_notes,
+             target_distance_km, created_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+           RETURNING {PLANNED_WORKOUT_COLUMNS}"#
+    ))
+    .bind(workout.mesocycle_id)
+    .bind(workout.user_id)
+    .bind(date.format("%Y-%m-%d").to_string())
+    .bind(&workout.workout_type)
+    .bind(workout.duration_min)
+    .bind(&workout.duration_category)
+    .bind(&workout.target_hr_zones)
+    .bind(&workout.target_pace_zones)
+    .bind(workout.expected_tss)
+    .bind(&workout.description)
+    .bind(&workout.coach_notes)
+    .bind(workout.target_distance_km)
+    .bind(Utc::now().to_rfc3339())
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(copy)
+}
+
+async fn update_span(
+    conn: &mut SqliteConnection,
+    meso: &Mesocycle,
+    start_date: &str,
+    end_date: &str,
+    load_weeks: i64,
+) -> AppResult<Mesocycle> {
+    sqlx::query("UPDATE mesocycles SET start_date = ?, end_date = ?, load_weeks = ? WHERE id = ?")
+        .bind(start_date)
+        .bind(end_date)
+        .bind(load_weeks)
+        .bind(meso.id)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(Mesocycle {
+        start_date: start_date.to_string(),
+        end_date: end_date.to_string(),
+        load_weeks,
+        ..meso.clone()
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::plans::{self, CreateMacrocycle, CreateMesocycle, CreatePlannedWorkout};
+    use crate::domain::compliance::TriggerReason;
+    use crate::domain::zones::calculate_hr_zones;
+    use sqlx::Row;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+
+    async fn setup_pool() -> SqlitePool {
+        let opts = SqliteConnectOptions::new()
+            .filename(":memory:")
+            .create_if_missing(true)
+            .pragma("foreign_keys", "ON");
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(opts)
+            .await
+            .expect("Failed to create test pool");
+
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    async fn create_test_user(pool: &SqlitePool) -> i64 {
+        let row = sqlx::query(
+            "INSERT INTO users (email, password_hash) VALUES ('adjust@example.com', 'hash') RETURNING id",
+        )
+        .fetch_one(pool)
+        .await
+        .expect("create test user");
+        row.get("id")
+    }
+
+    /// A 3+1 mesocycle starting Monday 2026-03-02 with a simple repeating
+    /// week (easy, tempo, rest, easy, rest, easy, long run), followed by an
+    /// unscheduled second mesocycle.
+    async fn seed_plan(pool: &SqlitePool, user_id: i64) -> (i64, i64) {
+        let race_goal_id: i64 = sqlx::query_scalar(
+            r#"INSERT INTO race_goals (user_id, race_name, distance_m, race_date)
+               VALUES (?, 'Test 10K', 10000.0, '2026-06-28') RETURNING id"#,
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .expect("race goal");
+        let macrocycle = plans::create_macrocycle(
+            pool,
+            &CreateMacrocycle {
+                user_id,
+                race_goal_id,
+                start_date: "2026-03-02".to_string(),
+                end_date: "2026-06-28".to_string(),
+                target_ctl: Some(50.0),
+                coach_message: None,
+            },
+        )
+        .await
+        .expect("macrocycle");
+        let mut meso_ids = Vec::new();
+        for (seq, start, end) in [
+            (1, "2026-03-02", "2026-03-29"),
+            (2, "2026-03-30", "2026-04-26"),
+        ] {
+            let meso = plans::create_mesocycle(
+                pool,
+                &CreateMesocycle {
+                    macrocycle_id: macrocycle.id,
+                    sequence_number: seq,
+                    phase: "capacity".to_string(),
+                    focus: "aerobic_capacity".to_string(),
+                    load_weeks: 3,
+                    recovery_weeks: 1,
+                    target_volume_km: Some(40.0),
+                    start_date: start.to_string(),
+                    end_date: end.to_string(),
+                },
+            )
+            .await
+            .expect("mesocycle");
+            meso_ids.push(meso.id);
+        }
+
+        let pattern = [
+            ("easy_run", 40.0),
+            ("tempo_run", 60.0),
+            ("rest", 0.0),
+            ("easy_run", 40.0),
+            ("rest", 0.0),
+            ("easy_run", 40.0),
+            ("long_run", 90.0),
+        ];
+        let start = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        for day in 0..28 {
+            let (workout_type, tss) = pattern[day % 7];
+            plans::create_planned_workout(
+                pool,
+                &CreatePlannedWorkout {
+                    mesocycle_id: meso_ids[0],
+                    user_id,
+                    scheduled_date: (start + Duration::days(day as i64))
+                        .format("%Y-%m-%d")
+                        .to_string(),
+                    workout_type: workout_type.to_string(),
+                    duration_min: (tss > 0.0).then_some(45),
+                    duration_category: (tss > 0.0).then(|| "medium".to_string()),
+                    target_hr_zones: None,
+                    target_pace_zones: None,
+                    expected_tss: Some(tss),
+                    description: None,
+                    coach_notes: None,
+                    target_distance_km: None,
+                },
+            )
+            .await
+            .expect("planned workout");
+        }
+        (meso_ids[0], meso_ids[1])
+    }
+
+    async fn workouts_between(
+        pool: &SqlitePool,
+        user_id: i64,
+        from: &str,
+        to: &str,
+    ) -> Vec<PlannedWorkout> {
+        sqlx::query_as::<_, PlannedWorkout>(&format!(
+            r#"SELECT {PLANNED_WORKOUT_COLUMNS} FROM planned_workouts
+               WHERE user_id = ? AND scheduled_date BETWEEN ? AND ?
+               ORDER BY scheduled_date, id"#
+        ))
+        .bind(user_id)
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool)
+        .await
+        .expect("list planned workouts")
+    }
+
+    async fn workout_on(pool: &SqlitePool, user_id: i64, date: &str) -> PlannedWorkout {
+        workouts_between(pool, user_id, date, date)
+            .await
+            .into_iter()
+            .next()
+            .expect("workout on date")
+    }
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, 1).unwrap()
+    }
+
+    fn adjustment(operation: AdjustmentOperation) -> PlanAdjustment {
+        PlanAdjustment {
+            trigger_reason: TriggerReason::SustainedHarderThanPrescribed,
+            operations: vec![operation],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_supersedes_and_reject() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        let skip = adjustment(AdjustmentOperation::SkipWorkout { workout_id: 1 });
+
+        let first = create_adjustment(&pool, user_id, None, &skip, "tired")
+            .await
+            .expect("create");
+        assert_eq!(first.status, "pending");
+        assert_eq!(first.adjustment, skip);
+        let trigger: String =
+            sqlx::query_scalar("SELECT trigger_reason FROM plan_adjustments WHERE id = ?")
+                .bind(first.id)
+                .fetch_one(&pool)
+                .await
+                .expect("trigger");
+        assert_eq!(trigger, "sustained_harder_than_prescribed");
+
+        let rejected = reject_adjustment(&pool, first.id, user_id)
+            .await
+            .expect("reject");
+        assert_eq!(rejected.status, "rejected");
+        assert!(rejected.resolved_at.is_some());
+        assert!(matches!(
+            reject_adjustment(&pool, first.id, user_id).await,
+            Err(AppError::Conflict(_))
+        ));
+        assert!(matches!(
+            reject_adjustment(&pool, 999, user_id).await,
+            Err(AppError::NotFound(_))
+        ));
+
+        let pending = list_adjustments(&pool, user_id, Some("pending"))
+            .await
+            .expect("list");
+        assert!(pending.is_empty());
+        let all = list_adjustments(&pool, user_id, None).await.expect("list");
+        assert_eq!(all.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reduce_intensity_applies_and_revalidates() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        seed_plan(&pool, user_id).await;
+        let ctx = AdjustmentContext {
+            hr_zones: calculate_hr_zones(170),
+            pace_zones: None,
+            athlete_ctl: 0.0,
+            today: today(),
+        };
+
+        let tempo = workout_on(&pool, user_id, "2026-03-03").await;
+        let proposal = create_adjustment(
+            &pool,
+            user_id,
+            None,
+            &adjustment(AdjustmentOperation::ReduceIntensity {
+                workout_id: tempo.id,
+                new_workout_type: WorkoutType::EasyRun,
+                duration_category: None,
+            }),
+            "Three hard sessions in a row",
+        )
+        .await
+        .expect("create");
+
+        // Preview leaves the plan untouched
+        let preview = preview_adjustment(&pool, &proposal, &ctx)
+            .await
+            .expect("preview");
+        assert_eq!(preview.len(), 1);
+        assert_eq!(
+            workout_on(&pool, user_id, "2026-03-03").await.workout_type,
+            "tempo_run"
+        );
+
+        let (accepted, changes) = accept_adjustment(&pool, proposal.id, user_id, &ctx)
+            .await
+            .expect("accept");
+        assert_eq!(accepted.status, "accepted");
+        assert_eq!(changes, preview);
+        let easy = workout_on(&pool, user_id, "2026-03-03").await;
+        assert_eq!(easy.workout_type, "easy_run");
+        assert_eq!(easy.duration_category.as_deref(), Some("medium"));
+        assert!(easy.target_hr_zones.is_some());
+
+        // Not pending any more
+        assert!(matches!(
+            accept_adjustment(&pool, proposal.id, user_id, &ctx).await,
+            Err(AppError::Conflict(_))
+        ));
+
+        // "Reducing" to a harder session is refused
+        let proposal = create_adjustment(
+            &pool,
+            user_id,
+            None,
+            &adjustment(AdjustmentOperation::ReduceIntensity {
+                workout_id: easy.id,
+                new_workout_type: WorkoutType::Vo2maxIntervals,
+                duration_category: None,
+            }),
+            "typo",
+        )
+        .await
+        .expect("create");
+        assert!(matches!(
+            accept_adjustment(&pool, proposal.id, user_id, &ctx).await,
+            Err(AppError::BadRequest(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_change_that_breaks_validation_rolls_back() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        seed_plan(&pool, user_id).await;
+        let ctx = AdjustmentContext {
+            hr_zones: calculate_hr_zones(170),
+            pace_zones: None,
+            athlete_ctl: 0.0,
+            today: today(),
+        };
+
+        // A second long run in the week is not allowed
+        let easy = workout_on(&pool, user_id, "2026-03-07").await;
+        let proposal = create_adjustment(
+            &pool,
+            user_id,
+            None,
+            &adjustment(AdjustmentOperation::SwapWorkout {
+                workout_id: easy.id,
+                new_workout_type: WorkoutType::LongRun,
+                duration_category: None,
+            }),
+            "More volume",
+        )
+        .await
+        .expect("create");
+
+        let err = accept_adjustment(&pool, proposal.id, user_id, &ctx)
+            .await
+            .expect_err("second long run");
+        assert!(
+            matches!(&err, AppError::BadRequest(msg) if msg.contains("TooManyLongRuns")),
+            "{err:?}"
+        );
+        assert_eq!(
+            workout_on(&pool, user_id, "2026-03-07").await.workout_type,
+            "easy_run"
+        );
+        let proposal = get_adjustment(&pool, proposal.id, user_id)
+            .await
+            .expect("get")
+            .expect("exists");
+        assert_eq!(proposal.status, "pending");
+    }
+
+    #[tokio::test]
+    async fn test_recovery_day_and_skip() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        seed_plan(&pool, user_id).await;
+        let ctx = AdjustmentContext {
+            hr_zones: calculate_hr_zones(170),
+            pace_zones: None,
+            athlete_ctl: 0.0,
+            today: today(),
+        };
+
+        let proposal = create_adjustment(
+            &pool,
+            user_id,
+            None,
+            &adjustment(AdjustmentOperation::AddRecoveryDay {
+                date: "2026-03-05".to_string(),
+            }),
+            "Sore legs",
+        )
+        .await
+        .expect("create");
+        accept_adjustment(&pool, proposal.id, user_id, &ctx)
+            .await
+            .expect("accept");
+        let rest = workout_on(&pool, user_id, "2026-03-05").await;
+        assert_eq!(rest.workout_type, "rest");
+        assert_eq!(rest.expected_tss, Some(0.0));
+
+        let tempo = workout_on(&pool, user_id, "2026-03-10").await;
+        let proposal = create_adjustment(
+            &pool,
+            user_id,
+            None,
+            &adjustment(AdjustmentOperation::SkipWorkout {
+                workout_id: tempo.id,
+            }),
+            "Travel",
+        )
+        .await
+        .expect("create");
+        let (_, changes) = accept_adjustment(&pool, proposal.id, user_id, &ctx)
+            .await
+            .expect("accept");
+        assert!(matches!(changes[0], PlanChange::WorkoutRemoved { .. }));
+        assert!(
+            workouts_between(&pool, user_id, "2026-03-10", "2026-03-10")
+                .await
+                .is_empty()
+        );
+
+        // Past workouts are history
+        let late = AdjustmentContext {
+            today: NaiveDate::from_ymd_opt(2026, 3, 20).unwrap(),
+            ..ctx
+        };
+        let first = workout_on(&pool, user_id, "2026-03-02").await;
+        let proposal = create_adjustment(
+            &pool,
+            user_id,
+            None,
+            &adjustment(AdjustmentOperation::SkipWorkout {
+                workout_id: first.id,
+            }),
+            "Too late",
+        )
+        .await
+        .expect("create");
+        assert!(matches!(
+            accept_adjustment(&pool, proposal.id, user_id, &late).await,
+            Err(AppError::Conflict(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_operations_apply_together_or_not_at_all() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        seed_plan(&pool, user_id).await;
+        let ctx = AdjustmentContext {
+            hr_zones: calculate_hr_zones(170),
+            pace_zones: None,
+            athlete_ctl: 0.0,
+            today: today(),
+        };
+
+        let tempo = workout_on(&pool, user_id, "2026-03-03").await;
+        let easy = workout_on(&pool, user_id, "2026-03-07").await;
+        let both = PlanAdjustment {
+            trigger_reason: TriggerReason::HighAtlCtlRatio,
+            operations: vec![
+                AdjustmentOperation::ReduceIntensity {
+                    workout_id: tempo.id,
+                    new_workout_type: WorkoutType::EasyRun,
+                    duration_category: None,
+                },
+                AdjustmentOperation::AddRecoveryDay {
+                    date: "2026-03-05".to_string(),
+                },
+            ],
+        };
+        let proposal = create_adjustment(&pool, user_id, None, &both, "Load is spiking")
+            .await
+            .expect("create");
+        let (_, changes) = accept_adjustment(&pool, proposal.id, user_id, &ctx)
+            .await
+            .expect("accept");
+        assert_eq!(changes.len(), 2);
+        assert_eq!(
+            workout_on(&pool, user_id, "2026-03-03").await.workout_type,
+            "easy_run"
+        );
+        assert_eq!(
+            workout_on(&pool, user_id, "2026-03-05").await.workout_type,
+            "rest"
+        );
+
+        // The second operation fails validation, so the first is rolled back
+        let tempo = workout_on(&pool, user_id, "2026-03-10").await;
+        let broken = PlanAdjustment {
+            trigger_reason: TriggerReason::SustainedEasierThanPrescribed,
+            operations: vec![
+                AdjustmentOperation::SkipWorkout {
+                    workout_id: tempo.id,
+                },
+                AdjustmentOperation::SwapWorkout {
+                    workout_id: easy.id,
+                    new_workout_type: WorkoutType::LongRun,
+                    duration_category: None,
+                },
+            ],
+        };
+        let proposal = create_adjustment(&pool, user_id, None, &broken, "More volume")
+            .await
+            .expect("create");
+        assert!(matches!(
+            accept_adjustment(&pool, proposal.id, user_id, &ctx).await,
+            Err(AppError::BadRequest(_))
+        ));
+        assert_eq!(
+            workout_on(&pool, user_id, "2026-03-10").await.workout_type,
+            "tempo_run"
+        );
+
+        let empty = PlanAdjustment {
+            trigger_reason: TriggerReason::ExtendedAbsence,
+            operations: vec![],
+        };
+        let proposal = create_adjustment(&pool, user_id, None, &empty, "Nothing")
+            .await
+            .expect("create");
+        assert!(matches!(
+            preview_adjustment(&pool, &proposal, &ctx).await,
+            Err(AppError::BadRequest(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_extend_mesocycle_repeats_last_load_week() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        let (meso_id, next_id) = seed_plan(&pool, user_id).await;
+        let ctx = AdjustmentContext {
+            hr_zones: calculate_hr_zones(170),
+            pace_zones: None,
+            athlete_ctl: 0.0,
+            today: today(),
+        };
+
+        let proposal = create_adjustment(
+            &pool,
+            user_id,
+            None,
+            &adjustment(AdjustmentOperation::ExtendMesocycle {
+                mesocycle_id: meso_id,
+                weeks: 1,
+            }),
+            "Needs another week of base",
+        )
+        .await
+        .expect("create");
+        let (_, changes) = accept_adjustment(&pool, proposal.id, user_id, &ctx)
+            .await
+            .expect("accept");
+        assert_eq!(
+            changes[0],
+            PlanChange::MesocycleChanged {
+                mesocycle_id: meso_id,
+                before: MesocycleSpan {
+                    start_date: "2026-03-02".to_string(),
+                    end_date: "2026-03-29".to_string(),
+                    load_weeks: 3,
+                },
+                after: MesocycleSpan {
+                    start_date: "2026-03-02".to_string(),
+                    end_date: "2026-04-05".to_string(),
+                    load_weeks: 4,
+                },
+            }
+        );
+        assert!(matches!(
+            &changes[1],
+            PlanChange::MesocycleChanged { mesocycle_id, after, .. }
+                if *mesocycle_id == next_id && after.start_date == "2026-04-06" && after.load_weeks == 2
+        ));
+
+        // Week 4 repeats week 3; the recovery week moved to week 5
+        let workouts = workouts_between(&pool, user_id, "2026-03-02", "2026-04-05").await;
+        assert_eq!(workouts.len(), 35);
+        assert_eq!(
+            workout_on(&pool, user_id, "2026-03-24").await.workout_type,
+            "tempo_run"
+        );
+        assert_eq!(
+            workout_on(&pool, user_id, "2026-03-31").await.workout_type,
+            "tempo_run"
+        );
+        let added = changes
+            .iter()
+            .filter(|c| matches!(c, PlanChange::WorkoutAdded { .. }))
+            .count();
+        let moved = changes
+            .iter()
+            .filter(|c| matches!(c, PlanChange::WorkoutMoved { .. }))
+            .count();
+        assert_eq!((added, moved), (7, 7));
+
+        // The next mesocycle cannot give up its last load week
+        let proposal = create_adjustment(
+            &pool,
+            user_id,
+            None,
+            &adjustment(AdjustmentOperation::ExtendMesocycle {
+                mesocycle_id: meso_id,
+                weeks: 2,
+            }),
+            "Even more base",
+        )
+        .await
+        .expect("create");
+        assert!(matches!(
+            accept_adjustment(&pool, proposal.id, user_id, &ctx).await,
+            Err(AppError::Conflict(_))
+        ));
+    }
+}
@@ -0,0 +1,258 @@
+use serde::Serialize;
+use sqlx::FromRow;
+use sqlx::sqlite::{SqliteConnection, SqlitePool};
+
+use crate::error::AppResult;
+
+// ---------------------------------------------------------------------------
+// ChatMessage
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ChatMessage {
+    pub id: i64,
+    pub user_id: i64,
+    /// 'athlete', 'coach' or 'system'.
+    pub role: String,
+    pub content: String,
+    /// What produced the message, e.g. 'freeform' or 'workout_analysis'.
+    pub context_type: Option<String>,
+    pub related_workout_id: Option<i64>,
+    pub created_at: String,
+}
+
+pub struct CreateChatMessage {
+    pub user_id: i64,
+    pub role: String,
+    pub content: String,
+    pub context_type: Option<String>,
+    pub related_workout_id: Option<i64>,
+}
+
+/// Append a message to the user's chat timeline.
+pub async fn create_chat_message(
+    pool: &SqlitePool,
+    input: &CreateChatMessage,
+) -> AppResult<ChatMessage> {
+    let mut conn = pool.acquire().await?;
+    insert_chat_message(&mut conn, input).await
+}
+
+/// Append an athlete message and the coach's reply in one transaction, so
+/// the timeline never holds a message without its reply.
+pub async fn create_chat_exchange(
+    pool: &SqlitePool,
+    message: &CreateChatMessage,
+    reply: &CreateChatMessage,
+) -> AppResult<(ChatMessage, ChatMessage)> {
+    let mut tx = pool.begin().await?;
+    let message = insert_chat_message(&mut tx, message).await?;
+    let reply = insert_chat_message(&mut tx, reply).await?;
+    tx.commit().await?;
+    Ok((message, reply))
+}
+
+/// Post a workout analysis's message, replacing the message of an earlier
+/// analysis of the same workout, so a regenerated analysis is not posted
+/// twice.
+pub async fn replace_workout_analysis_message(
+    conn: &mut SqliteConnection,
+    user_id: i64,
+    workout_id: i64,
+    content: &str,
+) -> AppResult<ChatMessage> {
+    let replaced = sqlx::query_as::<_, ChatMessage>(
+        r#"UPDATE chat_messages SET content = ?
+           WHERE user_id = ? AND related_workout_id = ? AND context_type = 'workout_analysis'
+           RETURNING id, user_id, role, content, context_type, related_workout_id, created_at"#,
+    )
+    .bind(content)
+    .bind(user_id)
+    .bind(workout_id)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    match replaced {
+        Some(message) => Ok(message),
+        None => {
+            insert_chat_message(
+                conn,
+                &CreateChatMessage {
+                    user_id,
+                    role: "coach".to_string(),
+                    content: content.to_string(),
+                    context_type: Some("workout_analysis".to_string()),
+                    related_workout_id: Some(workout_id),
+                },
+            )
+            .await
+        }
+    }
+}
+
+async fn insert_chat_message(
+    conn: &mut SqliteConnection,
+    input: &CreateChatMessage,
+) -> AppResult<ChatMessage> {
+    let message = sqlx::query_as::<_, ChatMessage>(
+        r#"INSERT INTO chat_messages (user_id, role, content, context_type, related_workout_id)
+           VALUES (?, ?, ?, ?, ?)
+           RETURNING id, user_id, role, content, context_type, related_workout_id, created_at"#,
+    )
+    .bind(input.user_id)
+    .bind(&input.role)
+    .bind(&input.content)
+    .bind(&input.context_type)
+    .bind(input.related_workout_id)
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(message)
+}
+
+/// Up to `limit` of the user's messages, oldest first. With `before`, only
+/// messages older than that message ID (cursor pagination from newest).
+pub async fn list_chat_messages(
+    pool: &SqlitePool,
+    user_id: i64,
+    limit: i64,
+    before: Option<i64>,
+) -> AppResult<Vec<ChatMessage>> {
+    let mut messages = sqlx::query_as::<_, ChatMessage>(
+        r#"SELECT id, user_id, role, content, context_type, related_workout_id, created_at
+           FROM chat_messages
+           WHERE user_id = ? AND (? IS NULL OR id < ?)
+           ORDER BY id DESC
+           LIMIT ?"#,
+    )
+    .bind(user_id)
+    .bind(before)
+    .bind(before)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    messages.reverse();
+    Ok(messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::Row;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+
+    async fn setup_pool() -> SqlitePool {
+        let opts = SqliteConnectOptions::new()
+            .filename(":memory:")
+            .create_if_missing(true)
+            .pragma("foreign_keys", "ON");
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(opts)
+            .await
+            .expect("Failed to create test pool");
+
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    async fn create_test_user(pool: &SqlitePool, email: &str) -> i64 {
+        let row =
+            sqlx::query("INSERT INTO users (email, password_hash) VALUES (?, 'hash') RETURNING id")
+                .bind(email)
+                .fetch_one(pool)
+                .await
+                .expect("create test user");
+        row.get("id")
+    }
+
+    fn message(user_id: i64, role: &str, content: &str) -> CreateChatMessage {
+        CreateChatMessage {
+            user_id,
+            role: role.to_string(),
+            content: content.to_string(),
+            context_type: Some("freeform".to_string()),
+            related_workout_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_and_list_chat_messages() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool, "test@example.com").await;
+        let other_id = create_test_user(&pool, "other@example.com").await;
+
+        let mut ids = vec![];
+        for i in 0..5 {
+            let role = if i % 2 == 0 { "athlete" } else { "coach" };
+            let created = create_chat_message(&pool, &message(user_id, role, &format!("m{i}")))
+                .await
+                .expect("create message");
+            assert_eq!(created.role, role);
+            ids.push(created.id);
+        }
+        create_chat_message(&pool, &message(other_id, "athlete", "not yours"))
+            .await
+            .unwrap();
+
+        let all = list_chat_messages(&pool, user_id, 50, None).await.unwrap();
+        let contents: Vec<_> = all.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, ["m0", "m1", "m2", "m3", "m4"]);
+
+        // The newest page first, then older pages by cursor, each oldest first.
+        let page = list_chat_messages(&pool, user_id, 2, None).await.unwrap();
+        assert_eq!(page.iter().map(|m| m.id).collect::<Vec<_>>(), ids[3..]);
+        let page = list_chat_messages(&pool, user_id, 2, Some(ids[3]))
+            .await
+            .unwrap();
+        assert_eq!(page.iter().map(|m| m.id).collect::<Vec<_>>(), ids[1..3]);
+        let page = list_chat_messages(&pool, user_id, 2, Some(ids[1]))
+            .await
+            .unwrap();
+        assert_eq!(page.iter().map(|m| m.id).collect::<Vec<_>>(), ids[..1]);
+    }
+
+    #[tokio::test]
+    async fn test_chat_message_role_is_checked() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool, "test@example.com").await;
+
+        let result = create_chat_message(&pool, &message(user_id, "assistant", "hi")).await;
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_chat_exchange_is_stored_together() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool, "test@example.com").await;
+
+        let (question, answer) = create_chat_exchange(
+            &pool,
+            &message(user_id, "athlete", "How was my week?"),
+            &message(user_id, "coach", "Solid."),
+        )
+        .await
+        .expect("create exchange");
+        assert_eq!(question.role, "athlete");
+        assert_eq!(answer.role, "coach");
+        assert!(answer.id > question.id);
+
+        // A reply that cannot be stored takes the message with it
+        let result = create_chat_exchange(
+            &pool,
+            &message(user_id, "athlete", "And now?"),
+            &message(user_id, "assistant", "Invalid role."),
+        )
+        .await;
+        assert!(result.is_err());
+        let all = list_chat_messages(&pool, user_id, 50, None).await.unwrap();
+        let contents: Vec<_> = all.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, ["How was my week?", "Solid."]);
+    }
+}
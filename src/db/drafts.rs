@@ -0,0 +1,529 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use sqlx::sqlite::SqlitePool;
+
+use crate::domain::skeleton::MacrocycleSkeleton;
+use crate::error::{AppError, AppResult};
+
+/// How long a generated skeleton can be edited and confirmed.
+pub const DRAFT_TTL: TimeDelta = TimeDelta::hours(48);
+
+// ---------------------------------------------------------------------------
+// PlanDraft
+// ---------------------------------------------------------------------------
+
+#[derive(FromRow)]
+struct DraftRow {
+    id: i64,
+    user_id: i64,
+    race_goal_id: i64,
+    skeleton: String,
+    version: i64,
+    status: String,
+    macrocycle_id: Option<i64>,
+    expires_at: String,
+    created_at: String,
+    updated_at: String,
+}
+
+/// A generated macrocycle skeleton awaiting confirmation. Serializes with the
+/// skeleton's fields inline, next to the draft's own.
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanDraft {
+    pub id: i64,
+    pub user_id: i64,
+    pub race_goal_id: i64,
+    /// Starts at 1 and increases with every edit.
+    pub version: i64,
+    /// 'draft' or 'confirmed'.
+    pub status: String,
+    /// The plan created on confirmation.
+    pub macrocycle_id: Option<i64>,
+    pub expires_at: String,
+    pub created_at: String,
+    pub updated_at: String,
+    #[serde(flatten)]
+    pub skeleton: MacrocycleSkeleton,
+}
+
+impl TryFrom<DraftRow> for PlanDraft {
+    type Error = AppError;
+
+    fn try_from(row: DraftRow) -> AppResult<Self> {
+        let skeleton = serde_json::from_str(&row.skeleton).map_err(|e| {
+            AppError::Internal(format!("Invalid stored plan draft {}: {e}", row.id))
+        })?;
+        Ok(Self {
+            id: row.id,
+            user_id: row.user_id,
+            race_goal_id: row.race_goal_id,
+            version: row.version,
+            status: row.status,
+            macrocycle_id: row.macrocycle_id,
+            expires_at: row.expires_at,
+            created_at: row.created_at,
+            updated_at: row.updated_at,
+            skeleton,
+        })
+    }
+}
+
+const DRAFT_COLUMNS: &str = "id, user_id, race_goal_id, skeleton, version, status, macrocycle_id, \
+     expires_at, created_at, updated_at";
+
+fn skeleton_json(skeleton: &MacrocycleSkeleton) -> AppResult<String> {
+    serde_json::to_string(skeleton)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize plan draft: {e}")))
+}
+
+/// Store a freshly generated skeleton, open for `DRAFT_TTL` from `now`.
+pub async fn create_draft(
+    pool: &SqlitePool,
+    user_id: i64,
+    race_goal_id: i64,
+    skeleton: &MacrocycleSkeleton,
+    now: DateTime<Utc>,
+) -> AppResult<PlanDraft> {
+    let skeleton = skeleton_json(skeleton)?;
+    let mut tx = pool.begin().await?;
+    let row = sqlx::query_as::<_, DraftRow>(&format!(
+        r#"INSERT INTO plan_drafts (user_id, race_goal_id, skeleton, expires_at)
+           VALUES (?, ?, ?, ?)
+           RETURNING {DRAFT_COLUMNS}"#
+    ))
+    .bind(user_id)
+    .bind(race_goal_id)
+    .bind(&skeleton)
+    .bind((now + DRAFT_TTL).to_rfc3339())
+    .fetch_one(&mut *tx)
+    .await?;
+    insert_revision(&mut tx, row.id, row.version, &skeleton, "generated", None).await?;
+    tx.commit().await?;
+
+    row.try_into()
+}
+
+/// The user's draft if it is still open: not confirmed and not expired as
+/// of `now`.
+pub async fn get_open_draft(
+    pool: &SqlitePool,
+    user_id: i64,
+    draft_id: i64,
+    now: DateTime<Utc>,
+) -> AppResult<Option<PlanDraft>> {
+    let row = sqlx::query_as::<_, DraftRow>(&format!(
+        r#"SELECT {DRAFT_COLUMNS} FROM plan_drafts
+           WHERE id = ? AND user_id = ? AND status = 'draft'
+             AND datetime(expires_at) > datetime(?)"#
+    ))
+    .bind(draft_id)
+    .bind(user_id)
+    .bind(now.to_rfc3339())
+    .fetch_optional(pool)
+    .await?;
+
+    row.map(PlanDraft::try_from).transpose()
+}
+
+/// Replace an open draft's skeleton, bumping its version and recording the
+/// new version in the draft's history. `source` is 'edited' or 'revised';
+/// `feedback` is the athlete's request behind a revision. Returns `None` if
+/// the draft is no longer open or was changed since `expected_version`.
+pub async fn update_draft_skeleton(
+    pool: &SqlitePool,
+    draft_id: i64,
+    expected_version: i64,
+    skeleton: &MacrocycleSkeleton,
+    source: &str,
+    feedback: Option<&str>,
+) -> AppResult<Option<PlanDraft>> {
+    let skeleton = skeleton_json(skeleton)?;
+    let mut tx = pool.begin().await?;
+    let row = sqlx::query_as::<_, DraftRow>(&format!(
+        r#"UPDATE plan_drafts
+           SET skeleton = ?, version = version + 1, updated_at = datetime('now')
+           WHERE id = ? AND version = ? AND status = 'draft'
+           RETURNING {DRAFT_COLUMNS}"#
+    ))
+    .bind(&skeleton)
+    .bind(draft_id)
+    .bind(expected_version)
+    .fetch_optional(&mut *tx)
+    .await?;
+    let Some(row) = row else {
+        return Ok(None);
+    };
+    insert_revision(&mut tx, row.id, row.version, &skeleton, source, feedback).await?;
+    tx.commit().await?;
+
+    row.try_into().map(Some)
+}
+
+/// Claim an open draft for confirmation before its plan is generated, so a
+/// second confirmation cannot generate another plan from it. Returns false
+/// if the draft is no longer open or was changed since `expected_version`.
+pub async fn claim_draft(
+    pool: &SqlitePool,
+    draft_id: i64,
+    expected_version: i64,
+) -> AppResult<bool> {
+    let result = sqlx::query(
+        r#"UPDATE plan_drafts
+           SET status = 'confirming', updated_at = datetime('now')
+           WHERE id = ? AND version = ? AND status = 'draft'"#,
+    )
+    .bind(draft_id)
+    .bind(expected_version)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Reopen a claimed draft after its plan could not be generated.
+pub async fn release_draft(pool: &SqlitePool, draft_id: i64) -> AppResult<()> {
+    sqlx::query(
+        r#"UPDATE plan_drafts SET status = 'draft', updated_at = datetime('now')
+           WHERE id = ? AND status = 'confirming'"#,
+    )
+    .bind(draft_id)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Close a claimed draft once its plan has been created. Returns false if
+/// the draft was not claimed.
+pub async fn mark_draft_confirmed(
+    pool: &SqlitePool,
+    draft_id: i64,
+    macrocycle_id: i64,
+) -> AppResult<bool> {
+    let result = sqlx::query(
+        r#"UPDATE plan_drafts
+           SET status = 'confirmed', macrocycle_id = ?, updated_at = datetime('now')
+           WHERE id = ? AND status = 'confirming'"#,
+    )
+    .bind(macrocycle_id)
+    .bind(draft_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Delete unconfirmed drafts that have expired as of `now`. Returns the
+/// number removed.
+pub async fn delete_expired_drafts(pool: &SqlitePool, now: DateTime<Utc>) -> AppResult<u64> {
+    let result = sqlx::query(
+        "DELETE FROM plan_drafts WHERE status = 'draft' AND datetime(expires_at) <= datetime(?)",
+    )
+    .bind(now.to_rfc3339())
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+// ---------------------------------------------------------------------------
+// DraftRevision
+// ---------------------------------------------------------------------------
+
+#[derive(FromRow)]
+struct RevisionRow {
+    id: i64,
+    draft_id: i64,
+    version: i64,
+    skeleton: String,
+    source: String,
+    feedback: Option<String>,
+    created_at: String,
+}
+
+/// One version of a draft's skeleton.
+#[derive(Debug, Clone, Serialize)]
+pub struct DraftRevision {
+    pub id: i64,
+    pub draft_id: i64,
+    pub version: i64,
+    pub skeleton: MacrocycleSkeleton,
+    /// 'generated', 'edited' or 'revised'.
+    pub source: String,
+    /// The athlete's request that produced a 'revised' version.
+    pub feedback: Option<String>,
+    pub created_at: String,
+}
+
+impl TryFrom<RevisionRow> for DraftRevision {
+    type Error = AppError;
+
+    fn try_from(row: RevisionRow) -> AppResult<Self> {
+        let skeleton = serde_json::from_str(&row.skeleton).map_err(|e| {
+            AppError::Internal(format!(
+                "Invalid stored plan draft revision {}: {e}",
+                row.id
+            ))
+        })?;
+        Ok(Self {
+            id: row.id,
+            draft_id: row.draft_id,
+            version: row.version,
+            skeleton,
+            source: row.source,
+            feedback: row.feedback,
+            created_at: row.created_at,
+        })
+    }
+}
+
+async fn insert_revision(
+    tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+    draft_id: i64,
+    version: i64,
+    skeleton: &str,
+    source: &str,
+    feedback: Option<&str>,
+) -> AppResult<()> {
+    sqlx::query(
+        r#"INSERT INTO plan_draft_revisions (draft_id, version, skeleton, source, feedback)
+           VALUES (?, ?, ?, ?, ?)"#,
+    )
+    .bind(draft_id)
+    .bind(version)
+    .bind(skeleton)
+    .bind(source)
+    .bind(feedback)
+    .execute(&mut **tx)
+    .await?;
+    Ok(())
+}
+
+/// Every version of a draft, oldest first.
+pub async fn list_revisions(pool: &SqlitePool, draft_id: i64) -> AppResult<Vec<DraftRevision>> {
+    let rows = sqlx::query_as::<_, RevisionRow>(
+        r#"SELECT id, draft_id, version, skeleton, source, feedback, created_at
+           FROM plan_draft_revisions WHERE draft_id = ? ORDER BY version"#,
+    )
+    .bind(draft_id)
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter().map(DraftRevision::try_from).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::skeleton::MesocycleSkeleton;
+    use sqlx::Row;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+
+    async fn setup_pool() -> SqlitePool {
+        let opts = SqliteConnectOptions::new()
+            .filename(":memory:")
+            .create_if_missing(true)
+            .pragma("foreign_keys", "ON");
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(opts)
+            .await
+            .expect("Failed to create test pool");
+
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    /// A user with an active race goal. Returns (user_id, race_goal_id).
+    async fn create_test_user(pool: &SqlitePool, email: &str) -> (i64, i64) {
+        let user_id: i64 =
+            sqlx::query("INSERT INTO users (email, password_hash) VALUES (?, 'hash') RETURNING id")
+                .bind(email)
+                .fetch_one(pool)
+                .await
+                .expect("create test user")
+                .get("id");
+        let race_goal_id: i64 = sqlx::query(
+            r#"INSERT INTO race_goals (user_id, distance_m, race_date)
+               VALUES (?, 42195.0, '2026-09-27') RETURNING id"#,
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .expect("create race goal")
+        .get("id");
+        (user_id, race_goal_id)
+    }
+
+    fn skeleton(volume: f64) -> MacrocycleSkeleton {
+        MacrocycleSkeleton {
+            target_ctl: 55.0,
+            coach_message: "Build then sharpen.".to_string(),
+            mesocycles: vec![MesocycleSkeleton {
+                sequence_number: 1,
+                phase: "capacity".to_string(),
+                focus: "aerobic_capacity".to_string(),
+                load_weeks: 3,
+                recovery_weeks: 1,
+                target_volume_km: volume,
+            }],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_draft_lifecycle() {
+        let pool = setup_pool().await;
+        let (user_id, race_goal_id) = create_test_user(&pool, "test@example.com").await;
+        let (other_id, _) = create_test_user(&pool, "other@example.com").await;
+        let now = Utc::now();
+
+        let draft = create_draft(&pool, user_id, race_goal_id, &skeleton(40.0), now)
+            .await
+            .unwrap();
+        assert_eq!(draft.version, 1);
+        assert_eq!(draft.status, "draft");
+        assert_eq!(draft.skeleton.mesocycles[0].target_volume_km, 40.0);
+
+        assert!(
+            get_open_draft(&pool, other_id, draft.id, now)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let updated = update_draft_skeleton(&pool, draft.id, 1, &skeleton(42.0), "edited", None)
+            .await
+            .unwrap()
+            .expect("draft is open at version 1");
+        assert_eq!(updated.version, 2);
+        // A stale version is rejected.
+        assert!(
+            update_draft_skeleton(&pool, draft.id, 1, &skeleton(50.0), "edited", None)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let open = get_open_draft(&pool, user_id, draft.id, now)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(open.skeleton.mesocycles[0].target_volume_km, 42.0);
+
+        let revised = update_draft_skeleton(
+            &pool,
+            draft.id,
+            2,
+            &skeleton(44.0),
+            "revised",
+            Some("A little more volume please"),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(revised.version, 3);
+
+        // The stale update left no trace in the history
+        let revisions = list_revisions(&pool, draft.id).await.unwrap();
+        let history: Vec<_> = revisions
+            .iter()
+            .map(|r| {
+                (
+                    r.version,
+                    r.source.as_str(),
+                    r.skeleton.mesocycles[0].target_volume_km,
+                )
+            })
+            .collect();
+        assert_eq!(
+            history,
+            vec![
+                (1, "generated", 40.0),
+                (2, "edited", 42.0),
+                (3, "revised", 44.0)
+            ]
+        );
+        assert_eq!(
+            revisions[2].feedback.as_deref(),
+            Some("A little more volume please")
+        );
+
+        let macrocycle_id: i64 = sqlx::query(
+            r#"INSERT INTO macrocycles (user_id, race_goal_id, start_date, end_date)
+               VALUES (?, ?, '2026-06-01', '2026-09-27') RETURNING id"#,
+        )
+        .bind(user_id)
+        .bind(race_goal_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap()
+        .get("id");
+        // Only a claimed draft can be confirmed, and only once
+        assert!(
+            !mark_draft_confirmed(&pool, draft.id, macrocycle_id)
+                .await
+                .unwrap()
+        );
+        assert!(!claim_draft(&pool, draft.id, 2).await.unwrap());
+        assert!(claim_draft(&pool, draft.id, 3).await.unwrap());
+        assert!(!claim_draft(&pool, draft.id, 3).await.unwrap());
+        assert!(
+            get_open_draft(&pool, user_id, draft.id, now)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        release_draft(&pool, draft.id).await.unwrap();
+        assert!(claim_draft(&pool, draft.id, 3).await.unwrap());
+        assert!(
+            mark_draft_confirmed(&pool, draft.id, macrocycle_id)
+                .await
+                .unwrap()
+        );
+        assert!(
+            !mark_draft_confirmed(&pool, draft.id, macrocycle_id)
+                .await
+                .unwrap()
+        );
+        assert!(
+            get_open_draft(&pool, user_id, draft.id, now)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_drafts_expire() {
+        let pool = setup_pool().await;
+        let (user_id, race_goal_id) = create_test_user(&pool, "test@example.com").await;
+        let now = Utc::now();
+
+        let draft = create_draft(&pool, user_id, race_goal_id, &skeleton(40.0), now)
+            .await
+            .unwrap();
+        let later = now + DRAFT_TTL - TimeDelta::minutes(1);
+        assert!(
+            get_open_draft(&pool, user_id, draft.id, later)
+                .await
+                .unwrap()
+                .is_some()
+        );
+        assert_eq!(delete_expired_drafts(&pool, later).await.unwrap(), 0);
+
+        let expired = now + DRAFT_TTL;
+        assert!(
+            get_open_draft(&pool, user_id, draft.id, expired)
+                .await
+                .unwrap()
+                .is_none()
+        );
+        assert_eq!(delete_expired_drafts(&pool, expired).await.unwrap(), 1);
+    }
+}
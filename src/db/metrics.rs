@@ -0,0 +1,648 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+use sqlx::sqlite::SqlitePool;
+use sqlx::{FromRow, QueryBuilder, Row, Sqlite};
+
+use crate::domain::load_tracking::{DailyLoad, compute_series};
+use crate::error::{AppError, AppResult};
+
+// ---------------------------------------------------------------------------
+// DailyMetrics
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct DailyMetrics {
+    pub id: i64,
+    pub user_id: i64,
+    pub date: String,
+    pub total_tss: f64,
+    pub atl: f64,
+    pub ctl: f64,
+    pub tsb: f64,
+}
+
+impl DailyMetrics {
+    pub fn to_load(&self) -> AppResult<DailyLoad> {
+        Ok(DailyLoad {
+            date: parse_date(&self.date)?,
+            total_tss: self.total_tss,
+            atl: self.atl,
+            ctl: self.ctl,
+            tsb: self.tsb,
+        })
+    }
+}
+
+fn parse_date(date: &str) -> AppResult<NaiveDate> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|e| AppError::Internal(format!("Invalid date '{date}' in daily_metrics: {e}")))
+}
+
+/// The earliest daily metrics row for a user — the CTL/ATL bootstrap written
+/// when the profile was created. Everything after it is derived.
+pub async fn get_bootstrap_metrics(
+    pool: &SqlitePool,
+    user_id: i64,
+) -> AppResult<Option<DailyMetrics>> {
+    let row = sqlx::query_as::<_, DailyMetrics>(
+        r#"SELECT id, user_id, date, total_tss, atl, ctl, tsb
+           FROM daily_metrics WHERE user_id = ?
+           ORDER BY date ASC LIMIT 1"#,
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row)
+}
+
+/// Get the daily metrics row for a specific date (YYYY-MM-DD).
+pub async fn get_daily_metrics_for_date(
+    pool: &SqlitePool,
+    user_id: i64,
+    date: &str,
+) -> AppResult<Option<DailyMetrics>> {
+    let row = sqlx::query_as::<_, DailyMetrics>(
+        r#"SELECT id, user_id, date, total_tss, atl, ctl, tsb
+           FROM daily_metrics WHERE user_id = ? AND date = ?"#,
+    )
+    .bind(user_id)
+    .bind(date)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row)
+}
+
+/// The latest daily metrics row before `date` (YYYY-MM-DD): training load
+/// going into that day.
+pub async fn get_metrics_before(
+    pool: &SqlitePool,
+    user_id: i64,
+    date: &str,
+) -> AppResult<Option<DailyMetrics>> {
+    let row = sqlx::query_as::<_, DailyMetrics>(
+        r#"SELECT id, user_id, date, total_tss, atl, ctl, tsb
+           FROM daily_metrics WHERE user_id = ? AND date < ?
+           ORDER BY date DESC LIMIT 1"#,
+    )
+    .bind(user_id)
+    .bind(date)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(row)
+}
+
+/// CTL going into `date` (YYYY-MM-DD): the latest row before it, else the
+/// bootstrap row (workouts dated before the bootstrap), else 0.
+pub async fn get_ctl_before(pool: &SqlitePool, user_id: i64, date: &str) -> AppResult<f64> {
+    match get_metrics_before(pool, user_id, date).await? {
+        Some(row) => Ok(row.ctl),
+        None => Ok(get_bootstrap_metrics(pool, user_id)
+            .await?
+            .map_or(0.0, |row| row.ctl)),
+    }
+}
+
+/// The date (YYYY-MM-DD) of each user's latest daily metrics row, ordered by
+/// user. Used to roll every series forward over days without workouts.
+pub async fn get_latest_metrics_dates(pool: &SqlitePool) -> AppResult<Vec<(i64, String)>> {
+    let rows = sqlx::query(
+        "SELECT user_id, MAX(date) AS date FROM daily_metrics GROUP BY user_id ORDER BY user_id",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows
+        .iter()
+        .map(|r| (r.get("user_id"), r.get("date")))
+        .collect())
+}
+
+/// Get daily metrics between two dates (inclusive), ordered by date.
+pub async fn get_daily_metrics_range(
+    pool: &SqlitePool,
+    user_id: i64,
+    from: &str,
+    to: &str,
+) -> AppResult<Vec<DailyMetrics>> {
+    let rows = sqlx::query_as::<_, DailyMetrics>(
+        r#"SELECT id, user_id, date, total_tss, atl, ctl, tsb
+           FROM daily_metrics WHERE user_id = ? AND date >= ? AND date <= ?
+           ORDER BY date ASC"#,
+    )
+    .bind(user_id)
+    .bind(from)
+    .bind(to)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+// ---------------------------------------------------------------------------
+// Daily TSS
+// ---------------------------------------------------------------------------
+
+/// Actual TSS per day between two dates (inclusive).
+///
+/// Sums uploaded workouts (their own TSS, falling back to the linked plan's
+/// expected TSS while unscored) and planned workouts marked complete by hand
+/// without an upload (expected TSS, scaled by actual vs planned duration).
+pub async fn get_actual_tss_by_date(
+    pool: &SqlitePool,
+    user_id: i64,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> AppResult<BTreeMap<NaiveDate, f64>> {
+    let from = from.format("%Y-%m-%d").to_string();
+    let to = to.format("%Y-%m-%d").to_string();
+
+    let rows = sqlx::query(
+        r#"SELECT date, TOTAL(tss) AS total_tss FROM (
+               SELECT substr(cw.started_at, 1, 10) AS date,
+                      COALESCE(cw.tss, pw.expected_tss, 0) AS tss
+               FROM completed_workouts cw
+               LEFT JOIN planned_workouts pw ON pw.id = cw.planned_workout_id
+               WHERE cw.user_id = ? AND substr(cw.started_at, 1, 10) BETWEEN ? AND ?
+               UNION ALL
+               SELECT scheduled_date AS date,
+                      CASE WHEN actual_duration_min IS NOT NULL AND duration_min > 0
+                           THEN expected_tss * actual_duration_min / duration_min
+                           ELSE expected_tss END AS tss
+               FROM planned_workouts
+               WHERE user_id = ? AND is_completed = 1 AND completed_workout_id IS NULL
+                 AND expected_tss IS NOT NULL AND scheduled_date BETWEEN ? AND ?
+           )
+           GROUP BY date"#,
+    )
+    .bind(user_id)
+    .bind(&from)
+    .bind(&to)
+    .bind(user_id)
+    .bind(&from)
+    .bind(&to)
+    .fetch_all(pool)
+    .await?;
+
+    collect_tss(rows)
+}
+
+/// Planned (not yet completed) TSS per day between two dates (inclusive).
+/// Used to project ATL/CTL/TSB forward through the plan.
+pub async fn get_planned_tss_by_date(
+    pool: &SqlitePool,
+    user_id: i64,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> AppResult<BTreeMap<NaiveDate, f64>> {
+    let rows = sqlx::query(
+        r#"SELECT scheduled_date AS date, TOTAL(expected_tss) AS total_tss
+           FROM planned_workouts
+           WHERE user_id = ? AND is_completed = 0 AND expected_tss IS NOT NULL
+             AND scheduled_date BETWEEN ? AND ?
+           GROUP BY scheduled_date"#,
+    )
+    .bind(user_id)
+    .bind(from.format("%Y-%m-%d").to_string())
+    .bind(to.format("%Y-%m-%d").to_string())
+    .fetch_all(pool)
+    .await?;
+
+    collect_tss(rows)
+}
+
+fn collect_tss(rows: Vec<sqlx::sqlite::SqliteRow>) -> AppResult<BTreeMap<NaiveDate, f64>> {
+    rows.iter()
+        .map(|r| {
+            let date: String = r.get("date");
+            Ok((parse_date(&date)?, r.get::<f64, _>("total_tss")))
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Recomputation
+// ---------------------------------------------------------------------------
+
+/// Recompute ATL/CTL/TSB for every day from `from` through `today`.
+///
+/// The bootstrap row is never rewritten: it is the seed, and workouts on or
+/// before its date are already reflected in the bootstrap estimate. If the
+/// day before `from` has no row (a gap in the series), the whole series is
+/// rebuilt from the bootstrap. Idempotent: running it again, or over a wider
+/// range, produces the same rows. No-op for users without a bootstrap row.
+pub async fn recompute_daily_metrics(
+    pool: &SqlitePool,
+    user_id: i64,
+    from: NaiveDate,
+    today: NaiveDate,
+) -> AppResult<()> {
+    let Some(bootstrap) = get_bootstrap_metrics(pool, user_id).await? else {
+        return Ok(());
+    };
+    let bootstrap = bootstrap.to_load()?;
+
+    let seed = match from.pred_opt() {
+        Some(day_before) if day_before > bootstrap.date => {
+            let key = day_before.format("%Y-%m-%d").to_string();
+            match get_daily_metrics_for_date(pool, user_id, &key).await? {
+                Some(row) => row.to_load()?,
+                None => bootstrap,
+            }
+        }
+        _ => bootstrap,
+    };
+
+    let start = seed.date.succ_opt().unwrap_or(seed.date);
+    if start > today {
+        return Ok(());
+    }
+
+    let tss_by_date = get_actual_tss_by_date(pool, user_id, start, today).await?;
+    let series = compute_series(&seed, today, &tss_by_date);
+
+    let mut tx = pool.begin().await?;
+
+    // Anything from `start` on is derived; clear it (including rows past
+    // `today` left over from a clock change) and write the fresh series.
+    sqlx::query("DELETE FROM daily_metrics WHERE user_id = ? AND date >= ?")
+        .bind(user_id)
+        .bind(start.format("%Y-%m-%d").to_string())
+        .execute(&mut *tx)
+        .await?;
+
+    for chunk in series.chunks(500) {
+        let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+            "INSERT INTO daily_metrics (user_id, date, total_tss, atl, ctl, tsb) ",
+        );
+        builder.push_values(chunk, |mut b, day| {
+            b.push_bind(user_id)
+                .push_bind(day.date.format("%Y-%m-%d").to_string())
+                .push_bind(day.total_tss)
+                .push_bind(day.atl)
+                .push_bind(day.ctl)
+                .push_bind(day.tsb);
+        });
+        builder.build().execute(&mut *tx).await?;
+    }
+
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Project ATL/CTL/TSB from `today`'s row through `end` (inclusive) using
+/// the expected TSS of planned workouts not yet completed. Nothing is
+/// stored. Empty if the user has no row for `today` or `end` is not after it.
+pub async fn project_daily_metrics(
+    pool: &SqlitePool,
+    user_id: i64,
+    today: NaiveDate,
+    end: NaiveDate,
+) -> AppResult<Vec<DailyLoad>> {
+    let key = today.format("%Y-%m-%d").to_string();
+    let Some(seed) = get_daily_metrics_for_date(pool, user_id, &key).await? else {
+        return Ok(vec![]);
+    };
+    let Some(start) = today.succ_opt().filter(|start| *start <= end) else {
+        return Ok(vec![]);
+    };
+
+    let planned = get_planned_tss_by_date(pool, user_id, start, end).await?;
+    Ok(compute_series(&seed.to_load()?, end, &planned))
+}
+
+/// Recompute after a workout on `date` (YYYY-MM-DD or an RFC 3339 timestamp)
+/// was added, completed or removed.
+pub async fn recompute_after_workout_change(
+    pool: &SqlitePool,
+    user_id: i64,
+    date: &str,
+    today: NaiveDate,
+) -> AppResult<()> {
+    let date = parse_date(date.get(..10).unwrap_or(date))?;
+    recompute_daily_metrics(pool, user_id, date, today).await
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::profiles::create_daily_metrics;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+
+    async fn setup_pool() -> SqlitePool {
+        let opts = SqliteConnectOptions::new()
+            .filename(":memory:")
+            .create_if_missing(true)
+            .pragma("foreign_keys", "ON");
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(opts)
+            .await
+            .expect("Failed to create test pool");
+
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    async fn create_test_user(pool: &SqlitePool) -> i64 {
+        let row = sqlx::query(
+            "INSERT INTO users (email, password_hash) VALUES ('metrics@example.com', 'hash') RETURNING id",
+        )
+        .fetch_one(pool)
+        .await
+        .expect("create test user");
+        row.get("id")
+    }
+
+    async fn insert_completed(pool: &SqlitePool, user_id: i64, started_at: &str, tss: Option<f64>) -> i64 {
+        let row = sqlx::query(
+            r#"INSERT INTO completed_workouts (user_id, source, started_at, duration_seconds, distance_m, tss)
+               VALUES (?, 'fit_upload', ?, 3600, 10000.0, ?)
+               RETURNING id"#,
+        )
+        .bind(user_id)
+        .bind(started_at)
+        .bind(tss)
+        .fetch_one(pool)
+        .await
+        .expect("insert completed workout");
+        row.get("id")
+    }
+
+    /// Seed a profile-less plan: a macrocycle and mesocycle to hang planned workouts on.
+    async fn create_test_mesocycle(pool: &SqlitePool, user_id: i64) -> i64 {
+        let rg: i64 = sqlx::query(
+            r#"INSERT INTO race_goals (user_id, distance_m, race_date)
+               VALUES (?, 42195.0, '2026-09-27') RETURNING id"#,
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+        .get("id");
+        let mc: i64 = sqlx::query(
+            r#"INSERT INTO macrocycles (user_id, race_goal_id, start_date, end_date)
+               VALUES (?, ?, '2026-03-01', '2026-09-27') RETURNING id"#,
+        )
+        .bind(user_id)
+        .bind(rg)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+        .get("id");
+        sqlx::query(
+            r#"INSERT INTO mesocycles (macrocycle_id, sequence_number, phase, focus, load_weeks, recovery_weeks, target_volume_km, start_date, end_date)
+               VALUES (?, 1, 'capacity', 'aerobic_capacity', 3, 1, 160.0, '2026-03-01', '2026-03-28')
+               RETURNING id"#,
+        )
+        .bind(mc)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+        .get("id")
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    async fn all_rows(pool: &SqlitePool, user_id: i64) -> Vec<(String, f64, f64, f64, f64)> {
+        get_daily_metrics_range(pool, user_id, "0000-01-01", "9999-12-31")
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|m| (m.date, m.total_tss, m.atl, m.ctl, m.tsb))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_recompute_without_bootstrap_is_noop() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        recompute_daily_metrics(&pool, user_id, date("2026-03-01"), date("2026-03-10"))
+            .await
+            .expect("recompute");
+        assert!(all_rows(&pool, user_id).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_recompute_fills_rest_days_and_keeps_bootstrap() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        create_daily_metrics(&pool, user_id, "2026-03-01", 0.0, 30.0, 30.0, 0.0)
+            .await
+            .unwrap();
+        insert_completed(&pool, user_id, "2026-03-03T07:00:00+00:00", Some(90.0)).await;
+        // On the bootstrap day — already part of the bootstrap estimate
+        insert_completed(&pool, user_id, "2026-03-01T07:00:00+00:00", Some(500.0)).await;
+
+        recompute_daily_metrics(&pool, user_id, date("2026-03-01"), date("2026-03-05"))
+            .await
+            .expect("recompute");
+
+        let rows = all_rows(&pool, user_id).await;
+        let dates: Vec<_> = rows.iter().map(|r| r.0.as_str()).collect();
+        assert_eq!(
+            dates,
+            vec!["2026-03-01", "2026-03-02", "2026-03-03", "2026-03-04", "2026-03-05"]
+        );
+        assert_eq!(rows[0], ("2026-03-01".to_string(), 0.0, 30.0, 30.0, 0.0));
+        assert_eq!(rows[1].1, 0.0);
+        assert_eq!(rows[2].1, 90.0);
+        assert!(rows[2].3 > rows[1].3, "CTL rises on the workout day");
+        assert!(rows[3].3 < rows[2].3, "CTL decays on the rest day after");
+    }
+
+    #[tokio::test]
+    async fn test_incremental_updates_match_full_backfill() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        create_daily_metrics(&pool, user_id, "2026-03-01", 0.0, 25.0, 35.0, 10.0)
+            .await
+            .unwrap();
+
+        // Daily updates as workouts come in
+        recompute_daily_metrics(&pool, user_id, date("2026-03-02"), date("2026-03-02"))
+            .await
+            .unwrap();
+        insert_completed(&pool, user_id, "2026-03-03T07:00:00+00:00", Some(70.0)).await;
+        recompute_daily_metrics(&pool, user_id, date("2026-03-03"), date("2026-03-03"))
+            .await
+            .unwrap();
+        recompute_daily_metrics(&pool, user_id, date("2026-03-04"), date("2026-03-05"))
+            .await
+            .unwrap();
+        insert_completed(&pool, user_id, "2026-03-06T07:00:00+00:00", Some(110.0)).await;
+        recompute_daily_metrics(&pool, user_id, date("2026-03-06"), date("2026-03-08"))
+            .await
+            .unwrap();
+        let incremental = all_rows(&pool, user_id).await;
+
+        // A full backfill (run twice) must produce exactly the same rows
+        for _ in 0..2 {
+            recompute_daily_metrics(&pool, user_id, date("2026-03-01"), date("2026-03-08"))
+                .await
+                .unwrap();
+            assert_eq!(all_rows(&pool, user_id).await, incremental);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_late_upload_rewrites_following_days() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        create_daily_metrics(&pool, user_id, "2026-03-01", 0.0, 30.0, 30.0, 0.0)
+            .await
+            .unwrap();
+        recompute_daily_metrics(&pool, user_id, date("2026-03-01"), date("2026-03-06"))
+            .await
+            .unwrap();
+        let before = all_rows(&pool, user_id).await;
+
+        insert_completed(&pool, user_id, "2026-03-03T07:00:00+00:00", Some(80.0)).await;
+        recompute_after_workout_change(&pool, user_id, "2026-03-03T07:00:00+00:00", date("2026-03-06"))
+            .await
+            .unwrap();
+        let after = all_rows(&pool, user_id).await;
+
+        assert_eq!(before[..2], after[..2], "days before the upload are untouched");
+        assert_eq!(after[2].1, 80.0);
+        assert!(after[5].3 > before[5].3, "later days carry the extra load");
+    }
+
+    #[tokio::test]
+    async fn test_actual_tss_sources() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        let meso_id = create_test_mesocycle(&pool, user_id).await;
+
+        // Manually completed, ran 30 of 60 planned minutes → half of expected TSS
+        sqlx::query(
+            r#"INSERT INTO planned_workouts
+                (mesocycle_id, user_id, scheduled_date, workout_type, duration_min, expected_tss,
+                 is_completed, actual_duration_min)
+               VALUES (?, ?, '2026-03-02', 'easy_run', 60, 50.0, 1, 30)"#,
+        )
+        .bind(meso_id)
+        .bind(user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // Uploaded but unscored, linked to a plan → planned TSS stands in
+        let planned_id: i64 = sqlx::query(
+            r#"INSERT INTO planned_workouts
+                (mesocycle_id, user_id, scheduled_date, workout_type, duration_min, expected_tss)
+               VALUES (?, ?, '2026-03-03', 'tempo_run', 50, 70.0) RETURNING id"#,
+        )
+        .bind(meso_id)
+        .bind(user_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap()
+        .get("id");
+        let cw = insert_completed(&pool, user_id, "2026-03-03T07:00:00+00:00", None).await;
+        sqlx::query("UPDATE completed_workouts SET planned_workout_id = ? WHERE id = ?")
+            .bind(planned_id)
+            .bind(cw)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE planned_workouts SET is_completed = 1, completed_workout_id = ? WHERE id = ?")
+            .bind(cw)
+            .bind(planned_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // Two scored workouts on one day are summed; an unplanned unscored one adds 0
+        insert_completed(&pool, user_id, "2026-03-04T07:00:00+00:00", Some(40.0)).await;
+        insert_completed(&pool, user_id, "2026-03-04T18:00:00+00:00", Some(25.0)).await;
+        insert_completed(&pool, user_id, "2026-03-05T07:00:00+00:00", None).await;
+
+        let tss = get_actual_tss_by_date(&pool, user_id, date("2026-03-01"), date("2026-03-31"))
+            .await
+            .unwrap();
+        assert_eq!(tss.get(&date("2026-03-02")), Some(&25.0));
+        assert_eq!(tss.get(&date("2026-03-03")), Some(&70.0));
+        assert_eq!(tss.get(&date("2026-03-04")), Some(&65.0));
+        assert_eq!(tss.get(&date("2026-03-05")), Some(&0.0));
+
+        // Only the uncompleted plan entries count towards projection
+        let planned = get_planned_tss_by_date(&pool, user_id, date("2026-03-01"), date("2026-03-31"))
+            .await
+            .unwrap();
+        assert!(planned.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_projection_uses_planned_tss_and_is_not_stored() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        let meso_id = create_test_mesocycle(&pool, user_id).await;
+        create_daily_metrics(&pool, user_id, "2026-03-01", 0.0, 30.0, 30.0, 0.0)
+            .await
+            .unwrap();
+        recompute_daily_metrics(&pool, user_id, date("2026-03-01"), date("2026-03-03"))
+            .await
+            .unwrap();
+        for (day, tss) in [("2026-03-04", 60.0), ("2026-03-06", 90.0)] {
+            sqlx::query(
+                r#"INSERT INTO planned_workouts (mesocycle_id, user_id, scheduled_date, workout_type, expected_tss)
+                   VALUES (?, ?, ?, 'easy_run', ?)"#,
+            )
+            .bind(meso_id)
+            .bind(user_id)
+            .bind(day)
+            .bind(tss)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let projected = project_daily_metrics(&pool, user_id, date("2026-03-03"), date("2026-03-07"))
+            .await
+            .unwrap();
+        let tss: Vec<_> = projected.iter().map(|d| d.total_tss).collect();
+        assert_eq!(tss, vec![60.0, 0.0, 90.0, 0.0]);
+        assert_eq!(projected[0].date, date("2026-03-04"));
+        assert_eq!(all_rows(&pool, user_id).await.len(), 3, "projection is not persisted");
+
+        let none = project_daily_metrics(&pool, user_id, date("2026-03-03"), date("2026-03-03"))
+            .await
+            .unwrap();
+        assert!(none.is_empty());
+    }
+
+    #[tokio::test]
+    async fn ctl_before_falls_back_to_bootstrap() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        assert_eq!(get_ctl_before(&pool, user_id, "2026-03-05").await.unwrap(), 0.0);
+
+        create_daily_metrics(&pool, user_id, "2026-03-01", 0.0, 30.0, 40.0, 10.0)
+            .await
+            .unwrap();
+        create_daily_metrics(&pool, user_id, "2026-03-02", 50.0, 32.0, 42.0, 10.0)
+            .await
+            .unwrap();
+
+        assert_eq!(get_ctl_before(&pool, user_id, "2026-03-05").await.unwrap(), 42.0);
+        assert_eq!(get_ctl_before(&pool, user_id, "2026-03-02").await.unwrap(), 40.0);
+        // Before the bootstrap date: use the bootstrap fitness
+        assert_eq!(get_ctl_before(&pool, user_id, "2026-02-01").await.unwrap(), 40.0);
+    }
+}
@@ -2,3 +2,8 @@ pub mod users;
 pub mod sessions;
 pub mod profiles;
 pub mod plans;
+pub mod workouts;
+pub mod metrics;
+pub mod adjustments;
+pub mod chat;
+pub mod drafts;
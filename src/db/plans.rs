@@ -1,8 +1,12 @@
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use sqlx::sqlite::SqlitePool;
+use sqlx::sqlite::{SqliteConnection, SqlitePool};
 use sqlx::{FromRow, Row};
 
+use crate::domain::compliance::Prescription;
+use crate::domain::scoring::{format_zone_list, parse_zone_list};
+use crate::domain::types::{HrZones, PaceZones};
+use crate::domain::workouts::{DurationCategory, WorkoutRegistry, WorkoutType};
 use crate::error::AppResult;
 
 // ---------------------------------------------------------------------------
@@ -36,6 +40,14 @@ pub struct CreateMacrocycle {
 pub async fn create_macrocycle(
     pool: &SqlitePool,
     input: &CreateMacrocycle,
+) -> AppResult<Macrocycle> {
+    let mut conn = pool.acquire().await?;
+    insert_macrocycle(&mut conn, input).await
+}
+
+async fn insert_macrocycle(
+    conn: &mut SqliteConnection,
+    input: &CreateMacrocycle,
 ) -> AppResult<Macrocycle> {
     let now = Utc::now().to_rfc3339();
 
@@ -51,7 +63,7 @@ pub async fn create_macrocycle(
     .bind(input.target_ctl)
     .bind(&input.coach_message)
     .bind(&now)
-    .fetch_one(pool)
+    .fetch_one(&mut *conn)
     .await?;
 
     Ok(Macrocycle {
@@ -95,6 +107,43 @@ pub async fn get_current_macrocycle(
     }))
 }
 
+/// Store a new plan in one transaction: the macrocycle, its mesocycles (their
+/// `macrocycle_id` is set here) and the first mesocycle's workouts (their
+/// `mesocycle_id` is set here). The first mesocycle is made active. Returns
+/// what was stored.
+pub async fn create_plan(
+    pool: &SqlitePool,
+    macrocycle: &CreateMacrocycle,
+    mesocycles: Vec<CreateMesocycle>,
+    first_workouts: Vec<CreatePlannedWorkout>,
+) -> AppResult<(Macrocycle, Vec<Mesocycle>, Vec<PlannedWorkout>)> {
+    let mut tx = pool.begin().await?;
+
+    let macrocycle = insert_macrocycle(&mut tx, macrocycle).await?;
+    let mut stored_mesocycles = Vec::new();
+    for mut meso in mesocycles {
+        meso.macrocycle_id = macrocycle.id;
+        stored_mesocycles.push(insert_mesocycle(&mut tx, &meso).await?);
+    }
+
+    let mut workouts = Vec::new();
+    if let Some(first) = stored_mesocycles.first_mut() {
+        sqlx::query("UPDATE mesocycles SET status = 'active' WHERE id = ?")
+            .bind(first.id)
+            .execute(&mut *tx)
+            .await?;
+        first.status = "active".to_string();
+
+        for mut workout in first_workouts {
+            workout.mesocycle_id = first.id;
+            workouts.push(insert_planned_workout(&mut tx, &workout).await?);
+        }
+    }
+
+    tx.commit().await?;
+    Ok((macrocycle, stored_mesocycles, workouts))
+}
+
 // ---------------------------------------------------------------------------
 // Mesocycle
 // ---------------------------------------------------------------------------
@@ -113,6 +162,9 @@ pub struct Mesocycle {
     pub end_date: String,
     pub status: String,
     pub evaluation_summary: Option<String>,
+    /// When the scheduler found the mesocycle due for its transition, if it
+    /// has been.
+    pub transition_due_at: Option<String>,
     pub created_at: String,
 }
 
@@ -133,6 +185,14 @@ pub struct CreateMesocycle {
 pub async fn create_mesocycle(
     pool: &SqlitePool,
     input: &CreateMesocycle,
+) -> AppResult<Mesocycle> {
+    let mut conn = pool.acquire().await?;
+    insert_mesocycle(&mut conn, input).await
+}
+
+async fn insert_mesocycle(
+    conn: &mut SqliteConnection,
+    input: &CreateMesocycle,
 ) -> AppResult<Mesocycle> {
     let now = Utc::now().to_rfc3339();
 
@@ -142,7 +202,8 @@ pub async fn create_mesocycle(
              target_volume_km, start_date, end_date, created_at)
            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
            RETURNING id, macrocycle_id, sequence_number, phase, focus, load_weeks, recovery_weeks,
-                     target_volume_km, start_date, end_date, status, evaluation_summary, created_at"#,
+                     target_volume_km, start_date, end_date, status, evaluation_summary,
+                     transition_due_at, created_at"#,
     )
     .bind(input.macrocycle_id)
     .bind(input.sequence_number)
@@ -154,7 +215,7 @@ pub async fn create_mesocycle(
     .bind(&input.start_date)
     .bind(&input.end_date)
     .bind(&now)
-    .fetch_one(pool)
+    .fetch_one(&mut *conn)
     .await?;
 
     Ok(Mesocycle {
@@ -170,6 +231,7 @@ pub async fn create_mesocycle(
         end_date: row.get("end_date"),
         status: row.get("status"),
         evaluation_summary: row.get("evaluation_summary"),
+        transition_due_at: row.get("transition_due_at"),
         created_at: row.get("created_at"),
     })
 }
@@ -181,7 +243,8 @@ pub async fn get_mesocycles(
 ) -> AppResult<Vec<Mesocycle>> {
     let rows = sqlx::query(
         r#"SELECT id, macrocycle_id, sequence_number, phase, focus, load_weeks, recovery_weeks,
-                  target_volume_km, start_date, end_date, status, evaluation_summary, created_at
+                  target_volume_km, start_date, end_date, status, evaluation_summary,
+                  transition_due_at, created_at
            FROM mesocycles WHERE macrocycle_id = ?
            ORDER BY sequence_number ASC"#,
     )
@@ -204,11 +267,246 @@ pub async fn get_mesocycles(
             end_date: r.get("end_date"),
             status: r.get("status"),
             evaluation_summary: r.get("evaluation_summary"),
+            transition_due_at: r.get("transition_due_at"),
             created_at: r.get("created_at"),
         })
         .collect())
 }
 
+/// Get a mesocycle by ID. Verifies user ownership via its macrocycle.
+pub async fn get_mesocycle(
+    pool: &SqlitePool,
+    mesocycle_id: i64,
+    user_id: i64,
+) -> AppResult<Option<Mesocycle>> {
+    let meso = sqlx::query_as::<_, Mesocycle>(
+        r#"SELECT m.id, m.macrocycle_id, m.sequence_number, m.phase, m.focus, m.load_weeks,
+                  m.recovery_weeks, m.target_volume_km, m.start_date, m.end_date, m.status,
+                  m.evaluation_summary, m.transition_due_at, m.created_at
+           FROM mesocycles m
+           JOIN macrocycles mc ON m.macrocycle_id = mc.id
+           WHERE m.id = ? AND mc.user_id = ?"#,
+    )
+    .bind(mesocycle_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(meso)
+}
+
+/// Get the mesocycle that follows `mesocycle` in its macrocycle, if any.
+pub async fn get_next_mesocycle(
+    pool: &SqlitePool,
+    mesocycle: &Mesocycle,
+) -> AppResult<Option<Mesocycle>> {
+    let meso = sqlx::query_as::<_, Mesocycle>(
+        r#"SELECT id, macrocycle_id, sequence_number, phase, focus, load_weeks, recovery_weeks,
+                  target_volume_km, start_date, end_date, status, evaluation_summary,
+                  transition_due_at, created_at
+           FROM mesocycles WHERE macrocycle_id = ? AND sequence_number = ?"#,
+    )
+    .bind(mesocycle.macrocycle_id)
+    .bind(mesocycle.sequence_number + 1)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(meso)
+}
+
+/// Set a mesocycle's status ('pending', 'active' or 'completed').
+pub async fn update_mesocycle_status(
+    pool: &SqlitePool,
+    mesocycle_id: i64,
+    status: &str,
+) -> AppResult<()> {
+    sqlx::query("UPDATE mesocycles SET status = ? WHERE id = ?")
+        .bind(status)
+        .bind(mesocycle_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Claim a mesocycle for its transition before it is evaluated, so a second
+/// transition cannot generate another next block. Returns false if the
+/// mesocycle is completed or already being transitioned.
+pub async fn claim_mesocycle_transition(pool: &SqlitePool, mesocycle_id: i64) -> AppResult<bool> {
+    let result = sqlx::query(
+        r#"UPDATE mesocycles SET transition_started_at = ?
+           WHERE id = ? AND status != 'completed' AND transition_started_at IS NULL"#,
+    )
+    .bind(Utc::now().to_rfc3339())
+    .bind(mesocycle_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Release a claimed mesocycle after its transition failed, so it can be
+/// retried.
+pub async fn release_mesocycle_transition(pool: &SqlitePool, mesocycle_id: i64) -> AppResult<()> {
+    sqlx::query("UPDATE mesocycles SET transition_started_at = NULL WHERE id = ?")
+        .bind(mesocycle_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// The next mesocycle's generated block, stored when its predecessor is
+/// completed.
+#[derive(Debug)]
+pub struct NextBlock {
+    pub mesocycle_id: i64,
+    pub workouts: Vec<CreatePlannedWorkout>,
+    /// The new weekly volume target, if the transition adjusted it.
+    pub target_volume_km: Option<f64>,
+}
+
+/// Mark a mesocycle completed with its evaluation, and store and activate
+/// the next block in the same transaction. Returns the next block's stored
+/// workouts.
+pub async fn complete_mesocycle(
+    pool: &SqlitePool,
+    mesocycle_id: i64,
+    evaluation_summary: &str,
+    next: Option<NextBlock>,
+) -> AppResult<Vec<PlannedWorkout>> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"UPDATE mesocycles
+           SET status = 'completed', evaluation_summary = ?, transition_started_at = NULL
+           WHERE id = ?"#,
+    )
+    .bind(evaluation_summary)
+    .bind(mesocycle_id)
+    .execute(&mut *tx)
+    .await?;
+
+    let mut workouts = Vec::new();
+    if let Some(next) = next {
+        sqlx::query(
+            r#"UPDATE mesocycles
+               SET status = 'active', target_volume_km = COALESCE(?, target_volume_km)
+               WHERE id = ?"#,
+        )
+        .bind(next.target_volume_km)
+        .bind(next.mesocycle_id)
+        .execute(&mut *tx)
+        .await?;
+
+        for mut workout in next.workouts {
+            workout.mesocycle_id = next.mesocycle_id;
+            workouts.push(insert_planned_workout(&mut tx, &workout).await?);
+        }
+    }
+
+    tx.commit().await?;
+    Ok(workouts)
+}
+
+/// Activate pending mesocycles of active macrocycles that have started and
+/// already have workouts. Returns the number of mesocycles activated.
+pub async fn activate_started_mesocycles(pool: &SqlitePool, today: &str) -> AppResult<u64> {
+    let result = sqlx::query(
+        r#"UPDATE mesocycles SET status = 'active'
+           WHERE status = 'pending' AND start_date <= ? AND end_date >= ?
+             AND EXISTS (SELECT 1 FROM planned_workouts pw WHERE pw.mesocycle_id = mesocycles.id)
+             AND macrocycle_id IN (SELECT id FROM macrocycles WHERE status = 'active')"#,
+    )
+    .bind(today)
+    .bind(today)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// Complete active mesocycles whose end date has passed when there is no
+/// block left to generate: either they are the last mesocycle of their
+/// macrocycle, or the next one already has workouts. Mesocycles whose next
+/// block is still empty wait for a transition evaluation instead.
+pub async fn complete_ended_mesocycles(pool: &SqlitePool, today: &str) -> AppResult<u64> {
+    let result = sqlx::query(
+        r#"UPDATE mesocycles SET status = 'completed'
+           WHERE status = 'active' AND end_date < ?
+             AND NOT EXISTS (
+                 SELECT 1 FROM mesocycles n
+                 WHERE n.macrocycle_id = mesocycles.macrocycle_id
+                   AND n.sequence_number = mesocycles.sequence_number + 1
+                   AND NOT EXISTS (SELECT 1 FROM planned_workouts pw WHERE pw.mesocycle_id = n.id)
+             )"#,
+    )
+    .bind(today)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// An active mesocycle due for its transition evaluation, with its owner.
+#[derive(Debug, Clone, FromRow)]
+pub struct DueMesocycle {
+    pub user_id: i64,
+    #[sqlx(flatten)]
+    pub mesocycle: Mesocycle,
+}
+
+/// Active mesocycles that need a transition evaluation as of `today`: the
+/// recovery week has been reached, the next mesocycle has no workouts yet,
+/// and either the end date has passed or the last non-rest workout is done.
+pub async fn get_mesocycles_due_for_transition(
+    pool: &SqlitePool,
+    today: &str,
+) -> AppResult<Vec<DueMesocycle>> {
+    let rows = sqlx::query_as::<_, DueMesocycle>(
+        r#"SELECT mc.user_id, m.id, m.macrocycle_id, m.sequence_number, m.phase, m.focus,
+                  m.load_weeks, m.recovery_weeks, m.target_volume_km, m.start_date, m.end_date,
+                  m.status, m.evaluation_summary, m.transition_due_at, m.created_at
+           FROM mesocycles m
+           JOIN macrocycles mc ON m.macrocycle_id = mc.id
+           JOIN mesocycles n
+             ON n.macrocycle_id = m.macrocycle_id AND n.sequence_number = m.sequence_number + 1
+           WHERE m.status = 'active' AND mc.status = 'active'
+             AND date(m.start_date, '+' || (m.load_weeks * 7) || ' days') <= ?1
+             AND EXISTS (SELECT 1 FROM planned_workouts pw WHERE pw.mesocycle_id = m.id)
+             AND NOT EXISTS (SELECT 1 FROM planned_workouts pw WHERE pw.mesocycle_id = n.id)
+             AND (m.end_date < ?1 OR (
+                 SELECT pw.is_completed FROM planned_workouts pw
+                 WHERE pw.mesocycle_id = m.id AND pw.workout_type != 'rest'
+                 ORDER BY pw.scheduled_date DESC LIMIT 1
+             ) = 1)
+           ORDER BY m.start_date ASC, m.id ASC"#,
+    )
+    .bind(today)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
+
+/// Flag a mesocycle as due for its transition, so the athlete is prompted to
+/// start it. Returns false if it was already flagged.
+pub async fn mark_transition_due(
+    pool: &SqlitePool,
+    mesocycle_id: i64,
+    today: &str,
+) -> AppResult<bool> {
+    let result = sqlx::query(
+        "UPDATE mesocycles SET transition_due_at = ? WHERE id = ? AND transition_due_at IS NULL",
+    )
+    .bind(today)
+    .bind(mesocycle_id)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
 // ---------------------------------------------------------------------------
 // PlannedWorkout
 // ---------------------------------------------------------------------------
@@ -237,6 +535,22 @@ pub struct PlannedWorkout {
     pub created_at: String,
 }
 
+impl PlannedWorkout {
+    /// What this session prescribes, for compliance scoring. `None` if the
+    /// stored workout type is unknown.
+    pub fn prescription(&self) -> Option<Prescription> {
+        let zones = |z: &Option<String>| z.as_deref().map(parse_zone_list).unwrap_or_default();
+        Some(Prescription {
+            workout_type: WorkoutType::from_str(&self.workout_type)?,
+            duration_min: self.duration_min,
+            target_distance_km: self.target_distance_km,
+            expected_tss: self.expected_tss,
+            target_hr_zones: zones(&self.target_hr_zones),
+            target_pace_zones: zones(&self.target_pace_zones),
+        })
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct CreatePlannedWorkout {
     pub mesocycle_id: i64,
@@ -257,6 +571,14 @@ pub struct CreatePlannedWorkout {
 pub async fn create_planned_workout(
     pool: &SqlitePool,
     input: &CreatePlannedWorkout,
+) -> AppResult<PlannedWorkout> {
+    let mut conn = pool.acquire().await?;
+    insert_planned_workout(&mut conn, input).await
+}
+
+async fn insert_planned_workout(
+    conn: &mut SqliteConnection,
+    input: &CreatePlannedWorkout,
 ) -> AppResult<PlannedWorkout> {
     let now = Utc::now().to_rfc3339();
 
@@ -285,7 +607,7 @@ pub async fn create_planned_workout(
     .bind(&input.coach_notes)
     .bind(input.target_distance_km)
     .bind(&now)
-    .fetch_one(pool)
+    .fetch_one(&mut *conn)
     .await?;
 
     Ok(PlannedWorkout {
@@ -370,7 +692,8 @@ pub async fn complete_workout(
 
     let row = sqlx::query(
         r#"UPDATE planned_workouts
-           SET is_completed = 1, completed_at = ?, rpe = ?, athlete_notes = ?, actual_duration_min = ?
+           SET is_completed = 1, completed_at = ?, completed_by_link = 0, rpe = ?,
+               athlete_notes = ?, actual_duration_min = ?
            WHERE id = ? AND user_id = ?
            RETURNING id, mesocycle_id, user_id, scheduled_date, workout_type, duration_min,
                      duration_category, target_hr_zones, target_pace_zones, expected_tss,
@@ -416,6 +739,251 @@ pub async fn complete_workout(
     }
 }
 
+/// Date (YYYY-MM-DD) of the user's last time trial scheduled before `before`,
+/// done or not.
+pub async fn get_last_time_trial_date(
+    pool: &SqlitePool,
+    user_id: i64,
+    before: &str,
+) -> AppResult<Option<String>> {
+    let date = sqlx::query_scalar::<_, Option<String>>(
+        r#"SELECT MAX(scheduled_date) FROM planned_workouts
+           WHERE user_id = ? AND workout_type = 'time_trial' AND scheduled_date < ?"#,
+    )
+    .bind(user_id)
+    .bind(before)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(date)
+}
+
+/// Find the planned workout an uploaded activity on `date` (YYYY-MM-DD) should
+/// be matched to: the first running workout that day not already linked to a
+/// completed workout. Rest days and strength sessions never match.
+pub async fn find_unlinked_planned_workout_for_date(
+    pool: &SqlitePool,
+    user_id: i64,
+    date: &str,
+) -> AppResult<Option<PlannedWorkout>> {
+    let workout = sqlx::query_as::<_, PlannedWorkout>(
+        r#"SELECT id, mesocycle_id, user_id, scheduled_date, workout_type, duration_min,
+                  duration_category, target_hr_zones, target_pace_zones, expected_tss,
+                  description, coach_notes, target_distance_km, is_completed,
+                  completed_workout_id, rpe, athlete_notes, actual_duration_min,
+                  completed_at, created_at
+           FROM planned_workouts
+           WHERE user_id = ? AND scheduled_date = ? AND completed_workout_id IS NULL
+             AND workout_type NOT IN
+                 ('rest', 'strength_precision', 'strength_performance', 'strength_power')
+           ORDER BY id ASC
+           LIMIT 1"#,
+    )
+    .bind(user_id)
+    .bind(date)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(workout)
+}
+
+/// All planned workouts scheduled on `date` (YYYY-MM-DD), completed or not.
+pub async fn get_workouts_for_date(
+    pool: &SqlitePool,
+    user_id: i64,
+    date: &str,
+) -> AppResult<Vec<PlannedWorkout>> {
+    let workouts = sqlx::query_as::<_, PlannedWorkout>(
+        r#"SELECT id, mesocycle_id, user_id, scheduled_date, workout_type, duration_min,
+                  duration_category, target_hr_zones, target_pace_zones, expected_tss,
+                  description, coach_notes, target_distance_km, is_completed,
+                  completed_workout_id, rpe, athlete_notes, actual_duration_min,
+                  completed_at, created_at
+           FROM planned_workouts
+           WHERE user_id = ? AND scheduled_date = ?
+           ORDER BY id ASC"#,
+    )
+    .bind(user_id)
+    .bind(date)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(workouts)
+}
+
+/// The next `limit` uncompleted planned workouts scheduled after `date`
+/// (YYYY-MM-DD), in date order.
+pub async fn get_upcoming_workouts(
+    pool: &SqlitePool,
+    user_id: i64,
+    date: &str,
+    limit: i64,
+) -> AppResult<Vec<PlannedWorkout>> {
+    let workouts = sqlx::query_as::<_, PlannedWorkout>(
+        r#"SELECT id, mesocycle_id, user_id, scheduled_date, workout_type, duration_min,
+                  duration_category, target_hr_zones, target_pace_zones, expected_tss,
+                  description, coach_notes, target_distance_km, is_completed,
+                  completed_workout_id, rpe, athlete_notes, actual_duration_min,
+                  completed_at, created_at
+           FROM planned_workouts
+           WHERE user_id = ? AND scheduled_date > ? AND is_completed = 0
+           ORDER BY scheduled_date ASC, id ASC
+           LIMIT ?"#,
+    )
+    .bind(user_id)
+    .bind(date)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(workouts)
+}
+
+/// Resolve the user's uncompleted workouts scheduled on or after `date`
+/// (YYYY-MM-DD) again with new zones, so their descriptions show the current
+/// ranges. Past and completed workouts, rest days and strength sessions are
+/// left alone. Returns the workouts that changed, in date order.
+pub async fn refresh_workout_zones(
+    pool: &SqlitePool,
+    user_id: i64,
+    date: &str,
+    hr_zones: &HrZones,
+    pace_zones: Option<&PaceZones>,
+) -> AppResult<Vec<PlannedWorkout>> {
+    let registry = WorkoutRegistry::new();
+    let mut tx = pool.begin().await?;
+
+    let workouts = sqlx::query_as::<_, PlannedWorkout>(
+        r#"SELECT id, mesocycle_id, user_id, scheduled_date, workout_type, duration_min,
+                  duration_category, target_hr_zones, target_pace_zones, expected_tss,
+                  description, coach_notes, target_distance_km, is_completed,
+                  completed_workout_id, rpe, athlete_notes, actual_duration_min,
+                  completed_at, created_at
+           FROM planned_workouts
+           WHERE user_id = ? AND scheduled_date >= ? AND is_completed = 0
+           ORDER BY scheduled_date ASC, id ASC"#,
+    )
+    .bind(user_id)
+    .bind(date)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut updated = Vec::new();
+    for workout in workouts {
+        let Some(workout_type) = WorkoutType::from_str(&workout.workout_type) else {
+            continue;
+        };
+        let duration_category = workout
+            .duration_category
+            .as_deref()
+            .and_then(DurationCategory::from_str)
+            .unwrap_or(DurationCategory::Medium);
+        let Some(resolved) =
+            registry.resolve(&workout_type, &duration_category, hr_zones, pace_zones)
+        else {
+            continue;
+        };
+
+        let hr = format_zone_list(&resolved.target_hr_zones);
+        let pace = format_zone_list(&resolved.target_pace_zones);
+        if workout.description.as_deref() == Some(resolved.description.as_str())
+            && workout.target_hr_zones == hr
+            && workout.target_pace_zones == pace
+        {
+            continue;
+        }
+
+        let workout = sqlx::query_as::<_, PlannedWorkout>(
+            r#"UPDATE planned_workouts
+               SET description = ?, target_hr_zones = ?, target_pace_zones = ?
+               WHERE id = ?
+               RETURNING id, mesocycle_id, user_id, scheduled_date, workout_type, duration_min,
+                         duration_category, target_hr_zones, target_pace_zones, expected_tss,
+                         description, coach_notes, target_distance_km, is_completed,
+                         completed_workout_id, rpe, athlete_notes, actual_duration_min,
+                         completed_at, created_at"#,
+        )
+        .bind(&resolved.description)
+        .bind(hr)
+        .bind(pace)
+        .bind(workout.id)
+        .fetch_one(&mut *tx)
+        .await?;
+        updated.push(workout);
+    }
+
+    tx.commit().await?;
+    Ok(updated)
+}
+
+/// Link a completed workout to a planned workout and mark the plan entry as
+/// completed. Existing athlete feedback (completed_at, actual duration) is
+/// kept if the workout was already marked complete by hand. Returns `None`
+/// without linking if the planned workout is gone or another workout was
+/// linked to it first.
+pub async fn link_completed_workout(
+    conn: &mut SqliteConnection,
+    planned_workout_id: i64,
+    completed_workout_id: i64,
+    actual_duration_min: i64,
+) -> AppResult<Option<PlannedWorkout>> {
+    let now = Utc::now().to_rfc3339();
+
+    let Some(workout) = sqlx::query_as::<_, PlannedWorkout>(
+        r#"UPDATE planned_workouts
+           SET completed_workout_id = ?, is_completed = 1,
+               completed_by_link = completed_at IS NULL,
+               completed_at = COALESCE(completed_at, ?),
+               actual_duration_min = COALESCE(actual_duration_min, ?)
+           WHERE id = ? AND completed_workout_id IS NULL
+           RETURNING id, mesocycle_id, user_id, scheduled_date, workout_type, duration_min,
+                     duration_category, target_hr_zones, target_pace_zones, expected_tss,
+                     description, coach_notes, target_distance_km, is_completed,
+                     completed_workout_id, rpe, athlete_notes, actual_duration_min,
+                     completed_at, created_at"#,
+    )
+    .bind(completed_workout_id)
+    .bind(&now)
+    .bind(actual_duration_min)
+    .bind(planned_workout_id)
+    .fetch_optional(&mut *conn)
+    .await?
+    else {
+        return Ok(None);
+    };
+
+    sqlx::query("UPDATE completed_workouts SET planned_workout_id = ? WHERE id = ?")
+        .bind(planned_workout_id)
+        .bind(completed_workout_id)
+        .execute(&mut *conn)
+        .await?;
+
+    Ok(Some(workout))
+}
+
+/// Detach a completed workout that is being deleted from the planned workout
+/// it was matched to and clear its actual duration. A planned workout the
+/// link completed returns to not-completed; one the athlete marked complete
+/// by hand stays completed.
+pub async fn unlink_completed_workout(
+    conn: &mut SqliteConnection,
+    completed_workout_id: i64,
+) -> AppResult<()> {
+    sqlx::query(
+        r#"UPDATE planned_workouts
+           SET completed_workout_id = NULL, actual_duration_min = NULL,
+               is_completed = CASE WHEN completed_by_link = 1 THEN 0 ELSE is_completed END,
+               completed_at = CASE WHEN completed_by_link = 1 THEN NULL ELSE completed_at END,
+               completed_by_link = 0
+           WHERE completed_workout_id = ?"#,
+    )
+    .bind(completed_workout_id)
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(())
+}
+
 /// Get workouts from the previous mesocycle (by sequence_number) within the same macrocycle.
 /// Returns empty Vec if this is the first mesocycle.
 pub async fn get_previous_mesocycle_workouts(
@@ -826,6 +1394,233 @@ mod tests {
         assert_eq!(mesocycles[1].phase, "utilization");
     }
 
+    #[tokio::test]
+    async fn test_mesocycle_lookup_and_completion() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        let race_goal_id = create_test_race_goal(&pool, user_id).await;
+        let mc = create_test_macrocycle(&pool, user_id, race_goal_id).await;
+        let meso1 = create_test_mesocycle(&pool, mc.id).await;
+        let meso2 = create_mesocycle(
+            &pool,
+            &CreateMesocycle {
+                macrocycle_id: mc.id,
+                sequence_number: 2,
+                phase: "utilization".to_string(),
+                focus: "aerobic_utilization".to_string(),
+                load_weeks: 2,
+                recovery_weeks: 1,
+                target_volume_km: Some(140.0),
+                start_date: "2026-03-29".to_string(),
+                end_date: "2026-04-18".to_string(),
+            },
+        )
+        .await
+        .expect("create meso 2");
+
+        let found = get_mesocycle(&pool, meso1.id, user_id)
+            .await
+            .expect("should not error")
+            .expect("should exist");
+        assert_eq!(found.status, "pending");
+        assert!(
+            get_mesocycle(&pool, meso1.id, user_id + 1)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let next = get_next_mesocycle(&pool, &meso1)
+            .await
+            .unwrap()
+            .expect("next");
+        assert_eq!(next.id, meso2.id);
+        assert!(get_next_mesocycle(&pool, &meso2).await.unwrap().is_none());
+
+        update_mesocycle_status(&pool, meso1.id, "active")
+            .await
+            .unwrap();
+        assert!(claim_mesocycle_transition(&pool, meso1.id).await.unwrap());
+        assert!(
+            !claim_mesocycle_transition(&pool, meso1.id).await.unwrap(),
+            "a claimed mesocycle cannot be claimed again"
+        );
+        release_mesocycle_transition(&pool, meso1.id).await.unwrap();
+        assert!(claim_mesocycle_transition(&pool, meso1.id).await.unwrap());
+
+        let next = NextBlock {
+            mesocycle_id: meso2.id,
+            workouts: vec![plan_workout_input(user_id, "2026-03-30", "easy_run")],
+            target_volume_km: Some(126.0),
+        };
+        let stored = complete_mesocycle(&pool, meso1.id, "Solid block.", Some(next))
+            .await
+            .expect("complete");
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].mesocycle_id, meso2.id);
+        assert!(
+            !claim_mesocycle_transition(&pool, meso1.id).await.unwrap(),
+            "a completed mesocycle cannot be claimed"
+        );
+
+        let meso1 = get_mesocycle(&pool, meso1.id, user_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(meso1.status, "completed");
+        assert_eq!(meso1.evaluation_summary.as_deref(), Some("Solid block."));
+        let meso2 = get_mesocycle(&pool, meso2.id, user_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(meso2.status, "active");
+        assert_eq!(meso2.target_volume_km, Some(126.0));
+    }
+
+    #[tokio::test]
+    async fn test_complete_mesocycle_rolls_back_on_failure() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        let race_goal_id = create_test_race_goal(&pool, user_id).await;
+        let (macrocycle, mesocycles) = plan_inputs(user_id, race_goal_id);
+        let (_, mesos, _) = create_plan(&pool, &macrocycle, mesocycles, Vec::new())
+            .await
+            .unwrap();
+        assert!(
+            claim_mesocycle_transition(&pool, mesos[0].id)
+                .await
+                .unwrap()
+        );
+
+        let next = NextBlock {
+            mesocycle_id: mesos[1].id,
+            workouts: vec![
+                plan_workout_input(user_id, "2026-03-30", "easy_run"),
+                plan_workout_input(user_id, "2026-03-31", "not_a_workout"),
+            ],
+            target_volume_km: Some(126.0),
+        };
+        assert!(
+            complete_mesocycle(&pool, mesos[0].id, "Solid block.", Some(next))
+                .await
+                .is_err()
+        );
+
+        let fetched = get_mesocycles(&pool, mesos[0].macrocycle_id).await.unwrap();
+        assert_eq!(fetched[0].status, "active");
+        assert!(fetched[0].evaluation_summary.is_none());
+        assert_eq!(fetched[1].status, "pending");
+        assert_eq!(fetched[1].target_volume_km, Some(160.0));
+        assert!(
+            get_planned_workouts(&pool, mesos[1].id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    fn plan_workout_input(
+        user_id: i64,
+        scheduled_date: &str,
+        workout_type: &str,
+    ) -> CreatePlannedWorkout {
+        CreatePlannedWorkout {
+            mesocycle_id: 0,
+            user_id,
+            scheduled_date: scheduled_date.to_string(),
+            workout_type: workout_type.to_string(),
+            duration_min: Some(45),
+            duration_category: None,
+            target_hr_zones: None,
+            target_pace_zones: None,
+            expected_tss: None,
+            description: None,
+            coach_notes: None,
+            target_distance_km: None,
+        }
+    }
+
+    fn plan_inputs(user_id: i64, race_goal_id: i64) -> (CreateMacrocycle, Vec<CreateMesocycle>) {
+        let macrocycle = CreateMacrocycle {
+            user_id,
+            race_goal_id,
+            start_date: "2026-03-01".to_string(),
+            end_date: "2026-04-18".to_string(),
+            target_ctl: Some(65.0),
+            coach_message: None,
+        };
+        let mesocycles = [
+            (1, "capacity", "2026-03-01", "2026-03-28"),
+            (2, "utilization", "2026-03-29", "2026-04-18"),
+        ]
+        .into_iter()
+        .map(
+            |(sequence_number, phase, start_date, end_date)| CreateMesocycle {
+                macrocycle_id: 0,
+                sequence_number,
+                phase: phase.to_string(),
+                focus: "aerobic_capacity".to_string(),
+                load_weeks: 3,
+                recovery_weeks: 1,
+                target_volume_km: Some(160.0),
+                start_date: start_date.to_string(),
+                end_date: end_date.to_string(),
+            },
+        )
+        .collect();
+        (macrocycle, mesocycles)
+    }
+
+    #[tokio::test]
+    async fn test_create_plan_stores_first_block() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        let race_goal_id = create_test_race_goal(&pool, user_id).await;
+        let (macrocycle, mesocycles) = plan_inputs(user_id, race_goal_id);
+        let workouts = vec![
+            plan_workout_input(user_id, "2026-03-02", "easy_run"),
+            plan_workout_input(user_id, "2026-03-03", "rest"),
+        ];
+
+        let (mc, mesos, stored) = create_plan(&pool, &macrocycle, mesocycles, workouts)
+            .await
+            .expect("create_plan should succeed");
+
+        assert_eq!(mesos.len(), 2);
+        assert!(mesos.iter().all(|m| m.macrocycle_id == mc.id));
+        let fetched = get_mesocycles(&pool, mc.id).await.unwrap();
+        assert_eq!(fetched[0].status, "active");
+        assert_eq!(fetched[1].status, "pending");
+        assert_eq!(stored.len(), 2);
+        assert!(stored.iter().all(|w| w.mesocycle_id == mesos[0].id));
+    }
+
+    #[tokio::test]
+    async fn test_create_plan_rolls_back_on_failure() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        let race_goal_id = create_test_race_goal(&pool, user_id).await;
+        let (macrocycle, mesocycles) = plan_inputs(user_id, race_goal_id);
+        let workouts = vec![
+            plan_workout_input(user_id, "2026-03-02", "easy_run"),
+            plan_workout_input(user_id, "2026-03-03", "not_a_workout"),
+        ];
+
+        assert!(
+            create_plan(&pool, &macrocycle, mesocycles, workouts)
+                .await
+                .is_err()
+        );
+
+        for table in ["macrocycles", "mesocycles", "planned_workouts"] {
+            let count: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {table}"))
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+            assert_eq!(count, 0, "{table} should be empty");
+        }
+    }
+
     // -----------------------------------------------------------------------
     // PlannedWorkout tests
     // -----------------------------------------------------------------------
@@ -1105,6 +1900,80 @@ mod tests {
             .expect("create test workout")
     }
 
+    #[tokio::test]
+    async fn test_refresh_workout_zones_only_touches_open_future_runs() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        let race_goal_id = create_test_race_goal(&pool, user_id).await;
+        let mc = create_test_macrocycle(&pool, user_id, race_goal_id).await;
+        let meso = create_test_mesocycle(&pool, mc.id).await;
+
+        let past = create_test_workout(&pool, meso.id, user_id).await;
+        let mut ids = Vec::new();
+        for (date, workout_type) in [
+            ("2026-03-10", "easy_run"),
+            ("2026-03-11", "rest"),
+            ("2026-03-12", "easy_run"),
+        ] {
+            let mut input = CreatePlannedWorkout {
+                mesocycle_id: meso.id,
+                user_id,
+                scheduled_date: date.to_string(),
+                workout_type: workout_type.to_string(),
+                duration_min: None,
+                duration_category: None,
+                target_hr_zones: None,
+                target_pace_zones: None,
+                expected_tss: None,
+                description: Some("Rest day".to_string()),
+                coach_notes: None,
+                target_distance_km: None,
+            };
+            if workout_type == "easy_run" {
+                input.duration_category = Some("medium".to_string());
+                input.description = Some("Easy recovery run.".to_string());
+            }
+            ids.push(
+                create_planned_workout(&pool, &input)
+                    .await
+                    .expect("create")
+                    .id,
+            );
+        }
+        complete_workout(&pool, ids[2], user_id, None, None, None)
+            .await
+            .expect("complete");
+
+        let hr_zones = crate::domain::zones::calculate_hr_zones(175);
+        let updated = refresh_workout_zones(&pool, user_id, "2026-03-05", &hr_zones, None)
+            .await
+            .expect("refresh");
+
+        assert_eq!(updated.len(), 1);
+        assert_eq!(updated[0].id, ids[0]);
+        assert!(updated[0].description.as_deref().unwrap().contains("bpm"));
+        assert_eq!(updated[0].target_hr_zones.as_deref(), Some("Z1, Z2"));
+
+        let workouts = get_planned_workouts(&pool, meso.id).await.expect("list");
+        let description = |id| {
+            workouts
+                .iter()
+                .find(|w| w.id == id)
+                .unwrap()
+                .description
+                .clone()
+        };
+        assert_eq!(description(past.id).as_deref(), Some("Easy recovery run."));
+        assert_eq!(description(ids[1]).as_deref(), Some("Rest day"));
+        assert_eq!(description(ids[2]).as_deref(), Some("Easy recovery run."));
+
+        // Nothing changes when the zones are the same
+        let again = refresh_workout_zones(&pool, user_id, "2026-03-05", &hr_zones, None)
+            .await
+            .expect("refresh");
+        assert!(again.is_empty());
+    }
+
     #[tokio::test]
     async fn test_complete_workout_all_fields() {
         let pool = setup_pool().await;
@@ -1166,6 +2035,273 @@ mod tests {
         assert!(result.is_err());
     }
 
+    // -----------------------------------------------------------------------
+    // Upload matching tests
+    // -----------------------------------------------------------------------
+
+    async fn create_test_completed_workout(pool: &SqlitePool, user_id: i64) -> i64 {
+        let row = sqlx::query(
+            r#"INSERT INTO completed_workouts (user_id, source, started_at, duration_seconds, distance_m)
+               VALUES (?, 'fit_upload', '2026-03-03T07:00:00+00:00', 2820, 8700.0)
+               RETURNING id"#,
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .expect("create test completed workout");
+        row.get("id")
+    }
+
+    #[tokio::test]
+    async fn test_find_unlinked_planned_workout_skips_rest_and_linked() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        let race_goal_id = create_test_race_goal(&pool, user_id).await;
+        let mc = create_test_macrocycle(&pool, user_id, race_goal_id).await;
+        let meso = create_test_mesocycle(&pool, mc.id).await;
+
+        let rest = CreatePlannedWorkout {
+            mesocycle_id: meso.id,
+            user_id,
+            scheduled_date: "2026-03-05".to_string(),
+            workout_type: "rest".to_string(),
+            duration_min: None,
+            duration_category: None,
+            target_hr_zones: None,
+            target_pace_zones: None,
+            expected_tss: None,
+            description: None,
+            coach_notes: None,
+            target_distance_km: None,
+        };
+        create_planned_workout(&pool, &rest).await.unwrap();
+        let found = find_unlinked_planned_workout_for_date(&pool, user_id, "2026-03-05")
+            .await
+            .unwrap();
+        assert!(found.is_none(), "rest days should never match");
+
+        let workout = create_test_workout(&pool, meso.id, user_id).await;
+        let found = find_unlinked_planned_workout_for_date(&pool, user_id, "2026-03-03")
+            .await
+            .unwrap()
+            .expect("easy run should match");
+        assert_eq!(found.id, workout.id);
+
+        let completed_id = create_test_completed_workout(&pool, user_id).await;
+        let mut conn = pool.acquire().await.unwrap();
+        link_completed_workout(&mut conn, workout.id, completed_id, 47)
+            .await
+            .unwrap();
+        drop(conn);
+        let found = find_unlinked_planned_workout_for_date(&pool, user_id, "2026-03-03")
+            .await
+            .unwrap();
+        assert!(found.is_none(), "already linked workouts should not match");
+    }
+
+    #[tokio::test]
+    async fn test_last_time_trial_date_before() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        let race_goal_id = create_test_race_goal(&pool, user_id).await;
+        let mc = create_test_macrocycle(&pool, user_id, race_goal_id).await;
+        let meso = create_test_mesocycle(&pool, mc.id).await;
+        create_test_workout(&pool, meso.id, user_id).await;
+
+        let last = get_last_time_trial_date(&pool, user_id, "2026-12-31")
+            .await
+            .unwrap();
+        assert!(last.is_none(), "an easy run is not a time trial");
+
+        for date in ["2026-03-12", "2026-04-09"] {
+            let time_trial = CreatePlannedWorkout {
+                mesocycle_id: meso.id,
+                user_id,
+                scheduled_date: date.to_string(),
+                workout_type: "time_trial".to_string(),
+                duration_min: Some(55),
+                duration_category: Some("long".to_string()),
+                target_hr_zones: None,
+                target_pace_zones: None,
+                expected_tss: Some(75.0),
+                description: None,
+                coach_notes: None,
+                target_distance_km: None,
+            };
+            create_planned_workout(&pool, &time_trial).await.unwrap();
+        }
+
+        let last = get_last_time_trial_date(&pool, user_id, "2026-04-01")
+            .await
+            .unwrap();
+        assert_eq!(last.as_deref(), Some("2026-03-12"));
+        let last = get_last_time_trial_date(&pool, user_id, "2026-12-31")
+            .await
+            .unwrap();
+        assert_eq!(last.as_deref(), Some("2026-04-09"));
+    }
+
+    #[tokio::test]
+    async fn test_link_completed_workout_sets_both_sides() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        let race_goal_id = create_test_race_goal(&pool, user_id).await;
+        let mc = create_test_macrocycle(&pool, user_id, race_goal_id).await;
+        let meso = create_test_mesocycle(&pool, mc.id).await;
+        let workout = create_test_workout(&pool, meso.id, user_id).await;
+        let completed_id = create_test_completed_workout(&pool, user_id).await;
+
+        let mut conn = pool.acquire().await.unwrap();
+        let linked = link_completed_workout(&mut conn, workout.id, completed_id, 47)
+            .await
+            .expect("link should succeed")
+            .expect("workout was unlinked");
+        drop(conn);
+        assert_eq!(linked.is_completed, 1);
+        assert_eq!(linked.completed_workout_id, Some(completed_id));
+        assert_eq!(linked.actual_duration_min, Some(47));
+        assert!(linked.completed_at.is_some());
+
+        let row = sqlx::query("SELECT planned_workout_id FROM completed_workouts WHERE id = ?")
+            .bind(completed_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let planned_id: Option<i64> = row.get("planned_workout_id");
+        assert_eq!(planned_id, Some(workout.id));
+    }
+
+    #[tokio::test]
+    async fn test_link_completed_workout_keeps_manual_duration() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        let race_goal_id = create_test_race_goal(&pool, user_id).await;
+        let mc = create_test_macrocycle(&pool, user_id, race_goal_id).await;
+        let meso = create_test_mesocycle(&pool, mc.id).await;
+        let workout = create_test_workout(&pool, meso.id, user_id).await;
+        complete_workout(&pool, workout.id, user_id, Some(6), None, Some(50))
+            .await
+            .unwrap();
+        let completed_id = create_test_completed_workout(&pool, user_id).await;
+
+        let mut conn = pool.acquire().await.unwrap();
+        let linked = link_completed_workout(&mut conn, workout.id, completed_id, 47)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(linked.actual_duration_min, Some(50));
+        assert_eq!(linked.rpe, Some(6));
+    }
+
+    #[tokio::test]
+    async fn test_link_completed_workout_skips_already_linked() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        let race_goal_id = create_test_race_goal(&pool, user_id).await;
+        let mc = create_test_macrocycle(&pool, user_id, race_goal_id).await;
+        let meso = create_test_mesocycle(&pool, mc.id).await;
+        let workout = create_test_workout(&pool, meso.id, user_id).await;
+        let first_id = create_test_completed_workout(&pool, user_id).await;
+        let second_id = sqlx::query_scalar::<_, i64>(
+            r#"INSERT INTO completed_workouts (user_id, source, started_at, duration_seconds, distance_m)
+               VALUES (?, 'fit_upload', '2026-03-03T18:00:00+00:00', 1800, 5000.0)
+               RETURNING id"#,
+        )
+        .bind(user_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        let mut conn = pool.acquire().await.unwrap();
+        link_completed_workout(&mut conn, workout.id, first_id, 47)
+            .await
+            .unwrap()
+            .expect("first link");
+        let second = link_completed_workout(&mut conn, workout.id, second_id, 30)
+            .await
+            .unwrap();
+        assert!(second.is_none(), "a linked workout is not linked again");
+        drop(conn);
+
+        let planned_id: Option<i64> =
+            sqlx::query_scalar("SELECT planned_workout_id FROM completed_workouts WHERE id = ?")
+                .bind(second_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert!(planned_id.is_none());
+        let row = sqlx::query(
+            "SELECT completed_workout_id, actual_duration_min FROM planned_workouts WHERE id = ?",
+        )
+        .bind(workout.id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        let linked_id: Option<i64> = row.get("completed_workout_id");
+        let actual_duration_min: Option<i64> = row.get("actual_duration_min");
+        assert_eq!(linked_id, Some(first_id));
+        assert_eq!(actual_duration_min, Some(47));
+    }
+
+    #[tokio::test]
+    async fn test_unlink_completed_workout() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        let race_goal_id = create_test_race_goal(&pool, user_id).await;
+        let mc = create_test_macrocycle(&pool, user_id, race_goal_id).await;
+        let meso = create_test_mesocycle(&pool, mc.id).await;
+        let workout = create_test_workout(&pool, meso.id, user_id).await;
+        let completed_id = create_test_completed_workout(&pool, user_id).await;
+        let mut conn = pool.acquire().await.unwrap();
+        link_completed_workout(&mut conn, workout.id, completed_id, 47)
+            .await
+            .unwrap();
+        unlink_completed_workout(&mut conn, completed_id)
+            .await
+            .unwrap();
+        drop(conn);
+
+        let found = find_unlinked_planned_workout_for_date(&pool, user_id, "2026-03-03")
+            .await
+            .unwrap()
+            .expect("workout is matchable again");
+        assert_eq!(found.id, workout.id);
+        assert_eq!(found.is_completed, 0);
+        assert!(found.completed_at.is_none());
+        assert!(found.actual_duration_min.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unlink_completed_workout_keeps_manual_completion() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        let race_goal_id = create_test_race_goal(&pool, user_id).await;
+        let mc = create_test_macrocycle(&pool, user_id, race_goal_id).await;
+        let meso = create_test_mesocycle(&pool, mc.id).await;
+        let workout = create_test_workout(&pool, meso.id, user_id).await;
+        let manual = complete_workout(&pool, workout.id, user_id, Some(6), None, None)
+            .await
+            .unwrap();
+        let completed_id = create_test_completed_workout(&pool, user_id).await;
+        let mut conn = pool.acquire().await.unwrap();
+        link_completed_workout(&mut conn, workout.id, completed_id, 47)
+            .await
+            .unwrap();
+        unlink_completed_workout(&mut conn, completed_id)
+            .await
+            .unwrap();
+        drop(conn);
+
+        let found = find_unlinked_planned_workout_for_date(&pool, user_id, "2026-03-03")
+            .await
+            .unwrap()
+            .expect("workout is matchable again");
+        assert_eq!(found.is_completed, 1);
+        assert_eq!(found.completed_at, manual.completed_at);
+        assert_eq!(found.rpe, Some(6));
+        assert!(found.actual_duration_min.is_none());
+    }
+
     // -----------------------------------------------------------------------
     // get_previous_mesocycle_workouts tests
     // -----------------------------------------------------------------------
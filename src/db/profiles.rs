@@ -1,6 +1,6 @@
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
-use sqlx::sqlite::SqlitePool;
+use sqlx::sqlite::{SqliteConnection, SqlitePool};
 use sqlx::{FromRow, Row};
 
 use crate::error::{AppError, AppResult};
@@ -338,27 +338,164 @@ pub async fn get_active_race_goal(
     }))
 }
 
+// ---------------------------------------------------------------------------
+// RaceResult
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct RaceResult {
+    pub id: i64,
+    pub user_id: i64,
+    pub race_name: Option<String>,
+    pub distance_m: f64,
+    pub time_seconds: i64,
+    pub race_date: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateRaceResult {
+    pub user_id: i64,
+    pub race_name: Option<String>,
+    pub distance_m: f64,
+    pub time_seconds: i64,
+    pub race_date: String,
+}
+
+/// Store a race result.
+pub async fn create_race_result(
+    pool: &SqlitePool,
+    result: &CreateRaceResult,
+) -> AppResult<RaceResult> {
+    let now = Utc::now().to_rfc3339();
+
+    let created = sqlx::query_as::<_, RaceResult>(
+        r#"INSERT INTO race_results (user_id, race_name, distance_m, time_seconds, race_date, created_at)
+           VALUES (?, ?, ?, ?, ?, ?)
+           RETURNING id, user_id, race_name, distance_m, time_seconds, race_date, created_at"#,
+    )
+    .bind(result.user_id)
+    .bind(&result.race_name)
+    .bind(result.distance_m)
+    .bind(result.time_seconds)
+    .bind(&result.race_date)
+    .bind(&now)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(created)
+}
+
+/// The user's most recent race result by race date, if any.
+pub async fn get_latest_race_result(
+    pool: &SqlitePool,
+    user_id: i64,
+) -> AppResult<Option<RaceResult>> {
+    let result = sqlx::query_as::<_, RaceResult>(
+        r#"SELECT id, user_id, race_name, distance_m, time_seconds, race_date, created_at
+           FROM race_results WHERE user_id = ?
+           ORDER BY race_date DESC, id DESC LIMIT 1"#,
+    )
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(result)
+}
+
 // ---------------------------------------------------------------------------
 // Physiological history helpers
 // ---------------------------------------------------------------------------
 
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct FtpaceEntry {
+    pub id: i64,
+    pub pace_m_per_s: f64,
+    pub source: String,
+    pub recorded_at: String,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct LthrEntry {
+    pub id: i64,
+    pub lthr: i64,
+    pub source: String,
+    pub recorded_at: String,
+    pub notes: Option<String>,
+}
+
+/// Set the athlete's current LTHR and/or FTPace and record each value given
+/// in its history, in one transaction.
+pub async fn record_thresholds(
+    pool: &SqlitePool,
+    user_id: i64,
+    lthr: Option<i64>,
+    ftpace_m_per_s: Option<f64>,
+    source: &str,
+    notes: Option<&str>,
+) -> AppResult<AthleteProfile> {
+    let now = Utc::now().to_rfc3339();
+    let mut tx = pool.begin().await?;
+
+    let profile = sqlx::query_as::<_, AthleteProfile>(
+        r#"UPDATE athlete_profiles
+           SET lthr = COALESCE(?, lthr), ftpace_m_per_s = COALESCE(?, ftpace_m_per_s),
+               updated_at = ?
+           WHERE user_id = ?
+           RETURNING id, user_id, name, age, weight_kg, resting_hr, max_hr, lthr,
+                     ftpace_m_per_s, current_weekly_volume_km, experience_level,
+                     sports_background, created_at, updated_at"#,
+    )
+    .bind(lthr)
+    .bind(ftpace_m_per_s)
+    .bind(&now)
+    .bind(user_id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::NotFound("No athlete profile found".to_string()))?;
+
+    if let Some(lthr) = lthr {
+        insert_lthr_entry(&mut tx, user_id, lthr, source, notes).await?;
+    }
+    if let Some(pace) = ftpace_m_per_s {
+        insert_ftpace_entry(&mut tx, user_id, pace, source, notes).await?;
+    }
+
+    tx.commit().await?;
+    Ok(profile)
+}
+
 /// Record a new FTPace measurement in the history table.
 pub async fn create_ftpace_entry(
     pool: &SqlitePool,
     user_id: i64,
     pace: f64,
     source: &str,
+    notes: Option<&str>,
+) -> AppResult<()> {
+    let mut conn = pool.acquire().await?;
+    insert_ftpace_entry(&mut conn, user_id, pace, source, notes).await
+}
+
+async fn insert_ftpace_entry(
+    conn: &mut SqliteConnection,
+    user_id: i64,
+    pace: f64,
+    source: &str,
+    notes: Option<&str>,
 ) -> AppResult<()> {
     let now = Utc::now().to_rfc3339();
 
     sqlx::query(
-        "INSERT INTO ftpace_history (user_id, pace_m_per_s, source, recorded_at) VALUES (?, ?, ?, ?)",
+        "INSERT INTO ftpace_history (user_id, pace_m_per_s, source, recorded_at, notes) VALUES (?, ?, ?, ?, ?)",
     )
     .bind(user_id)
     .bind(pace)
     .bind(source)
     .bind(&now)
-    .execute(pool)
+    .bind(notes)
+    .execute(&mut *conn)
     .await?;
 
     Ok(())
@@ -370,22 +507,61 @@ pub async fn create_lthr_entry(
     user_id: i64,
     lthr: i64,
     source: &str,
+    notes: Option<&str>,
+) -> AppResult<()> {
+    let mut conn = pool.acquire().await?;
+    insert_lthr_entry(&mut conn, user_id, lthr, source, notes).await
+}
+
+async fn insert_lthr_entry(
+    conn: &mut SqliteConnection,
+    user_id: i64,
+    lthr: i64,
+    source: &str,
+    notes: Option<&str>,
 ) -> AppResult<()> {
     let now = Utc::now().to_rfc3339();
 
     sqlx::query(
-        "INSERT INTO lthr_history (user_id, lthr, source, recorded_at) VALUES (?, ?, ?, ?)",
+        "INSERT INTO lthr_history (user_id, lthr, source, recorded_at, notes) VALUES (?, ?, ?, ?, ?)",
     )
     .bind(user_id)
     .bind(lthr)
     .bind(source)
     .bind(&now)
-    .execute(pool)
+    .bind(notes)
+    .execute(&mut *conn)
     .await?;
 
     Ok(())
 }
 
+/// The user's FTPace history, oldest first.
+pub async fn list_ftpace_history(pool: &SqlitePool, user_id: i64) -> AppResult<Vec<FtpaceEntry>> {
+    let entries = sqlx::query_as::<_, FtpaceEntry>(
+        r#"SELECT id, pace_m_per_s, source, recorded_at, notes FROM ftpace_history
+           WHERE user_id = ? ORDER BY recorded_at ASC, id ASC"#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(entries)
+}
+
+/// The user's LTHR history, oldest first.
+pub async fn list_lthr_history(pool: &SqlitePool, user_id: i64) -> AppResult<Vec<LthrEntry>> {
+    let entries = sqlx::query_as::<_, LthrEntry>(
+        r#"SELECT id, lthr, source, recorded_at, notes FROM lthr_history
+           WHERE user_id = ? ORDER BY recorded_at ASC, id ASC"#,
+    )
+    .bind(user_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(entries)
+}
+
 // ---------------------------------------------------------------------------
 // Daily metrics
 // ---------------------------------------------------------------------------
@@ -607,12 +783,50 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[tokio::test]
+    async fn test_latest_race_result_is_by_race_date() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+
+        assert!(
+            get_latest_race_result(&pool, user_id)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        for (distance_m, time_seconds, race_date) in
+            [(10000.0, 2460, "2026-09-20"), (5000.0, 1180, "2026-05-03")]
+        {
+            create_race_result(
+                &pool,
+                &CreateRaceResult {
+                    user_id,
+                    race_name: None,
+                    distance_m,
+                    time_seconds,
+                    race_date: race_date.to_string(),
+                },
+            )
+            .await
+            .expect("create_race_result should succeed");
+        }
+
+        // The 5K was entered last but run earlier
+        let latest = get_latest_race_result(&pool, user_id)
+            .await
+            .expect("get_latest_race_result should succeed")
+            .expect("a result");
+        assert_eq!(latest.race_date, "2026-09-20");
+        assert_eq!(latest.time_seconds, 2460);
+    }
+
     #[tokio::test]
     async fn test_create_ftpace_entry() {
         let pool = setup_pool().await;
         let user_id = create_test_user(&pool).await;
 
-        create_ftpace_entry(&pool, user_id, 4.5, "race")
+        create_ftpace_entry(&pool, user_id, 4.5, "race", None)
             .await
             .expect("create_ftpace_entry should succeed");
 
@@ -630,7 +844,7 @@ mod tests {
         let pool = setup_pool().await;
         let user_id = create_test_user(&pool).await;
 
-        create_lthr_entry(&pool, user_id, 170, "time_trial")
+        create_lthr_entry(&pool, user_id, 170, "time_trial", None)
             .await
             .expect("create_lthr_entry should succeed");
 
@@ -642,6 +856,61 @@ mod tests {
         assert_eq!(count, 1);
     }
 
+    #[tokio::test]
+    async fn test_record_thresholds_updates_profile_and_history_together() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        create_profile(&pool, &test_create_profile_input(user_id))
+            .await
+            .expect("create_profile should succeed");
+
+        let profile = record_thresholds(&pool, user_id, Some(172), None, "time_trial", None)
+            .await
+            .expect("record_thresholds should succeed");
+        assert_eq!(profile.lthr, 172);
+        assert_eq!(profile.ftpace_m_per_s, Some(4.5));
+
+        // A history row that can't be stored undoes the profile update
+        let result = record_thresholds(&pool, user_id, Some(160), Some(4.0), "guess", None).await;
+        assert!(result.is_err());
+        let profile = get_profile_by_user_id(&pool, user_id)
+            .await
+            .expect("get_profile should succeed")
+            .expect("a profile");
+        assert_eq!(profile.lthr, 172);
+        assert_eq!(profile.ftpace_m_per_s, Some(4.5));
+        let lthr = list_lthr_history(&pool, user_id).await.expect("list");
+        assert_eq!(lthr.iter().map(|e| e.lthr).collect::<Vec<_>>(), vec![172]);
+    }
+
+    #[tokio::test]
+    async fn test_threshold_history_lists_oldest_first() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+
+        create_lthr_entry(&pool, user_id, 165, "estimate", None)
+            .await
+            .expect("create");
+        create_lthr_entry(&pool, user_id, 168, "workout_derived", Some("Tempo run"))
+            .await
+            .expect("create");
+        create_ftpace_entry(&pool, user_id, 3.9, "manual", None)
+            .await
+            .expect("create");
+
+        let lthr = list_lthr_history(&pool, user_id).await.expect("list");
+        assert_eq!(
+            lthr.iter().map(|e| e.lthr).collect::<Vec<_>>(),
+            vec![165, 168]
+        );
+        assert_eq!(lthr[1].source, "workout_derived");
+        assert_eq!(lthr[1].notes.as_deref(), Some("Tempo run"));
+
+        let ftpace = list_ftpace_history(&pool, user_id).await.expect("list");
+        assert_eq!(ftpace.len(), 1);
+        assert_eq!(ftpace[0].source, "manual");
+    }
+
     #[tokio::test]
     async fn test_create_daily_metrics() {
         let pool = setup_pool().await;
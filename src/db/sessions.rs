@@ -1,4 +1,4 @@
-use chrono::{TimeDelta, Utc};
+use chrono::{DateTime, TimeDelta, Utc};
 use serde::Serialize;
 use sqlx::sqlite::SqlitePool;
 use sqlx::FromRow;
@@ -68,7 +68,15 @@ pub async fn delete_session(pool: &SqlitePool, session_id: &str) -> AppResult<()
 
 /// Delete all expired sessions. Returns the number of sessions removed.
 pub async fn cleanup_expired_sessions(pool: &SqlitePool) -> AppResult<u64> {
-    let result = sqlx::query("DELETE FROM sessions WHERE expires_at <= datetime('now')")
+    cleanup_sessions_expired_at(pool, Utc::now()).await
+}
+
+/// Delete all sessions expired as of `now`. Returns the number removed.
+/// Both sides go through `datetime()` so RFC 3339 timestamps compare by
+/// instant rather than as text.
+pub async fn cleanup_sessions_expired_at(pool: &SqlitePool, now: DateTime<Utc>) -> AppResult<u64> {
+    let result = sqlx::query("DELETE FROM sessions WHERE datetime(expires_at) <= datetime(?)")
+        .bind(now.to_rfc3339())
         .execute(pool)
         .await?;
 
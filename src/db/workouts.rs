@@ -0,0 +1,1246 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::{SqliteConnection, SqlitePool};
+use sqlx::{FromRow, QueryBuilder, Row, Sqlite};
+
+use crate::db::{chat, plans};
+use crate::domain::classification::{Classification, ClassificationCheck};
+use crate::domain::compliance::{ComplianceVerdict, Execution, OffTargetStreak, off_target_streak};
+use crate::domain::ftpace_estimate::SteadySegment;
+use crate::error::{AppError, AppResult};
+use crate::fit::FitRecord;
+
+/// Rows per multi-row INSERT when storing time-series records. Keeps us well
+/// under SQLite's bound-parameter limit.
+const RECORD_BATCH_SIZE: usize = 500;
+
+/// Most recent verdicts considered when counting an off-target streak.
+const STREAK_LOOKBACK: i64 = 20;
+
+// ---------------------------------------------------------------------------
+// CompletedWorkout
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct CompletedWorkout {
+    pub id: i64,
+    pub user_id: i64,
+    pub planned_workout_id: Option<i64>,
+    pub fit_file_path: Option<String>,
+    pub source: String,
+    pub hr_data_sufficient: i64,
+    pub started_at: String,
+    pub duration_seconds: i64,
+    pub distance_m: f64,
+    pub avg_hr: Option<i64>,
+    pub max_hr: Option<i64>,
+    pub avg_pace_m_per_s: Option<f64>,
+    pub max_pace_m_per_s: Option<f64>,
+    pub avg_cadence: Option<f64>,
+    pub elevation_gain_m: Option<f64>,
+    pub elevation_loss_m: Option<f64>,
+    pub ngp_m_per_s: Option<f64>,
+    pub intensity_factor: Option<f64>,
+    pub rtss: Option<f64>,
+    pub hrtss: Option<f64>,
+    pub tss: Option<f64>,
+    pub trimp: Option<f64>,
+    pub hr_zone_1_seconds: i64,
+    pub hr_zone_2_seconds: i64,
+    pub hr_zone_3_seconds: i64,
+    pub hr_zone_4_seconds: i64,
+    pub hr_zone_5_seconds: i64,
+    pub hr_zone_6_seconds: i64,
+    pub hr_zone_7_seconds: i64,
+    pub pace_zone_1_seconds: i64,
+    pub pace_zone_2_seconds: i64,
+    pub pace_zone_3_seconds: i64,
+    pub pace_zone_4_seconds: i64,
+    pub pace_zone_5_seconds: i64,
+    pub pace_zone_6_seconds: i64,
+    pub aerobic_effect: Option<f64>,
+    pub anaerobic_effect: Option<f64>,
+    pub classification: Option<String>,
+    pub expected_classification: Option<String>,
+    pub classification_matches: Option<i64>,
+    pub compliance: Option<String>,
+    pub coach_summary: Option<String>,
+    pub coach_commentary: Option<String>,
+    pub coach_message: Option<String>,
+    pub analyzed_at: Option<String>,
+    pub created_at: String,
+}
+
+impl CompletedWorkout {
+    /// Seconds in HR zones 1-7, index 0 = zone 1.
+    pub fn hr_zone_seconds(&self) -> [i64; 7] {
+        [
+            self.hr_zone_1_seconds,
+            self.hr_zone_2_seconds,
+            self.hr_zone_3_seconds,
+            self.hr_zone_4_seconds,
+            self.hr_zone_5_seconds,
+            self.hr_zone_6_seconds,
+            self.hr_zone_7_seconds,
+        ]
+    }
+
+    /// Seconds in pace zones 1-6, index 0 = zone 1.
+    pub fn pace_zone_seconds(&self) -> [i64; 6] {
+        [
+            self.pace_zone_1_seconds,
+            self.pace_zone_2_seconds,
+            self.pace_zone_3_seconds,
+            self.pace_zone_4_seconds,
+            self.pace_zone_5_seconds,
+            self.pace_zone_6_seconds,
+        ]
+    }
+
+    /// The stored comparison of the classification with the matched planned
+    /// workout, of type `planned_workout_type`. `None` if none was stored.
+    pub fn classification_check(&self, planned_workout_type: &str) -> Option<ClassificationCheck> {
+        let matches = self.classification_matches?;
+        Some(ClassificationCheck {
+            planned_workout_type: planned_workout_type.to_string(),
+            expected: self
+                .expected_classification
+                .as_deref()
+                .and_then(Classification::from_str),
+            actual: self
+                .classification
+                .as_deref()
+                .and_then(Classification::from_str),
+            matches: matches != 0,
+        })
+    }
+
+    /// What the athlete did, for compliance scoring.
+    pub fn execution(&self) -> Execution {
+        Execution {
+            duration_seconds: self.duration_seconds,
+            distance_m: self.distance_m,
+            tss: self.tss,
+            hr_zone_seconds: (self.hr_data_sufficient != 0).then(|| self.hr_zone_seconds()),
+            pace_zone_seconds: self.pace_zone_seconds(),
+            classification: self.classification.as_deref().and_then(Classification::from_str),
+        }
+    }
+}
+
+const COMPLETED_WORKOUT_COLUMNS: &str = r#"id, user_id, planned_workout_id, fit_file_path, source,
+    hr_data_sufficient, started_at, duration_seconds, distance_m, avg_hr, max_hr,
+    avg_pace_m_per_s, max_pace_m_per_s, avg_cadence, elevation_gain_m, elevation_loss_m,
+    ngp_m_per_s, intensity_factor, rtss, hrtss, tss, trimp,
+    hr_zone_1_seconds, hr_zone_2_seconds, hr_zone_3_seconds, hr_zone_4_seconds,
+    hr_zone_5_seconds, hr_zone_6_seconds, hr_zone_7_seconds,
+    pace_zone_1_seconds, pace_zone_2_seconds, pace_zone_3_seconds,
+    pace_zone_4_seconds, pace_zone_5_seconds, pace_zone_6_seconds,
+    aerobic_effect, anaerobic_effect, classification, expected_classification,
+    classification_matches, compliance, coach_summary, coach_commentary, coach_message, analyzed_at, created_at"#;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateCompletedWorkout {
+    pub user_id: i64,
+    pub source: String,
+    pub hr_data_sufficient: bool,
+    pub started_at: String,
+    pub duration_seconds: i64,
+    pub distance_m: f64,
+    pub avg_hr: Option<i64>,
+    pub max_hr: Option<i64>,
+    pub avg_pace_m_per_s: Option<f64>,
+    pub max_pace_m_per_s: Option<f64>,
+    pub avg_cadence: Option<f64>,
+    pub elevation_gain_m: Option<f64>,
+    pub elevation_loss_m: Option<f64>,
+    pub ngp_m_per_s: Option<f64>,
+    pub intensity_factor: Option<f64>,
+    pub rtss: Option<f64>,
+    pub hrtss: Option<f64>,
+    pub tss: Option<f64>,
+    pub trimp: Option<f64>,
+    /// Seconds in HR zones 1-7, index 0 = zone 1.
+    pub hr_zone_seconds: [i64; 7],
+    /// Seconds in pace zones 1-6, index 0 = zone 1.
+    pub pace_zone_seconds: [i64; 6],
+    pub aerobic_effect: Option<f64>,
+    pub anaerobic_effect: Option<f64>,
+    pub classification: Option<String>,
+    /// Comparison of the classification with the matched planned workout.
+    pub classification_check: Option<ClassificationCheck>,
+    pub compliance: Option<String>,
+}
+
+/// Create a completed workout. Returns `AppError::Conflict` if the user
+/// already has a workout starting at the same instant (duplicate upload).
+pub async fn create_completed_workout(
+    pool: &SqlitePool,
+    input: &CreateCompletedWorkout,
+) -> AppResult<CompletedWorkout> {
+    let mut conn = pool.acquire().await?;
+    insert_completed_workout(&mut conn, input).await
+}
+
+/// The planned workout an upload was matched to.
+#[derive(Debug)]
+pub struct PlanLink {
+    pub planned_workout_id: i64,
+    pub actual_duration_min: i64,
+    /// Compliance verdict to store instead if another workout was linked to
+    /// the planned workout first, so the upload counts as unplanned.
+    pub unplanned_compliance: String,
+}
+
+/// Store an uploaded workout with its raw file, per-second records and
+/// steady segments in one transaction, linked to the planned workout in
+/// `link` if that is still unlinked. `store_file` writes the raw file for
+/// the new workout and returns its path; if it or any insert fails, no row
+/// is kept. Returns the linked planned workout, or `None` if there was none
+/// to link and the workout was stored without a plan comparison.
+pub async fn create_uploaded_workout(
+    pool: &SqlitePool,
+    input: &CreateCompletedWorkout,
+    records: &[FitRecord],
+    steady_segments: &[SteadySegment],
+    link: Option<&PlanLink>,
+    store_file: impl AsyncFnOnce(&CompletedWorkout) -> AppResult<String>,
+) -> AppResult<(CompletedWorkout, Option<plans::PlannedWorkout>)> {
+    let mut tx = pool.begin().await?;
+
+    let mut workout = insert_completed_workout(&mut tx, input).await?;
+    let path = store_file(&workout).await?;
+    sqlx::query(
+        "UPDATE completed_workouts SET fit_file_path = ?, steady_segments = ? WHERE id = ?",
+    )
+    .bind(&path)
+    .bind(segments_json(steady_segments)?)
+    .bind(workout.id)
+    .execute(&mut *tx)
+    .await?;
+    workout.fit_file_path = Some(path);
+    insert_records(&mut tx, workout.id, records).await?;
+
+    let mut planned = None;
+    if let Some(link) = link {
+        planned = plans::link_completed_workout(
+            &mut tx,
+            link.planned_workout_id,
+            workout.id,
+            link.actual_duration_min,
+        )
+        .await?;
+        if planned.is_none() {
+            sqlx::query(
+                r#"UPDATE completed_workouts
+                   SET expected_classification = NULL, classification_matches = NULL,
+                       compliance = ?
+                   WHERE id = ?"#,
+            )
+            .bind(&link.unplanned_compliance)
+            .bind(workout.id)
+            .execute(&mut *tx)
+            .await?;
+            workout.expected_classification = None;
+            workout.classification_matches = None;
+            workout.compliance = Some(link.unplanned_compliance.clone());
+        }
+    }
+
+    tx.commit().await?;
+    Ok((workout, planned))
+}
+
+async fn insert_completed_workout(
+    conn: &mut SqliteConnection,
+    input: &CreateCompletedWorkout,
+) -> AppResult<CompletedWorkout> {
+    let now = Utc::now().to_rfc3339();
+
+    let result = sqlx::query_as::<_, CompletedWorkout>(&format!(
+        r#"INSERT INTO completed_workouts
+            (user_id, source, hr_data_sufficient, started_at, duration_seconds, distance_m,
+             avg_hr, max_hr, avg_pace_m_per_s, max_pace_m_per_s, avg_cadence,
+             elevation_gain_m, elevation_loss_m, ngp_m_per_s, intensity_factor, rtss, hrtss,
+             tss, trimp,
+             hr_zone_1_seconds, hr_zone_2_seconds, hr_zone_3_seconds, hr_zone_4_seconds,
+             hr_zone_5_seconds, hr_zone_6_seconds, hr_zone_7_seconds,
+             pace_zone_1_seconds, pace_zone_2_seconds, pace_zone_3_seconds,
+             pace_zone_4_seconds, pace_zone_5_seconds, pace_zone_6_seconds,
+             aerobic_effect, anaerobic_effect, classification, expected_classification,
+             classification_matches, compliance, created_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,
+                   ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+           RETURNING {COMPLETED_WORKOUT_COLUMNS}"#
+    ))
+    .bind(input.user_id)
+    .bind(&input.source)
+    .bind(input.hr_data_sufficient as i64)
+    .bind(&input.started_at)
+    .bind(input.duration_seconds)
+    .bind(input.distance_m)
+    .bind(input.avg_hr)
+    .bind(input.max_hr)
+    .bind(input.avg_pace_m_per_s)
+    .bind(input.max_pace_m_per_s)
+    .bind(input.avg_cadence)
+    .bind(input.elevation_gain_m)
+    .bind(input.elevation_loss_m)
+    .bind(input.ngp_m_per_s)
+    .bind(input.intensity_factor)
+    .bind(input.rtss)
+    .bind(input.hrtss)
+    .bind(input.tss)
+    .bind(input.trimp)
+    .bind(input.hr_zone_seconds[0])
+    .bind(input.hr_zone_seconds[1])
+    .bind(input.hr_zone_seconds[2])
+    .bind(input.hr_zone_seconds[3])
+    .bind(input.hr_zone_seconds[4])
+    .bind(input.hr_zone_seconds[5])
+    .bind(input.hr_zone_seconds[6])
+    .bind(input.pace_zone_seconds[0])
+    .bind(input.pace_zone_seconds[1])
+    .bind(input.pace_zone_seconds[2])
+    .bind(input.pace_zone_seconds[3])
+    .bind(input.pace_zone_seconds[4])
+    .bind(input.pace_zone_seconds[5])
+    .bind(input.aerobic_effect)
+    .bind(input.anaerobic_effect)
+    .bind(&input.classification)
+    .bind(
+        input
+            .classification_check
+            .as_ref()
+            .and_then(|check| check.expected)
+            .map(|c| c.as_str()),
+    )
+    .bind(
+        input
+            .classification_check
+            .as_ref()
+            .map(|check| check.matches as i64),
+    )
+    .bind(&input.compliance)
+    .bind(&now)
+    .fetch_one(&mut *conn)
+    .await;
+
+    match result {
+        Ok(workout) => Ok(workout),
+        Err(sqlx::Error::Database(db_err)) if db_err.message().contains("UNIQUE constraint") => {
+            Err(AppError::Conflict(
+                "A workout with this start time has already been uploaded".to_string(),
+            ))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Get a completed workout by ID, scoped to the user.
+pub async fn get_completed_workout(
+    pool: &SqlitePool,
+    workout_id: i64,
+    user_id: i64,
+) -> AppResult<Option<CompletedWorkout>> {
+    let workout = sqlx::query_as::<_, CompletedWorkout>(&format!(
+        "SELECT {COMPLETED_WORKOUT_COLUMNS} FROM completed_workouts WHERE id = ? AND user_id = ?"
+    ))
+    .bind(workout_id)
+    .bind(user_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(workout)
+}
+
+/// The user's `limit` most recent workouts, newest first.
+pub async fn list_recent_workouts(
+    pool: &SqlitePool,
+    user_id: i64,
+    limit: i64,
+) -> AppResult<Vec<CompletedWorkout>> {
+    let workouts = sqlx::query_as::<_, CompletedWorkout>(&format!(
+        r#"SELECT {COMPLETED_WORKOUT_COLUMNS} FROM completed_workouts
+           WHERE user_id = ? ORDER BY started_at DESC LIMIT ?"#
+    ))
+    .bind(user_id)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(workouts)
+}
+
+/// The user's average weekly distance in km over the `weeks` weeks before
+/// `before`. `None` when their uploads don't reach back to the start of that
+/// window, since a partial history would understate the volume.
+pub async fn get_recent_weekly_volume_km(
+    pool: &SqlitePool,
+    user_id: i64,
+    before: NaiveDate,
+    weeks: i64,
+) -> AppResult<Option<f64>> {
+    let since = (before - Duration::weeks(weeks)).to_string();
+    let first_started_at: Option<String> =
+        sqlx::query_scalar("SELECT MIN(started_at) FROM completed_workouts WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_one(pool)
+            .await?;
+    if first_started_at.is_none_or(|first| first.as_str() > since.as_str()) {
+        return Ok(None);
+    }
+
+    let distance_m: f64 = sqlx::query_scalar(
+        r#"SELECT COALESCE(SUM(distance_m), 0.0) FROM completed_workouts
+           WHERE user_id = ? AND started_at >= ? AND started_at < ?"#,
+    )
+    .bind(user_id)
+    .bind(&since)
+    .bind(before.to_string())
+    .fetch_one(pool)
+    .await?;
+
+    Ok(Some(distance_m / 1000.0 / weeks as f64))
+}
+
+/// Delete a completed workout (its records cascade) and return the planned
+/// workout it completed to not-completed, in one transaction. Scoped to the
+/// user.
+pub async fn delete_completed_workout(
+    pool: &SqlitePool,
+    workout_id: i64,
+    user_id: i64,
+) -> AppResult<bool> {
+    let mut tx = pool.begin().await?;
+
+    let result = sqlx::query("DELETE FROM completed_workouts WHERE id = ? AND user_id = ?")
+        .bind(workout_id)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    if result.rows_affected() == 0 {
+        return Ok(false);
+    }
+    plans::unlink_completed_workout(&mut tx, workout_id).await?;
+
+    tx.commit().await?;
+    Ok(true)
+}
+
+/// Compliance verdicts of the user's most recent workouts started at or
+/// before `up_to_started_at`, newest first. Workouts without a verdict are
+/// skipped.
+pub async fn list_recent_compliance(
+    pool: &SqlitePool,
+    user_id: i64,
+    up_to_started_at: &str,
+    limit: i64,
+) -> AppResult<Vec<String>> {
+    let verdicts = sqlx::query_scalar(
+        r#"SELECT compliance FROM completed_workouts
+           WHERE user_id = ? AND compliance IS NOT NULL AND started_at <= ?
+           ORDER BY started_at DESC LIMIT ?"#,
+    )
+    .bind(user_id)
+    .bind(up_to_started_at)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(verdicts)
+}
+
+/// The user's run of consecutive off-target workouts up to and including the
+/// one started at `up_to_started_at`.
+///
+/// Derived from the stored verdicts on every call rather than kept as a
+/// counter: workouts can be uploaded out of order or deleted, and either
+/// would leave a stored count wrong for every workout after it.
+pub async fn get_off_target_streak(
+    pool: &SqlitePool,
+    user_id: i64,
+    up_to_started_at: &str,
+) -> AppResult<OffTargetStreak> {
+    let verdicts = list_recent_compliance(pool, user_id, up_to_started_at, STREAK_LOOKBACK).await?;
+    Ok(off_target_streak(
+        verdicts.iter().filter_map(|v| ComplianceVerdict::from_str(v)),
+    ))
+}
+
+/// Start time of the user's last workout before `started_at`, if any.
+pub async fn get_previous_started_at(
+    pool: &SqlitePool,
+    user_id: i64,
+    started_at: &str,
+) -> AppResult<Option<String>> {
+    let previous = sqlx::query_scalar(
+        r#"SELECT started_at FROM completed_workouts
+           WHERE user_id = ? AND started_at < ?
+           ORDER BY started_at DESC LIMIT 1"#,
+    )
+    .bind(user_id)
+    .bind(started_at)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(previous)
+}
+
+/// Store Coach Jan's analysis of a workout and post its message to the
+/// athlete's chat in one transaction, replacing any previous analysis and
+/// its message.
+pub async fn save_workout_analysis(
+    pool: &SqlitePool,
+    user_id: i64,
+    workout_id: i64,
+    summary: &str,
+    commentary: &str,
+    coach_message: &str,
+) -> AppResult<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"UPDATE completed_workouts
+           SET coach_summary = ?, coach_commentary = ?, coach_message = ?, analyzed_at = ?
+           WHERE id = ?"#,
+    )
+    .bind(summary)
+    .bind(commentary)
+    .bind(coach_message)
+    .bind(Utc::now().to_rfc3339())
+    .bind(workout_id)
+    .execute(&mut *tx)
+    .await?;
+    chat::replace_workout_analysis_message(&mut tx, user_id, workout_id, coach_message).await?;
+
+    tx.commit().await?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Workout records (time series)
+// ---------------------------------------------------------------------------
+
+/// Bulk-insert a workout's time-series records in a single transaction.
+pub async fn insert_workout_records(
+    pool: &SqlitePool,
+    workout_id: i64,
+    records: &[FitRecord],
+) -> AppResult<()> {
+    let mut tx = pool.begin().await?;
+    insert_records(&mut tx, workout_id, records).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+async fn insert_records(
+    conn: &mut SqliteConnection,
+    workout_id: i64,
+    records: &[FitRecord],
+) -> AppResult<()> {
+    for chunk in records.chunks(RECORD_BATCH_SIZE) {
+        let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(
+            r#"INSERT INTO workout_records
+                (workout_id, timestamp_ms, heart_rate, speed_m_per_s, latitude, longitude,
+                 altitude_m, cadence, power_watts, distance_m, temperature_c) "#,
+        );
+        builder.push_values(chunk, |mut b, r| {
+            b.push_bind(workout_id)
+                .push_bind(r.timestamp_ms as i64)
+                .push_bind(r.heart_rate.map(i64::from))
+                .push_bind(r.speed_m_per_s)
+                .push_bind(r.latitude)
+                .push_bind(r.longitude)
+                .push_bind(r.altitude_m)
+                .push_bind(r.cadence.map(i64::from))
+                .push_bind(r.power_watts.map(i64::from))
+                .push_bind(r.distance_m)
+                .push_bind(r.temperature_c.map(i64::from));
+        });
+        builder.build().execute(&mut *conn).await?;
+    }
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Steady segments (FTPace estimate input)
+// ---------------------------------------------------------------------------
+
+/// Steady segments of the user's `limit` most recent workouts that have
+/// enough HR data and started on or after `since`, newest first, by workout
+/// ID. `None` for a workout whose segments were never stored.
+pub async fn list_recent_steady_segments(
+    pool: &SqlitePool,
+    user_id: i64,
+    since: &str,
+    limit: i64,
+) -> AppResult<Vec<(i64, Option<Vec<SteadySegment>>)>> {
+    let rows = sqlx::query(
+        r#"SELECT id, steady_segments FROM (
+               SELECT id, hr_data_sufficient, started_at, steady_segments
+               FROM completed_workouts
+               WHERE user_id = ? ORDER BY started_at DESC LIMIT ?
+           )
+           WHERE hr_data_sufficient != 0 AND started_at >= ?
+           ORDER BY started_at DESC"#,
+    )
+    .bind(user_id)
+    .bind(limit)
+    .bind(since)
+    .fetch_all(pool)
+    .await?;
+
+    rows.iter()
+        .map(|row| {
+            let segments = row
+                .get::<Option<String>, _>("steady_segments")
+                .map(|json| {
+                    serde_json::from_str(&json).map_err(|e| {
+                        AppError::Internal(format!("Invalid stored steady segments: {e}"))
+                    })
+                })
+                .transpose()?;
+            Ok((row.get("id"), segments))
+        })
+        .collect()
+}
+
+/// Store the steady segments found in a workout's records.
+pub async fn set_steady_segments(
+    pool: &SqlitePool,
+    workout_id: i64,
+    steady_segments: &[SteadySegment],
+) -> AppResult<()> {
+    sqlx::query("UPDATE completed_workouts SET steady_segments = ? WHERE id = ?")
+        .bind(segments_json(steady_segments)?)
+        .bind(workout_id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+fn segments_json(steady_segments: &[SteadySegment]) -> AppResult<String> {
+    serde_json::to_string(steady_segments)
+        .map_err(|e| AppError::Internal(format!("Failed to serialize steady segments: {e}")))
+}
+
+/// Count stored records for a workout.
+pub async fn count_workout_records(pool: &SqlitePool, workout_id: i64) -> AppResult<i64> {
+    let row = sqlx::query("SELECT COUNT(*) AS n FROM workout_records WHERE workout_id = ?")
+        .bind(workout_id)
+        .fetch_one(pool)
+        .await?;
+
+    Ok(row.get("n"))
+}
+
+/// A workout's stored time-series records in order. Timestamps are rebuilt
+/// from the workout's start time.
+pub async fn list_workout_records(pool: &SqlitePool, workout_id: i64) -> AppResult<Vec<FitRecord>> {
+    let rows = sqlx::query(
+        r#"SELECT w.started_at, r.timestamp_ms, r.heart_rate, r.speed_m_per_s, r.latitude,
+                  r.longitude, r.altitude_m, r.cadence, r.power_watts, r.distance_m,
+                  r.temperature_c
+           FROM workout_records r JOIN completed_workouts w ON w.id = r.workout_id
+           WHERE r.workout_id = ? ORDER BY r.timestamp_ms ASC"#,
+    )
+    .bind(workout_id)
+    .fetch_all(pool)
+    .await?;
+
+    rows.iter()
+        .map(|row| {
+            let started_at: String = row.get("started_at");
+            let started_at = DateTime::parse_from_rfc3339(&started_at)
+                .map_err(|e| AppError::Internal(format!("Invalid workout start time: {e}")))?;
+            let timestamp_ms: i64 = row.get("timestamp_ms");
+            Ok(FitRecord {
+                timestamp: started_at.with_timezone(&Utc) + Duration::milliseconds(timestamp_ms),
+                timestamp_ms: timestamp_ms as u64,
+                heart_rate: row.get::<Option<i64>, _>("heart_rate").map(|v| v as u16),
+                speed_m_per_s: row.get("speed_m_per_s"),
+                distance_m: row.get("distance_m"),
+                altitude_m: row.get("altitude_m"),
+                cadence: row.get::<Option<i64>, _>("cadence").map(|v| v as u16),
+                power_watts: row.get::<Option<i64>, _>("power_watts").map(|v| v as u16),
+                latitude: row.get("latitude"),
+                longitude: row.get("longitude"),
+                temperature_c: row.get::<Option<i64>, _>("temperature_c").map(|v| v as i8),
+            })
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+
+    async fn setup_pool() -> SqlitePool {
+        let opts = SqliteConnectOptions::new()
+            .filename(":memory:")
+            .create_if_missing(true)
+            .pragma("foreign_keys", "ON");
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(opts)
+            .await
+            .expect("Failed to create test pool");
+
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    async fn create_test_user(pool: &SqlitePool) -> i64 {
+        let row = sqlx::query(
+            "INSERT INTO users (email, password_hash) VALUES ('workouts@example.com', 'hash') RETURNING id",
+        )
+        .fetch_one(pool)
+        .await
+        .expect("create test user");
+        row.get("id")
+    }
+
+    fn test_input(user_id: i64) -> CreateCompletedWorkout {
+        CreateCompletedWorkout {
+            user_id,
+            source: "fit_upload".to_string(),
+            hr_data_sufficient: true,
+            started_at: "2026-03-03T07:00:00+00:00".to_string(),
+            duration_seconds: 2700,
+            distance_m: 8500.0,
+            avg_hr: Some(142),
+            max_hr: Some(158),
+            avg_pace_m_per_s: Some(3.15),
+            max_pace_m_per_s: Some(4.0),
+            avg_cadence: Some(86.0),
+            elevation_gain_m: Some(40.0),
+            elevation_loss_m: Some(38.0),
+            ngp_m_per_s: Some(3.2),
+            intensity_factor: Some(0.8),
+            rtss: Some(48.0),
+            hrtss: Some(51.0),
+            tss: Some(48.0),
+            trimp: Some(62.5),
+            hr_zone_seconds: [300, 2100, 300, 0, 0, 0, 0],
+            pace_zone_seconds: [120, 2400, 180, 0, 0, 0],
+            aerobic_effect: Some(2.4),
+            anaerobic_effect: Some(0.0),
+            classification: Some("aerobic_capacity".to_string()),
+            classification_check: Some(ClassificationCheck {
+                planned_workout_type: "tempo_run".to_string(),
+                expected: Some(Classification::AerobicUtilization),
+                actual: Some(Classification::AerobicCapacity),
+                matches: false,
+            }),
+            compliance: Some("on_target".to_string()),
+        }
+    }
+
+    fn record(second: u64) -> FitRecord {
+        FitRecord {
+            timestamp: DateTime::from_timestamp(1_700_000_000 + second as i64, 0).unwrap(),
+            timestamp_ms: second * 1000,
+            heart_rate: Some(140),
+            speed_m_per_s: Some(3.0),
+            distance_m: Some(second as f64 * 3.0),
+            altitude_m: Some(100.0),
+            cadence: Some(88),
+            power_watts: None,
+            latitude: Some(52.1),
+            longitude: Some(4.3),
+            temperature_c: Some(12),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_and_get_completed_workout() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+
+        let created = create_completed_workout(&pool, &test_input(user_id))
+            .await
+            .expect("create");
+        assert_eq!(created.user_id, user_id);
+        assert_eq!(created.source, "fit_upload");
+        assert_eq!(created.hr_data_sufficient, 1);
+        assert_eq!(created.duration_seconds, 2700);
+        assert!(created.planned_workout_id.is_none());
+        assert!(created.fit_file_path.is_none());
+
+        let fetched = get_completed_workout(&pool, created.id, user_id)
+            .await
+            .expect("get")
+            .expect("should exist");
+        assert_eq!(fetched.distance_m, 8500.0);
+        assert_eq!(fetched.avg_hr, Some(142));
+        assert_eq!(fetched.rtss, Some(48.0));
+        assert_eq!(fetched.tss, Some(48.0));
+        assert_eq!(fetched.trimp, Some(62.5));
+        assert_eq!(fetched.hr_zone_seconds(), [300, 2100, 300, 0, 0, 0, 0]);
+        assert_eq!(fetched.pace_zone_seconds(), [120, 2400, 180, 0, 0, 0]);
+        assert_eq!(fetched.aerobic_effect, Some(2.4));
+        assert_eq!(fetched.classification.as_deref(), Some("aerobic_capacity"));
+        assert_eq!(
+            fetched.classification_check("tempo_run"),
+            test_input(user_id).classification_check
+        );
+        assert_eq!(fetched.compliance.as_deref(), Some("on_target"));
+
+        let other_user = get_completed_workout(&pool, created.id, user_id + 1)
+            .await
+            .expect("get");
+        assert!(other_user.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_start_time_is_conflict() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+
+        create_completed_workout(&pool, &test_input(user_id))
+            .await
+            .expect("first create");
+        let result = create_completed_workout(&pool, &test_input(user_id)).await;
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_uploaded_workout_stores_path_and_records() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        let records: Vec<FitRecord> = (0..600).map(record).collect();
+        let segments = vec![SteadySegment {
+            avg_hr: 150.0,
+            avg_speed_m_per_s: 3.4,
+        }];
+
+        let created = create_uploaded_workout(
+            &pool,
+            &test_input(user_id),
+            &records,
+            &segments,
+            None,
+            async |w| Ok(format!("data/fit_files/1/2026-03-03_{}.fit", w.id)),
+        )
+        .await
+        .expect("create");
+        let (created, planned) = created;
+        assert!(planned.is_none());
+        let path = format!("data/fit_files/1/2026-03-03_{}.fit", created.id);
+        assert_eq!(created.fit_file_path.as_deref(), Some(path.as_str()));
+
+        let fetched = get_completed_workout(&pool, created.id, user_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(fetched.fit_file_path.as_deref(), Some(path.as_str()));
+        assert_eq!(count_workout_records(&pool, created.id).await.unwrap(), 600);
+        assert_eq!(
+            list_recent_steady_segments(&pool, user_id, "2026-01-01", 20)
+                .await
+                .unwrap(),
+            vec![(created.id, Some(segments))]
+        );
+    }
+
+    /// An easy run planned for 2026-03-03, the day of `test_input`.
+    async fn create_test_planned_workout(pool: &SqlitePool, user_id: i64) -> i64 {
+        let race_goal_id: i64 = sqlx::query_scalar(
+            r#"INSERT INTO race_goals (user_id, race_name, distance_m, race_date)
+               VALUES (?, 'Test Marathon', 42195.0, '2026-09-27') RETURNING id"#,
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        let macrocycle_id: i64 = sqlx::query_scalar(
+            r#"INSERT INTO macrocycles (user_id, race_goal_id, start_date, end_date)
+               VALUES (?, ?, '2026-03-01', '2026-09-27') RETURNING id"#,
+        )
+        .bind(user_id)
+        .bind(race_goal_id)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        let mesocycle_id: i64 = sqlx::query_scalar(
+            r#"INSERT INTO mesocycles
+                   (macrocycle_id, sequence_number, phase, focus, load_weeks, recovery_weeks,
+                    start_date, end_date)
+               VALUES (?, 1, 'capacity', 'aerobic_capacity', 3, 1, '2026-03-01', '2026-03-28')
+               RETURNING id"#,
+        )
+        .bind(macrocycle_id)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        sqlx::query_scalar(
+            r#"INSERT INTO planned_workouts (mesocycle_id, user_id, scheduled_date, workout_type)
+               VALUES (?, ?, '2026-03-03', 'easy_run') RETURNING id"#,
+        )
+        .bind(mesocycle_id)
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_create_uploaded_workout_links_the_planned_workout() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        let planned_id = create_test_planned_workout(&pool, user_id).await;
+        let link = PlanLink {
+            planned_workout_id: planned_id,
+            actual_duration_min: 45,
+            unplanned_compliance: "unplanned".to_string(),
+        };
+
+        let (created, planned) = create_uploaded_workout(
+            &pool,
+            &test_input(user_id),
+            &[],
+            &[],
+            Some(&link),
+            async |_| Ok("data/fit_files/1/run.fit".to_string()),
+        )
+        .await
+        .expect("create");
+
+        let planned = planned.expect("planned workout was open");
+        assert_eq!(planned.completed_workout_id, Some(created.id));
+        assert_eq!(created.compliance.as_deref(), Some("on_target"));
+        let fetched = get_completed_workout(&pool, created.id, user_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(fetched.planned_workout_id, Some(planned_id));
+    }
+
+    #[tokio::test]
+    async fn test_create_uploaded_workout_is_unplanned_when_linked_first() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        let planned_id = create_test_planned_workout(&pool, user_id).await;
+        let link = PlanLink {
+            planned_workout_id: planned_id,
+            actual_duration_min: 45,
+            unplanned_compliance: "unplanned".to_string(),
+        };
+
+        // Another upload links the planned workout first
+        let mut first = test_input(user_id);
+        first.started_at = "2026-03-03T06:00:00+00:00".to_string();
+        let (first, _) =
+            create_uploaded_workout(&pool, &first, &[], &[], Some(&link), async |_| {
+                Ok("data/fit_files/1/first.fit".to_string())
+            })
+            .await
+            .unwrap();
+
+        let (created, planned) = create_uploaded_workout(
+            &pool,
+            &test_input(user_id),
+            &[],
+            &[],
+            Some(&link),
+            async |_| Ok("data/fit_files/1/second.fit".to_string()),
+        )
+        .await
+        .expect("stored without the plan");
+        assert!(planned.is_none());
+
+        let fetched = get_completed_workout(&pool, created.id, user_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(fetched.planned_workout_id.is_none());
+        assert_eq!(fetched.compliance.as_deref(), Some("unplanned"));
+        assert!(fetched.expected_classification.is_none());
+        assert!(fetched.classification_matches.is_none());
+        let linked: Option<i64> =
+            sqlx::query_scalar("SELECT completed_workout_id FROM planned_workouts WHERE id = ?")
+                .bind(planned_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(linked, Some(first.id));
+    }
+
+    #[tokio::test]
+    async fn test_steady_segments_are_missing_until_stored() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        let created = create_completed_workout(&pool, &test_input(user_id))
+            .await
+            .unwrap();
+
+        let listed = list_recent_steady_segments(&pool, user_id, "2026-01-01", 20)
+            .await
+            .unwrap();
+        assert_eq!(listed, vec![(created.id, None)]);
+
+        set_steady_segments(&pool, created.id, &[]).await.unwrap();
+        let listed = list_recent_steady_segments(&pool, user_id, "2026-01-01", 20)
+            .await
+            .unwrap();
+        assert_eq!(listed, vec![(created.id, Some(Vec::new()))]);
+
+        // Only workouts since the lookback start count
+        assert!(
+            list_recent_steady_segments(&pool, user_id, "2026-03-04", 20)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_uploaded_workout_keeps_nothing_when_file_fails() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        let records: Vec<FitRecord> = (0..600).map(record).collect();
+
+        let result = create_uploaded_workout(
+            &pool,
+            &test_input(user_id),
+            &records,
+            &[],
+            None,
+            async |_| Err(AppError::Internal("disk full".to_string())),
+        )
+        .await;
+        assert!(matches!(result, Err(AppError::Internal(_))));
+
+        assert!(
+            list_recent_workouts(&pool, user_id, 10)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+        let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM workout_records")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(stored, 0);
+    }
+
+    #[tokio::test]
+    async fn test_insert_records_in_batches_and_cascade_delete() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        let created = create_completed_workout(&pool, &test_input(user_id))
+            .await
+            .unwrap();
+
+        let records: Vec<FitRecord> = (0..1201).map(record).collect();
+        insert_workout_records(&pool, created.id, &records)
+            .await
+            .expect("insert records");
+        assert_eq!(count_workout_records(&pool, created.id).await.unwrap(), 1201);
+
+        let stored = list_workout_records(&pool, created.id).await.unwrap();
+        assert_eq!(stored.len(), 1201);
+        assert_eq!(stored[600].timestamp_ms, 600_000);
+        assert_eq!(stored[600].heart_rate, Some(140));
+        assert_eq!(stored[600].speed_m_per_s, Some(3.0));
+        assert_eq!(stored[600].temperature_c, Some(12));
+        assert_eq!(
+            stored[600].timestamp - stored[0].timestamp,
+            Duration::seconds(600)
+        );
+
+        assert!(delete_completed_workout(&pool, created.id, user_id).await.unwrap());
+        assert_eq!(count_workout_records(&pool, created.id).await.unwrap(), 0);
+        assert!(!delete_completed_workout(&pool, created.id, user_id).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_list_recent_compliance_newest_first() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+
+        for (started_at, compliance) in [
+            ("2026-03-03T07:00:00+00:00", Some("on_target")),
+            ("2026-03-05T07:00:00+00:00", Some("harder_than_prescribed")),
+            ("2026-03-04T07:00:00+00:00", None),
+            ("2026-03-06T07:00:00+00:00", Some("unplanned")),
+        ] {
+            let mut input = test_input(user_id);
+            input.started_at = started_at.to_string();
+            input.compliance = compliance.map(str::to_string);
+            create_completed_workout(&pool, &input).await.expect("create");
+        }
+
+        let latest = "2026-03-06T07:00:00+00:00";
+        let verdicts = list_recent_compliance(&pool, user_id, latest, 10)
+            .await
+            .expect("list");
+        assert_eq!(verdicts, vec!["unplanned", "harder_than_prescribed", "on_target"]);
+        let verdicts = list_recent_compliance(&pool, user_id, latest, 1)
+            .await
+            .expect("list");
+        assert_eq!(verdicts, vec!["unplanned"]);
+        let verdicts = list_recent_compliance(&pool, user_id, "2026-03-05T07:00:00+00:00", 10)
+            .await
+            .expect("list");
+        assert_eq!(verdicts, vec!["harder_than_prescribed", "on_target"]);
+    }
+
+    #[tokio::test]
+    async fn test_off_target_streak_resets_and_follows_deletes() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+
+        let mut ids = Vec::new();
+        for (started_at, compliance) in [
+            ("2026-03-02T07:00:00+00:00", "harder_than_prescribed"),
+            ("2026-03-03T07:00:00+00:00", "harder_than_prescribed"),
+            ("2026-03-04T07:00:00+00:00", "on_target"),
+            ("2026-03-05T07:00:00+00:00", "harder_than_prescribed"),
+        ] {
+            let mut input = test_input(user_id);
+            input.started_at = started_at.to_string();
+            input.compliance = Some(compliance.to_string());
+            let workout = create_completed_workout(&pool, &input)
+                .await
+                .expect("create");
+            ids.push(workout.id);
+        }
+
+        let count = async |up_to: &str| {
+            get_off_target_streak(&pool, user_id, up_to)
+                .await
+                .expect("streak")
+                .consecutive_off_target_count
+        };
+
+        assert_eq!(count("2026-03-03T07:00:00+00:00").await, 2);
+        // The on-target session resets the streak
+        assert_eq!(count("2026-03-04T07:00:00+00:00").await, 0);
+        assert_eq!(count("2026-03-05T07:00:00+00:00").await, 1);
+
+        // Deleting it joins the runs on either side
+        let deleted = delete_completed_workout(&pool, ids[2], user_id)
+            .await
+            .expect("delete");
+        assert!(deleted);
+        assert_eq!(count("2026-03-05T07:00:00+00:00").await, 3);
+    }
+
+    #[tokio::test]
+    async fn test_save_workout_analysis_and_previous_start() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+
+        let first = create_completed_workout(&pool, &test_input(user_id))
+            .await
+            .expect("create");
+        let mut input = test_input(user_id);
+        input.started_at = "2026-03-10T07:00:00+00:00".to_string();
+        let second = create_completed_workout(&pool, &input).await.expect("create");
+        assert!(second.analyzed_at.is_none());
+
+        let previous = get_previous_started_at(&pool, user_id, &second.started_at)
+            .await
+            .expect("previous");
+        assert_eq!(previous.as_deref(), Some(first.started_at.as_str()));
+        assert!(get_previous_started_at(&pool, user_id, &first.started_at)
+            .await
+            .expect("previous")
+            .is_none());
+
+        save_workout_analysis(
+            &pool,
+            user_id,
+            second.id,
+            "Solid easy run.",
+            "Aerobic work.",
+            "Nice job!",
+        )
+        .await
+        .expect("save");
+        let analyzed = get_completed_workout(&pool, second.id, user_id)
+            .await
+            .expect("get")
+            .expect("exists");
+        assert_eq!(analyzed.coach_summary.as_deref(), Some("Solid easy run."));
+        assert_eq!(analyzed.coach_commentary.as_deref(), Some("Aerobic work."));
+        assert_eq!(analyzed.coach_message.as_deref(), Some("Nice job!"));
+        assert!(analyzed.analyzed_at.is_some());
+
+        // A regenerated analysis replaces the chat message of the first one
+        save_workout_analysis(
+            &pool,
+            user_id,
+            second.id,
+            "Easy run.",
+            "Aerobic work.",
+            "Well done!",
+        )
+        .await
+        .expect("save again");
+        let messages = chat::list_chat_messages(&pool, user_id, 10, None)
+            .await
+            .unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].content, "Well done!");
+        assert_eq!(
+            messages[0].context_type.as_deref(),
+            Some("workout_analysis")
+        );
+        assert_eq!(messages[0].related_workout_id, Some(second.id));
+    }
+
+    #[tokio::test]
+    async fn test_recent_weekly_volume_needs_full_history() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        let before = NaiveDate::from_ymd_opt(2026, 3, 30).unwrap();
+
+        // Uploads only start inside the window
+        create_completed_workout(&pool, &test_input(user_id))
+            .await
+            .expect("create");
+        assert!(
+            get_recent_weekly_volume_km(&pool, user_id, before, 4)
+                .await
+                .expect("volume")
+                .is_none()
+        );
+
+        let mut input = test_input(user_id);
+        input.started_at = "2026-02-20T07:00:00+00:00".to_string();
+        create_completed_workout(&pool, &input)
+            .await
+            .expect("create");
+        input.started_at = "2026-03-20T07:00:00+00:00".to_string();
+        input.distance_m = 11500.0;
+        create_completed_workout(&pool, &input)
+            .await
+            .expect("create");
+        input.started_at = "2026-03-30T07:00:00+00:00".to_string();
+        create_completed_workout(&pool, &input)
+            .await
+            .expect("create");
+
+        // 8.5 km + 11.5 km over four weeks; the runs outside are ignored
+        let volume = get_recent_weekly_volume_km(&pool, user_id, before, 4)
+            .await
+            .expect("volume");
+        assert_eq!(volume, Some(5.0));
+    }
+}
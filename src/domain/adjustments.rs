@@ -0,0 +1,593 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+
+use super::compliance::TriggerReason;
+use super::types::{HrZones, PaceZones};
+use super::validation::{
+    PlannedDay, ValidationContext, ValidationError, WeekPlan, WeekType, validate_week_plan,
+};
+use super::workouts::{DurationCategory, WorkoutRegistry, WorkoutType};
+
+// ---------------------------------------------------------------------------
+// Plan adjustments (PRODUCT_DESIGN §7)
+// ---------------------------------------------------------------------------
+//
+// Claude may only change a plan through this closed set of operations. A
+// proposal names the §3.7 trigger that opened it and lists one or more
+// operations; it is applied as a whole only when the athlete accepts it:
+//
+//   swap_workout        replace a session with another type
+//   reduce_intensity    replace a session with a less intense type
+//   increase_intensity  replace a session with a more intense type
+//   add_recovery_day    turn the session on a date into a rest day
+//   extend_mesocycle    repeat the last load week 1-2 times before the
+//                       recovery week; the next mesocycle starts later
+//   skip_workout        drop a session
+//
+// Only future sessions that have not been completed can change. An applied
+// change must not introduce a `validate_week_plan` error that the affected
+// weeks did not already have, or make an existing one worse.
+//
+
+/// Most weeks a single extend_mesocycle may add.
+pub const MAX_EXTENSION_WEEKS: i64 = 2;
+
+/// A proposed plan change: the trigger that allowed it and the operations to
+/// apply, in order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlanAdjustment {
+    pub trigger_reason: TriggerReason,
+    pub operations: Vec<AdjustmentOperation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AdjustmentOperation {
+    SwapWorkout {
+        workout_id: i64,
+        new_workout_type: WorkoutType,
+        #[serde(default)]
+        duration_category: Option<DurationCategory>,
+    },
+    ReduceIntensity {
+        workout_id: i64,
+        new_workout_type: WorkoutType,
+        #[serde(default)]
+        duration_category: Option<DurationCategory>,
+    },
+    IncreaseIntensity {
+        workout_id: i64,
+        new_workout_type: WorkoutType,
+        #[serde(default)]
+        duration_category: Option<DurationCategory>,
+    },
+    AddRecoveryDay {
+        date: String,
+    },
+    ExtendMesocycle {
+        mesocycle_id: i64,
+        weeks: i64,
+    },
+    SkipWorkout {
+        workout_id: i64,
+    },
+}
+
+impl AdjustmentOperation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SwapWorkout { .. } => "swap_workout",
+            Self::ReduceIntensity { .. } => "reduce_intensity",
+            Self::IncreaseIntensity { .. } => "increase_intensity",
+            Self::AddRecoveryDay { .. } => "add_recovery_day",
+            Self::ExtendMesocycle { .. } => "extend_mesocycle",
+            Self::SkipWorkout { .. } => "skip_workout",
+        }
+    }
+
+    /// All operation names, for tool schemas.
+    pub fn all_types() -> [&'static str; 6] {
+        [
+            "swap_workout",
+            "reduce_intensity",
+            "increase_intensity",
+            "add_recovery_day",
+            "extend_mesocycle",
+            "skip_workout",
+        ]
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Sessions
+// ---------------------------------------------------------------------------
+
+/// The planned-workout fields for a session type, resolved against the
+/// athlete's zones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionFields {
+    pub workout_type: WorkoutType,
+    pub duration_category: Option<DurationCategory>,
+    pub duration_min: Option<i64>,
+    pub description: Option<String>,
+    pub target_hr_zones: Vec<u8>,
+    pub target_pace_zones: Vec<u8>,
+    pub expected_tss: f64,
+}
+
+impl SessionFields {
+    /// Expected TSS per minute, a proxy for intensity (TSS/h = 100 × IF²).
+    pub fn tss_per_minute(&self) -> f64 {
+        match self.duration_min {
+            Some(min) if min > 0 => self.expected_tss / min as f64,
+            _ => 0.0,
+        }
+    }
+}
+
+/// Resolve a running session or rest day. `None` for strength sessions and
+/// types without a template, which adjustments cannot schedule.
+pub fn resolve_session(
+    registry: &WorkoutRegistry,
+    workout_type: WorkoutType,
+    duration_category: DurationCategory,
+    hr_zones: &HrZones,
+    pace_zones: Option<&PaceZones>,
+) -> Option<SessionFields> {
+    if workout_type == WorkoutType::Rest {
+        return Some(SessionFields {
+            workout_type,
+            duration_category: None,
+            duration_min: None,
+            description: Some("Rest day".to_string()),
+            target_hr_zones: vec![],
+            target_pace_zones: vec![],
+            expected_tss: 0.0,
+        });
+    }
+
+    let resolved = registry.resolve(&workout_type, &duration_category, hr_zones, pace_zones)?;
+    Some(SessionFields {
+        workout_type,
+        duration_category: Some(duration_category),
+        duration_min: Some(resolved.duration_min as i64),
+        description: Some(resolved.description),
+        target_hr_zones: resolved.target_hr_zones,
+        target_pace_zones: resolved.target_pace_zones,
+        expected_tss: resolved.expected_tss,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Diff
+// ---------------------------------------------------------------------------
+
+/// A planned session as shown in an adjustment diff.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionSummary {
+    pub scheduled_date: String,
+    pub workout_type: String,
+    pub duration_min: Option<i64>,
+    pub expected_tss: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MesocycleSpan {
+    pub start_date: String,
+    pub end_date: String,
+    pub load_weeks: i64,
+}
+
+/// One change an adjustment makes to the plan.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum PlanChange {
+    WorkoutUpdated {
+        workout_id: i64,
+        before: SessionSummary,
+        after: SessionSummary,
+    },
+    WorkoutRemoved {
+        workout_id: i64,
+        before: SessionSummary,
+    },
+    WorkoutAdded {
+        after: SessionSummary,
+    },
+    WorkoutMoved {
+        workout_id: i64,
+        from: String,
+        to: String,
+    },
+    MesocycleChanged {
+        mesocycle_id: i64,
+        before: MesocycleSpan,
+        after: MesocycleSpan,
+    },
+}
+
+// ---------------------------------------------------------------------------
+// Re-validation
+// ---------------------------------------------------------------------------
+
+/// A planned session, as needed to rebuild weeks for validation.
+#[derive(Debug, Clone)]
+pub struct ScheduledSession {
+    pub date: NaiveDate,
+    pub workout_type: WorkoutType,
+    pub duration_category: Option<DurationCategory>,
+    pub expected_tss: f64,
+    pub target_distance_km: Option<f64>,
+}
+
+/// Group a mesocycle's sessions into 7-day weeks from its start date. Weeks
+/// after `load_weeks` are recovery weeks; volume and TSS targets are the sums
+/// of the sessions.
+pub fn mesocycle_weeks(
+    start: NaiveDate,
+    load_weeks: i64,
+    total_weeks: i64,
+    sessions: &[ScheduledSession],
+) -> Vec<WeekPlan> {
+    (0..total_weeks.max(0))
+        .map(|week| {
+            let week_start = start + chrono::Duration::weeks(week);
+            let week_end = week_start + chrono::Duration::days(7);
+            let in_week: Vec<&ScheduledSession> = sessions
+                .iter()
+                .filter(|s| s.date >= week_start && s.date < week_end)
+                .collect();
+            WeekPlan {
+                week_number: week as u32 + 1,
+                week_type: if week < load_weeks {
+                    WeekType::Load
+                } else {
+                    WeekType::Recovery
+                },
+                target_volume_km: in_week.iter().filter_map(|s| s.target_distance_km).sum(),
+                target_weekly_tss: in_week.iter().map(|s| s.expected_tss).sum(),
+                days: in_week
+                    .iter()
+                    .map(|s| PlannedDay {
+                        date: s.date.format("%Y-%m-%d").to_string(),
+                        workout_type: s.workout_type,
+                        duration_category: s.duration_category,
+                        expected_tss: s.expected_tss,
+                        target_distance_km: s.target_distance_km,
+                    })
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+fn validate_weeks(weeks: &[WeekPlan], athlete_ctl: f64) -> Vec<Vec<ValidationError>> {
+    weeks
+        .iter()
+        .enumerate()
+        .map(|(i, week)| {
+            let previous_week_volume_km = i
+                .checked_sub(1)
+                .map(|prev| weeks[prev].target_volume_km)
+                .filter(|km| *km > 0.0);
+            validate_week_plan(
+                week,
+                &ValidationContext {
+                    athlete_ctl,
+                    previous_week_volume_km,
+                },
+            )
+        })
+        .collect()
+}
+
+/// Whether `after` is the same error as `before` and no more severe: the
+/// same count, date or overshoot, or less.
+fn no_worse_than(after: &ValidationError, before: &ValidationError) -> bool {
+    use ValidationError::*;
+
+    fn tss_overshoot(tss: f64, min: f64, max: f64) -> f64 {
+        (tss - max).max(min - tss)
+    }
+    fn distance_gap(planned_km: f64, target_km: f64) -> f64 {
+        (planned_km - target_km).abs() / target_km
+    }
+
+    match (after, before) {
+        (NoRestDay, NoRestDay) => true,
+        (TooManyIntensitySessions { count: a, .. }, TooManyIntensitySessions { count: b, .. })
+        | (TooManyLongRuns { count: a, .. }, TooManyLongRuns { count: b, .. }) => a <= b,
+        (DuplicateDate { date: a }, DuplicateDate { date: b }) => a == b,
+        (
+            VolumeIncreaseTooHigh {
+                increase_pct: a, ..
+            },
+            VolumeIncreaseTooHigh {
+                increase_pct: b, ..
+            },
+        ) => a <= b,
+        (
+            WeeklyTssOutOfRange { tss, min, max },
+            WeeklyTssOutOfRange {
+                tss: before_tss,
+                min: before_min,
+                max: before_max,
+            },
+        ) => {
+            tss_overshoot(*tss, *min, *max) <= tss_overshoot(*before_tss, *before_min, *before_max)
+        }
+        (
+            DistanceMismatch {
+                planned_km,
+                target_km,
+            },
+            DistanceMismatch {
+                planned_km: before_planned,
+                target_km: before_target,
+            },
+        ) => distance_gap(*planned_km, *target_km) <= distance_gap(*before_planned, *before_target),
+        _ => false,
+    }
+}
+
+/// Validation errors in `after` that the same week did not already have in
+/// `before`, or has in a worse form (e.g. a fifth intensity session where
+/// there were four), as "week N: error" messages.
+pub fn new_violations(before: &[WeekPlan], after: &[WeekPlan], athlete_ctl: f64) -> Vec<String> {
+    let before_errors = validate_weeks(before, athlete_ctl);
+    let after_errors = validate_weeks(after, athlete_ctl);
+
+    let mut violations = Vec::new();
+    for (i, errors) in after_errors.iter().enumerate() {
+        let existing = before_errors.get(i).map(Vec::as_slice).unwrap_or_default();
+        for error in errors {
+            if !existing.iter().any(|e| no_worse_than(error, e)) {
+                violations.push(format!("week {}: {:?}", after[i].week_number, error));
+            }
+        }
+    }
+    violations
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::zones::calculate_hr_zones;
+    use serde_json::json;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn session(d: &str, workout_type: WorkoutType, tss: f64) -> ScheduledSession {
+        ScheduledSession {
+            date: date(d),
+            workout_type,
+            duration_category: Some(DurationCategory::Medium),
+            expected_tss: tss,
+            target_distance_km: None,
+        }
+    }
+
+    #[test]
+    fn adjustments_use_tagged_json() {
+        let adjustment: PlanAdjustment = serde_json::from_value(json!({
+            "trigger_reason": "sustained_harder_than_prescribed",
+            "operations": [
+                {"type": "reduce_intensity", "workout_id": 12, "new_workout_type": "easy_run"},
+                {"type": "add_recovery_day", "date": "2026-03-05"}
+            ]
+        }))
+        .unwrap();
+        assert_eq!(
+            adjustment,
+            PlanAdjustment {
+                trigger_reason: TriggerReason::SustainedHarderThanPrescribed,
+                operations: vec![
+                    AdjustmentOperation::ReduceIntensity {
+                        workout_id: 12,
+                        new_workout_type: WorkoutType::EasyRun,
+                        duration_category: None,
+                    },
+                    AdjustmentOperation::AddRecoveryDay {
+                        date: "2026-03-05".to_string(),
+                    },
+                ],
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(&adjustment).unwrap()["operations"][0]["type"],
+            "reduce_intensity"
+        );
+
+        let unknown = serde_json::from_value::<AdjustmentOperation>(json!({
+            "type": "delete_plan"
+        }));
+        assert!(unknown.is_err());
+        let untriggered = serde_json::from_value::<PlanAdjustment>(json!({
+            "trigger_reason": "felt_like_it",
+            "operations": []
+        }));
+        assert!(untriggered.is_err());
+    }
+
+    #[test]
+    fn every_operation_type_roundtrips() {
+        let operations = vec![
+            AdjustmentOperation::SwapWorkout {
+                workout_id: 1,
+                new_workout_type: WorkoutType::FartlekStructured,
+                duration_category: Some(DurationCategory::Short),
+            },
+            AdjustmentOperation::ReduceIntensity {
+                workout_id: 2,
+                new_workout_type: WorkoutType::EasyRun,
+                duration_category: None,
+            },
+            AdjustmentOperation::IncreaseIntensity {
+                workout_id: 3,
+                new_workout_type: WorkoutType::TempoRun,
+                duration_category: None,
+            },
+            AdjustmentOperation::AddRecoveryDay {
+                date: "2026-03-05".to_string(),
+            },
+            AdjustmentOperation::ExtendMesocycle {
+                mesocycle_id: 4,
+                weeks: 1,
+            },
+            AdjustmentOperation::SkipWorkout { workout_id: 5 },
+        ];
+        let names: Vec<&str> = operations.iter().map(AdjustmentOperation::as_str).collect();
+        assert_eq!(names, AdjustmentOperation::all_types());
+
+        for operation in operations {
+            let value = serde_json::to_value(&operation).unwrap();
+            assert_eq!(value["type"], operation.as_str());
+            let parsed: AdjustmentOperation = serde_json::from_value(value).unwrap();
+            assert_eq!(parsed, operation);
+        }
+    }
+
+    #[test]
+    fn rest_resolves_without_a_template() {
+        let registry = WorkoutRegistry::new();
+        let zones = calculate_hr_zones(170);
+        let rest = resolve_session(
+            &registry,
+            WorkoutType::Rest,
+            DurationCategory::Medium,
+            &zones,
+            None,
+        )
+        .unwrap();
+        assert_eq!(rest.expected_tss, 0.0);
+        assert_eq!(rest.duration_category, None);
+        assert_eq!(rest.tss_per_minute(), 0.0);
+
+        let strength = resolve_session(
+            &registry,
+            WorkoutType::StrengthPower,
+            DurationCategory::Medium,
+            &zones,
+            None,
+        );
+        assert!(strength.is_none());
+    }
+
+    #[test]
+    fn tempo_is_more_intense_than_easy() {
+        let registry = WorkoutRegistry::new();
+        let zones = calculate_hr_zones(170);
+        let resolve =
+            |wt| resolve_session(&registry, wt, DurationCategory::Medium, &zones, None).unwrap();
+        assert!(
+            resolve(WorkoutType::TempoRun).tss_per_minute()
+                > resolve(WorkoutType::EasyRun).tss_per_minute()
+        );
+    }
+
+    #[test]
+    fn sessions_group_into_mesocycle_weeks() {
+        let sessions = vec![
+            session("2026-03-02", WorkoutType::EasyRun, 40.0),
+            session("2026-03-08", WorkoutType::LongRun, 90.0),
+            session("2026-03-09", WorkoutType::Rest, 0.0),
+            session("2026-03-23", WorkoutType::RecoveryRun, 20.0),
+        ];
+        let weeks = mesocycle_weeks(date("2026-03-02"), 3, 4, &sessions);
+        assert_eq!(weeks.len(), 4);
+        assert_eq!(weeks[0].days.len(), 2);
+        assert_eq!(weeks[0].target_weekly_tss, 130.0);
+        assert_eq!(weeks[1].days.len(), 1);
+        assert_eq!(weeks[2].days.len(), 0);
+        assert_eq!(weeks[3].week_type, WeekType::Recovery);
+        assert_eq!(weeks[2].week_type, WeekType::Load);
+    }
+
+    #[test]
+    fn only_new_violations_are_reported() {
+        // Week without a rest day is already invalid; removing the long run
+        // keeps it that way without a new error.
+        let before = mesocycle_weeks(
+            date("2026-03-02"),
+            1,
+            1,
+            &[
+                session("2026-03-02", WorkoutType::EasyRun, 40.0),
+                session("2026-03-03", WorkoutType::LongRun, 90.0),
+            ],
+        );
+        let after = mesocycle_weeks(
+            date("2026-03-02"),
+            1,
+            1,
+            &[session("2026-03-02", WorkoutType::EasyRun, 40.0)],
+        );
+        assert!(new_violations(&before, &after, 0.0).is_empty());
+
+        // Replacing the only rest day with a long run adds errors
+        let before = mesocycle_weeks(
+            date("2026-03-02"),
+            1,
+            1,
+            &[
+                session("2026-03-02", WorkoutType::Rest, 0.0),
+                session("2026-03-08", WorkoutType::LongRun, 90.0),
+            ],
+        );
+        let after = mesocycle_weeks(
+            date("2026-03-02"),
+            1,
+            1,
+            &[
+                session("2026-03-02", WorkoutType::LongRun, 90.0),
+                session("2026-03-08", WorkoutType::LongRun, 90.0),
+            ],
+        );
+        let violations = new_violations(&before, &after, 0.0);
+        assert_eq!(violations.len(), 2, "{violations:?}");
+        assert!(violations[0].starts_with("week 1: NoRestDay"));
+    }
+
+    #[test]
+    fn worse_existing_violations_are_reported() {
+        let week =
+            |sessions: &[ScheduledSession]| mesocycle_weeks(date("2026-03-02"), 1, 1, sessions);
+        let intensity = |d| session(d, WorkoutType::Vo2maxIntervals, 60.0);
+        let four = [
+            intensity("2026-03-02"),
+            intensity("2026-03-03"),
+            intensity("2026-03-04"),
+            intensity("2026-03-05"),
+            session("2026-03-06", WorkoutType::Rest, 0.0),
+        ];
+
+        // A fifth intensity session in a week that already has four
+        let mut five = four.to_vec();
+        five.push(intensity("2026-03-07"));
+        let violations = new_violations(&week(&four), &week(&five), 0.0);
+        assert_eq!(violations.len(), 1, "{violations:?}");
+        assert!(violations[0].starts_with("week 1: TooManyIntensitySessions { count: 5"));
+
+        // Taking one away leaves a smaller, already known violation
+        assert!(new_violations(&week(&four), &week(&four[1..]), 0.0).is_empty());
+
+        // CTL 10 allows 35-140 weekly TSS; the week is at 240
+        let ctl = 10.0;
+        let mut lower = four.to_vec();
+        lower[0] = session("2026-03-02", WorkoutType::EasyRun, 40.0);
+        assert!(new_violations(&week(&four), &week(&lower), ctl).is_empty());
+        let violations = new_violations(&week(&lower), &week(&four), ctl);
+        assert!(
+            violations
+                .iter()
+                .any(|v| v.starts_with("week 1: WeeklyTssOutOfRange")),
+            "{violations:?}"
+        );
+    }
+}
@@ -0,0 +1,319 @@
+use serde::{Deserialize, Serialize};
+
+use super::workouts::WorkoutType;
+
+// ---------------------------------------------------------------------------
+// Workout classification (PRODUCT_DESIGN §3.4)
+// ---------------------------------------------------------------------------
+//
+// Maps what an athlete actually did onto the mesocycle focus vocabulary.
+// Inputs are seconds in the 7 LTHR-based HR zones and the 0-5 effect scores:
+//
+//   zone_1_3 = (Z1 + Z2 + Z3) / total
+//   zone_4_5 = (Z4 + Z5) / total
+//   zone_6_7 = (Z6 + Z7) / total
+//
+// Rules, first match wins:
+//   1. zone_1_3 >= 80% and anaerobic < 1.5                  → aerobic_capacity
+//   2. zone_4_5 >= 20% and aerobic >= 2.5                   → aerobic_utilization
+//   3. zone_6_7 >= 10% and anaerobic >= 2.5                 → anaerobic_capacity
+//   4. zone_6_7 >= 5% and aerobic >= 2.0 and anaerobic >= 2.0 → anaerobic_utilization
+//   5. otherwise                                            → mixed
+//
+
+/// Share of time in Z1-Z3 that makes a session aerobic capacity work.
+pub const AEROBIC_CAPACITY_FRACTION: f64 = 0.80;
+
+/// Anaerobic effect an aerobic capacity session stays below.
+pub const AEROBIC_CAPACITY_MAX_ANAEROBIC: f64 = 1.5;
+
+/// Share of time in Z4-Z5 that makes a session aerobic utilization work.
+pub const AEROBIC_UTILIZATION_FRACTION: f64 = 0.20;
+
+/// Aerobic effect an aerobic utilization session reaches.
+pub const AEROBIC_UTILIZATION_EFFECT: f64 = 2.5;
+
+/// Share of time in Z6-Z7 that makes a session anaerobic capacity work.
+pub const ANAEROBIC_CAPACITY_FRACTION: f64 = 0.10;
+
+/// Anaerobic effect an anaerobic capacity session reaches.
+pub const ANAEROBIC_CAPACITY_EFFECT: f64 = 2.5;
+
+/// Share of time in Z6-Z7 that makes a session anaerobic utilization work.
+pub const ANAEROBIC_UTILIZATION_FRACTION: f64 = 0.05;
+
+/// Aerobic and anaerobic effect an anaerobic utilization session reaches.
+pub const ANAEROBIC_UTILIZATION_EFFECT: f64 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Classification {
+    AerobicCapacity,
+    AerobicUtilization,
+    AnaerobicCapacity,
+    AnaerobicUtilization,
+    Mixed,
+}
+
+impl Classification {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AerobicCapacity => "aerobic_capacity",
+            Self::AerobicUtilization => "aerobic_utilization",
+            Self::AnaerobicCapacity => "anaerobic_capacity",
+            Self::AnaerobicUtilization => "anaerobic_utilization",
+            Self::Mixed => "mixed",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "aerobic_capacity" => Some(Self::AerobicCapacity),
+            "aerobic_utilization" => Some(Self::AerobicUtilization),
+            "anaerobic_capacity" => Some(Self::AnaerobicCapacity),
+            "anaerobic_utilization" => Some(Self::AnaerobicUtilization),
+            "mixed" => Some(Self::Mixed),
+            _ => None,
+        }
+    }
+
+    /// The classification a well-executed session of `workout_type` should
+    /// get. `None` for rest, strength, drills and time trials, which are not
+    /// classified.
+    pub fn expected_for(workout_type: WorkoutType) -> Option<Self> {
+        use WorkoutType::*;
+        match workout_type {
+            EasyRun | LongRun | LongRunModerate | AerobicDevelopment | ModerateRun
+            | ShakeoutRun | RecoveryRun => Some(Self::AerobicCapacity),
+            SteadyRun | TempoRun | UnderOver | CruiseIntervals | ProgressionRun
+            | LongRunProgression | LactateClearance => Some(Self::AerobicUtilization),
+            Vo2maxIntervals | Track800m | Track1200m | Track1600m | TrackMilePace => {
+                Some(Self::AnaerobicUtilization)
+            }
+            Track200m | Track400m | AnaerobicHills | AnaerobicFlat | AnaerobicPower
+            | HillSprints | PlyoRunning => Some(Self::AnaerobicCapacity),
+            TrackMixed | TrackRaceCombo | MixedEnergy | FartlekStructured | RaceSpecific => {
+                Some(Self::Mixed)
+            }
+            TimeTrial | FormDrills | Rest | StrengthPrecision | StrengthPerformance
+            | StrengthPower => None,
+        }
+    }
+}
+
+/// Classify a workout from seconds in HR zones 1-7 and its effect scores.
+/// Returns `None` if no HR zone time was recorded.
+pub fn classify_workout(
+    hr_zone_seconds: &[i64; 7],
+    aerobic_effect: f64,
+    anaerobic_effect: f64,
+) -> Option<Classification> {
+    let total: i64 = hr_zone_seconds.iter().sum();
+    if total <= 0 {
+        return None;
+    }
+    let fraction = |zones: &[i64]| zones.iter().sum::<i64>() as f64 / total as f64;
+    let zone_1_3 = fraction(&hr_zone_seconds[..3]);
+    let zone_4_5 = fraction(&hr_zone_seconds[3..5]);
+    let zone_6_7 = fraction(&hr_zone_seconds[5..]);
+
+    let classification = if zone_1_3 >= AEROBIC_CAPACITY_FRACTION
+        && anaerobic_effect < AEROBIC_CAPACITY_MAX_ANAEROBIC
+    {
+        Classification::AerobicCapacity
+    } else if zone_4_5 >= AEROBIC_UTILIZATION_FRACTION
+        && aerobic_effect >= AEROBIC_UTILIZATION_EFFECT
+    {
+        Classification::AerobicUtilization
+    } else if zone_6_7 >= ANAEROBIC_CAPACITY_FRACTION
+        && anaerobic_effect >= ANAEROBIC_CAPACITY_EFFECT
+    {
+        Classification::AnaerobicCapacity
+    } else if zone_6_7 >= ANAEROBIC_UTILIZATION_FRACTION
+        && aerobic_effect >= ANAEROBIC_UTILIZATION_EFFECT
+        && anaerobic_effect >= ANAEROBIC_UTILIZATION_EFFECT
+    {
+        Classification::AnaerobicUtilization
+    } else {
+        Classification::Mixed
+    };
+    Some(classification)
+}
+
+// ---------------------------------------------------------------------------
+// Planned vs executed
+// ---------------------------------------------------------------------------
+
+/// How the executed classification compares with the planned session type.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClassificationCheck {
+    pub planned_workout_type: String,
+    pub expected: Option<Classification>,
+    pub actual: Option<Classification>,
+    /// False when the athlete trained a different system than prescribed,
+    /// e.g. turned an easy run into a tempo. True when either side is unknown.
+    pub matches: bool,
+}
+
+/// Compare an executed classification with the planned workout type.
+/// A mixed prescription accepts any result except pure aerobic capacity work.
+pub fn check_against_plan(
+    actual: Option<Classification>,
+    planned: WorkoutType,
+) -> ClassificationCheck {
+    let expected = Classification::expected_for(planned);
+    let matches = match (expected, actual) {
+        (Some(Classification::Mixed), Some(actual)) => actual != Classification::AerobicCapacity,
+        (Some(expected), Some(actual)) => expected == actual,
+        _ => true,
+    };
+    ClassificationCheck {
+        planned_workout_type: planned.as_str().to_string(),
+        expected,
+        actual,
+        matches,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classification_roundtrip() {
+        for c in [
+            Classification::AerobicCapacity,
+            Classification::AerobicUtilization,
+            Classification::AnaerobicCapacity,
+            Classification::AnaerobicUtilization,
+            Classification::Mixed,
+        ] {
+            assert_eq!(Classification::from_str(c.as_str()), Some(c));
+            assert_eq!(serde_json::to_value(c).unwrap(), c.as_str());
+        }
+        assert_eq!(Classification::from_str("race_specific"), None);
+    }
+
+    #[test]
+    fn easy_run_is_aerobic_capacity() {
+        let zones = [600, 3000, 120, 0, 0, 0, 0];
+        assert_eq!(
+            classify_workout(&zones, 2.3, 0.0),
+            Some(Classification::AerobicCapacity)
+        );
+    }
+
+    #[test]
+    fn strides_dont_make_an_easy_run_anaerobic() {
+        let zones = [600, 2400, 60, 0, 0, 0, 0];
+        assert_eq!(
+            classify_workout(&zones, 2.2, 1.2),
+            Some(Classification::AerobicCapacity)
+        );
+    }
+
+    #[test]
+    fn tempo_is_aerobic_utilization() {
+        let zones = [900, 600, 300, 1500, 0, 0, 0];
+        assert_eq!(
+            classify_workout(&zones, 3.2, 0.2),
+            Some(Classification::AerobicUtilization)
+        );
+    }
+
+    #[test]
+    fn threshold_time_needs_aerobic_effect() {
+        // 25% in Z4 but too short to reach an aerobic effect of 2.5
+        let zones = [600, 600, 0, 400, 0, 0, 0];
+        assert_eq!(
+            classify_workout(&zones, 2.4, 0.5),
+            Some(Classification::Mixed)
+        );
+        assert_eq!(
+            classify_workout(&zones, 2.5, 0.5),
+            Some(Classification::AerobicUtilization)
+        );
+    }
+
+    #[test]
+    fn sprints_are_anaerobic_capacity() {
+        let zones = [1200, 1200, 300, 200, 100, 400, 0];
+        assert_eq!(
+            classify_workout(&zones, 2.1, 4.2),
+            Some(Classification::AnaerobicCapacity)
+        );
+    }
+
+    #[test]
+    fn race_pace_reps_are_anaerobic_utilization() {
+        let zones = [1200, 900, 300, 200, 200, 200, 0];
+        assert_eq!(
+            classify_workout(&zones, 2.4, 2.2),
+            Some(Classification::AnaerobicUtilization)
+        );
+        // Without the aerobic load it is neither
+        assert_eq!(
+            classify_workout(&zones, 1.9, 2.2),
+            Some(Classification::Mixed)
+        );
+    }
+
+    #[test]
+    fn anaerobic_effect_without_high_hr_is_mixed() {
+        // Mostly easy HR, but the anaerobic effect rules out aerobic capacity
+        let zones = [1200, 1800, 300, 200, 100, 0, 0];
+        assert_eq!(
+            classify_workout(&zones, 2.1, 4.2),
+            Some(Classification::Mixed)
+        );
+    }
+
+    #[test]
+    fn no_hr_time_is_unclassified() {
+        assert_eq!(classify_workout(&[0; 7], 0.0, 0.0), None);
+    }
+
+    #[test]
+    fn every_running_type_with_an_energy_system_has_an_expectation() {
+        for wt in WorkoutType::all_running() {
+            if matches!(wt, WorkoutType::TimeTrial | WorkoutType::FormDrills) {
+                continue;
+            }
+            assert!(Classification::expected_for(wt).is_some(), "{wt:?}");
+        }
+        assert_eq!(Classification::expected_for(WorkoutType::Rest), None);
+    }
+
+    #[test]
+    fn easy_run_turned_into_tempo_is_flagged() {
+        let check = check_against_plan(
+            Some(Classification::AerobicUtilization),
+            WorkoutType::EasyRun,
+        );
+        assert_eq!(check.expected, Some(Classification::AerobicCapacity));
+        assert!(!check.matches);
+        assert_eq!(check.planned_workout_type, "easy_run");
+
+        let check = check_against_plan(Some(Classification::AerobicCapacity), WorkoutType::EasyRun);
+        assert!(check.matches);
+    }
+
+    #[test]
+    fn mixed_prescription_accepts_any_hard_session() {
+        let planned = WorkoutType::FartlekStructured;
+        assert!(check_against_plan(Some(Classification::AnaerobicCapacity), planned).matches);
+        assert!(check_against_plan(Some(Classification::AerobicUtilization), planned).matches);
+        assert!(!check_against_plan(Some(Classification::AerobicCapacity), planned).matches);
+    }
+
+    #[test]
+    fn unknown_sides_match() {
+        assert!(check_against_plan(None, WorkoutType::TempoRun).matches);
+        assert!(check_against_plan(Some(Classification::Mixed), WorkoutType::TimeTrial).matches);
+    }
+}
@@ -0,0 +1,634 @@
+use serde::{Deserialize, Serialize};
+
+use super::classification::{Classification, check_against_plan};
+use super::scoring::ZoneTime;
+use super::workouts::WorkoutType;
+
+// ---------------------------------------------------------------------------
+// Prescription compliance
+// ---------------------------------------------------------------------------
+//
+// Compares an executed workout with the planned session it was matched to.
+// Three independent signals each say harder, easier or neither:
+//
+//   intensity — time in zones vs target zones (HR if available, else pace):
+//               harder if >= 15% of the time is above the highest target
+//               zone; easier if an intensity session spent < 5% of the time
+//               at or above its highest target zone, or a steady session
+//               spent >= 25% below its lowest target zone
+//   load      — actual vs expected TSS, outside ±25%
+//   volume    — distance vs target distance (or duration vs planned
+//               duration), outside ±20%
+//
+// Verdict:
+//   unplanned       — no planned workout
+//   different_type  — classification trained the other energy system
+//                     (aerobic vs anaerobic) than the session prescribed
+//   harder / easier — more signals one way than the other
+//   on_target       — otherwise (including mixed signals, e.g. shorter but
+//                     faster)
+//
+
+/// Fraction of time above the highest target zone that makes a session harder.
+pub const ABOVE_TARGET_FRACTION: f64 = 0.15;
+
+/// Fraction of time below the lowest target zone that makes a steady session
+/// easier.
+pub const BELOW_TARGET_FRACTION: f64 = 0.25;
+
+/// An intensity session with less time than this at or above its highest
+/// target zone never reached the work intensity.
+pub const MIN_WORK_ZONE_FRACTION: f64 = 0.05;
+
+/// Allowed deviation of actual from expected TSS.
+pub const TSS_TOLERANCE: f64 = 0.25;
+
+/// Allowed deviation of distance or duration from the plan.
+pub const VOLUME_TOLERANCE: f64 = 0.20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ComplianceVerdict {
+    OnTarget,
+    HarderThanPrescribed,
+    EasierThanPrescribed,
+    DifferentType,
+    Unplanned,
+}
+
+impl ComplianceVerdict {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::OnTarget => "on_target",
+            Self::HarderThanPrescribed => "harder_than_prescribed",
+            Self::EasierThanPrescribed => "easier_than_prescribed",
+            Self::DifferentType => "different_type",
+            Self::Unplanned => "unplanned",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "on_target" => Some(Self::OnTarget),
+            "harder_than_prescribed" => Some(Self::HarderThanPrescribed),
+            "easier_than_prescribed" => Some(Self::EasierThanPrescribed),
+            "different_type" => Some(Self::DifferentType),
+            "unplanned" => Some(Self::Unplanned),
+            _ => None,
+        }
+    }
+
+    /// Direction of an off-target verdict, if it has one.
+    pub fn direction(&self) -> Option<Direction> {
+        match self {
+            Self::HarderThanPrescribed => Some(Direction::Harder),
+            Self::EasierThanPrescribed => Some(Direction::Easier),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Harder,
+    Easier,
+}
+
+/// What the planned session asked for. Zones are 1-based.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Prescription {
+    pub workout_type: WorkoutType,
+    pub duration_min: Option<i64>,
+    pub target_distance_km: Option<f64>,
+    pub expected_tss: Option<f64>,
+    pub target_hr_zones: Vec<u8>,
+    pub target_pace_zones: Vec<u8>,
+}
+
+/// What the athlete did.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Execution {
+    pub duration_seconds: i64,
+    pub distance_m: f64,
+    pub tss: Option<f64>,
+    /// `None` when HR data is insufficient.
+    pub hr_zone_seconds: Option<[i64; 7]>,
+    pub pace_zone_seconds: [i64; 6],
+    pub classification: Option<Classification>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ComplianceAssessment {
+    pub verdict: ComplianceVerdict,
+    /// Fraction of time in the target zones, 0..=1.
+    pub time_in_target_zones: Option<f64>,
+    /// Fraction of time above the highest target zone, 0..=1.
+    pub time_above_target_zones: Option<f64>,
+    pub tss_ratio: Option<f64>,
+    pub duration_ratio: Option<f64>,
+    pub distance_ratio: Option<f64>,
+    pub intensity: Option<Direction>,
+    pub load: Option<Direction>,
+    pub volume: Option<Direction>,
+}
+
+impl ComplianceAssessment {
+    fn without_signals(verdict: ComplianceVerdict) -> Self {
+        Self {
+            verdict,
+            time_in_target_zones: None,
+            time_above_target_zones: None,
+            tss_ratio: None,
+            duration_ratio: None,
+            distance_ratio: None,
+            intensity: None,
+            load: None,
+            volume: None,
+        }
+    }
+}
+
+fn ratio(actual: f64, planned: Option<f64>) -> Option<f64> {
+    planned.filter(|&p| p > 0.0).map(|p| actual / p)
+}
+
+fn ratio_direction(ratio: Option<f64>, tolerance: f64) -> Option<Direction> {
+    match ratio? {
+        r if r > 1.0 + tolerance => Some(Direction::Harder),
+        r if r < 1.0 - tolerance => Some(Direction::Easier),
+        _ => None,
+    }
+}
+
+/// Zone time and target zones to judge intensity by: HR when available and
+/// prescribed, else pace.
+fn zone_basis(prescription: &Prescription, execution: &Execution) -> Option<(ZoneTime, Vec<u8>)> {
+    let hr = execution
+        .hr_zone_seconds
+        .filter(|_| !prescription.target_hr_zones.is_empty())
+        .map(|seconds| (seconds.to_vec(), &prescription.target_hr_zones));
+    let pace = Some(execution.pace_zone_seconds)
+        .filter(|_| !prescription.target_pace_zones.is_empty())
+        .map(|seconds| (seconds.to_vec(), &prescription.target_pace_zones));
+
+    [hr, pace]
+        .into_iter()
+        .flatten()
+        .map(|(seconds, targets)| (ZoneTime { seconds }, targets.clone()))
+        .find(|(time, _)| time.total_seconds() > 0)
+}
+
+/// Assess a workout against its prescription (`None` = unplanned).
+pub fn assess_compliance(
+    prescription: Option<&Prescription>,
+    execution: &Execution,
+) -> ComplianceAssessment {
+    let Some(prescription) = prescription else {
+        return ComplianceAssessment::without_signals(ComplianceVerdict::Unplanned);
+    };
+
+    let mut assessment = ComplianceAssessment::without_signals(ComplianceVerdict::OnTarget);
+
+    // Intensity
+    if let Some((time, targets)) = zone_basis(prescription, execution) {
+        let zone_count = time.seconds.len() as u8;
+        let lowest = targets.iter().copied().min().unwrap_or(1);
+        let highest = targets.iter().copied().max().unwrap_or(zone_count);
+        let above = time.fraction_in(&(highest + 1..=zone_count).collect::<Vec<_>>());
+        let below = time.fraction_in(&(1..lowest).collect::<Vec<_>>());
+        let at_or_above_work = time.fraction_in(&(highest..=zone_count).collect::<Vec<_>>());
+
+        assessment.time_in_target_zones = Some(time.fraction_in(&targets));
+        assessment.time_above_target_zones = Some(above);
+        assessment.intensity = if above >= ABOVE_TARGET_FRACTION {
+            Some(Direction::Harder)
+        } else if prescription.workout_type.is_intensity() {
+            (at_or_above_work < MIN_WORK_ZONE_FRACTION).then_some(Direction::Easier)
+        } else {
+            (below >= BELOW_TARGET_FRACTION).then_some(Direction::Easier)
+        };
+    }
+
+    // Load
+    assessment.tss_ratio = execution
+        .tss
+        .and_then(|tss| ratio(tss, prescription.expected_tss));
+    assessment.load = ratio_direction(assessment.tss_ratio, TSS_TOLERANCE);
+
+    // Volume: distance when a distance was prescribed, else duration
+    assessment.duration_ratio = ratio(
+        execution.duration_seconds as f64 / 60.0,
+        prescription.duration_min.map(|d| d as f64),
+    );
+    assessment.distance_ratio = ratio(
+        execution.distance_m / 1000.0,
+        prescription.target_distance_km,
+    );
+    assessment.volume = ratio_direction(
+        assessment.distance_ratio.or(assessment.duration_ratio),
+        VOLUME_TOLERANCE,
+    );
+
+    assessment.verdict = if trained_other_system(execution.classification, prescription) {
+        ComplianceVerdict::DifferentType
+    } else {
+        let signals = [assessment.intensity, assessment.load, assessment.volume];
+        let harder = signals
+            .iter()
+            .filter(|&&s| s == Some(Direction::Harder))
+            .count();
+        let easier = signals
+            .iter()
+            .filter(|&&s| s == Some(Direction::Easier))
+            .count();
+        match harder.cmp(&easier) {
+            std::cmp::Ordering::Greater => ComplianceVerdict::HarderThanPrescribed,
+            std::cmp::Ordering::Less => ComplianceVerdict::EasierThanPrescribed,
+            std::cmp::Ordering::Equal => ComplianceVerdict::OnTarget,
+        }
+    };
+
+    assessment
+}
+
+/// True if the classification mismatches the plan across energy systems.
+/// Capacity vs utilization within a system is judged by the signals instead.
+fn trained_other_system(actual: Option<Classification>, prescription: &Prescription) -> bool {
+    fn is_anaerobic(c: Classification) -> Option<bool> {
+        match c {
+            Classification::AerobicCapacity | Classification::AerobicUtilization => Some(false),
+            Classification::AnaerobicCapacity | Classification::AnaerobicUtilization => Some(true),
+            Classification::Mixed => None,
+        }
+    }
+
+    let check = check_against_plan(actual, prescription.workout_type);
+    if check.matches {
+        return false;
+    }
+    match (
+        check.expected.and_then(is_anaerobic),
+        actual.and_then(is_anaerobic),
+    ) {
+        (Some(expected), Some(actual)) => expected != actual,
+        _ => false,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Off-target streak
+// ---------------------------------------------------------------------------
+
+/// Consecutive planned workouts off target in the same direction, most recent
+/// first. Feeds the §3.7 stability rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct OffTargetStreak {
+    pub consecutive_off_target_count: u32,
+    pub off_target_direction: Option<Direction>,
+}
+
+/// Count the current streak from verdicts ordered newest first. Unplanned
+/// workouts are skipped; on-target and different-type workouts end the
+/// streak.
+pub fn off_target_streak(
+    verdicts_newest_first: impl IntoIterator<Item = ComplianceVerdict>,
+) -> OffTargetStreak {
+    let mut streak = OffTargetStreak {
+        consecutive_off_target_count: 0,
+        off_target_direction: None,
+    };
+
+    for verdict in verdicts_newest_first {
+        if verdict == ComplianceVerdict::Unplanned {
+            continue;
+        }
+        match (verdict.direction(), streak.off_target_direction) {
+            (Some(direction), None) => {
+                streak.off_target_direction = Some(direction);
+                streak.consecutive_off_target_count = 1;
+            }
+            (Some(direction), Some(current)) if direction == current => {
+                streak.consecutive_off_target_count += 1;
+            }
+            _ => break,
+        }
+    }
+
+    streak
+}
+
+// ---------------------------------------------------------------------------
+// Plan adjustment triggers (§3.7)
+// ---------------------------------------------------------------------------
+//
+// The plan stays stable through single off days. Claude may only propose an
+// adjustment when one of these fires:
+//
+//   - 3+ consecutive planned workouts off target in the same direction
+//   - 7+ days since the previous workout
+//   - ATL/CTL above 1.5 (acute load far ahead of fitness)
+//
+
+/// Consecutive same-direction off-target workouts that open adjustments.
+pub const ADJUSTMENT_STREAK: u32 = 3;
+
+/// Days without training that open adjustments.
+pub const ADJUSTMENT_GAP_DAYS: i64 = 7;
+
+/// ATL/CTL ratio above which the athlete is overreaching.
+pub const OVERREACHING_ATL_CTL_RATIO: f64 = 1.5;
+
+/// Which §3.7 trigger opened an adjustment; stored with every proposal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TriggerReason {
+    SustainedHarderThanPrescribed,
+    SustainedEasierThanPrescribed,
+    ExtendedAbsence,
+    HighAtlCtlRatio,
+}
+
+impl TriggerReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::SustainedHarderThanPrescribed => "sustained_harder_than_prescribed",
+            Self::SustainedEasierThanPrescribed => "sustained_easier_than_prescribed",
+            Self::ExtendedAbsence => "extended_absence",
+            Self::HighAtlCtlRatio => "high_atl_ctl_ratio",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "sustained_harder_than_prescribed" => Some(Self::SustainedHarderThanPrescribed),
+            "sustained_easier_than_prescribed" => Some(Self::SustainedEasierThanPrescribed),
+            "extended_absence" => Some(Self::ExtendedAbsence),
+            "high_atl_ctl_ratio" => Some(Self::HighAtlCtlRatio),
+            _ => None,
+        }
+    }
+
+    pub fn all() -> &'static [&'static str] {
+        &[
+            "sustained_harder_than_prescribed",
+            "sustained_easier_than_prescribed",
+            "extended_absence",
+            "high_atl_ctl_ratio",
+        ]
+    }
+}
+
+/// The §3.7 triggers that currently fire. Empty means the plan stays as is.
+pub fn adjustment_triggers(
+    streak: &OffTargetStreak,
+    days_since_last_workout: Option<i64>,
+    atl_ctl_ratio: Option<f64>,
+) -> Vec<TriggerReason> {
+    let mut triggers = Vec::new();
+    if streak.consecutive_off_target_count >= ADJUSTMENT_STREAK {
+        match streak.off_target_direction {
+            Some(Direction::Harder) => triggers.push(TriggerReason::SustainedHarderThanPrescribed),
+            Some(Direction::Easier) => triggers.push(TriggerReason::SustainedEasierThanPrescribed),
+            None => {}
+        }
+    }
+    if days_since_last_workout.is_some_and(|days| days >= ADJUSTMENT_GAP_DAYS) {
+        triggers.push(TriggerReason::ExtendedAbsence);
+    }
+    if atl_ctl_ratio.is_some_and(|ratio| ratio > OVERREACHING_ATL_CTL_RATIO) {
+        triggers.push(TriggerReason::HighAtlCtlRatio);
+    }
+    triggers
+}
+
+/// Whether the §3.7 stability rules allow a plan adjustment.
+pub fn adjustment_eligible(
+    streak: &OffTargetStreak,
+    days_since_last_workout: Option<i64>,
+    atl_ctl_ratio: Option<f64>,
+) -> bool {
+    !adjustment_triggers(streak, days_since_last_workout, atl_ctl_ratio).is_empty()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn easy_run() -> Prescription {
+        Prescription {
+            workout_type: WorkoutType::EasyRun,
+            duration_min: Some(45),
+            target_distance_km: None,
+            expected_tss: Some(40.0),
+            target_hr_zones: vec![1, 2],
+            target_pace_zones: vec![1, 2],
+        }
+    }
+
+    fn vo2max() -> Prescription {
+        Prescription {
+            workout_type: WorkoutType::Vo2maxIntervals,
+            duration_min: Some(60),
+            target_distance_km: None,
+            expected_tss: Some(80.0),
+            target_hr_zones: vec![1, 2, 5, 6],
+            target_pace_zones: vec![1, 2, 5],
+        }
+    }
+
+    fn execution(minutes: i64, tss: f64, hr_zone_seconds: [i64; 7]) -> Execution {
+        Execution {
+            duration_seconds: minutes * 60,
+            distance_m: minutes as f64 * 180.0,
+            tss: Some(tss),
+            hr_zone_seconds: Some(hr_zone_seconds),
+            pace_zone_seconds: [0; 6],
+            classification: None,
+        }
+    }
+
+    #[test]
+    fn verdict_roundtrip() {
+        for v in [
+            ComplianceVerdict::OnTarget,
+            ComplianceVerdict::HarderThanPrescribed,
+            ComplianceVerdict::EasierThanPrescribed,
+            ComplianceVerdict::DifferentType,
+            ComplianceVerdict::Unplanned,
+        ] {
+            assert_eq!(ComplianceVerdict::from_str(v.as_str()), Some(v));
+            assert_eq!(serde_json::to_value(v).unwrap(), v.as_str());
+        }
+    }
+
+    #[test]
+    fn no_prescription_is_unplanned() {
+        let a = assess_compliance(None, &execution(45, 40.0, [600, 2100, 0, 0, 0, 0, 0]));
+        assert_eq!(a.verdict, ComplianceVerdict::Unplanned);
+        assert!(a.tss_ratio.is_none());
+    }
+
+    #[test]
+    fn easy_run_as_prescribed_is_on_target() {
+        let a = assess_compliance(
+            Some(&easy_run()),
+            &execution(46, 42.0, [600, 2100, 60, 0, 0, 0, 0]),
+        );
+        assert_eq!(a.verdict, ComplianceVerdict::OnTarget);
+        assert!(a.time_in_target_zones.unwrap() > 0.95);
+        assert!((a.tss_ratio.unwrap() - 1.05).abs() < 1e-9);
+        assert_eq!((a.intensity, a.load, a.volume), (None, None, None));
+    }
+
+    #[test]
+    fn easy_run_run_as_tempo_is_harder() {
+        let mut e = execution(45, 65.0, [300, 300, 600, 1500, 0, 0, 0]);
+        e.classification = Some(Classification::AerobicUtilization);
+        let a = assess_compliance(Some(&easy_run()), &e);
+        assert_eq!(a.verdict, ComplianceVerdict::HarderThanPrescribed);
+        assert_eq!(a.intensity, Some(Direction::Harder));
+        assert_eq!(a.load, Some(Direction::Harder));
+    }
+
+    #[test]
+    fn cut_short_is_easier() {
+        let a = assess_compliance(
+            Some(&easy_run()),
+            &execution(25, 22.0, [300, 1200, 0, 0, 0, 0, 0]),
+        );
+        assert_eq!(a.verdict, ComplianceVerdict::EasierThanPrescribed);
+        assert_eq!(a.volume, Some(Direction::Easier));
+        assert_eq!(a.load, Some(Direction::Easier));
+    }
+
+    #[test]
+    fn intervals_that_never_reach_work_zone_are_easier() {
+        let a = assess_compliance(
+            Some(&vo2max()),
+            &execution(60, 55.0, [900, 2700, 0, 0, 0, 0, 0]),
+        );
+        assert_eq!(a.intensity, Some(Direction::Easier));
+        assert_eq!(a.load, Some(Direction::Easier));
+        assert_eq!(a.verdict, ComplianceVerdict::EasierThanPrescribed);
+    }
+
+    #[test]
+    fn shorter_but_faster_is_on_target() {
+        let a = assess_compliance(
+            Some(&easy_run()),
+            &execution(30, 38.0, [0, 900, 900, 0, 0, 0, 0]),
+        );
+        assert_eq!(a.intensity, Some(Direction::Harder));
+        assert_eq!(a.volume, Some(Direction::Easier));
+        assert_eq!(a.verdict, ComplianceVerdict::OnTarget);
+    }
+
+    #[test]
+    fn other_energy_system_is_different_type() {
+        let mut e = execution(45, 45.0, [600, 2000, 100, 0, 0, 0, 0]);
+        e.classification = Some(Classification::AnaerobicCapacity);
+        let a = assess_compliance(Some(&easy_run()), &e);
+        assert_eq!(a.verdict, ComplianceVerdict::DifferentType);
+    }
+
+    #[test]
+    fn distance_prescription_takes_precedence_over_duration() {
+        let mut p = easy_run();
+        p.target_distance_km = Some(8.0);
+        let mut e = execution(45, 40.0, [600, 2100, 0, 0, 0, 0, 0]);
+        e.distance_m = 12_000.0;
+        let a = assess_compliance(Some(&p), &e);
+        assert!((a.distance_ratio.unwrap() - 1.5).abs() < 1e-9);
+        assert_eq!(a.volume, Some(Direction::Harder));
+    }
+
+    #[test]
+    fn falls_back_to_pace_zones_without_hr() {
+        let mut e = execution(45, 40.0, [0; 7]);
+        e.hr_zone_seconds = None;
+        e.pace_zone_seconds = [0, 300, 1800, 600, 0, 0];
+        let a = assess_compliance(Some(&easy_run()), &e);
+        assert_eq!(a.intensity, Some(Direction::Harder));
+        assert!(a.time_above_target_zones.unwrap() > 0.8);
+    }
+
+    #[test]
+    fn streak_counts_same_direction() {
+        use ComplianceVerdict::*;
+        let streak = off_target_streak([
+            HarderThanPrescribed,
+            Unplanned,
+            HarderThanPrescribed,
+            HarderThanPrescribed,
+            EasierThanPrescribed,
+            HarderThanPrescribed,
+        ]);
+        assert_eq!(streak.consecutive_off_target_count, 3);
+        assert_eq!(streak.off_target_direction, Some(Direction::Harder));
+    }
+
+    #[test]
+    fn on_target_breaks_streak() {
+        use ComplianceVerdict::*;
+        let streak = off_target_streak([OnTarget, EasierThanPrescribed, EasierThanPrescribed]);
+        assert_eq!(streak.consecutive_off_target_count, 0);
+        assert_eq!(streak.off_target_direction, None);
+
+        let streak = off_target_streak([EasierThanPrescribed, DifferentType, EasierThanPrescribed]);
+        assert_eq!(streak.consecutive_off_target_count, 1);
+    }
+
+    #[test]
+    fn adjustment_needs_a_trigger() {
+        let streak = |count, direction| OffTargetStreak {
+            consecutive_off_target_count: count,
+            off_target_direction: direction,
+        };
+        assert!(!adjustment_eligible(
+            &streak(2, Some(Direction::Harder)),
+            Some(1),
+            Some(1.2)
+        ));
+        assert!(adjustment_eligible(
+            &streak(3, Some(Direction::Harder)),
+            Some(1),
+            None
+        ));
+        assert!(adjustment_eligible(&streak(0, None), Some(7), None));
+        assert!(adjustment_eligible(&streak(0, None), None, Some(1.6)));
+        assert!(!adjustment_eligible(&streak(0, None), None, None));
+    }
+
+    #[test]
+    fn triggers_name_what_fired() {
+        let streak = |count, direction| OffTargetStreak {
+            consecutive_off_target_count: count,
+            off_target_direction: direction,
+        };
+        assert_eq!(
+            adjustment_triggers(&streak(3, Some(Direction::Harder)), Some(1), None),
+            vec![TriggerReason::SustainedHarderThanPrescribed]
+        );
+        assert_eq!(
+            adjustment_triggers(&streak(4, Some(Direction::Easier)), Some(8), Some(1.6)),
+            vec![
+                TriggerReason::SustainedEasierThanPrescribed,
+                TriggerReason::ExtendedAbsence,
+                TriggerReason::HighAtlCtlRatio,
+            ]
+        );
+        assert!(
+            adjustment_triggers(&streak(2, Some(Direction::Easier)), Some(6), Some(1.5)).is_empty()
+        );
+        for s in TriggerReason::all() {
+            assert_eq!(TriggerReason::from_str(s).unwrap().as_str(), *s);
+        }
+    }
+}
@@ -0,0 +1,550 @@
+use serde::{Deserialize, Serialize};
+
+use crate::fit::FitRecord;
+
+// ---------------------------------------------------------------------------
+// Aerobic / anaerobic training effect (PRODUCT_DESIGN §3.4)
+// ---------------------------------------------------------------------------
+//
+// Aerobic effect — per second:
+//   intensity = (HR - resting_HR) / (max_HR - resting_HR)   (%HRR ≈ %VO2max)
+//   rate      = 0 if intensity < 0.30, else k1 * e^(k2 * intensity)
+//   EPOC     += rate - k3 * EPOC
+// HR above 104% LTHR is capped there; the excess is scored by the anaerobic
+// effect. Aerobic effect = min(5, peak EPOC / fitness_factor / scale).
+//
+// Anaerobic effect — intervals where HR > 104% LTHR or speed > FTPace,
+// ignoring intervals shorter than 5 s. Each interval scores
+//   intensity_score = (avg_%VO2max - 0.85)^2 * 100   (0 at or below 85%)
+//   duration_weight = 1 if d <= 120 s, else max(0.3, 1 - (d - 120) / 300)
+//   interval_score  = intensity_score * duration_weight * d / 60
+// Anaerobic effect = min(5, Σ interval_score / fitness_factor / scale).
+//
+// HR lags a sprint by tens of seconds, so %VO2max each second is the higher
+// of %HRR and speed / FTPace * (%VO2max at FTPace), capped at the
+// supramaximal ceiling.
+//
+// Fitness factor = 1 + CTL / 100 * 0.5: the same session has less effect on
+// a fitter athlete.
+//
+// Reference scores are pinned by the calibration harness in
+// `tests/calibration/`; re-run it after changing any coefficient.
+//
+
+/// Upper bound of both effect scores.
+pub const MAX_EFFECT: f64 = 5.0;
+
+/// Intensity below which no EPOC accumulates (below the aerobic threshold).
+const MIN_EPOC_INTENSITY: f64 = 0.30;
+
+/// %VO2max above which an interval stresses the anaerobic system.
+const ANAEROBIC_INTENSITY_FLOOR: f64 = 0.85;
+
+/// Longest interval counted at full anaerobic weight, in seconds.
+const FULL_WEIGHT_SECONDS: usize = 120;
+
+/// Seconds past `FULL_WEIGHT_SECONDS` over which the weight falls to zero,
+/// before the floor applies.
+const WEIGHT_FALLOFF_SECONDS: f64 = 300.0;
+
+/// Least weight of a long interval.
+const MIN_DURATION_WEIGHT: f64 = 0.3;
+
+/// Tunable coefficients for the effect models.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EffectCoefficients {
+    /// Base EPOC accumulation coefficient, per second.
+    pub k1: f64,
+    /// Exponential weighting of intensity in the EPOC rate.
+    pub k2: f64,
+    /// Fraction of accumulated EPOC that decays each second.
+    pub k3: f64,
+    /// Peak EPOC worth one aerobic effect point.
+    pub aerobic_epoc_per_point: f64,
+    /// Fraction of LTHR above which a second counts as anaerobic.
+    pub anaerobic_hr_threshold: f64,
+    /// Fraction of FTPace above which a second counts as anaerobic.
+    pub anaerobic_pace_threshold: f64,
+    /// Intervals shorter than this many seconds are ignored as noise.
+    pub min_interval_seconds: usize,
+    /// %VO2max of running at FTPace; speed scales it linearly.
+    pub ftpace_intensity: f64,
+    /// Highest %VO2max a second can score; sprints run above VO2max pace.
+    pub max_intensity: f64,
+    /// Summed interval score worth one anaerobic effect point.
+    pub anaerobic_load_per_point: f64,
+}
+
+impl Default for EffectCoefficients {
+    fn default() -> Self {
+        Self {
+            k1: 0.1,
+            k2: 5.0,
+            k3: 0.0003,
+            aerobic_epoc_per_point: 2200.0,
+            anaerobic_hr_threshold: 1.04,
+            anaerobic_pace_threshold: 1.0,
+            min_interval_seconds: 5,
+            ftpace_intensity: 0.95,
+            max_intensity: 1.2,
+            anaerobic_load_per_point: 12.0,
+        }
+    }
+}
+
+/// Scale applied to raw scores for an athlete's fitness: 1.0 at CTL 0, 1.25
+/// at CTL 50, 1.5 at CTL 100.
+pub fn fitness_factor(ctl: f64) -> f64 {
+    1.0 + ctl.max(0.0) / 100.0 * 0.5
+}
+
+fn scale_effect(raw: f64, per_point: f64, ctl: f64) -> f64 {
+    // Also normalizes -0.0 from summing no intervals
+    if raw <= 0.0 {
+        return 0.0;
+    }
+    let effect = (raw / per_point / fitness_factor(ctl)).clamp(0.0, MAX_EFFECT);
+    (effect * 10.0).round() / 10.0
+}
+
+/// %HRR of a heart rate, clamped to 0-1.
+fn hr_reserve_fraction(hr: f64, resting_hr: u16, max_hr: u16) -> f64 {
+    let reserve = f64::from(max_hr.saturating_sub(resting_hr));
+    if reserve <= 0.0 {
+        return 0.0;
+    }
+    ((hr - f64::from(resting_hr)) / reserve).clamp(0.0, 1.0)
+}
+
+// ---------------------------------------------------------------------------
+// Aerobic effect
+// ---------------------------------------------------------------------------
+
+/// Peak EPOC reached during the workout. Seconds without HR only decay.
+pub fn peak_epoc(
+    records: &[FitRecord],
+    lthr: u16,
+    resting_hr: u16,
+    max_hr: u16,
+    coefficients: &EffectCoefficients,
+) -> f64 {
+    if max_hr <= resting_hr {
+        return 0.0;
+    }
+    let hr_cap = f64::from(lthr) * coefficients.anaerobic_hr_threshold;
+
+    let mut epoc: f64 = 0.0;
+    let mut peak: f64 = 0.0;
+    for record in records {
+        let intensity = record
+            .heart_rate
+            .filter(|&hr| hr > 0)
+            .map(|hr| hr_reserve_fraction(f64::from(hr).min(hr_cap), resting_hr, max_hr));
+        let rate = match intensity {
+            Some(intensity) if intensity >= MIN_EPOC_INTENSITY => {
+                coefficients.k1 * (coefficients.k2 * intensity).exp()
+            }
+            _ => 0.0,
+        };
+        epoc += rate - coefficients.k3 * epoc;
+        peak = peak.max(epoc);
+    }
+    peak
+}
+
+/// Aerobic training effect on a 0-5 scale.
+pub fn calculate_aerobic_effect(
+    records: &[FitRecord],
+    lthr: u16,
+    resting_hr: u16,
+    max_hr: u16,
+    ctl: f64,
+    coefficients: &EffectCoefficients,
+) -> f64 {
+    let peak = peak_epoc(records, lthr, resting_hr, max_hr, coefficients);
+    scale_effect(peak, coefficients.aerobic_epoc_per_point, ctl)
+}
+
+// ---------------------------------------------------------------------------
+// Anaerobic effect
+// ---------------------------------------------------------------------------
+
+/// A run of consecutive seconds above the anaerobic threshold.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AnaerobicInterval {
+    /// Index of the first record in the interval.
+    pub start: usize,
+    pub duration_s: usize,
+    /// Average %VO2max over the interval.
+    pub avg_intensity: f64,
+}
+
+/// Weight of an interval by duration: up to 2 minutes counts fully, longer
+/// efforts shift toward aerobic and count less, down to 0.3.
+pub fn duration_weight(duration_s: usize) -> f64 {
+    if duration_s <= FULL_WEIGHT_SECONDS {
+        return 1.0;
+    }
+    let past = (duration_s - FULL_WEIGHT_SECONDS) as f64;
+    (1.0 - past / WEIGHT_FALLOFF_SECONDS).max(MIN_DURATION_WEIGHT)
+}
+
+/// Whether a second is above the anaerobic threshold, and its %VO2max.
+fn anaerobic_intensity(
+    record: &FitRecord,
+    lthr: u16,
+    resting_hr: u16,
+    max_hr: u16,
+    ftpace_m_per_s: Option<f64>,
+    coefficients: &EffectCoefficients,
+) -> (bool, f64) {
+    let hr = record.heart_rate.filter(|&hr| hr > 0).map(f64::from);
+    let ftpace = ftpace_m_per_s.filter(|&ftpace| ftpace > 0.0);
+    let speed = record.speed_m_per_s.filter(|&speed| speed > 0.0);
+
+    let above_hr =
+        hr.is_some_and(|hr| lthr > 0 && hr > f64::from(lthr) * coefficients.anaerobic_hr_threshold);
+    let above_pace = ftpace
+        .zip(speed)
+        .is_some_and(|(ftpace, speed)| speed > ftpace * coefficients.anaerobic_pace_threshold);
+
+    let hr_intensity = hr
+        .map(|hr| hr_reserve_fraction(hr, resting_hr, max_hr))
+        .unwrap_or(0.0);
+    let pace_intensity = ftpace
+        .zip(speed)
+        .map(|(ftpace, speed)| speed / ftpace * coefficients.ftpace_intensity)
+        .unwrap_or(0.0);
+    let intensity = hr_intensity
+        .max(pace_intensity)
+        .min(coefficients.max_intensity);
+
+    (above_hr || above_pace, intensity)
+}
+
+/// Detect intervals above the anaerobic threshold, dropping those shorter
+/// than the minimum interval length.
+pub fn detect_anaerobic_intervals(
+    records: &[FitRecord],
+    lthr: u16,
+    resting_hr: u16,
+    max_hr: u16,
+    ftpace_m_per_s: Option<f64>,
+    coefficients: &EffectCoefficients,
+) -> Vec<AnaerobicInterval> {
+    let mut intervals = Vec::new();
+    let mut current: Option<(usize, f64)> = None;
+
+    let mut close = |start: usize, end: usize, sum: f64| {
+        let duration_s = end - start;
+        if duration_s >= coefficients.min_interval_seconds {
+            intervals.push(AnaerobicInterval {
+                start,
+                duration_s,
+                avg_intensity: sum / duration_s as f64,
+            });
+        }
+    };
+
+    for (i, record) in records.iter().enumerate() {
+        let (above, intensity) = anaerobic_intensity(
+            record,
+            lthr,
+            resting_hr,
+            max_hr,
+            ftpace_m_per_s,
+            coefficients,
+        );
+        current = match (current, above) {
+            (Some((start, sum)), true) => Some((start, sum + intensity)),
+            (None, true) => Some((i, intensity)),
+            (Some((start, sum)), false) => {
+                close(start, i, sum);
+                None
+            }
+            (None, false) => None,
+        };
+    }
+    if let Some((start, sum)) = current {
+        close(start, records.len(), sum);
+    }
+
+    intervals
+}
+
+/// Anaerobic score of one interval before normalization.
+pub fn interval_score(interval: &AnaerobicInterval) -> f64 {
+    let above = (interval.avg_intensity - ANAEROBIC_INTENSITY_FLOOR).max(0.0);
+    let intensity_score = above * above * 100.0;
+    intensity_score * duration_weight(interval.duration_s) * interval.duration_s as f64 / 60.0
+}
+
+/// Anaerobic training effect on a 0-5 scale.
+pub fn calculate_anaerobic_effect(
+    records: &[FitRecord],
+    lthr: u16,
+    resting_hr: u16,
+    max_hr: u16,
+    ftpace_m_per_s: Option<f64>,
+    ctl: f64,
+    coefficients: &EffectCoefficients,
+) -> f64 {
+    let raw: f64 = detect_anaerobic_intervals(
+        records,
+        lthr,
+        resting_hr,
+        max_hr,
+        ftpace_m_per_s,
+        coefficients,
+    )
+    .iter()
+    .map(interval_score)
+    .sum();
+    scale_effect(raw, coefficients.anaerobic_load_per_point, ctl)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+
+    const LTHR: u16 = 170;
+    const RESTING: u16 = 50;
+    const MAX: u16 = 185;
+    const FTPACE: f64 = 4.5;
+
+    /// (seconds, speed m/s, HR) blocks expanded to a 1 Hz stream.
+    fn stream(blocks: &[(usize, f64, Option<u16>)]) -> Vec<FitRecord> {
+        blocks
+            .iter()
+            .flat_map(|&(seconds, speed, hr)| std::iter::repeat_n((speed, hr), seconds))
+            .enumerate()
+            .map(|(i, (speed, hr))| FitRecord {
+                timestamp: DateTime::from_timestamp(1_700_000_000 + i as i64, 0).unwrap(),
+                timestamp_ms: i as u64 * 1000,
+                heart_rate: hr,
+                speed_m_per_s: Some(speed),
+                distance_m: None,
+                altitude_m: None,
+                cadence: None,
+                power_watts: None,
+                latitude: None,
+                longitude: None,
+                temperature_c: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn no_epoc_below_aerobic_threshold() {
+        let c = EffectCoefficients::default();
+        // %HRR (90 - 50) / 135 ≈ 0.296
+        let below = stream(&[(3600, 2.0, Some(90))]);
+        assert_eq!(peak_epoc(&below, LTHR, RESTING, MAX, &c), 0.0);
+        let above = stream(&[(3600, 2.0, Some(92))]);
+        assert!(peak_epoc(&above, LTHR, RESTING, MAX, &c) > 0.0);
+    }
+
+    #[test]
+    fn epoc_accumulates_then_decays() {
+        let c = EffectCoefficients::default();
+        let records = stream(&[(60, 3.0, Some(140)), (60, 3.0, Some(60))]);
+        let intensity = (140.0 - 50.0) / 135.0;
+        let rate = c.k1 * (c.k2 * intensity).exp();
+        // Closed form of 60 steps of EPOC += rate - k3 * EPOC from zero
+        let expected = rate * (1.0 - (1.0 - c.k3).powi(60)) / c.k3;
+        let peak = peak_epoc(&records, LTHR, RESTING, MAX, &c);
+        assert!(
+            (peak - expected).abs() < 1e-9,
+            "peak {peak}, expected {expected}"
+        );
+    }
+
+    #[test]
+    fn harder_and_longer_raise_aerobic_effect() {
+        let c = EffectCoefficients::default();
+        let short = calculate_aerobic_effect(
+            &stream(&[(900, 3.0, Some(140))]),
+            LTHR,
+            RESTING,
+            MAX,
+            50.0,
+            &c,
+        );
+        let long = calculate_aerobic_effect(
+            &stream(&[(3600, 3.0, Some(140))]),
+            LTHR,
+            RESTING,
+            MAX,
+            50.0,
+            &c,
+        );
+        let hard = calculate_aerobic_effect(
+            &stream(&[(3600, 4.0, Some(165))]),
+            LTHR,
+            RESTING,
+            MAX,
+            50.0,
+            &c,
+        );
+        assert!(short < long, "{short} vs {long}");
+        assert!(long < hard, "{long} vs {hard}");
+    }
+
+    #[test]
+    fn aerobic_intensity_capped_at_threshold() {
+        let c = EffectCoefficients::default();
+        let at_cap = peak_epoc(&stream(&[(600, 5.0, Some(177))]), LTHR, RESTING, MAX, &c);
+        let above = peak_epoc(&stream(&[(600, 5.0, Some(184))]), LTHR, RESTING, MAX, &c);
+        assert!((at_cap - above).abs() < 1e-9);
+    }
+
+    #[test]
+    fn no_hr_has_no_aerobic_effect() {
+        let c = EffectCoefficients::default();
+        let records = stream(&[(3600, 3.0, None)]);
+        assert_eq!(
+            calculate_aerobic_effect(&records, LTHR, RESTING, MAX, 50.0, &c),
+            0.0
+        );
+        // Degenerate HR range
+        let records = stream(&[(3600, 3.0, Some(140))]);
+        assert_eq!(
+            calculate_aerobic_effect(&records, LTHR, 185, 185, 50.0, &c),
+            0.0
+        );
+    }
+
+    #[test]
+    fn fitness_factor_follows_ctl() {
+        assert_eq!(fitness_factor(0.0), 1.0);
+        assert_eq!(fitness_factor(50.0), 1.25);
+        assert_eq!(fitness_factor(100.0), 1.5);
+        assert_eq!(fitness_factor(-10.0), 1.0);
+    }
+
+    #[test]
+    fn fitter_athletes_score_lower() {
+        let c = EffectCoefficients::default();
+        let records = stream(&[(3600, 3.0, Some(150))]);
+        let unfit = calculate_aerobic_effect(&records, LTHR, RESTING, MAX, 0.0, &c);
+        let fit = calculate_aerobic_effect(&records, LTHR, RESTING, MAX, 100.0, &c);
+        assert!(unfit > fit, "{unfit} vs {fit}");
+    }
+
+    #[test]
+    fn effects_clamped_to_scale() {
+        let c = EffectCoefficients {
+            aerobic_epoc_per_point: 1.0,
+            anaerobic_load_per_point: 0.01,
+            ..Default::default()
+        };
+        let records = stream(&[(3600, 6.0, Some(180))]);
+        assert_eq!(
+            calculate_aerobic_effect(&records, LTHR, RESTING, MAX, 50.0, &c),
+            MAX_EFFECT
+        );
+        assert_eq!(
+            calculate_anaerobic_effect(&records, LTHR, RESTING, MAX, Some(FTPACE), 50.0, &c),
+            MAX_EFFECT
+        );
+    }
+
+    #[test]
+    fn detects_intervals_from_hr_or_pace() {
+        let c = EffectCoefficients::default();
+        let records = stream(&[
+            (60, 3.0, Some(140)),
+            (30, 5.4, Some(150)), // pace only
+            (60, 3.0, Some(140)),
+            (120, 4.0, Some(180)), // HR only
+            (60, 3.0, Some(140)),
+            (4, 6.0, Some(140)), // too short
+            (60, 3.0, Some(140)),
+        ]);
+        let intervals = detect_anaerobic_intervals(&records, LTHR, RESTING, MAX, Some(FTPACE), &c);
+        assert_eq!(intervals.len(), 2);
+        assert_eq!((intervals[0].start, intervals[0].duration_s), (60, 30));
+        assert!((intervals[0].avg_intensity - 5.4 / 4.5 * 0.95).abs() < 1e-9);
+        assert_eq!((intervals[1].start, intervals[1].duration_s), (150, 120));
+        assert!((intervals[1].avg_intensity - 130.0 / 135.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn pace_trigger_fires_just_above_ftpace() {
+        let c = EffectCoefficients::default();
+        let at = stream(&[(60, 4.5, None)]);
+        assert!(detect_anaerobic_intervals(&at, LTHR, RESTING, MAX, Some(FTPACE), &c).is_empty());
+        let above = stream(&[(60, 4.55, None)]);
+        assert_eq!(
+            detect_anaerobic_intervals(&above, LTHR, RESTING, MAX, Some(FTPACE), &c).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn interval_intensity_capped() {
+        let c = EffectCoefficients::default();
+        let records = stream(&[(20, 9.0, None)]);
+        let intervals = detect_anaerobic_intervals(&records, LTHR, RESTING, MAX, Some(FTPACE), &c);
+        assert!((intervals[0].avg_intensity - c.max_intensity).abs() < 1e-9);
+    }
+
+    #[test]
+    fn interval_running_to_end_is_closed() {
+        let c = EffectCoefficients::default();
+        let records = stream(&[(60, 3.0, None), (20, 5.5, None)]);
+        let intervals = detect_anaerobic_intervals(&records, LTHR, RESTING, MAX, Some(FTPACE), &c);
+        assert_eq!(intervals.len(), 1);
+        assert_eq!(intervals[0].duration_s, 20);
+        // Without FTPace or HR nothing is detected
+        assert!(detect_anaerobic_intervals(&records, LTHR, RESTING, MAX, None, &c).is_empty());
+    }
+
+    #[test]
+    fn duration_weight_falls_after_two_minutes() {
+        assert_eq!(duration_weight(10), 1.0);
+        assert_eq!(duration_weight(120), 1.0);
+        assert!((duration_weight(180) - 0.8).abs() < 1e-9);
+        assert!((duration_weight(300) - 0.4).abs() < 1e-9);
+        assert_eq!(duration_weight(360), MIN_DURATION_WEIGHT);
+        assert_eq!(duration_weight(1200), MIN_DURATION_WEIGHT);
+    }
+
+    #[test]
+    fn interval_score_is_quadratic_above_floor() {
+        let interval = |avg_intensity, duration_s| AnaerobicInterval {
+            start: 0,
+            duration_s,
+            avg_intensity,
+        };
+        assert_eq!(interval_score(&interval(0.80, 60)), 0.0);
+        // (1.05 - 0.85)^2 * 100 = 4 per minute at full weight
+        assert!((interval_score(&interval(1.05, 60)) - 4.0).abs() < 1e-9);
+        assert!((interval_score(&interval(1.05, 30)) - 2.0).abs() < 1e-9);
+        // Three minutes at weight 0.8
+        assert!((interval_score(&interval(1.05, 180)) - 9.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn steady_run_has_no_anaerobic_effect() {
+        let c = EffectCoefficients::default();
+        let records = stream(&[(3600, 4.5, Some(170))]);
+        let effect =
+            calculate_anaerobic_effect(&records, LTHR, RESTING, MAX, Some(FTPACE), 50.0, &c);
+        assert_eq!(effect, 0.0);
+        assert!(effect.is_sign_positive());
+    }
+
+    #[test]
+    fn coefficients_deserialize_with_defaults() {
+        let c: EffectCoefficients = serde_json::from_str(r#"{"k2": 3.0}"#).unwrap();
+        assert_eq!(c.k2, 3.0);
+        assert_eq!(c.k1, EffectCoefficients::default().k1);
+    }
+}
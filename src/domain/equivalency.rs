@@ -0,0 +1,161 @@
+use serde::Serialize;
+
+// ---------------------------------------------------------------------------
+// Race equivalency — Riegel model (PRODUCT_DESIGN Workflow 3a)
+// ---------------------------------------------------------------------------
+//
+// Riegel's endurance formula predicts a time at one distance from a result
+// at another:
+//
+//   t2 = t1 * (d2 / d1) ^ 1.06
+//
+// FTPace is the pace an athlete can hold for one hour, so solving the same
+// formula for t2 = 3600 s gives the one-hour distance, and FTPace is that
+// distance divided by an hour:
+//
+//   d_1h   = d1 * (3600 / t1) ^ (1 / 1.06)
+//   FTPace = d_1h / 3600
+//
+// The model holds from about 1500 m to the marathon.
+
+/// Riegel's fatigue exponent.
+const RIEGEL_EXPONENT: f64 = 1.06;
+
+/// Shortest race the model is used for, in meters.
+pub const MIN_DISTANCE_M: f64 = 1500.0;
+
+/// The race distances predictions are made for, the ones `format_distance`
+/// names.
+pub const STANDARD_DISTANCES_M: [f64; 5] = [5000.0, 10000.0, 15000.0, 21097.5, 42195.0];
+
+/// A predicted finish time at one distance.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RacePrediction {
+    pub distance_m: f64,
+    pub time_seconds: i64,
+}
+
+/// Predict the time for `target_distance_m` from a result of `time_seconds`
+/// over `distance_m`.
+pub fn predict_time_seconds(distance_m: f64, time_seconds: f64, target_distance_m: f64) -> f64 {
+    time_seconds * (target_distance_m / distance_m).powf(RIEGEL_EXPONENT)
+}
+
+/// Predict the finish time at `target_distance_m`, rounded to the second.
+pub fn predict(distance_m: f64, time_seconds: f64, target_distance_m: f64) -> RacePrediction {
+    RacePrediction {
+        distance_m: target_distance_m,
+        time_seconds: predict_time_seconds(distance_m, time_seconds, target_distance_m).round()
+            as i64,
+    }
+}
+
+/// Predicted finish times at every standard race distance.
+pub fn predict_standard_distances(distance_m: f64, time_seconds: f64) -> Vec<RacePrediction> {
+    STANDARD_DISTANCES_M
+        .iter()
+        .map(|&d| predict(distance_m, time_seconds, d))
+        .collect()
+}
+
+/// FTPace in m/s equivalent to a race result: the speed over the distance
+/// the athlete would cover in one hour.
+pub fn ftpace_from_race(distance_m: f64, time_seconds: f64) -> f64 {
+    let one_hour_distance_m = distance_m * (3600.0 / time_seconds).powf(1.0 / RIEGEL_EXPONENT);
+    one_hour_distance_m / 3600.0
+}
+
+/// Format a distance in meters as a human-readable race distance string.
+pub fn format_distance(meters: f64) -> String {
+    // Common race distances
+    let m = meters.round() as i64;
+    match m {
+        5000 => "5K".to_string(),
+        10000 => "10K".to_string(),
+        15000 => "15K".to_string(),
+        21097 | 21098 | 21100 => "Half Marathon (21.1 km)".to_string(),
+        42195 | 42200 => "Marathon (42.2 km)".to_string(),
+        _ => {
+            if meters >= 1000.0 {
+                format!("{:.1} km", meters / 1000.0)
+            } else {
+                format!("{:.0} m", meters)
+            }
+        }
+    }
+}
+
+/// Format a finish time as h:mm:ss, or m:ss under an hour.
+pub fn format_race_time(time_seconds: i64) -> String {
+    let (h, m, s) = (
+        time_seconds / 3600,
+        time_seconds % 3600 / 60,
+        time_seconds % 60,
+    );
+    if h > 0 {
+        format!("{h}:{m:02}:{s:02}")
+    } else {
+        format!("{m}:{s:02}")
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_distance_predicts_same_time() {
+        assert_eq!(predict(10000.0, 2400.0, 10000.0).time_seconds, 2400);
+    }
+
+    #[test]
+    fn predicts_longer_and_shorter_distances() {
+        // 10K in 40:00 → 5K in 19:11, half marathon in 1:28:15
+        let predictions = predict_standard_distances(10000.0, 2400.0);
+        assert_eq!(predictions.len(), 5);
+        assert_eq!(format_race_time(predictions[0].time_seconds), "19:11");
+        assert_eq!(format_race_time(predictions[3].time_seconds), "1:28:15");
+        assert!(
+            predictions
+                .windows(2)
+                .all(|w| w[0].time_seconds < w[1].time_seconds)
+        );
+    }
+
+    #[test]
+    fn ftpace_is_the_one_hour_race_speed() {
+        // 10K in 40:00 → about 14.66 km in an hour
+        let ftpace = ftpace_from_race(10000.0, 2400.0);
+        assert!((ftpace - 4.072).abs() < 0.001, "got {ftpace}");
+        // A one-hour race is run at FTPace
+        assert!((ftpace_from_race(15000.0, 3600.0) - 15000.0 / 3600.0).abs() < 1e-9);
+        // Predicting the one-hour distance from the result gives 3600 s back
+        let t = predict_time_seconds(10000.0, 2400.0, ftpace * 3600.0);
+        assert!((t - 3600.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn format_distance_common_races() {
+        assert_eq!(format_distance(5000.0), "5K");
+        assert_eq!(format_distance(10000.0), "10K");
+        assert_eq!(format_distance(21097.0), "Half Marathon (21.1 km)");
+        assert_eq!(format_distance(42195.0), "Marathon (42.2 km)");
+    }
+
+    #[test]
+    fn format_distance_custom() {
+        assert_eq!(format_distance(8000.0), "8.0 km");
+        assert_eq!(format_distance(800.0), "800 m");
+    }
+
+    #[test]
+    fn format_race_time_under_and_over_an_hour() {
+        assert_eq!(format_race_time(1151), "19:11");
+        assert_eq!(format_race_time(5295), "1:28:15");
+        assert_eq!(format_race_time(3600), "1:00:00");
+    }
+}
@@ -0,0 +1,371 @@
+use serde::{Deserialize, Serialize};
+
+use crate::fit::FitRecord;
+
+// ---------------------------------------------------------------------------
+// Workout-derived FTPace (PRODUCT_DESIGN Workflow 3a)
+// ---------------------------------------------------------------------------
+//
+// Across an athlete's recent runs, every 5-minute block at a steady pace
+// where HR has settled gives one (HR, speed) point. Speed rises about
+// linearly with HR below threshold, so a least-squares line through the
+// points, read at LTHR, is the pace the athlete holds at threshold:
+//
+//   speed  = a + b * HR
+//   FTPace = a + b * LTHR
+//
+// The estimate is only as good as the fit and how far it has to be
+// extrapolated, which the confidence level reports.
+//
+
+/// Length of a steady segment, in seconds (samples at 1 Hz).
+pub const SEGMENT_S: usize = 5 * 60;
+
+/// The end of a segment HR is averaged over; HR lags a change of pace by a
+/// minute or so.
+const SETTLED_HR_S: usize = 4 * 60;
+
+/// Largest speed coefficient of variation within a steady segment.
+const MAX_SPEED_CV: f64 = 0.05;
+
+/// Widest HR range, in bpm, over the settled part of a steady segment.
+const MAX_HR_RANGE: u16 = 8;
+
+/// Slowest steady segment counted as running, in m/s.
+const MIN_SPEED_M_PER_S: f64 = 1.8;
+
+/// Least share of a segment's samples that must carry both speed and HR.
+const MIN_COVERAGE: f64 = 0.9;
+
+/// Fewest segments a line is fitted through.
+const MIN_SEGMENTS: usize = 3;
+
+/// How far back uploads are used, in days.
+pub const LOOKBACK_DAYS: i64 = 42;
+
+/// Most recent uploads used.
+pub const MAX_WORKOUTS: i64 = 20;
+
+/// Days a race, time trial or manually entered FTPace takes precedence over
+/// an estimate.
+pub const MEASURED_PRECEDENCE_DAYS: i64 = 28;
+
+/// Smallest change from the current FTPace worth applying, as a share.
+const MIN_CHANGE: f64 = 0.02;
+
+/// One steady block of a run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SteadySegment {
+    pub avg_hr: f64,
+    pub avg_speed_m_per_s: f64,
+}
+
+/// How far an estimate can be trusted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Confidence {
+    Low,
+    Medium,
+    High,
+}
+
+impl Confidence {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+}
+
+/// FTPace read off the pace-to-HR line at LTHR.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FtpaceEstimate {
+    pub ftpace_m_per_s: f64,
+    pub confidence: Confidence,
+    pub segments: usize,
+    pub workouts: usize,
+    /// Share of the speed variation the line explains.
+    pub r_squared: f64,
+    /// Average HR of the hardest segment as a share of LTHR.
+    pub highest_hr_fraction: f64,
+}
+
+/// Split a run's per-second records into 5-minute blocks and keep the ones
+/// at a steady pace with settled HR.
+pub fn steady_segments(records: &[FitRecord]) -> Vec<SteadySegment> {
+    records
+        .chunks_exact(SEGMENT_S)
+        .filter_map(|block| {
+            let speeds: Vec<f64> = block
+                .iter()
+                .filter_map(|r| r.speed_m_per_s)
+                .filter(|&s| s > 0.0)
+                .collect();
+            let settled: Vec<u16> = block[SEGMENT_S - SETTLED_HR_S..]
+                .iter()
+                .filter_map(|r| r.heart_rate)
+                .filter(|&hr| hr > 0)
+                .collect();
+            if (speeds.len() as f64) < SEGMENT_S as f64 * MIN_COVERAGE
+                || (settled.len() as f64) < SETTLED_HR_S as f64 * MIN_COVERAGE
+            {
+                return None;
+            }
+
+            let avg_speed = mean(&speeds);
+            let variance =
+                speeds.iter().map(|s| (s - avg_speed).powi(2)).sum::<f64>() / speeds.len() as f64;
+            let hr_range = settled.iter().max()? - settled.iter().min()?;
+            if avg_speed < MIN_SPEED_M_PER_S
+                || variance.sqrt() / avg_speed > MAX_SPEED_CV
+                || hr_range > MAX_HR_RANGE
+            {
+                return None;
+            }
+
+            let hr: Vec<f64> = settled.into_iter().map(f64::from).collect();
+            Some(SteadySegment {
+                avg_hr: mean(&hr),
+                avg_speed_m_per_s: avg_speed,
+            })
+        })
+        .collect()
+}
+
+/// Fit speed against HR over the steady segments of several workouts and
+/// extrapolate to `lthr`. `None` with fewer than 3 segments, or when speed
+/// doesn't rise with HR.
+///
+/// Confidence is high with 6+ segments from 2+ workouts, r² ≥ 0.8 and a
+/// segment within 92% of LTHR; medium with 4+ segments, r² ≥ 0.6 and a
+/// segment within 85% of LTHR; low otherwise.
+pub fn estimate_ftpace(workouts: &[Vec<SteadySegment>], lthr: i64) -> Option<FtpaceEstimate> {
+    let points: Vec<&SteadySegment> = workouts.iter().flatten().collect();
+    if points.len() < MIN_SEGMENTS || lthr <= 0 {
+        return None;
+    }
+
+    // Least squares: speed = a + b * HR
+    let n = points.len() as f64;
+    let mean_hr = points.iter().map(|p| p.avg_hr).sum::<f64>() / n;
+    let mean_speed = points.iter().map(|p| p.avg_speed_m_per_s).sum::<f64>() / n;
+    let (mut s_xy, mut s_xx, mut s_yy) = (0.0, 0.0, 0.0);
+    for p in &points {
+        let (dx, dy) = (p.avg_hr - mean_hr, p.avg_speed_m_per_s - mean_speed);
+        s_xy += dx * dy;
+        s_xx += dx * dx;
+        s_yy += dy * dy;
+    }
+    if s_xx <= 0.0 || s_xy <= 0.0 {
+        return None;
+    }
+    let slope = s_xy / s_xx;
+    let ftpace = mean_speed + slope * (lthr as f64 - mean_hr);
+    let r_squared = if s_yy > 0.0 {
+        s_xy * s_xy / (s_xx * s_yy)
+    } else {
+        0.0
+    };
+
+    let workouts = workouts.iter().filter(|w| !w.is_empty()).count();
+    let highest_hr_fraction = points.iter().map(|p| p.avg_hr).fold(0.0, f64::max) / lthr as f64;
+    let confidence =
+        if points.len() >= 6 && workouts >= 2 && r_squared >= 0.8 && highest_hr_fraction >= 0.92 {
+            Confidence::High
+        } else if points.len() >= 4 && r_squared >= 0.6 && highest_hr_fraction >= 0.85 {
+            Confidence::Medium
+        } else {
+            Confidence::Low
+        };
+
+    Some(FtpaceEstimate {
+        ftpace_m_per_s: ftpace,
+        confidence,
+        segments: points.len(),
+        workouts,
+        r_squared,
+        highest_hr_fraction,
+    })
+}
+
+/// Whether an estimate should become the athlete's FTPace: only at high
+/// confidence, when it moves the current value by 2% or more, and not within
+/// four weeks of a race, time trial or manual entry setting it.
+pub fn should_apply(
+    estimate: &FtpaceEstimate,
+    current_ftpace: Option<f64>,
+    days_since_measured: Option<i64>,
+) -> bool {
+    if estimate.confidence != Confidence::High || estimate.ftpace_m_per_s <= 0.0 {
+        return false;
+    }
+    if days_since_measured.is_some_and(|days| days < MEASURED_PRECEDENCE_DAYS) {
+        return false;
+    }
+    current_ftpace
+        .is_none_or(|current| (estimate.ftpace_m_per_s - current).abs() / current >= MIN_CHANGE)
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+
+    /// A 1 Hz stream of `seconds` samples at a fixed speed and HR.
+    fn stream(blocks: &[(usize, f64, Option<u16>)]) -> Vec<FitRecord> {
+        blocks
+            .iter()
+            .flat_map(|&(seconds, speed, hr)| std::iter::repeat_n((speed, hr), seconds))
+            .enumerate()
+            .map(|(i, (speed, hr))| FitRecord {
+                timestamp: DateTime::from_timestamp(1_700_000_000 + i as i64, 0).unwrap(),
+                timestamp_ms: i as u64 * 1000,
+                heart_rate: hr,
+                speed_m_per_s: Some(speed),
+                distance_m: None,
+                altitude_m: None,
+                cadence: None,
+                power_watts: None,
+                latitude: None,
+                longitude: None,
+                temperature_c: None,
+            })
+            .collect()
+    }
+
+    fn segment(avg_hr: f64, avg_speed_m_per_s: f64) -> SteadySegment {
+        SteadySegment {
+            avg_hr,
+            avg_speed_m_per_s,
+        }
+    }
+
+    #[test]
+    fn keeps_only_steady_blocks_with_settled_hr() {
+        let mut records = stream(&[
+            (300, 3.0, Some(140)),
+            (300, 4.0, Some(165)),
+            (300, 0.5, Some(100)),
+            (300, 3.0, None),
+        ]);
+        // HR still climbing through the second block
+        for (i, r) in records[360..600].iter_mut().enumerate() {
+            r.heart_rate = Some(150 + (i / 16) as u16);
+        }
+        // A fast stride in the first minute makes the first block unsteady
+        let mut unsteady = records.clone();
+        for r in &mut unsteady[..60] {
+            r.speed_m_per_s = Some(5.0);
+        }
+
+        assert_eq!(steady_segments(&records[..300]), vec![segment(140.0, 3.0)]);
+        assert_eq!(steady_segments(&records), vec![segment(140.0, 3.0)]);
+        assert!(steady_segments(&unsteady).is_empty());
+    }
+
+    #[test]
+    fn extrapolates_the_pace_to_hr_line_to_lthr() {
+        // speed = 0.04 * HR - 2.6 → 4.2 m/s at LTHR 170
+        let easy = vec![
+            segment(140.0, 3.0),
+            segment(145.0, 3.2),
+            segment(150.0, 3.4),
+        ];
+        let tempo = vec![
+            segment(155.0, 3.6),
+            segment(160.0, 3.8),
+            segment(165.0, 4.0),
+        ];
+        let estimate = estimate_ftpace(&[easy, tempo], 170).expect("an estimate");
+
+        assert!((estimate.ftpace_m_per_s - 4.2).abs() < 1e-9);
+        assert!((estimate.r_squared - 1.0).abs() < 1e-9);
+        assert_eq!(estimate.segments, 6);
+        assert_eq!(estimate.workouts, 2);
+        assert_eq!(estimate.confidence, Confidence::High);
+    }
+
+    #[test]
+    fn confidence_drops_with_extrapolation_and_scatter() {
+        // All easy: the line has to be carried 30 bpm past the data
+        let easy = vec![
+            segment(130.0, 2.6),
+            segment(135.0, 2.8),
+            segment(140.0, 3.0),
+        ];
+        let estimate = estimate_ftpace(&[easy.clone(), easy], 170).unwrap();
+        assert_eq!(estimate.confidence, Confidence::Low);
+
+        // Near LTHR but from a single workout
+        let one = vec![
+            segment(140.0, 3.0),
+            segment(150.0, 3.4),
+            segment(160.0, 3.8),
+            segment(165.0, 4.0),
+        ];
+        assert_eq!(
+            estimate_ftpace(&[one], 170).unwrap().confidence,
+            Confidence::Medium
+        );
+
+        // Scattered points
+        let scattered = vec![
+            segment(140.0, 3.6),
+            segment(145.0, 3.0),
+            segment(150.0, 3.7),
+            segment(160.0, 3.2),
+            segment(162.0, 3.9),
+            segment(165.0, 3.4),
+        ];
+        let estimate = estimate_ftpace(&[scattered, vec![]], 170).unwrap();
+        assert!(estimate.r_squared < 0.6);
+        assert_eq!(estimate.confidence, Confidence::Low);
+    }
+
+    #[test]
+    fn no_estimate_without_enough_rising_points() {
+        assert_eq!(
+            estimate_ftpace(&[vec![segment(140.0, 3.0), segment(160.0, 4.0)]], 170),
+            None
+        );
+        // Faster at a lower HR
+        let falling = vec![
+            segment(140.0, 4.0),
+            segment(150.0, 3.5),
+            segment(160.0, 3.0),
+        ];
+        assert_eq!(estimate_ftpace(&[falling], 170), None);
+    }
+
+    #[test]
+    fn applies_only_confident_meaningful_changes() {
+        let estimate = FtpaceEstimate {
+            ftpace_m_per_s: 4.2,
+            confidence: Confidence::High,
+            segments: 8,
+            workouts: 3,
+            r_squared: 0.9,
+            highest_hr_fraction: 0.95,
+        };
+        assert!(should_apply(&estimate, None, None));
+        assert!(should_apply(&estimate, Some(4.0), Some(40)));
+        assert!(!should_apply(&estimate, Some(4.15), None));
+        assert!(!should_apply(&estimate, None, Some(10)));
+
+        let medium = FtpaceEstimate {
+            confidence: Confidence::Medium,
+            ..estimate
+        };
+        assert!(!should_apply(&medium, None, None));
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::BTreeMap;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+// ---------------------------------------------------------------------------
+// ATL / CTL / TSB (Performance Management Chart)
+// ---------------------------------------------------------------------------
+//
+// Exponentially weighted moving averages of daily TSS:
+//   CTL_today = CTL_yesterday + (TSS_today - CTL_yesterday) / 42
+//   ATL_today = ATL_yesterday + (TSS_today - ATL_yesterday) / 7
+//   TSB_today = CTL_yesterday - ATL_yesterday
+//
+// TSB uses yesterday's values: it is the form the athlete carries *into*
+// the day, before that day's training is applied.
+//
+
+/// Chronic training load time constant (fitness), in days.
+pub const CTL_TIME_CONSTANT_DAYS: f64 = 42.0;
+
+/// Acute training load time constant (fatigue), in days.
+pub const ATL_TIME_CONSTANT_DAYS: f64 = 7.0;
+
+/// ATL/CTL/TSB for a single calendar day.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct DailyLoad {
+    pub date: NaiveDate,
+    pub total_tss: f64,
+    pub atl: f64,
+    pub ctl: f64,
+    pub tsb: f64,
+}
+
+/// Advance one day from the previous day's ATL/CTL given today's TSS.
+pub fn next_day(previous: &DailyLoad, total_tss: f64) -> DailyLoad {
+    let date = previous
+        .date
+        .succ_opt()
+        .expect("date overflow computing daily load");
+    DailyLoad {
+        date,
+        total_tss,
+        atl: previous.atl + (total_tss - previous.atl) / ATL_TIME_CONSTANT_DAYS,
+        ctl: previous.ctl + (total_tss - previous.ctl) / CTL_TIME_CONSTANT_DAYS,
+        tsb: previous.ctl - previous.atl,
+    }
+}
+
+/// Compute the series for every day after `seed` through `end` (inclusive).
+///
+/// Days missing from `tss_by_date` are rest days and count as TSS 0, so CTL
+/// and ATL decay correctly. Returns an empty Vec if `end` is not after the
+/// seed date. The result depends only on the inputs, so recomputing a range
+/// always produces the same numbers as advancing it one day at a time.
+pub fn compute_series(
+    seed: &DailyLoad,
+    end: NaiveDate,
+    tss_by_date: &BTreeMap<NaiveDate, f64>,
+) -> Vec<DailyLoad> {
+    let mut series = Vec::new();
+    let mut previous = *seed;
+
+    while previous.date < end {
+        let date = previous.date.succ_opt().expect("date overflow");
+        let tss = tss_by_date.get(&date).copied().unwrap_or(0.0);
+        let today = next_day(&previous, tss);
+        series.push(today);
+        previous = today;
+    }
+
+    series
+}
+
+// ---------------------------------------------------------------------------
+// Race-day readiness
+// ---------------------------------------------------------------------------
+
+/// Target TSB range on race day (PRODUCT_DESIGN §3.5): fresh but not detrained.
+pub const RACE_DAY_TSB_MIN: f64 = 15.0;
+pub const RACE_DAY_TSB_MAX: f64 = 25.0;
+
+/// How the projected load on race day compares with the plan's targets.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RaceDayProjection {
+    pub date: NaiveDate,
+    pub projected_ctl: f64,
+    pub projected_atl: f64,
+    pub projected_tsb: f64,
+    pub tsb_target_min: f64,
+    pub tsb_target_max: f64,
+    /// True if the projected TSB lands within the race-day target range.
+    pub tsb_on_target: bool,
+    pub target_ctl: Option<f64>,
+    /// Projected CTL minus target CTL (negative = short of target).
+    pub ctl_gap: Option<f64>,
+}
+
+/// Assess the projected load on race day against the TSB range and the
+/// macrocycle's target CTL.
+pub fn assess_race_day(race_day: &DailyLoad, target_ctl: Option<f64>) -> RaceDayProjection {
+    RaceDayProjection {
+        date: race_day.date,
+        projected_ctl: race_day.ctl,
+        projected_atl: race_day.atl,
+        projected_tsb: race_day.tsb,
+        tsb_target_min: RACE_DAY_TSB_MIN,
+        tsb_target_max: RACE_DAY_TSB_MAX,
+        tsb_on_target: (RACE_DAY_TSB_MIN..=RACE_DAY_TSB_MAX).contains(&race_day.tsb),
+        target_ctl,
+        ctl_gap: target_ctl.map(|target| race_day.ctl - target),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f64 = 1e-9;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn seed(ctl: f64, atl: f64) -> DailyLoad {
+        DailyLoad {
+            date: date("2026-03-01"),
+            total_tss: 0.0,
+            atl,
+            ctl,
+            tsb: ctl - atl,
+        }
+    }
+
+    #[test]
+    fn next_day_applies_ewma() {
+        let today = next_day(&seed(40.0, 50.0), 100.0);
+        assert_eq!(today.date, date("2026-03-02"));
+        assert!((today.ctl - (40.0 + 60.0 / 42.0)).abs() < EPSILON);
+        assert!((today.atl - (50.0 + 50.0 / 7.0)).abs() < EPSILON);
+        // TSB reflects the state going into the day
+        assert!((today.tsb - -10.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn rest_days_decay_load() {
+        let series = compute_series(&seed(40.0, 40.0), date("2026-03-08"), &BTreeMap::new());
+        assert_eq!(series.len(), 7);
+        assert!(series.iter().all(|d| d.total_tss == 0.0));
+        let last = series.last().unwrap();
+        assert!((last.ctl - 40.0 * (41.0f64 / 42.0).powi(7)).abs() < EPSILON);
+        assert!((last.atl - 40.0 * (6.0f64 / 7.0).powi(7)).abs() < EPSILON);
+        // ATL falls faster than CTL, so form improves while resting
+        assert!(last.tsb > 0.0);
+    }
+
+    #[test]
+    fn steady_load_converges_to_daily_tss() {
+        let tss: BTreeMap<_, _> = (0..365)
+            .map(|i| (date("2026-03-02") + chrono::Days::new(i), 60.0))
+            .collect();
+        let series = compute_series(&seed(0.0, 0.0), date("2027-03-01"), &tss);
+        let last = series.last().unwrap();
+        assert!((last.ctl - 60.0).abs() < 0.01, "ctl was {}", last.ctl);
+        assert!((last.atl - 60.0).abs() < 0.01, "atl was {}", last.atl);
+    }
+
+    #[test]
+    fn incremental_matches_backfill() {
+        let tss = BTreeMap::from([
+            (date("2026-03-02"), 80.0),
+            (date("2026-03-04"), 45.5),
+            (date("2026-03-07"), 120.0),
+        ]);
+        let backfill = compute_series(&seed(30.0, 35.0), date("2026-03-10"), &tss);
+
+        let mut daily = vec![];
+        let mut previous = seed(30.0, 35.0);
+        for d in 2..=10 {
+            let end = date(&format!("2026-03-{d:02}"));
+            let step = compute_series(&previous, end, &tss);
+            assert_eq!(step.len(), 1);
+            previous = step[0];
+            daily.push(previous);
+        }
+
+        assert_eq!(backfill, daily);
+    }
+
+    #[test]
+    fn taper_lands_race_day_in_tsb_range() {
+        // Six weeks of solid training, then a two-week taper
+        let mut tss = BTreeMap::new();
+        let mut day = date("2026-03-02");
+        for _ in 0..42 {
+            tss.insert(day, 70.0);
+            day = day.succ_opt().unwrap();
+        }
+        for _ in 0..13 {
+            tss.insert(day, 25.0);
+            day = day.succ_opt().unwrap();
+        }
+        let series = compute_series(&seed(50.0, 50.0), day, &tss);
+        let race = assess_race_day(series.last().unwrap(), Some(60.0));
+
+        assert_eq!(race.date, day);
+        assert!(race.tsb_on_target, "tsb was {}", race.projected_tsb);
+        let gap = race.ctl_gap.unwrap();
+        assert!((gap - (race.projected_ctl - 60.0)).abs() < EPSILON);
+    }
+
+    #[test]
+    fn race_day_without_taper_is_off_target() {
+        let load = DailyLoad {
+            date: date("2026-09-27"),
+            total_tss: 0.0,
+            atl: 70.0,
+            ctl: 65.0,
+            tsb: -5.0,
+        };
+        let race = assess_race_day(&load, None);
+        assert!(!race.tsb_on_target);
+        assert!(race.target_ctl.is_none());
+        assert!(race.ctl_gap.is_none());
+    }
+
+    #[test]
+    fn end_before_seed_is_empty() {
+        assert!(compute_series(&seed(30.0, 30.0), date("2026-03-01"), &BTreeMap::new()).is_empty());
+        assert!(compute_series(&seed(30.0, 30.0), date("2026-02-01"), &BTreeMap::new()).is_empty());
+    }
+}
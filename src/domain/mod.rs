@@ -1,5 +1,17 @@
 pub mod types;
 pub mod zones;
 pub mod bootstrap;
+pub mod load_tracking;
+pub mod scoring;
+pub mod effects;
+pub mod classification;
+pub mod compliance;
+pub mod summary;
 pub mod workouts;
 pub mod validation;
+pub mod adjustments;
+pub mod skeleton;
+pub mod repair;
+pub mod equivalency;
+pub mod time_trial;
+pub mod ftpace_estimate;
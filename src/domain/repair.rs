@@ -0,0 +1,350 @@
+use std::fmt;
+
+use super::validation::{ValidationContext, ValidationError, WeekPlan, validate_week_plan};
+use super::workouts::{DurationCategory, WorkoutType};
+
+// ---------------------------------------------------------------------------
+// Deterministic plan repair (PRODUCT_DESIGN §7.2)
+// ---------------------------------------------------------------------------
+//
+// Before a plan that fails `validate_week_plan` goes back to Claude, the
+// violations with an obvious fix are repaired locally:
+//
+//   NoRestDay                 the lowest-TSS day becomes a rest day
+//   TooManyIntensitySessions  the least important extra sessions become easy runs
+//   VolumeIncreaseTooHigh     the week's volume target drops to the 10% cap
+//
+// Every change is returned so it can be explained to the athlete.
+//
+
+/// Most intensity sessions a week may have.
+const MAX_INTENSITY_SESSIONS: usize = 3;
+
+/// Largest week-over-week volume increase, in percent.
+const MAX_VOLUME_INCREASE_PCT: f64 = 10.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Repair {
+    /// An extra intensity session replaced with an easy run.
+    DowngradeToEasy {
+        week_number: u32,
+        date: String,
+        from: WorkoutType,
+    },
+    /// A training day turned into the week's rest day.
+    AddRestDay {
+        week_number: u32,
+        date: String,
+        from: WorkoutType,
+    },
+    /// The week's volume target lowered to the allowed increase.
+    CapVolume {
+        week_number: u32,
+        /// First day of the week, where the change is noted.
+        date: String,
+        from_km: f64,
+        to_km: f64,
+    },
+}
+
+impl Repair {
+    /// The day whose coach note records this repair.
+    pub fn date(&self) -> &str {
+        match self {
+            Self::DowngradeToEasy { date, .. }
+            | Self::AddRestDay { date, .. }
+            | Self::CapVolume { date, .. } => date,
+        }
+    }
+}
+
+impl fmt::Display for Repair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DowngradeToEasy { from, .. } => write!(
+                f,
+                "Changed from {} to an easy run to keep the week at {} intensity sessions.",
+                from.display_name(),
+                MAX_INTENSITY_SESSIONS
+            ),
+            Self::AddRestDay { from, .. } => write!(
+                f,
+                "Changed from {} to a rest day so the week has one.",
+                from.display_name()
+            ),
+            Self::CapVolume { from_km, to_km, .. } => write!(
+                f,
+                "Weekly volume target lowered from {from_km:.1} km to {to_km:.1} km to keep the increase within {MAX_VOLUME_INCREASE_PCT}%."
+            ),
+        }
+    }
+}
+
+/// Fix the repairable violations in `weeks` in place and return the changes
+/// made. Other violations are left alone. Weeks are chained like
+/// `validate_plan_weeks`, so a capped week is the next week's baseline.
+pub fn repair_week_plans(weeks: &mut [WeekPlan], ctx: &ValidationContext) -> Vec<Repair> {
+    let mut repairs = Vec::new();
+    let mut ctx = ctx.clone();
+    for week in weeks.iter_mut() {
+        let errors = validate_week_plan(week, &ctx);
+
+        // A new rest day may also remove an extra intensity session, so it
+        // goes first.
+        if errors
+            .iter()
+            .any(|e| matches!(e, ValidationError::NoRestDay))
+            && let Some(day) = week
+                .days
+                .iter_mut()
+                .min_by(|a, b| a.expected_tss.total_cmp(&b.expected_tss))
+        {
+            repairs.push(Repair::AddRestDay {
+                week_number: week.week_number,
+                date: day.date.clone(),
+                from: day.workout_type,
+            });
+            day.workout_type = WorkoutType::Rest;
+            day.duration_category = None;
+            day.expected_tss = 0.0;
+            day.target_distance_km = None;
+        }
+
+        let mut intensity: Vec<usize> = (0..week.days.len())
+            .filter(|&i| week.days[i].workout_type.is_intensity())
+            .collect();
+        if intensity.len() > MAX_INTENSITY_SESSIONS {
+            intensity.sort_by(|&a, &b| {
+                let (a, b) = (&week.days[a], &week.days[b]);
+                intensity_priority(a.workout_type)
+                    .cmp(&intensity_priority(b.workout_type))
+                    .then(a.expected_tss.total_cmp(&b.expected_tss))
+            });
+            let excess = intensity.len() - MAX_INTENSITY_SESSIONS;
+            for &i in &intensity[..excess] {
+                let day = &mut week.days[i];
+                repairs.push(Repair::DowngradeToEasy {
+                    week_number: week.week_number,
+                    date: day.date.clone(),
+                    from: day.workout_type,
+                });
+                day.workout_type = WorkoutType::EasyRun;
+                day.duration_category.get_or_insert(DurationCategory::Short);
+            }
+        }
+
+        if let Some(from_km) = errors.iter().find_map(|e| match e {
+            ValidationError::VolumeIncreaseTooHigh { from_km, .. } => Some(*from_km),
+            _ => None,
+        }) {
+            // Round down to 0.1 km, staying clear of the exact cap where
+            // floating point could still fail validation.
+            let cap_km = from_km * (1.0 + MAX_VOLUME_INCREASE_PCT / 100.0);
+            let to_km = ((cap_km * 10.0) - 1e-6).floor() / 10.0;
+            repairs.push(Repair::CapVolume {
+                week_number: week.week_number,
+                date: week
+                    .days
+                    .first()
+                    .map(|d| d.date.clone())
+                    .unwrap_or_default(),
+                from_km: week.target_volume_km,
+                to_km,
+            });
+            // Keep the days' distances in proportion to the new target
+            let scale = to_km / week.target_volume_km;
+            for km in week
+                .days
+                .iter_mut()
+                .filter_map(|d| d.target_distance_km.as_mut())
+            {
+                *km = (*km * scale * 10.0).round() / 10.0;
+            }
+            week.target_volume_km = to_km;
+        }
+        ctx = ctx.after(week);
+    }
+    repairs
+}
+
+/// How much an intensity session matters to the plan. Race-specific work is
+/// kept over VO2max and anaerobic work, which is kept over threshold work.
+fn intensity_priority(workout_type: WorkoutType) -> u8 {
+    match workout_type {
+        WorkoutType::RaceSpecific | WorkoutType::TimeTrial => 3,
+        WorkoutType::Vo2maxIntervals
+        | WorkoutType::UnderOver
+        | WorkoutType::Track200m
+        | WorkoutType::Track400m
+        | WorkoutType::Track800m
+        | WorkoutType::Track1200m
+        | WorkoutType::Track1600m
+        | WorkoutType::TrackMixed
+        | WorkoutType::TrackMilePace
+        | WorkoutType::TrackRaceCombo
+        | WorkoutType::AnaerobicHills
+        | WorkoutType::AnaerobicFlat
+        | WorkoutType::AnaerobicPower => 2,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::validation::{PlannedDay, WeekType};
+
+    fn day(date: &str, workout_type: WorkoutType, expected_tss: f64) -> PlannedDay {
+        PlannedDay {
+            date: date.to_string(),
+            workout_type,
+            duration_category: Some(DurationCategory::Medium),
+            expected_tss,
+            target_distance_km: None,
+        }
+    }
+
+    fn week(target_volume_km: f64, days: Vec<PlannedDay>) -> WeekPlan {
+        WeekPlan {
+            week_number: 2,
+            week_type: WeekType::Load,
+            target_volume_km,
+            target_weekly_tss: 350.0,
+            days,
+        }
+    }
+
+    fn ctx(previous_week_volume_km: Option<f64>) -> ValidationContext {
+        ValidationContext {
+            athlete_ctl: 40.0,
+            previous_week_volume_km,
+        }
+    }
+
+    #[test]
+    fn downgrades_least_important_extra_intensity() {
+        let mut weeks = vec![week(
+            40.0,
+            vec![
+                day("2026-03-09", WorkoutType::RaceSpecific, 70.0),
+                day("2026-03-10", WorkoutType::TempoRun, 60.0),
+                day("2026-03-11", WorkoutType::Rest, 0.0),
+                day("2026-03-12", WorkoutType::Vo2maxIntervals, 65.0),
+                day("2026-03-13", WorkoutType::CruiseIntervals, 55.0),
+                day("2026-03-14", WorkoutType::AnaerobicHills, 60.0),
+                day("2026-03-15", WorkoutType::LongRun, 90.0),
+            ],
+        )];
+        let repairs = repair_week_plans(&mut weeks, &ctx(None));
+
+        assert_eq!(
+            repairs,
+            vec![
+                Repair::DowngradeToEasy {
+                    week_number: 2,
+                    date: "2026-03-13".to_string(),
+                    from: WorkoutType::CruiseIntervals,
+                },
+                Repair::DowngradeToEasy {
+                    week_number: 2,
+                    date: "2026-03-10".to_string(),
+                    from: WorkoutType::TempoRun,
+                },
+            ]
+        );
+        assert!(validate_week_plan(&weeks[0], &ctx(None)).is_empty());
+        assert_eq!(weeks[0].days[1].workout_type, WorkoutType::EasyRun);
+    }
+
+    #[test]
+    fn lowest_tss_day_becomes_rest() {
+        let mut weeks = vec![week(
+            40.0,
+            vec![
+                day("2026-03-09", WorkoutType::EasyRun, 45.0),
+                day("2026-03-10", WorkoutType::TempoRun, 60.0),
+                day("2026-03-11", WorkoutType::EasyRun, 30.0),
+                day("2026-03-12", WorkoutType::Vo2maxIntervals, 65.0),
+                day("2026-03-13", WorkoutType::EasyRun, 45.0),
+                day("2026-03-14", WorkoutType::SteadyRun, 50.0),
+                day("2026-03-15", WorkoutType::LongRun, 90.0),
+            ],
+        )];
+        let repairs = repair_week_plans(&mut weeks, &ctx(None));
+
+        assert_eq!(
+            repairs,
+            vec![Repair::AddRestDay {
+                week_number: 2,
+                date: "2026-03-11".to_string(),
+                from: WorkoutType::EasyRun,
+            }]
+        );
+        assert_eq!(weeks[0].days[2].workout_type, WorkoutType::Rest);
+        assert!(validate_week_plan(&weeks[0], &ctx(None)).is_empty());
+        assert_eq!(
+            repairs[0].to_string(),
+            "Changed from Easy Run to a rest day so the week has one."
+        );
+    }
+
+    #[test]
+    fn volume_is_capped_at_ten_percent() {
+        let mut weeks = vec![week(
+            50.0,
+            vec![
+                day("2026-03-09", WorkoutType::EasyRun, 45.0),
+                day("2026-03-10", WorkoutType::Rest, 0.0),
+                day("2026-03-15", WorkoutType::LongRun, 90.0),
+            ],
+        )];
+        let repairs = repair_week_plans(&mut weeks, &ctx(Some(37.0)));
+
+        assert_eq!(
+            repairs,
+            vec![Repair::CapVolume {
+                week_number: 2,
+                date: "2026-03-09".to_string(),
+                from_km: 50.0,
+                to_km: 40.6,
+            }]
+        );
+        assert_eq!(weeks[0].target_volume_km, 40.6);
+        assert!(validate_week_plan(&weeks[0], &ctx(Some(37.0))).is_empty());
+    }
+
+    #[test]
+    fn other_violations_are_left_alone() {
+        let mut weeks = vec![week(
+            40.0,
+            vec![
+                day("2026-03-09", WorkoutType::LongRun, 90.0),
+                day("2026-03-10", WorkoutType::Rest, 0.0),
+                day("2026-03-15", WorkoutType::LongRun, 90.0),
+            ],
+        )];
+        assert!(repair_week_plans(&mut weeks, &ctx(None)).is_empty());
+    }
+
+    #[test]
+    fn capped_week_is_the_next_weeks_baseline() {
+        let long_run = PlannedDay {
+            target_distance_km: Some(50.0),
+            ..day("2026-03-15", WorkoutType::LongRun, 90.0)
+        };
+        let mut weeks = vec![
+            week(
+                50.0,
+                vec![day("2026-03-09", WorkoutType::Rest, 0.0), long_run],
+            ),
+            week(44.0, vec![day("2026-03-16", WorkoutType::Rest, 0.0)]),
+        ];
+        let repairs = repair_week_plans(&mut weeks, &ctx(Some(37.0)));
+
+        // Week 2 goes from 40.6 to 44.0, another 8%, which is allowed
+        assert_eq!(repairs.len(), 1);
+        assert_eq!(weeks[0].target_volume_km, 40.6);
+        assert_eq!(weeks[0].days[1].target_distance_km, Some(40.6));
+        assert_eq!(weeks[1].target_volume_km, 44.0);
+    }
+}
@@ -0,0 +1,609 @@
+use serde::Serialize;
+
+use super::types::{HrZones, PaceZones};
+use crate::fit::FitRecord;
+
+// ---------------------------------------------------------------------------
+// Normalized Graded Pace (NGP)
+// ---------------------------------------------------------------------------
+//
+// For each second:
+//   grade         = elevation_change / horizontal_distance
+//   cost_factor   = 1 + 15.3 * grade + 4.2 * grade^2   (floored at 0.6)
+//   adjusted_speed = actual_speed * cost_factor
+// NGP = 4th-power mean of the 30 s rolling average of adjusted speed.
+//
+// The 4th-power mean weights hard efforts more than their share of time,
+// matching how normalized power/pace are computed, so an interval session
+// scores above its plain average pace.
+//
+
+/// Rolling window for smoothing adjusted speed, in seconds (samples at 1 Hz).
+pub const NGP_ROLLING_WINDOW_S: usize = 30;
+
+/// Lower bound on the grade cost factor. Steep downhills still cost effort.
+pub const MIN_COST_FACTOR: f64 = 0.6;
+
+/// Grades beyond this are treated as GPS/altimeter noise and clamped.
+pub const MAX_ABS_GRADE: f64 = 0.45;
+
+/// Below this horizontal distance (m) between samples the grade is not
+/// meaningful (standing still), so it is taken as flat.
+const MIN_GRADE_DISTANCE_M: f64 = 1.0;
+
+/// Metabolic cost of running at `grade` relative to the flat.
+pub fn grade_cost_factor(grade: f64) -> f64 {
+    let g = grade.clamp(-MAX_ABS_GRADE, MAX_ABS_GRADE);
+    (1.0 + 15.3 * g + 4.2 * g * g).max(MIN_COST_FACTOR)
+}
+
+/// Grade-adjusted speed (m/s) for each per-second record.
+///
+/// Speed comes from the record or, failing that, the distance delta. Records
+/// with neither count as stopped (0 m/s). Grade needs altitude and distance on
+/// both sides of the interval; otherwise the sample is treated as flat.
+pub fn grade_adjusted_speeds(records: &[FitRecord]) -> Vec<f64> {
+    records
+        .iter()
+        .enumerate()
+        .map(|(i, r)| {
+            let previous = i.checked_sub(1).map(|p| &records[p]);
+            let distance_delta = previous.and_then(|p| Some(r.distance_m? - p.distance_m?));
+
+            let speed = r
+                .speed_m_per_s
+                .or(distance_delta.map(|d| d.max(0.0)))
+                .unwrap_or(0.0);
+
+            let grade = match (previous, distance_delta) {
+                (Some(p), Some(d)) if d >= MIN_GRADE_DISTANCE_M => {
+                    match (r.altitude_m, p.altitude_m) {
+                        (Some(a), Some(b)) => (a - b) / d,
+                        _ => 0.0,
+                    }
+                }
+                _ => 0.0,
+            };
+
+            speed * grade_cost_factor(grade)
+        })
+        .collect()
+}
+
+/// Trailing rolling mean over `window` samples. Emits one value per full
+/// window; returns the overall mean if there are fewer samples than `window`.
+pub fn rolling_average(values: &[f64], window: usize) -> Vec<f64> {
+    if values.is_empty() || window == 0 {
+        return vec![];
+    }
+    if values.len() < window {
+        return vec![values.iter().sum::<f64>() / values.len() as f64];
+    }
+
+    let mut sum: f64 = values[..window].iter().sum();
+    let mut out = Vec::with_capacity(values.len() - window + 1);
+    out.push(sum / window as f64);
+    for i in window..values.len() {
+        sum += values[i] - values[i - window];
+        out.push(sum / window as f64);
+    }
+    out
+}
+
+/// Normalized Graded Pace in m/s. `None` if there are no records or the
+/// athlete never moved.
+pub fn calculate_ngp(records: &[FitRecord]) -> Option<f64> {
+    let adjusted = grade_adjusted_speeds(records);
+    let rolling = rolling_average(&adjusted, NGP_ROLLING_WINDOW_S);
+    if rolling.is_empty() {
+        return None;
+    }
+
+    let mean_fourth = rolling.iter().map(|v| v.powi(4)).sum::<f64>() / rolling.len() as f64;
+    let ngp = mean_fourth.powf(0.25);
+    (ngp > 0.0).then_some(ngp)
+}
+
+// ---------------------------------------------------------------------------
+// Training Stress Score
+// ---------------------------------------------------------------------------
+
+/// Intensity factor: NGP relative to functional threshold pace (both m/s).
+pub fn intensity_factor(ngp: f64, ftpace: f64) -> f64 {
+    ngp / ftpace
+}
+
+/// Running TSS: `(duration_s * NGP * IF) / (FTPace * 3600) * 100`.
+/// One hour at threshold pace scores 100.
+pub fn calculate_rtss(ngp: f64, ftpace: f64, duration_s: f64) -> f64 {
+    let intensity = intensity_factor(ngp, ftpace);
+    (duration_s * ngp * intensity) / (ftpace * 3600.0) * 100.0
+}
+
+/// Heart-rate TSS from lactate threshold HR, for athletes without an FTPace.
+///
+/// Each second contributes `(HR / LTHR)^2 / 3600 * 100`, so one hour at LTHR
+/// scores 100 — the same scale as rTSS with IF = HR / LTHR. Seconds without
+/// HR are skipped. `None` if no record carries HR.
+pub fn calculate_hrtss(records: &[FitRecord], lthr: u16) -> Option<f64> {
+    if lthr == 0 {
+        return None;
+    }
+    let lthr = f64::from(lthr);
+
+    let intensities: Vec<f64> = records
+        .iter()
+        .filter_map(|r| r.heart_rate)
+        .filter(|&hr| hr > 0)
+        .map(|hr| (f64::from(hr) / lthr).powi(2))
+        .collect();
+
+    if intensities.is_empty() {
+        return None;
+    }
+    Some(intensities.iter().sum::<f64>() / 3600.0 * 100.0)
+}
+
+/// Load scores for a workout.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LoadScores {
+    pub ngp_m_per_s: Option<f64>,
+    pub intensity_factor: Option<f64>,
+    pub rtss: Option<f64>,
+    pub hrtss: Option<f64>,
+    /// The TSS used for training load: rTSS when available, otherwise hrTSS.
+    pub tss: Option<f64>,
+}
+
+/// Score a workout from its per-second records.
+///
+/// rTSS needs the athlete's FTPace and some movement; when either is
+/// missing, falls back to hrTSS from LTHR — but only if the HR data is
+/// sufficient (see `domain::summary::MIN_HR_COVERAGE`).
+pub fn score_workout(
+    records: &[FitRecord],
+    duration_s: f64,
+    ftpace_m_per_s: Option<f64>,
+    lthr: u16,
+    hr_data_sufficient: bool,
+) -> LoadScores {
+    let ngp = calculate_ngp(records);
+
+    let (intensity, rtss) = match (ngp, ftpace_m_per_s) {
+        (Some(ngp), Some(ftpace)) if ftpace > 0.0 => (
+            Some(intensity_factor(ngp, ftpace)),
+            Some(calculate_rtss(ngp, ftpace, duration_s)),
+        ),
+        _ => (None, None),
+    };
+
+    let hrtss = if hr_data_sufficient {
+        calculate_hrtss(records, lthr)
+    } else {
+        None
+    };
+
+    LoadScores {
+        ngp_m_per_s: ngp,
+        intensity_factor: intensity,
+        rtss,
+        hrtss,
+        tss: rtss.or(hrtss),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// TRIMP (Banister, per-second)
+// ---------------------------------------------------------------------------
+//
+// For each second t:
+//   delta_hr_t = (HR(t) - resting_HR) / (max_HR - resting_HR)
+//   TRIMP += (1/60) * delta_hr_t * 0.2445 * e^(3.411 * delta_hr_t)
+//
+// Accumulating per second (rather than from average HR) keeps intervals from
+// being underestimated, since the weighting is exponential.
+//
+
+const TRIMP_COEFFICIENT: f64 = 0.2445;
+const TRIMP_EXPONENT: f64 = 3.411;
+
+/// Banister TRIMP accumulated over the per-second records. Seconds without
+/// HR contribute nothing; HR reserve fraction is clamped to 0..=1.
+/// `None` if the HR range is invalid or no record carries HR.
+pub fn calculate_trimp(records: &[FitRecord], resting_hr: u16, max_hr: u16) -> Option<f64> {
+    if max_hr <= resting_hr {
+        return None;
+    }
+    let resting = f64::from(resting_hr);
+    let reserve = f64::from(max_hr) - resting;
+
+    let per_second: Vec<f64> = records
+        .iter()
+        .filter_map(|r| r.heart_rate)
+        .filter(|&hr| hr > 0)
+        .map(|hr| {
+            let delta = ((f64::from(hr) - resting) / reserve).clamp(0.0, 1.0);
+            delta * TRIMP_COEFFICIENT * (TRIMP_EXPONENT * delta).exp() / 60.0
+        })
+        .collect();
+
+    if per_second.is_empty() {
+        return None;
+    }
+    Some(per_second.iter().sum())
+}
+
+// ---------------------------------------------------------------------------
+// Time in zone
+// ---------------------------------------------------------------------------
+
+/// Seconds spent in each zone. Index 0 is zone 1.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ZoneTime {
+    pub seconds: Vec<i64>,
+}
+
+impl ZoneTime {
+    fn new(zone_count: usize) -> Self {
+        Self {
+            seconds: vec![0; zone_count],
+        }
+    }
+
+    /// Seconds in a 1-based zone (0 for unknown zones).
+    pub fn seconds_in_zone(&self, zone: u8) -> i64 {
+        (zone as usize)
+            .checked_sub(1)
+            .and_then(|i| self.seconds.get(i))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Total seconds counted across all zones.
+    pub fn total_seconds(&self) -> i64 {
+        self.seconds.iter().sum()
+    }
+
+    /// Fraction of counted time spent in any of `zones` (1-based), 0..=1.
+    /// Used to compare execution with a prescription's target zones.
+    pub fn fraction_in(&self, zones: &[u8]) -> f64 {
+        let total = self.total_seconds();
+        if total == 0 {
+            return 0.0;
+        }
+        let inside: i64 = zones.iter().map(|&z| self.seconds_in_zone(z)).sum();
+        inside as f64 / total as f64
+    }
+}
+
+/// Seconds in each HR zone. Records without HR are skipped.
+pub fn hr_time_in_zone(records: &[FitRecord], zones: &HrZones) -> ZoneTime {
+    let mut time = ZoneTime::new(zones.len());
+    for hr in records.iter().filter_map(|r| r.heart_rate).filter(|&hr| hr > 0) {
+        if let Some(zone) = zones.zone_for_bpm(hr) {
+            add_second(&mut time, zone.zone);
+        }
+    }
+    time
+}
+
+/// Seconds in each pace zone. Records without speed are skipped.
+///
+/// Pace zone boundaries are rounded to 0.01 m/s and can leave small gaps
+/// between zones; a pace that falls in a gap is counted in the zone below.
+pub fn pace_time_in_zone(records: &[FitRecord], zones: &PaceZones) -> ZoneTime {
+    let mut time = ZoneTime::new(zones.len());
+    for speed in records.iter().filter_map(|r| r.speed_m_per_s) {
+        let zone = zones.zone_for_pace(speed).or_else(|| {
+            zones
+                .zones
+                .iter()
+                .filter(|z| z.min_pace_m_per_s <= speed)
+                .max_by_key(|z| z.zone)
+        });
+        if let Some(zone) = zone {
+            add_second(&mut time, zone.zone);
+        }
+    }
+    time
+}
+
+fn add_second(time: &mut ZoneTime, zone: u8) {
+    if let Some(slot) = (zone as usize)
+        .checked_sub(1)
+        .and_then(|i| time.seconds.get_mut(i))
+    {
+        *slot += 1;
+    }
+}
+
+/// Parse a stored zone prescription such as `"Z2"`, `"Z1, Z2"` or `"Z3-Z4"`
+/// into 1-based zone numbers. Unparseable parts are ignored.
+pub fn parse_zone_list(zones: &str) -> Vec<u8> {
+    let parse_one = |s: &str| s.trim().trim_start_matches(['Z', 'z']).parse::<u8>().ok();
+
+    let mut out = Vec::new();
+    for part in zones.split(',') {
+        match part.split_once('-') {
+            Some((lo, hi)) => {
+                if let (Some(lo), Some(hi)) = (parse_one(lo), parse_one(hi)) {
+                    out.extend(lo.min(hi)..=lo.max(hi));
+                }
+            }
+            None => out.extend(parse_one(part)),
+        }
+    }
+    out.sort_unstable();
+    out.dedup();
+    out
+}
+
+/// Format 1-based zone numbers as stored, e.g. `"Z1, Z2"`. `None` if empty.
+pub fn format_zone_list(zones: &[u8]) -> Option<String> {
+    if zones.is_empty() {
+        return None;
+    }
+    Some(
+        zones
+            .iter()
+            .map(|z| format!("Z{z}"))
+            .collect::<Vec<_>>()
+            .join(", "),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+
+    const EPSILON: f64 = 1e-6;
+
+    /// Build a 1 Hz stream from per-second (speed, altitude, hr) samples,
+    /// integrating distance from speed.
+    fn stream(samples: &[(f64, f64, Option<u16>)]) -> Vec<FitRecord> {
+        let mut distance = 0.0;
+        samples
+            .iter()
+            .enumerate()
+            .map(|(i, &(speed, altitude, hr))| {
+                if i > 0 {
+                    distance += speed;
+                }
+                FitRecord {
+                    timestamp: DateTime::from_timestamp(1_700_000_000 + i as i64, 0).unwrap(),
+                    timestamp_ms: i as u64 * 1000,
+                    heart_rate: hr,
+                    speed_m_per_s: Some(speed),
+                    distance_m: Some(distance),
+                    altitude_m: Some(altitude),
+                    cadence: Some(88),
+                    power_watts: None,
+                    latitude: None,
+                    longitude: None,
+                    temperature_c: None,
+                }
+            })
+            .collect()
+    }
+
+    fn flat(seconds: usize, speed: f64) -> Vec<FitRecord> {
+        stream(&vec![(speed, 100.0, Some(150)); seconds])
+    }
+
+    #[test]
+    fn cost_factor_values() {
+        assert!((grade_cost_factor(0.0) - 1.0).abs() < EPSILON);
+        assert!((grade_cost_factor(0.05) - (1.0 + 0.765 + 0.0105)).abs() < EPSILON);
+        // Steep downhill is floored
+        assert_eq!(grade_cost_factor(-0.1), MIN_COST_FACTOR);
+        // Spikes are clamped
+        assert_eq!(grade_cost_factor(3.0), grade_cost_factor(MAX_ABS_GRADE));
+    }
+
+    #[test]
+    fn rolling_average_window() {
+        assert_eq!(rolling_average(&[1.0, 2.0, 3.0, 4.0], 2), vec![1.5, 2.5, 3.5]);
+        assert_eq!(rolling_average(&[2.0, 4.0], 30), vec![3.0]);
+        assert!(rolling_average(&[], 30).is_empty());
+    }
+
+    #[test]
+    fn flat_steady_ngp_equals_pace() {
+        let records = flat(3600, 3.0);
+        let ngp = calculate_ngp(&records).unwrap();
+        assert!((ngp - 3.0).abs() < EPSILON, "ngp was {ngp}");
+    }
+
+    #[test]
+    fn one_hour_at_threshold_is_100_rtss() {
+        let records = flat(3600, 4.0);
+        let scores = score_workout(&records, 3600.0, Some(4.0), 165, true);
+        assert!((scores.intensity_factor.unwrap() - 1.0).abs() < EPSILON);
+        assert!((scores.rtss.unwrap() - 100.0).abs() < EPSILON);
+        assert_eq!(scores.tss, scores.rtss);
+    }
+
+    #[test]
+    fn easy_run_rtss() {
+        // 45 min at 75% of threshold pace → IF 0.75 → 0.75 h * 0.5625 * 100
+        let records = flat(2700, 3.0);
+        let rtss = score_workout(&records, 2700.0, Some(4.0), 165, true).rtss.unwrap();
+        assert!((rtss - 42.1875).abs() < 1e-4, "rtss was {rtss}");
+    }
+
+    #[test]
+    fn hilly_run_scores_above_flat_pace() {
+        // Repeated 2 min climbs at 5% and 2 min descents at 5% at constant speed
+        let mut samples = vec![];
+        let mut altitude = 100.0;
+        for second in 0..3600 {
+            let climbing = (second / 120) % 2 == 0;
+            altitude += if climbing { 0.05 * 3.0 } else { -0.05 * 3.0 };
+            samples.push((3.0, altitude, Some(150)));
+        }
+        let records = stream(&samples);
+
+        let ngp = calculate_ngp(&records).unwrap();
+        // Uphill cost (1.78x) outweighs the downhill saving (floored at 0.6x)
+        assert!(ngp > 3.5, "hilly ngp was {ngp}");
+        let flat_rtss = calculate_rtss(3.0, 4.0, 3600.0);
+        let hilly_rtss = calculate_rtss(ngp, 4.0, 3600.0);
+        assert!(hilly_rtss > flat_rtss);
+    }
+
+    #[test]
+    fn steep_descent_is_floored() {
+        let samples: Vec<_> = (0..600)
+            .map(|s| (3.0, 500.0 - s as f64 * 0.3, Some(140)))
+            .collect();
+        let ngp = calculate_ngp(&stream(&samples)).unwrap();
+        assert!((ngp - 3.0 * MIN_COST_FACTOR).abs() < 1e-3, "ngp was {ngp}");
+    }
+
+    #[test]
+    fn intervals_weight_hard_efforts() {
+        // 6 x (3 min at 4.5 m/s, 2 min jog at 2.5 m/s)
+        let mut samples = vec![];
+        for _ in 0..6 {
+            samples.extend(vec![(4.5, 100.0, Some(172)); 180]);
+            samples.extend(vec![(2.5, 100.0, Some(140)); 120]);
+        }
+        let records = stream(&samples);
+        let average = (4.5 * 180.0 + 2.5 * 120.0) / 300.0;
+
+        let ngp = calculate_ngp(&records).unwrap();
+        assert!(ngp > average + 0.1, "ngp {ngp} vs average {average}");
+        assert!(ngp < 4.5);
+
+        let scores = score_workout(&records, records.len() as f64, Some(4.2), 168, true);
+        let even_paced = calculate_rtss(average, 4.2, records.len() as f64);
+        assert!(scores.rtss.unwrap() > even_paced);
+    }
+
+    #[test]
+    fn stationary_has_no_ngp() {
+        let records = flat(120, 0.0);
+        assert!(calculate_ngp(&records).is_none());
+        assert!(calculate_ngp(&[]).is_none());
+    }
+
+    #[test]
+    fn falls_back_to_hrtss_without_ftpace() {
+        // One hour exactly at LTHR
+        let records = stream(&vec![(3.5, 100.0, Some(165)); 3600]);
+        let scores = score_workout(&records, 3600.0, None, 165, true);
+        assert!(scores.rtss.is_none());
+        assert!(scores.intensity_factor.is_none());
+        assert!((scores.hrtss.unwrap() - 100.0).abs() < EPSILON);
+        assert_eq!(scores.tss, scores.hrtss);
+        assert!(scores.ngp_m_per_s.is_some());
+    }
+
+    #[test]
+    fn hrtss_scales_with_intensity_squared() {
+        let easy = stream(&vec![(3.0, 100.0, Some(132)); 3600]);
+        let hrtss = calculate_hrtss(&easy, 165).unwrap();
+        assert!((hrtss - 64.0).abs() < EPSILON, "hrtss was {hrtss}");
+    }
+
+    #[test]
+    fn trimp_per_second_accumulation() {
+        // One hour at 60% HR reserve (resting 50, max 190 → HR 134)
+        let records = stream(&vec![(3.0, 100.0, Some(134)); 3600]);
+        let trimp = calculate_trimp(&records, 50, 190).unwrap();
+        let expected = 60.0 * 0.6 * 0.2445 * (3.411f64 * 0.6).exp();
+        assert!((trimp - expected).abs() < 1e-6, "trimp {trimp} vs {expected}");
+    }
+
+    #[test]
+    fn trimp_intervals_exceed_average_hr_estimate() {
+        let mut samples = vec![];
+        for _ in 0..6 {
+            samples.extend(vec![(4.5, 100.0, Some(180)); 180]);
+            samples.extend(vec![(2.5, 100.0, Some(130)); 120]);
+        }
+        let records = stream(&samples);
+        let trimp = calculate_trimp(&records, 50, 190).unwrap();
+
+        let avg_hr = (180.0 * 180.0 + 130.0 * 120.0) / 300.0;
+        let delta: f64 = (avg_hr - 50.0) / 140.0;
+        let from_average = 30.0 * delta * 0.2445 * (3.411 * delta).exp();
+        assert!(trimp > from_average, "{trimp} <= {from_average}");
+    }
+
+    #[test]
+    fn trimp_skips_missing_hr_and_clamps() {
+        let records = stream(&[(3.0, 100.0, None), (3.0, 100.0, Some(40)), (3.0, 100.0, Some(250))]);
+        let trimp = calculate_trimp(&records, 50, 190).unwrap();
+        // Below resting contributes 0; above max is clamped to delta = 1
+        let expected = 0.2445 * 3.411f64.exp() / 60.0;
+        assert!((trimp - expected).abs() < 1e-9);
+        assert!(calculate_trimp(&stream(&[(3.0, 100.0, None)]), 50, 190).is_none());
+        assert!(calculate_trimp(&records, 190, 190).is_none());
+    }
+
+    #[test]
+    fn hr_time_in_zone_counts_seconds() {
+        use crate::domain::zones::calculate_hr_zones;
+        // LTHR 170: Z2 = 139..149, Z5 = 170..176
+        let zones = calculate_hr_zones(170);
+        let mut samples = vec![(3.0, 100.0, Some(145)); 600];
+        samples.extend(vec![(4.0, 100.0, Some(172)); 300]);
+        samples.extend(vec![(3.0, 100.0, None); 60]);
+        let time = hr_time_in_zone(&stream(&samples), &zones);
+
+        assert_eq!(time.seconds.len(), 7);
+        assert_eq!(time.seconds_in_zone(2), 600);
+        assert_eq!(time.seconds_in_zone(5), 300);
+        assert_eq!(time.total_seconds(), 900);
+        assert!((time.fraction_in(&[2]) - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(time.seconds_in_zone(0), 0);
+        assert_eq!(time.seconds_in_zone(9), 0);
+    }
+
+    #[test]
+    fn pace_time_in_zone_handles_boundary_gaps() {
+        use crate::domain::zones::calculate_pace_zones;
+        // FTPace 4.0: Z2 = 3.00..3.40, Z3 = 3.44..3.80 — 3.42 sits in the gap
+        let zones = calculate_pace_zones(4.0);
+        let mut samples = vec![(3.2, 100.0, None); 100];
+        samples.extend(vec![(3.42, 100.0, None); 10]);
+        samples.extend(vec![(5.5, 100.0, None); 5]);
+        let time = pace_time_in_zone(&stream(&samples), &zones);
+
+        assert_eq!(time.seconds.len(), 6);
+        assert_eq!(time.seconds_in_zone(2), 110);
+        assert_eq!(time.seconds_in_zone(6), 5);
+        assert_eq!(time.total_seconds(), 115);
+    }
+
+    #[test]
+    fn parse_zone_list_formats() {
+        assert_eq!(parse_zone_list("Z2"), vec![2]);
+        assert_eq!(parse_zone_list("Z1, Z2"), vec![1, 2]);
+        assert_eq!(parse_zone_list("Z1-Z3"), vec![1, 2, 3]);
+        assert_eq!(parse_zone_list("Z4, Z5-Z6, Z5"), vec![4, 5, 6]);
+        assert!(parse_zone_list("").is_empty());
+        assert!(parse_zone_list("easy").is_empty());
+        assert_eq!(format_zone_list(&[1, 2]).as_deref(), Some("Z1, Z2"));
+        assert_eq!(format_zone_list(&[]), None);
+        assert_eq!(
+            parse_zone_list(&format_zone_list(&[3, 4]).unwrap()),
+            vec![3, 4]
+        );
+    }
+
+    #[test]
+    fn no_tss_without_ftpace_or_sufficient_hr() {
+        let records = stream(&vec![(3.0, 100.0, None); 600]);
+        let scores = score_workout(&records, 600.0, None, 165, false);
+        assert!(scores.tss.is_none());
+        assert!(scores.hrtss.is_none());
+        assert!(calculate_hrtss(&records, 165).is_none());
+    }
+}
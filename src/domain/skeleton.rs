@@ -0,0 +1,383 @@
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+// ---------------------------------------------------------------------------
+// Macrocycle skeletons (PRODUCT_DESIGN §7)
+// ---------------------------------------------------------------------------
+//
+// Plan generation starts with a skeleton: the sequence of mesocycles up to
+// race day, each with its load/recovery weeks and target weekly volume.
+// Claude drafts it, the athlete may edit it, and confirming it generates the
+// first mesocycle's workouts. Athlete edits go through `apply_patch` and must
+// pass `validate_skeleton`.
+//
+
+/// Load weeks allowed in one mesocycle.
+pub const MIN_LOAD_WEEKS: i64 = 1;
+pub const MAX_LOAD_WEEKS: i64 = 6;
+
+/// Recovery weeks allowed in one mesocycle (0 for a taper).
+pub const MAX_RECOVERY_WEEKS: i64 = 2;
+
+/// Highest weekly volume target accepted, in km.
+pub const MAX_WEEKLY_VOLUME_KM: f64 = 250.0;
+
+/// Largest increase in weekly volume target from one mesocycle to the next,
+/// and from the athlete's current volume to the first mesocycle.
+pub const MAX_BLOCK_VOLUME_INCREASE_PCT: f64 = 15.0;
+
+/// Most days the plan may end before race day.
+pub const MAX_DAYS_ENDING_BEFORE_RACE: i64 = 14;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MacrocycleSkeleton {
+    pub target_ctl: f64,
+    pub coach_message: String,
+    pub mesocycles: Vec<MesocycleSkeleton>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MesocycleSkeleton {
+    pub sequence_number: i64,
+    pub phase: String,
+    pub focus: String,
+    pub load_weeks: i64,
+    pub recovery_weeks: i64,
+    pub target_volume_km: f64,
+}
+
+impl MacrocycleSkeleton {
+    pub fn total_weeks(&self) -> i64 {
+        self.mesocycles
+            .iter()
+            .map(|m| m.load_weeks + m.recovery_weeks)
+            .sum()
+    }
+
+    /// Last day of the plan if it starts on `start`.
+    pub fn end_date(&self, start: NaiveDate) -> NaiveDate {
+        start + Duration::weeks(self.total_weeks()) - Duration::days(1)
+    }
+
+    /// Apply the athlete's edits. Fails without changing anything if a patch
+    /// names a mesocycle the skeleton does not have.
+    pub fn apply_patch(&mut self, patch: &SkeletonPatch) -> Result<(), SkeletonError> {
+        let mut patched = self.mesocycles.clone();
+        for edit in &patch.mesocycles {
+            let meso = patched
+                .iter_mut()
+                .find(|m| m.sequence_number == edit.sequence_number)
+                .ok_or(SkeletonError::UnknownMesocycle {
+                    sequence_number: edit.sequence_number,
+                })?;
+            if let Some(load_weeks) = edit.load_weeks {
+                meso.load_weeks = load_weeks;
+            }
+            if let Some(recovery_weeks) = edit.recovery_weeks {
+                meso.recovery_weeks = recovery_weeks;
+            }
+            if let Some(target_volume_km) = edit.target_volume_km {
+                meso.target_volume_km = target_volume_km;
+            }
+        }
+        self.mesocycles = patched;
+        Ok(())
+    }
+}
+
+/// Athlete edits to a skeleton draft. Only the structure and volume of
+/// existing mesocycles can change; phases and focus stay Claude's.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct SkeletonPatch {
+    pub mesocycles: Vec<MesocyclePatch>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MesocyclePatch {
+    pub sequence_number: i64,
+    pub load_weeks: Option<i64>,
+    pub recovery_weeks: Option<i64>,
+    pub target_volume_km: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum SkeletonError {
+    #[error("The plan has no mesocycles")]
+    NoMesocycles,
+    #[error("Mesocycle {sequence_number} does not exist")]
+    UnknownMesocycle { sequence_number: i64 },
+    #[error(
+        "Mesocycle {sequence_number}: load_weeks must be between {MIN_LOAD_WEEKS} and {MAX_LOAD_WEEKS}, got {load_weeks}"
+    )]
+    LoadWeeksOutOfRange {
+        sequence_number: i64,
+        load_weeks: i64,
+    },
+    #[error(
+        "Mesocycle {sequence_number}: recovery_weeks must be between 0 and {MAX_RECOVERY_WEEKS}, got {recovery_weeks}"
+    )]
+    RecoveryWeeksOutOfRange {
+        sequence_number: i64,
+        recovery_weeks: i64,
+    },
+    #[error(
+        "Mesocycle {sequence_number}: target_volume_km must be above 0 and at most {MAX_WEEKLY_VOLUME_KM}, got {target_volume_km}"
+    )]
+    VolumeOutOfRange {
+        sequence_number: i64,
+        target_volume_km: f64,
+    },
+    #[error(
+        "Mesocycle {sequence_number}: volume rises {increase_pct:.0}% from {from_km} km to {to_km} km (max {MAX_BLOCK_VOLUME_INCREASE_PCT}%)"
+    )]
+    VolumeIncreaseTooHigh {
+        sequence_number: i64,
+        from_km: f64,
+        to_km: f64,
+        increase_pct: f64,
+    },
+    #[error("The plan ends on {end_date}, after the race on {race_date}")]
+    EndsAfterRace {
+        end_date: NaiveDate,
+        race_date: NaiveDate,
+    },
+    #[error(
+        "The plan ends on {end_date}, more than {MAX_DAYS_ENDING_BEFORE_RACE} days before the race on {race_date}"
+    )]
+    EndsTooEarly {
+        end_date: NaiveDate,
+        race_date: NaiveDate,
+    },
+}
+
+/// Check a skeleton that would start on `start`: week counts and volumes per
+/// mesocycle, volume progression from `current_weekly_volume_km` through the
+/// mesocycles, and that the plan ends shortly before `race_date`.
+pub fn validate_skeleton(
+    skeleton: &MacrocycleSkeleton,
+    start: NaiveDate,
+    race_date: NaiveDate,
+    current_weekly_volume_km: f64,
+) -> Vec<SkeletonError> {
+    let mut errors = Vec::new();
+    if skeleton.mesocycles.is_empty() {
+        errors.push(SkeletonError::NoMesocycles);
+        return errors;
+    }
+
+    let mut previous_volume = Some(current_weekly_volume_km).filter(|v| *v > 0.0);
+    for meso in &skeleton.mesocycles {
+        let sequence_number = meso.sequence_number;
+        if !(MIN_LOAD_WEEKS..=MAX_LOAD_WEEKS).contains(&meso.load_weeks) {
+            errors.push(SkeletonError::LoadWeeksOutOfRange {
+                sequence_number,
+                load_weeks: meso.load_weeks,
+            });
+        }
+        if !(0..=MAX_RECOVERY_WEEKS).contains(&meso.recovery_weeks) {
+            errors.push(SkeletonError::RecoveryWeeksOutOfRange {
+                sequence_number,
+                recovery_weeks: meso.recovery_weeks,
+            });
+        }
+
+        let volume = meso.target_volume_km;
+        if !(volume > 0.0 && volume <= MAX_WEEKLY_VOLUME_KM) {
+            errors.push(SkeletonError::VolumeOutOfRange {
+                sequence_number,
+                target_volume_km: volume,
+            });
+            previous_volume = None;
+            continue;
+        }
+        if let Some(from_km) = previous_volume {
+            let increase_pct = (volume - from_km) / from_km * 100.0;
+            if increase_pct > MAX_BLOCK_VOLUME_INCREASE_PCT {
+                errors.push(SkeletonError::VolumeIncreaseTooHigh {
+                    sequence_number,
+                    from_km,
+                    to_km: volume,
+                    increase_pct,
+                });
+            }
+        }
+        previous_volume = Some(volume);
+    }
+
+    // Week counts out of range make the duration meaningless.
+    if errors.iter().any(|e| {
+        matches!(
+            e,
+            SkeletonError::LoadWeeksOutOfRange { .. }
+                | SkeletonError::RecoveryWeeksOutOfRange { .. }
+        )
+    }) {
+        return errors;
+    }
+
+    let end_date = skeleton.end_date(start);
+    if end_date > race_date {
+        errors.push(SkeletonError::EndsAfterRace {
+            end_date,
+            race_date,
+        });
+    } else if (race_date - end_date).num_days() > MAX_DAYS_ENDING_BEFORE_RACE {
+        errors.push(SkeletonError::EndsTooEarly {
+            end_date,
+            race_date,
+        });
+    }
+
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn meso(sequence_number: i64, load: i64, recovery: i64, volume: f64) -> MesocycleSkeleton {
+        MesocycleSkeleton {
+            sequence_number,
+            phase: "capacity".to_string(),
+            focus: "aerobic_capacity".to_string(),
+            load_weeks: load,
+            recovery_weeks: recovery,
+            target_volume_km: volume,
+        }
+    }
+
+    /// 4 + 4 + 2 = 10 weeks from 2026-03-02, ending 2026-05-10.
+    fn skeleton() -> MacrocycleSkeleton {
+        MacrocycleSkeleton {
+            target_ctl: 55.0,
+            coach_message: "Build then sharpen.".to_string(),
+            mesocycles: vec![
+                meso(1, 3, 1, 40.0),
+                meso(2, 3, 1, 45.0),
+                meso(3, 2, 0, 30.0),
+            ],
+        }
+    }
+
+    #[test]
+    fn valid_skeleton_passes() {
+        let s = skeleton();
+        assert_eq!(s.total_weeks(), 10);
+        assert_eq!(s.end_date(date("2026-03-02")), date("2026-05-10"));
+        let errors = validate_skeleton(&s, date("2026-03-02"), date("2026-05-10"), 38.0);
+        assert!(errors.is_empty(), "unexpected errors: {errors:?}");
+    }
+
+    #[test]
+    fn week_counts_and_volumes_are_bounded() {
+        let mut s = skeleton();
+        s.mesocycles[0].load_weeks = 40;
+        s.mesocycles[1].recovery_weeks = -1;
+        s.mesocycles[2].target_volume_km = 0.0;
+        let errors = validate_skeleton(&s, date("2026-03-02"), date("2026-05-10"), 38.0);
+        assert_eq!(
+            errors,
+            vec![
+                SkeletonError::LoadWeeksOutOfRange {
+                    sequence_number: 1,
+                    load_weeks: 40
+                },
+                SkeletonError::RecoveryWeeksOutOfRange {
+                    sequence_number: 2,
+                    recovery_weeks: -1
+                },
+                SkeletonError::VolumeOutOfRange {
+                    sequence_number: 3,
+                    target_volume_km: 0.0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn volume_progression_is_checked_from_current_volume() {
+        let s = skeleton();
+        let errors = validate_skeleton(&s, date("2026-03-02"), date("2026-05-10"), 30.0);
+        assert!(matches!(
+            errors.as_slice(),
+            [SkeletonError::VolumeIncreaseTooHigh {
+                sequence_number: 1,
+                ..
+            }]
+        ));
+
+        let mut s = skeleton();
+        s.mesocycles[1].target_volume_km = 60.0;
+        let errors = validate_skeleton(&s, date("2026-03-02"), date("2026-05-10"), 0.0);
+        match errors.as_slice() {
+            [
+                SkeletonError::VolumeIncreaseTooHigh {
+                    sequence_number: 2,
+                    increase_pct,
+                    ..
+                },
+            ] => assert!((increase_pct - 50.0).abs() < 1e-9),
+            other => panic!("unexpected errors: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn duration_must_fit_before_the_race() {
+        let s = skeleton();
+        let errors = validate_skeleton(&s, date("2026-03-02"), date("2026-05-09"), 0.0);
+        assert_eq!(
+            errors,
+            vec![SkeletonError::EndsAfterRace {
+                end_date: date("2026-05-10"),
+                race_date: date("2026-05-09"),
+            }]
+        );
+
+        assert!(validate_skeleton(&s, date("2026-03-02"), date("2026-05-24"), 0.0).is_empty());
+        let errors = validate_skeleton(&s, date("2026-03-02"), date("2026-05-25"), 0.0);
+        assert!(matches!(
+            errors.as_slice(),
+            [SkeletonError::EndsTooEarly { .. }]
+        ));
+    }
+
+    #[test]
+    fn patch_applies_edits_or_nothing() {
+        let mut s = skeleton();
+        let patch = SkeletonPatch {
+            mesocycles: vec![MesocyclePatch {
+                sequence_number: 2,
+                load_weeks: Some(4),
+                target_volume_km: Some(44.0),
+                ..Default::default()
+            }],
+        };
+        s.apply_patch(&patch).unwrap();
+        assert_eq!(s.mesocycles[1].load_weeks, 4);
+        assert_eq!(s.mesocycles[1].recovery_weeks, 1);
+        assert_eq!(s.mesocycles[1].target_volume_km, 44.0);
+
+        let patch = SkeletonPatch {
+            mesocycles: vec![
+                MesocyclePatch {
+                    sequence_number: 1,
+                    load_weeks: Some(2),
+                    ..Default::default()
+                },
+                MesocyclePatch {
+                    sequence_number: 9,
+                    ..Default::default()
+                },
+            ],
+        };
+        assert_eq!(
+            s.apply_patch(&patch),
+            Err(SkeletonError::UnknownMesocycle { sequence_number: 9 })
+        );
+        assert_eq!(s.mesocycles[0].load_weeks, 3);
+    }
+}
@@ -0,0 +1,267 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::fit::{FitActivity, FitRecord};
+
+/// Minimum fraction of samples that must carry HR for HR-based scoring.
+pub const MIN_HR_COVERAGE: f64 = 0.5;
+
+/// Altitude must move this far (m) from the last reference point before it
+/// counts as gain/loss. Filters GPS/barometer jitter.
+pub const ELEVATION_HYSTERESIS_M: f64 = 1.0;
+
+// ---------------------------------------------------------------------------
+// Workout Summary
+// ---------------------------------------------------------------------------
+
+/// Summary metrics computed from a parsed FIT activity (PRODUCT_DESIGN §3.3).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct WorkoutSummary {
+    pub started_at: DateTime<Utc>,
+    pub duration_seconds: i64,
+    pub distance_m: f64,
+    pub avg_hr: Option<i64>,
+    pub max_hr: Option<i64>,
+    pub avg_pace_m_per_s: Option<f64>,
+    pub max_pace_m_per_s: Option<f64>,
+    pub avg_cadence: Option<f64>,
+    pub elevation_gain_m: Option<f64>,
+    pub elevation_loss_m: Option<f64>,
+    pub hr_data_sufficient: bool,
+}
+
+/// Compute summary metrics for an activity.
+///
+/// Device session totals are preferred when present (they exclude paused
+/// time); otherwise values are derived from the 1 Hz record stream.
+/// Returns `None` if the activity has neither records nor a session.
+pub fn summarize_activity(activity: &FitActivity) -> Option<WorkoutSummary> {
+    let started_at = activity.start_time()?;
+    let records = activity.per_second_records();
+    let session = activity.sessions.first();
+
+    let duration_seconds = session
+        .and_then(|s| s.total_timer_time_s)
+        .map(|t| t.round() as i64)
+        .or_else(|| records.last().map(|r| (r.timestamp_ms / 1000) as i64))
+        .unwrap_or(0);
+
+    let distance_m = session
+        .and_then(|s| s.total_distance_m)
+        .or_else(|| records.iter().rev().find_map(|r| r.distance_m))
+        .unwrap_or(0.0);
+
+    let hr_samples: Vec<f64> = records
+        .iter()
+        .filter_map(|r| r.heart_rate)
+        .filter(|&hr| hr > 0)
+        .map(f64::from)
+        .collect();
+    let avg_hr = mean(&hr_samples)
+        .map(|v| v.round() as i64)
+        .or_else(|| session.and_then(|s| s.avg_heart_rate).map(i64::from));
+    let max_hr = hr_samples
+        .iter()
+        .copied()
+        .reduce(f64::max)
+        .map(|v| v as i64)
+        .or_else(|| session.and_then(|s| s.max_heart_rate).map(i64::from));
+
+    let avg_pace_m_per_s = if duration_seconds > 0 && distance_m > 0.0 {
+        Some(distance_m / duration_seconds as f64)
+    } else {
+        session.and_then(|s| s.avg_speed_m_per_s)
+    };
+    let max_pace_m_per_s = session.and_then(|s| s.max_speed_m_per_s).or_else(|| {
+        records
+            .iter()
+            .filter_map(|r| r.speed_m_per_s)
+            .reduce(f64::max)
+    });
+
+    // Cadence of zero means standing still; leave it out of the average.
+    let cadence_samples: Vec<f64> = records
+        .iter()
+        .filter_map(|r| r.cadence)
+        .filter(|&c| c > 0)
+        .map(f64::from)
+        .collect();
+    let avg_cadence = mean(&cadence_samples)
+        .or_else(|| session.and_then(|s| s.avg_cadence).map(f64::from));
+
+    let (elevation_gain_m, elevation_loss_m) = match session {
+        Some(s) if s.total_ascent_m.is_some() || s.total_descent_m.is_some() => (
+            s.total_ascent_m.map(f64::from),
+            s.total_descent_m.map(f64::from),
+        ),
+        _ => elevation_change(&records)
+            .map(|(gain, loss)| (Some(gain), Some(loss)))
+            .unwrap_or((None, None)),
+    };
+
+    let hr_data_sufficient = !records.is_empty()
+        && hr_samples.len() as f64 / records.len() as f64 >= MIN_HR_COVERAGE;
+
+    Some(WorkoutSummary {
+        started_at,
+        duration_seconds,
+        distance_m,
+        avg_hr,
+        max_hr,
+        avg_pace_m_per_s,
+        max_pace_m_per_s,
+        avg_cadence,
+        elevation_gain_m,
+        elevation_loss_m,
+        hr_data_sufficient,
+    })
+}
+
+/// Total (gain, loss) in metres using a hysteresis threshold.
+/// Returns `None` if no record carries altitude.
+pub fn elevation_change(records: &[FitRecord]) -> Option<(f64, f64)> {
+    let mut altitudes = records.iter().filter_map(|r| r.altitude_m);
+    let mut reference = altitudes.next()?;
+    let mut gain = 0.0;
+    let mut loss = 0.0;
+
+    for alt in altitudes {
+        let delta = alt - reference;
+        if delta >= ELEVATION_HYSTERESIS_M {
+            gain += delta;
+            reference = alt;
+        } else if -delta >= ELEVATION_HYSTERESIS_M {
+            loss += -delta;
+            reference = alt;
+        }
+    }
+
+    Some((gain, loss))
+}
+
+fn mean(values: &[f64]) -> Option<f64> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fit::FitSession;
+
+    fn record(second: i64, hr: Option<u16>, speed: f64, altitude: Option<f64>) -> FitRecord {
+        FitRecord {
+            timestamp: DateTime::from_timestamp(1_700_000_000 + second, 0).unwrap(),
+            timestamp_ms: second as u64 * 1000,
+            heart_rate: hr,
+            speed_m_per_s: Some(speed),
+            distance_m: Some(second as f64 * speed),
+            altitude_m: altitude,
+            cadence: Some(88),
+            power_watts: None,
+            latitude: None,
+            longitude: None,
+            temperature_c: None,
+        }
+    }
+
+    fn activity(records: Vec<FitRecord>, sessions: Vec<FitSession>) -> FitActivity {
+        FitActivity {
+            records,
+            sessions,
+            laps: vec![],
+            events: vec![],
+        }
+    }
+
+    #[test]
+    fn empty_activity_has_no_summary() {
+        assert!(summarize_activity(&activity(vec![], vec![])).is_none());
+    }
+
+    #[test]
+    fn summary_from_records_only() {
+        let records: Vec<_> = (0..=600)
+            .map(|s| record(s, Some(140 + (s % 10) as u16), 3.0, Some(100.0)))
+            .collect();
+        let summary = summarize_activity(&activity(records, vec![])).unwrap();
+
+        assert_eq!(summary.duration_seconds, 600);
+        assert_eq!(summary.distance_m, 1800.0);
+        assert_eq!(summary.avg_pace_m_per_s, Some(3.0));
+        assert_eq!(summary.max_pace_m_per_s, Some(3.0));
+        assert_eq!(summary.max_hr, Some(149));
+        assert_eq!(summary.avg_hr, Some(144));
+        assert_eq!(summary.avg_cadence, Some(88.0));
+        assert_eq!(summary.elevation_gain_m, Some(0.0));
+        assert!(summary.hr_data_sufficient);
+    }
+
+    #[test]
+    fn session_totals_take_precedence() {
+        let records: Vec<_> = (0..=60).map(|s| record(s, Some(150), 3.0, None)).collect();
+        let session = FitSession {
+            start_time: records[0].timestamp,
+            timestamp: None,
+            sport: Some(1),
+            sub_sport: None,
+            total_elapsed_time_s: Some(70.0),
+            total_timer_time_s: Some(55.4),
+            total_distance_m: Some(170.0),
+            total_calories: None,
+            avg_speed_m_per_s: None,
+            max_speed_m_per_s: Some(4.1),
+            avg_heart_rate: None,
+            max_heart_rate: None,
+            avg_cadence: None,
+            max_cadence: None,
+            avg_power_watts: None,
+            max_power_watts: None,
+            total_ascent_m: Some(12),
+            total_descent_m: Some(9),
+        };
+        let summary = summarize_activity(&activity(records, vec![session])).unwrap();
+
+        assert_eq!(summary.duration_seconds, 55);
+        assert_eq!(summary.distance_m, 170.0);
+        assert_eq!(summary.max_pace_m_per_s, Some(4.1));
+        assert_eq!(summary.elevation_gain_m, Some(12.0));
+        assert_eq!(summary.elevation_loss_m, Some(9.0));
+    }
+
+    #[test]
+    fn sparse_hr_is_insufficient() {
+        let records: Vec<_> = (0..100)
+            .map(|s| record(s, if s < 30 { Some(150) } else { None }, 3.0, None))
+            .collect();
+        let summary = summarize_activity(&activity(records, vec![])).unwrap();
+        assert!(!summary.hr_data_sufficient);
+        assert_eq!(summary.avg_hr, Some(150));
+    }
+
+    #[test]
+    fn elevation_hysteresis_ignores_jitter() {
+        let alts = [100.0, 100.4, 99.8, 100.3, 102.0, 104.0, 103.6, 101.0, 100.0];
+        let records: Vec<_> = alts
+            .iter()
+            .enumerate()
+            .map(|(i, &a)| record(i as i64, None, 3.0, Some(a)))
+            .collect();
+        let (gain, loss) = elevation_change(&records).unwrap();
+        assert!((gain - 4.0).abs() < 1e-9, "gain was {gain}");
+        assert!((loss - 4.0).abs() < 1e-9, "loss was {loss}");
+    }
+
+    #[test]
+    fn elevation_without_altitude_is_none() {
+        let records = vec![record(0, None, 3.0, None)];
+        assert!(elevation_change(&records).is_none());
+    }
+}
@@ -0,0 +1,310 @@
+use std::fmt;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use super::scoring::rolling_average;
+use super::validation::{WeekPlan, WeekType};
+use super::workouts::WorkoutType;
+use crate::fit::FitRecord;
+
+// ---------------------------------------------------------------------------
+// Threshold derivation (PRODUCT_DESIGN Workflow 3a)
+// ---------------------------------------------------------------------------
+//
+// A 30-minute time trial, run as hard as the athlete can hold:
+//
+//   segment = the 30 minutes with the highest average speed
+//   FTPace  = 95% of the segment's average speed
+//   LTHR    = average HR over the segment's final 20 minutes
+//
+// The first 10 minutes are left out of LTHR because HR is still rising.
+//
+
+/// Length of the time trial effort, in seconds (samples at 1 Hz).
+pub const SEGMENT_S: usize = 30 * 60;
+
+/// The end of the segment LTHR is averaged over, in seconds.
+pub const LTHR_WINDOW_S: usize = 20 * 60;
+
+/// FTPace as a share of the time trial's average speed.
+const FTPACE_FRACTION: f64 = 0.95;
+
+/// Least share of the LTHR window that must carry HR for an LTHR estimate.
+const MIN_HR_COVERAGE: f64 = 0.8;
+
+/// Thresholds measured by a time trial.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimeTrialThresholds {
+    /// Seconds from the start of the activity to the start of the segment.
+    pub segment_start_s: u64,
+    pub segment_avg_speed_m_per_s: f64,
+    pub ftpace_m_per_s: f64,
+    /// `None` when the final 20 minutes carry too little HR.
+    pub lthr: Option<i64>,
+}
+
+/// Derive FTPace and LTHR from a time trial's per-second records. `None` if
+/// the activity is shorter than the 30-minute effort or never moves.
+pub fn derive_thresholds(records: &[FitRecord]) -> Option<TimeTrialThresholds> {
+    if records.len() < SEGMENT_S {
+        return None;
+    }
+
+    let speeds: Vec<f64> = records
+        .iter()
+        .map(|r| r.speed_m_per_s.unwrap_or(0.0))
+        .collect();
+    let (start, avg_speed) = rolling_average(&speeds, SEGMENT_S)
+        .into_iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(&b.1))?;
+    if avg_speed <= 0.0 {
+        return None;
+    }
+
+    let end = start + SEGMENT_S;
+    let heart_rates: Vec<f64> = records[end - LTHR_WINDOW_S..end]
+        .iter()
+        .filter_map(|r| r.heart_rate)
+        .filter(|&hr| hr > 0)
+        .map(f64::from)
+        .collect();
+    let lthr = (heart_rates.len() as f64 >= LTHR_WINDOW_S as f64 * MIN_HR_COVERAGE)
+        .then(|| (heart_rates.iter().sum::<f64>() / heart_rates.len() as f64).round() as i64);
+
+    Some(TimeTrialThresholds {
+        segment_start_s: records[start].timestamp_ms / 1000,
+        segment_avg_speed_m_per_s: avg_speed,
+        ftpace_m_per_s: avg_speed * FTPACE_FRACTION,
+        lthr,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Scheduling
+// ---------------------------------------------------------------------------
+//
+// A time trial goes in a mesocycle's recovery week, where the athlete is
+// fresh, and at most once every four weeks. It takes the place of the
+// week's last easy run that has no intensity session on either side, as
+// long as the week stays within 3 intensity sessions.
+//
+
+/// Fewest days between two time trials.
+pub const MIN_DAYS_BETWEEN: i64 = 28;
+
+/// Most intensity sessions a week may have, the time trial included.
+const MAX_INTENSITY_SESSIONS: usize = 3;
+
+/// Where a time trial was scheduled and the workout it replaced.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeTrialPlacement {
+    pub week_number: u32,
+    pub date: String,
+    pub from: WorkoutType,
+}
+
+impl fmt::Display for TimeTrialPlacement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Changed from {} to a 30-minute time trial: you're fresh in the recovery week, \
+             and the result shows whether your FTPace and LTHR need updating.",
+            self.from.display_name()
+        )
+    }
+}
+
+/// Pick the day for a time trial in the last recovery week of `weeks`.
+/// `None` if the plan already has one, the last time trial is too recent,
+/// or no day fits.
+pub fn schedule_time_trial(
+    weeks: &[WeekPlan],
+    last_time_trial: Option<NaiveDate>,
+) -> Option<TimeTrialPlacement> {
+    if weeks
+        .iter()
+        .flat_map(|w| &w.days)
+        .any(|d| d.workout_type == WorkoutType::TimeTrial)
+    {
+        return None;
+    }
+    let week = weeks
+        .iter()
+        .rev()
+        .find(|w| w.week_type == WeekType::Recovery)?;
+    let intensity = week
+        .days
+        .iter()
+        .filter(|d| d.workout_type.is_intensity())
+        .count();
+    if intensity >= MAX_INTENSITY_SESSIONS {
+        return None;
+    }
+
+    let is_intensity = |i: Option<usize>| {
+        i.and_then(|i| week.days.get(i))
+            .is_some_and(|d| d.workout_type.is_intensity())
+    };
+    (0..week.days.len()).rev().find_map(|i| {
+        let day = &week.days[i];
+        let replaceable = matches!(
+            day.workout_type,
+            WorkoutType::EasyRun
+                | WorkoutType::AerobicDevelopment
+                | WorkoutType::ModerateRun
+                | WorkoutType::SteadyRun
+        );
+        if !replaceable || is_intensity(i.checked_sub(1)) || is_intensity(Some(i + 1)) {
+            return None;
+        }
+        let date = NaiveDate::parse_from_str(&day.date, "%Y-%m-%d").ok()?;
+        if last_time_trial.is_some_and(|last| (date - last).num_days() < MIN_DAYS_BETWEEN) {
+            return None;
+        }
+        Some(TimeTrialPlacement {
+            week_number: week.week_number,
+            date: day.date.clone(),
+            from: day.workout_type,
+        })
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::validation::PlannedDay;
+    use chrono::DateTime;
+
+    /// A 1 Hz stream of (speed, hr) samples.
+    fn stream(samples: &[(f64, Option<u16>)]) -> Vec<FitRecord> {
+        samples
+            .iter()
+            .enumerate()
+            .map(|(i, &(speed, hr))| FitRecord {
+                timestamp: DateTime::from_timestamp(1_700_000_000 + i as i64, 0).unwrap(),
+                timestamp_ms: i as u64 * 1000,
+                heart_rate: hr,
+                speed_m_per_s: Some(speed),
+                distance_m: None,
+                altitude_m: None,
+                cadence: None,
+                power_watts: None,
+                latitude: None,
+                longitude: None,
+                temperature_c: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn best_thirty_minutes_set_ftpace_and_lthr() {
+        // 15 min warmup, 10 min at 4.4 m/s with HR climbing, 20 min at
+        // 4.4 m/s at HR 172, 10 min cooldown
+        let mut samples = vec![(3.0, Some(140)); 900];
+        samples.extend(vec![(4.4, Some(160)); 600]);
+        samples.extend(vec![(4.4, Some(172)); 1200]);
+        samples.extend(vec![(2.8, Some(150)); 600]);
+        let tt = derive_thresholds(&stream(&samples)).expect("a 30-minute effort");
+
+        assert_eq!(tt.segment_start_s, 900);
+        assert!((tt.segment_avg_speed_m_per_s - 4.4).abs() < 1e-9);
+        assert!((tt.ftpace_m_per_s - 4.18).abs() < 1e-9);
+        assert_eq!(tt.lthr, Some(172));
+    }
+
+    #[test]
+    fn lthr_needs_hr_over_the_final_twenty_minutes() {
+        let mut samples = vec![(4.0, Some(170)); 600];
+        samples.extend(vec![(4.0, None); 1200]);
+        let tt = derive_thresholds(&stream(&samples)).expect("a 30-minute effort");
+
+        assert!((tt.ftpace_m_per_s - 3.8).abs() < 1e-9);
+        assert_eq!(tt.lthr, None);
+    }
+
+    #[test]
+    fn shorter_than_thirty_minutes_derives_nothing() {
+        assert_eq!(
+            derive_thresholds(&stream(&vec![(4.0, Some(170)); 1799])),
+            None
+        );
+        assert_eq!(derive_thresholds(&stream(&vec![(0.0, None); 1800])), None);
+    }
+
+    fn week(week_number: u32, week_type: WeekType, days: &[(&str, WorkoutType)]) -> WeekPlan {
+        WeekPlan {
+            week_number,
+            week_type,
+            target_volume_km: 30.0,
+            target_weekly_tss: 200.0,
+            days: days
+                .iter()
+                .map(|&(date, workout_type)| PlannedDay {
+                    date: date.to_string(),
+                    workout_type,
+                    duration_category: None,
+                    expected_tss: 40.0,
+                    target_distance_km: None,
+                })
+                .collect(),
+        }
+    }
+
+    fn recovery_week() -> WeekPlan {
+        week(
+            4,
+            WeekType::Recovery,
+            &[
+                ("2026-03-23", WorkoutType::Rest),
+                ("2026-03-24", WorkoutType::EasyRun),
+                ("2026-03-25", WorkoutType::TempoRun),
+                ("2026-03-26", WorkoutType::EasyRun),
+                ("2026-03-27", WorkoutType::EasyRun),
+                ("2026-03-28", WorkoutType::Rest),
+                ("2026-03-29", WorkoutType::LongRun),
+            ],
+        )
+    }
+
+    #[test]
+    fn time_trial_replaces_last_easy_run_away_from_intensity() {
+        let load = week(3, WeekType::Load, &[("2026-03-16", WorkoutType::EasyRun)]);
+        let placement = schedule_time_trial(&[load, recovery_week()], None);
+
+        assert_eq!(
+            placement,
+            Some(TimeTrialPlacement {
+                week_number: 4,
+                date: "2026-03-27".to_string(),
+                from: WorkoutType::EasyRun,
+            })
+        );
+        assert!(
+            placement
+                .unwrap()
+                .to_string()
+                .starts_with("Changed from Easy Run to a 30-minute time trial")
+        );
+    }
+
+    #[test]
+    fn no_time_trial_when_recent_planned_or_no_recovery_week() {
+        let recent = NaiveDate::from_ymd_opt(2026, 3, 1);
+        assert_eq!(schedule_time_trial(&[recovery_week()], recent), None);
+        let long_ago = NaiveDate::from_ymd_opt(2026, 2, 27);
+        assert!(schedule_time_trial(&[recovery_week()], long_ago).is_some());
+
+        let mut planned = recovery_week();
+        planned.days[1].workout_type = WorkoutType::TimeTrial;
+        assert_eq!(schedule_time_trial(&[planned], None), None);
+
+        let load = week(3, WeekType::Load, &[("2026-03-16", WorkoutType::EasyRun)]);
+        assert_eq!(schedule_time_trial(&[load], None), None);
+    }
+}
@@ -31,6 +31,45 @@ impl ExperienceLevel {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Threshold Source
+// ---------------------------------------------------------------------------
+
+/// Where an LTHR or FTPace value in the threshold history came from.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ThresholdSource {
+    Race,
+    TimeTrial,
+    Estimate,
+    WorkoutDerived,
+    Manual,
+}
+
+impl ThresholdSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Race => "race",
+            Self::TimeTrial => "time_trial",
+            Self::Estimate => "estimate",
+            Self::WorkoutDerived => "workout_derived",
+            Self::Manual => "manual",
+        }
+    }
+
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "race" => Some(Self::Race),
+            "time_trial" => Some(Self::TimeTrial),
+            "estimate" => Some(Self::Estimate),
+            "workout_derived" => Some(Self::WorkoutDerived),
+            "manual" => Some(Self::Manual),
+            _ => None,
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Heart-Rate Zone (7-zone model)
 // ---------------------------------------------------------------------------
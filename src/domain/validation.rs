@@ -9,6 +9,7 @@ pub struct PlannedDay {
     pub workout_type: WorkoutType,
     pub duration_category: Option<crate::domain::workouts::DurationCategory>,
     pub expected_tss: f64,
+    pub target_distance_km: Option<f64>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -26,28 +27,97 @@ pub struct WeekPlan {
     pub days: Vec<PlannedDay>,
 }
 
+#[derive(Debug, Clone)]
 pub struct ValidationContext {
     pub athlete_ctl: f64,
+    /// Volume of the last load week before the week being validated.
     pub previous_week_volume_km: Option<f64>,
 }
 
-#[derive(Debug)]
+impl ValidationContext {
+    /// The context for the week after `week`. A load week becomes the volume
+    /// the next week is compared with; a recovery week is skipped, so the
+    /// load week after it is compared with the last load week.
+    pub fn after(&self, week: &WeekPlan) -> Self {
+        let previous_week_volume_km = match week.week_type {
+            WeekType::Load => Some(week.target_volume_km),
+            WeekType::Recovery => self.previous_week_volume_km,
+        };
+        Self { athlete_ctl: self.athlete_ctl, previous_week_volume_km }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
 pub enum ValidationError {
-    TooManyIntensitySessions { count: usize },
+    #[error("{count} intensity sessions ({}), at most 3 allowed", dates.join(", "))]
+    TooManyIntensitySessions { count: usize, dates: Vec<String> },
+    #[error("no rest or recovery day")]
     NoRestDay,
-    TooManyLongRuns { count: usize },
+    #[error("{count} long runs ({}), at most 1 allowed", dates.join(", "))]
+    TooManyLongRuns { count: usize, dates: Vec<String> },
+    #[error("more than one workout on {date}")]
     DuplicateDate { date: String },
-    VolumeIncreaseTooHigh { increase_pct: f64 },
+    #[error("target volume {to_km:.1} km is {increase_pct:.0}% above the previous week's {from_km:.1} km, at most 10% allowed")]
+    VolumeIncreaseTooHigh { increase_pct: f64, from_km: f64, to_km: f64 },
+    #[error("weekly TSS {tss:.0} is outside {min:.0}-{max:.0} for the athlete's CTL")]
     WeeklyTssOutOfRange { tss: f64, min: f64, max: f64 },
+    #[error("workout distances add up to {planned_km:.1} km but the target volume is {target_km:.1} km, at most 10% apart allowed")]
+    DistanceMismatch { planned_km: f64, target_km: f64 },
+}
+
+impl ValidationError {
+    /// Severe errors make a plan unsafe to persist. The rest are soft: worth
+    /// repairing, but a plan that still has them can be kept.
+    pub fn is_severe(&self) -> bool {
+        matches!(self, Self::TooManyIntensitySessions { .. } | Self::NoRestDay)
+    }
+}
+
+/// A validation error together with the week it was found in.
+#[derive(Debug)]
+pub struct WeekValidationError {
+    pub week_number: u32,
+    /// First and last date of the week's days, if it has any.
+    pub dates: Option<(String, String)>,
+    pub error: ValidationError,
+}
+
+impl std::fmt::Display for WeekValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.dates {
+            Some((first, last)) => write!(f, "Week {} ({} to {}): {}", self.week_number, first, last, self.error),
+            None => write!(f, "Week {}: {}", self.week_number, self.error),
+        }
+    }
+}
+
+/// Validate every week of a plan in order. `ctx` holds the volume before the
+/// first week; each later week is compared with the load week before it.
+pub fn validate_plan_weeks(weeks: &[WeekPlan], ctx: &ValidationContext) -> Vec<WeekValidationError> {
+    let mut errors = Vec::new();
+    let mut ctx = ctx.clone();
+    for week in weeks {
+        let dates = week.days.iter().map(|d| &d.date).min()
+            .zip(week.days.iter().map(|d| &d.date).max())
+            .map(|(first, last)| (first.clone(), last.clone()));
+        for error in validate_week_plan(week, &ctx) {
+            errors.push(WeekValidationError { week_number: week.week_number, dates: dates.clone(), error });
+        }
+        ctx = ctx.after(week);
+    }
+    errors
 }
 
 pub fn validate_week_plan(week: &WeekPlan, ctx: &ValidationContext) -> Vec<ValidationError> {
     let mut errors = Vec::new();
 
     // Max 3 intensity sessions
-    let intensity_count = week.days.iter().filter(|d| d.workout_type.is_intensity()).count();
-    if intensity_count > 3 {
-        errors.push(ValidationError::TooManyIntensitySessions { count: intensity_count });
+    let intensity_dates: Vec<String> = week.days.iter()
+        .filter(|d| d.workout_type.is_intensity())
+        .map(|d| d.date.clone())
+        .collect();
+    if intensity_dates.len() > 3 {
+        errors.push(ValidationError::TooManyIntensitySessions { count: intensity_dates.len(), dates: intensity_dates });
     }
 
     // At least 1 rest or recovery day
@@ -59,11 +129,11 @@ pub fn validate_week_plan(week: &WeekPlan, ctx: &ValidationContext) -> Vec<Valid
     }
 
     // Max 1 long run (LongRun, LongRunProgression, or LongRunModerate)
-    let long_run_count = week.days.iter().filter(|d| {
+    let long_run_dates: Vec<String> = week.days.iter().filter(|d| {
         matches!(d.workout_type, WorkoutType::LongRun | WorkoutType::LongRunProgression | WorkoutType::LongRunModerate)
-    }).count();
-    if long_run_count > 1 {
-        errors.push(ValidationError::TooManyLongRuns { count: long_run_count });
+    }).map(|d| d.date.clone()).collect();
+    if long_run_dates.len() > 1 {
+        errors.push(ValidationError::TooManyLongRuns { count: long_run_dates.len(), dates: long_run_dates });
     }
 
     // No duplicate dates
@@ -80,12 +150,28 @@ pub fn validate_week_plan(week: &WeekPlan, ctx: &ValidationContext) -> Vec<Valid
             if prev_vol > 0.0 {
                 let increase_pct = (week.target_volume_km - prev_vol) / prev_vol * 100.0;
                 if increase_pct > 10.0 {
-                    errors.push(ValidationError::VolumeIncreaseTooHigh { increase_pct });
+                    errors.push(ValidationError::VolumeIncreaseTooHigh {
+                        increase_pct,
+                        from_km: prev_vol,
+                        to_km: week.target_volume_km,
+                    });
                 }
             }
         }
     }
 
+    // Workout distances add up to the target volume (within 10%). Only
+    // checked when every non-rest day has a distance; otherwise the missing
+    // days would always make the sum fall short.
+    let workout_days: Vec<&PlannedDay> = week.days.iter().filter(|d| d.workout_type != WorkoutType::Rest).collect();
+    let all_have_distance = !workout_days.is_empty() && workout_days.iter().all(|d| d.target_distance_km.is_some());
+    if all_have_distance && week.target_volume_km > 0.0 {
+        let planned_km: f64 = workout_days.iter().filter_map(|d| d.target_distance_km).sum();
+        if (planned_km - week.target_volume_km).abs() > week.target_volume_km * 0.1 {
+            errors.push(ValidationError::DistanceMismatch { planned_km, target_km: week.target_volume_km });
+        }
+    }
+
     // Weekly TSS range: 0.5x - 2.0x of CTL*7
     if ctx.athlete_ctl > 0.0 {
         let min_tss = ctx.athlete_ctl * 7.0 * 0.5;
@@ -108,11 +194,11 @@ mod tests {
     use crate::domain::workouts::{WorkoutType, DurationCategory};
 
     fn day(date: &str, wt: WorkoutType, cat: DurationCategory) -> PlannedDay {
-        PlannedDay { date: date.to_string(), workout_type: wt, duration_category: Some(cat), expected_tss: 50.0 }
+        PlannedDay { date: date.to_string(), workout_type: wt, duration_category: Some(cat), expected_tss: 50.0, target_distance_km: None }
     }
 
     fn rest_day(date: &str) -> PlannedDay {
-        PlannedDay { date: date.to_string(), workout_type: WorkoutType::Rest, duration_category: None, expected_tss: 0.0 }
+        PlannedDay { date: date.to_string(), workout_type: WorkoutType::Rest, duration_category: None, expected_tss: 0.0, target_distance_km: None }
     }
 
     #[test]
@@ -246,4 +332,91 @@ mod tests {
         let errors = validate_week_plan(&week, &ctx);
         assert!(errors.iter().any(|e| matches!(e, ValidationError::VolumeIncreaseTooHigh { .. })));
     }
+
+    #[test]
+    fn plan_errors_name_the_week_and_dates() {
+        let weeks = vec![WeekPlan {
+            week_number: 3,
+            week_type: WeekType::Load,
+            target_volume_km: 50.0,
+            target_weekly_tss: 350.0,
+            days: vec![
+                day("2026-03-16", WorkoutType::Vo2maxIntervals, DurationCategory::Short),
+                day("2026-03-17", WorkoutType::Track400m, DurationCategory::Short),
+                day("2026-03-18", WorkoutType::EasyRun, DurationCategory::Short),
+                day("2026-03-19", WorkoutType::TempoRun, DurationCategory::Short),
+                day("2026-03-20", WorkoutType::AnaerobicHills, DurationCategory::Short),
+                day("2026-03-21", WorkoutType::EasyRun, DurationCategory::Medium),
+                day("2026-03-22", WorkoutType::LongRun, DurationCategory::Medium),
+            ],
+        }];
+        let ctx = ValidationContext { athlete_ctl: 40.0, previous_week_volume_km: Some(40.0) };
+        let errors = validate_plan_weeks(&weeks, &ctx);
+        let messages: Vec<String> = errors.iter().map(ToString::to_string).collect();
+
+        assert_eq!(messages, vec![
+            "Week 3 (2026-03-16 to 2026-03-22): 4 intensity sessions (2026-03-16, 2026-03-17, 2026-03-19, 2026-03-20), at most 3 allowed",
+            "Week 3 (2026-03-16 to 2026-03-22): no rest or recovery day",
+            "Week 3 (2026-03-16 to 2026-03-22): target volume 50.0 km is 25% above the previous week's 40.0 km, at most 10% allowed",
+        ]);
+        assert!(errors[0].error.is_severe() && errors[1].error.is_severe());
+        assert!(!errors[2].error.is_severe());
+    }
+
+    #[test]
+    fn volume_chains_across_weeks_skipping_recovery() {
+        let load_week = |week_number, target_volume_km| WeekPlan {
+            week_number,
+            week_type: WeekType::Load,
+            target_volume_km,
+            target_weekly_tss: 250.0,
+            days: vec![rest_day("2026-03-02")],
+        };
+        let weeks = vec![
+            load_week(1, 42.0),
+            WeekPlan { week_type: WeekType::Recovery, ..load_week(2, 25.0) },
+            load_week(3, 46.0),
+            load_week(4, 55.0),
+        ];
+        let ctx = ValidationContext { athlete_ctl: 40.0, previous_week_volume_km: Some(40.0) };
+        let errors = validate_plan_weeks(&weeks, &ctx);
+
+        // Week 3 is compared with week 1, not the recovery week
+        let volume_errors: Vec<(u32, f64)> = errors.iter().filter_map(|e| match e.error {
+            ValidationError::VolumeIncreaseTooHigh { from_km, .. } => Some((e.week_number, from_km)),
+            _ => None,
+        }).collect();
+        assert_eq!(volume_errors, vec![(4, 46.0)]);
+    }
+
+    #[test]
+    fn distances_must_match_target_volume() {
+        let with_distance = |date: &str, wt, km| PlannedDay { target_distance_km: Some(km), ..day(date, wt, DurationCategory::Medium) };
+        let mut week = WeekPlan {
+            week_number: 1,
+            week_type: WeekType::Load,
+            target_volume_km: 40.0,
+            target_weekly_tss: 250.0,
+            days: vec![
+                with_distance("2026-03-02", WorkoutType::EasyRun, 10.0),
+                rest_day("2026-03-03"),
+                with_distance("2026-03-04", WorkoutType::TempoRun, 10.0),
+                with_distance("2026-03-08", WorkoutType::LongRun, 18.0),
+            ],
+        };
+        let ctx = ValidationContext { athlete_ctl: 40.0, previous_week_volume_km: None };
+        assert!(validate_week_plan(&week, &ctx).is_empty());
+
+        week.days[3].target_distance_km = Some(28.0);
+        let errors = validate_week_plan(&week, &ctx);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].to_string(),
+            "workout distances add up to 48.0 km but the target volume is 40.0 km, at most 10% apart allowed"
+        );
+
+        // A workout day without a distance leaves the sum unchecked
+        week.days.push(day("2026-03-06", WorkoutType::EasyRun, DurationCategory::Short));
+        assert!(validate_week_plan(&week, &ctx).is_empty());
+    }
 }
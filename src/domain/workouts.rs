@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 
+use crate::domain::zones::format_pace_range;
+
 // ---------------------------------------------------------------------------
 // WorkoutType enum
 // ---------------------------------------------------------------------------
@@ -1216,13 +1218,14 @@ impl WorkoutRegistry {
     }
 
     /// Resolve a workout type + duration category into a description with the
-    /// athlete's actual zone values filled in.
+    /// athlete's actual zone values filled in. The description goes stale when
+    /// the zones change, so upcoming workouts are resolved again then.
     pub fn resolve(
         &self,
         workout_type: &WorkoutType,
         duration_category: &DurationCategory,
         hr_zones: &crate::domain::types::HrZones,
-        _pace_zones: Option<&crate::domain::types::PaceZones>,
+        pace_zones: Option<&crate::domain::types::PaceZones>,
     ) -> Option<ResolvedWorkout> {
         let template = self.templates.get(workout_type)?;
         let params = template.durations.get(duration_category)?;
@@ -1239,12 +1242,31 @@ impl WorkoutRegistry {
             }
         }).collect();
 
+        // Build pace zone string, when the athlete has an FTPace
+        let pace_zone_str: Vec<String> = pace_zones.map(|pz| template.target_pace_zones.iter()
+            .filter_map(|z| pz.zones.iter().find(|zone| zone.zone == *z))
+            .map(|zone| format!("Z{} ({})", zone.zone, format_pace_range(zone)))
+            .collect()).unwrap_or_default();
+
+        let mut targets = Vec::new();
+        if !hr_zone_str.is_empty() {
+            targets.push(format!("HR {}", hr_zone_str.join(", ")));
+        }
+        if !pace_zone_str.is_empty() {
+            targets.push(format!("pace {}", pace_zone_str.join(", ")));
+        }
+        let description = if targets.is_empty() {
+            template.description.to_string()
+        } else {
+            format!("{}. Target {}", template.description, targets.join("; "))
+        };
+
         Some(ResolvedWorkout {
             workout_type: *workout_type,
             duration_category: *duration_category,
             duration_min: params.total_duration_min,
             structure: params.structure.to_string(),
-            description: template.description.to_string(),
+            description,
             target_hr_zones: template.target_hr_zones.clone(),
             target_pace_zones: template.target_pace_zones.clone(),
             hr_zone_display: hr_zone_str.join(", "),
@@ -1332,6 +1354,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn resolved_description_names_the_athletes_zones() {
+        let registry = WorkoutRegistry::new();
+        let hr_zones = crate::domain::zones::calculate_hr_zones(170);
+        let resolved = registry.resolve(&WorkoutType::RecoveryRun, &DurationCategory::Short, &hr_zones, None).unwrap();
+        assert_eq!(resolved.description, "Very easy recovery effort. Target HR Z1 (0-138 bpm)");
+
+        let pace_zones = crate::domain::zones::calculate_pace_zones(4.0);
+        let resolved = registry.resolve(&WorkoutType::RecoveryRun, &DurationCategory::Short, &hr_zones, Some(&pace_zones)).unwrap();
+        assert_eq!(resolved.description, "Very easy recovery effort. Target HR Z1 (0-138 bpm); pace Z1 (slower than 5:34/km)");
+    }
+
     #[test]
     fn rest_and_strength_types_exist() {
         assert!(WorkoutType::from_str("rest").is_some());
@@ -166,6 +166,33 @@ pub fn calculate_pace_zones(ftpace_m_per_s: f64) -> PaceZones {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Display
+// ---------------------------------------------------------------------------
+
+/// Format a speed in m/s as a min/km pace string, e.g. "4:45/km".
+pub fn format_pace(m_per_s: f64) -> String {
+    let min_per_km = 1000.0 / m_per_s / 60.0;
+    let mins = min_per_km as u32;
+    let secs = ((min_per_km - mins as f64) * 60.0) as u32;
+    format!("{}:{:02}/km", mins, secs)
+}
+
+/// A pace zone's range from its slow to its fast end, e.g.
+/// "5:33/km to 4:54/km". Open-ended zones read "slower than" / "faster than".
+pub fn format_pace_range(zone: &PaceZone) -> String {
+    match (zone.min_pace_m_per_s > 0.0, zone.max_pace_m_per_s) {
+        (true, Some(max)) => format!(
+            "{} to {}",
+            format_pace(zone.min_pace_m_per_s),
+            format_pace(max)
+        ),
+        (true, None) => format!("faster than {}", format_pace(zone.min_pace_m_per_s)),
+        (false, Some(max)) => format!("slower than {}", format_pace(max)),
+        (false, None) => "any pace".to_string(),
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
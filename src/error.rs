@@ -16,6 +16,11 @@ pub enum AppError {
     #[error("Conflict: {0}")]
     Conflict(String),
 
+    /// The request was valid but no acceptable result could be produced for
+    /// it, e.g. Claude's answers kept failing validation.
+    #[error("Unprocessable: {0}")]
+    Unprocessable(String),
+
     #[error("Database error: {0}")]
     Database(#[from] sqlx::Error),
 
@@ -23,13 +28,16 @@ pub enum AppError {
     Internal(String),
 }
 
-impl IntoResponse for AppError {
-    fn into_response(self) -> Response {
-        let (status, message) = match &self {
+impl AppError {
+    /// The HTTP status and the message safe to show the client. Database and
+    /// internal errors are logged here and replaced by a generic message.
+    pub fn status_and_message(&self) -> (StatusCode, String) {
+        match self {
             AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg.clone()),
             AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
             AppError::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized".to_string()),
             AppError::Conflict(msg) => (StatusCode::CONFLICT, msg.clone()),
+            AppError::Unprocessable(msg) => (StatusCode::UNPROCESSABLE_ENTITY, msg.clone()),
             AppError::Database(e) => {
                 tracing::error!("Database error: {e}");
                 (
@@ -44,8 +52,13 @@ impl IntoResponse for AppError {
                     "Internal server error".to_string(),
                 )
             }
-        };
+        }
+    }
+}
 
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let (status, message) = self.status_and_message();
         let body = axum::Json(json!({ "error": message }));
         (status, body).into_response()
     }
@@ -101,6 +114,14 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_unprocessable_status() {
+        assert_eq!(
+            status_of(AppError::Unprocessable("no valid result".into())).await,
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+    }
+
     #[tokio::test]
     async fn test_internal_status() {
         assert_eq!(
@@ -0,0 +1,355 @@
+pub mod parser;
+pub mod records;
+pub mod types;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+pub use records::{FitEvent, FitLap, FitRecord, FitSession};
+
+use types::{MESG_EVENT, MESG_LAP, MESG_RECORD, MESG_SESSION};
+
+// ---------------------------------------------------------------------------
+// Errors
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, thiserror::Error)]
+pub enum FitError {
+    #[error("Invalid FIT header: {0}")]
+    InvalidHeader(String),
+
+    #[error("FIT file truncated: expected {expected} bytes, got {actual}")]
+    Truncated { expected: usize, actual: usize },
+
+    #[error("FIT CRC mismatch: expected {expected:#06x}, computed {actual:#06x}")]
+    CrcMismatch { expected: u16, actual: u16 },
+
+    #[error("Data message references undefined local message type {0}")]
+    MissingDefinition(u8),
+
+    #[error("Malformed FIT data: {0}")]
+    Malformed(String),
+}
+
+// ---------------------------------------------------------------------------
+// Activity
+// ---------------------------------------------------------------------------
+
+/// Everything we extract from an activity FIT file.
+#[derive(Debug, Clone, Serialize)]
+pub struct FitActivity {
+    /// Time-series samples in file order, as recorded by the device.
+    pub records: Vec<FitRecord>,
+    pub sessions: Vec<FitSession>,
+    pub laps: Vec<FitLap>,
+    pub events: Vec<FitEvent>,
+}
+
+impl FitActivity {
+    /// Activity start: the first session's start time, else the first record.
+    pub fn start_time(&self) -> Option<DateTime<Utc>> {
+        self.sessions
+            .first()
+            .map(|s| s.start_time)
+            .or_else(|| self.records.first().map(|r| r.timestamp))
+    }
+
+    /// Records resampled onto a 1 Hz grid (see `records::resample_per_second`).
+    pub fn per_second_records(&self) -> Vec<FitRecord> {
+        records::resample_per_second(&self.records)
+    }
+}
+
+/// Parse an activity FIT file into typed records, sessions, laps and events.
+///
+/// The header and file CRCs are verified; messages we don't model (file_id,
+/// device_info, ...) are decoded and then ignored.
+pub fn parse_fit(bytes: &[u8]) -> Result<FitActivity, FitError> {
+    let (_, messages) = parser::parse_messages(bytes)?;
+
+    let mut activity = FitActivity {
+        records: Vec::new(),
+        sessions: Vec::new(),
+        laps: Vec::new(),
+        events: Vec::new(),
+    };
+
+    for msg in &messages {
+        match msg.global_message_number {
+            MESG_RECORD => activity.records.extend(FitRecord::from_message(msg)),
+            MESG_SESSION => activity.sessions.extend(FitSession::from_message(msg)),
+            MESG_LAP => activity.laps.extend(FitLap::from_message(msg)),
+            MESG_EVENT => activity.events.extend(FitEvent::from_message(msg)),
+            _ => {}
+        }
+    }
+
+    if let Some(first) = activity.records.first().map(|r| r.timestamp) {
+        for record in &mut activity.records {
+            record.timestamp_ms = (record.timestamp - first).num_milliseconds().max(0) as u64;
+        }
+    }
+
+    Ok(activity)
+}
+
+// ---------------------------------------------------------------------------
+// Test support
+// ---------------------------------------------------------------------------
+
+/// Hand-assembles FIT byte streams for tests.
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::parser::crc16;
+
+    /// A field definition triple: (field number, size, base type).
+    /// For developer fields the third byte is the developer data index.
+    pub fn field(number: u8, size: u8, base_type: u8) -> (u8, u8, u8) {
+        (number, size, base_type)
+    }
+
+    pub struct FitBuilder {
+        short_header: bool,
+        data: Vec<u8>,
+    }
+
+    impl FitBuilder {
+        pub fn new() -> Self {
+            Self {
+                short_header: false,
+                data: Vec::new(),
+            }
+        }
+
+        /// Use the legacy 12-byte header (no header CRC).
+        pub fn short_header(mut self) -> Self {
+            self.short_header = true;
+            self
+        }
+
+        fn push_definition(
+            mut self,
+            local: u8,
+            global: u16,
+            big_endian: bool,
+            fields: &[(u8, u8, u8)],
+            dev_fields: Option<&[(u8, u8, u8)]>,
+        ) -> Self {
+            let dev_flag = if dev_fields.is_some() { 0x20 } else { 0 };
+            self.data.push(0x40 | dev_flag | (local & 0x0F));
+            self.data.push(0);
+            self.data.push(big_endian as u8);
+            if big_endian {
+                self.data.extend_from_slice(&global.to_be_bytes());
+            } else {
+                self.data.extend_from_slice(&global.to_le_bytes());
+            }
+            self.data.push(fields.len() as u8);
+            for &(n, s, t) in fields {
+                self.data.extend_from_slice(&[n, s, t]);
+            }
+            if let Some(dev) = dev_fields {
+                self.data.push(dev.len() as u8);
+                for &(n, s, i) in dev {
+                    self.data.extend_from_slice(&[n, s, i]);
+                }
+            }
+            self
+        }
+
+        pub fn definition(self, local: u8, global: u16, fields: &[(u8, u8, u8)]) -> Self {
+            self.push_definition(local, global, false, fields, None)
+        }
+
+        pub fn big_endian_definition(self, local: u8, global: u16, fields: &[(u8, u8, u8)]) -> Self {
+            self.push_definition(local, global, true, fields, None)
+        }
+
+        pub fn developer_definition(
+            self,
+            local: u8,
+            global: u16,
+            fields: &[(u8, u8, u8)],
+            dev_fields: &[(u8, u8, u8)],
+        ) -> Self {
+            self.push_definition(local, global, false, fields, Some(dev_fields))
+        }
+
+        /// Normal data message with the given raw field bytes.
+        pub fn data(mut self, local: u8, bytes: &[u8]) -> Self {
+            self.data.push(local & 0x0F);
+            self.data.extend_from_slice(bytes);
+            self
+        }
+
+        /// Compressed-timestamp data message (local types 0-3 only).
+        pub fn compressed(mut self, local: u8, offset: u8, bytes: &[u8]) -> Self {
+            self.data.push(0x80 | ((local & 0x03) << 5) | (offset & 0x1F));
+            self.data.extend_from_slice(bytes);
+            self
+        }
+
+        pub fn build(self) -> Vec<u8> {
+            let header_size: u8 = if self.short_header { 12 } else { 14 };
+            let mut out = vec![header_size, 0x20];
+            out.extend_from_slice(&2132u16.to_le_bytes());
+            out.extend_from_slice(&(self.data.len() as u32).to_le_bytes());
+            out.extend_from_slice(b".FIT");
+            if !self.short_header {
+                let crc = crc16(&out);
+                out.extend_from_slice(&crc.to_le_bytes());
+            }
+            out.extend_from_slice(&self.data);
+            let crc = crc16(&out);
+            out.extend_from_slice(&crc.to_le_bytes());
+            out
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::{FitBuilder, field};
+    use super::types::FIT_EPOCH_OFFSET_S;
+    use super::*;
+
+    /// 2024-03-10T07:00:00Z as a FIT timestamp.
+    const START: u32 = (1_710_054_000 - FIT_EPOCH_OFFSET_S) as u32;
+
+    fn record_bytes(ts: Option<u32>, hr: u8, speed_mm_s: u16, dist_cm: u32) -> Vec<u8> {
+        let mut b = Vec::new();
+        if let Some(ts) = ts {
+            b.extend_from_slice(&ts.to_le_bytes());
+        }
+        b.push(hr);
+        b.extend_from_slice(&speed_mm_s.to_le_bytes());
+        b.extend_from_slice(&dist_cm.to_le_bytes());
+        b.extend_from_slice(&3000u16.to_le_bytes()); // altitude 100 m
+        b
+    }
+
+    /// A short easy run: file_id, timer start, 5 records (the last 4 with
+    /// compressed timestamps, one 3 s smart-recording gap), a lap and a session.
+    fn easy_run_fit() -> Vec<u8> {
+        let record_fields = [field(3, 1, 0x02), field(6, 2, 0x84), field(5, 4, 0x86), field(2, 2, 0x84)];
+        let mut full = vec![field(253, 4, 0x86)];
+        full.extend_from_slice(&record_fields);
+
+        let mut lap = Vec::new();
+        lap.extend_from_slice(&(START + 6).to_le_bytes());
+        lap.extend_from_slice(&START.to_le_bytes());
+        lap.extend_from_slice(&6000u32.to_le_bytes());
+        lap.extend_from_slice(&1800u32.to_le_bytes());
+        lap.push(148);
+
+        let mut session = lap.clone();
+        session.push(1); // sport: running
+
+        FitBuilder::new()
+            // file_id: type=activity(4), manufacturer=garmin(1)
+            .definition(0, 0, &[field(0, 1, 0x00), field(1, 2, 0x84)])
+            .data(0, &[4, 1, 0])
+            // event: timer start
+            .definition(1, MESG_EVENT, &[field(253, 4, 0x86), field(0, 1, 0x00), field(1, 1, 0x00)])
+            .data(1, &[START.to_le_bytes().as_slice(), &[0, 0]].concat())
+            .definition(2, MESG_RECORD, &full)
+            .data(2, &record_bytes(Some(START), 140, 3000, 0))
+            .definition(3, MESG_RECORD, &record_fields)
+            .compressed(3, (START + 1) as u8, &record_bytes(None, 142, 3000, 300))
+            .compressed(3, (START + 2) as u8, &record_bytes(None, 145, 3100, 610))
+            .compressed(3, (START + 5) as u8, &record_bytes(None, 150, 3200, 1570))
+            .compressed(3, (START + 6) as u8, &record_bytes(None, 151, 3000, 1800))
+            .definition(
+                4,
+                MESG_LAP,
+                &[field(253, 4, 0x86), field(2, 4, 0x86), field(8, 4, 0x86), field(9, 4, 0x86), field(15, 1, 0x02)],
+            )
+            .data(4, &lap)
+            .definition(
+                5,
+                MESG_SESSION,
+                &[
+                    field(253, 4, 0x86),
+                    field(2, 4, 0x86),
+                    field(8, 4, 0x86),
+                    field(9, 4, 0x86),
+                    field(16, 1, 0x02),
+                    field(5, 1, 0x00),
+                ],
+            )
+            .data(5, &session)
+            .build()
+    }
+
+    #[test]
+    fn parses_complete_activity() {
+        let activity = parse_fit(&easy_run_fit()).expect("parse");
+
+        assert_eq!(activity.records.len(), 5);
+        assert_eq!(activity.laps.len(), 1);
+        assert_eq!(activity.sessions.len(), 1);
+        assert_eq!(activity.events.len(), 1);
+
+        let hrs: Vec<_> = activity.records.iter().map(|r| r.heart_rate).collect();
+        assert_eq!(hrs, vec![Some(140), Some(142), Some(145), Some(150), Some(151)]);
+
+        let offsets: Vec<_> = activity.records.iter().map(|r| r.timestamp_ms).collect();
+        assert_eq!(offsets, vec![0, 1000, 2000, 5000, 6000]);
+
+        let last = activity.records.last().unwrap();
+        assert_eq!(last.distance_m, Some(18.0));
+        assert_eq!(last.speed_m_per_s, Some(3.0));
+        assert_eq!(last.altitude_m, Some(100.0));
+        assert_eq!(last.power_watts, None);
+        assert_eq!(last.latitude, None);
+
+        let session = &activity.sessions[0];
+        assert_eq!(session.start_time.to_rfc3339(), "2024-03-10T07:00:00+00:00");
+        assert_eq!(session.total_timer_time_s, Some(6.0));
+        assert_eq!(session.total_distance_m, Some(18.0));
+        assert_eq!(session.avg_heart_rate, Some(148));
+        assert_eq!(session.sport, Some(1));
+
+        assert_eq!(activity.laps[0].avg_heart_rate, Some(148));
+        assert!(!activity.events[0].is_timer_stop());
+        assert_eq!(activity.start_time(), Some(session.start_time));
+    }
+
+    #[test]
+    fn per_second_records_fill_smart_recording_gap() {
+        let activity = parse_fit(&easy_run_fit()).expect("parse");
+        let per_second = activity.per_second_records();
+
+        assert_eq!(per_second.len(), 7);
+        let offsets: Vec<_> = per_second.iter().map(|r| r.timestamp_ms).collect();
+        assert_eq!(offsets, vec![0, 1000, 2000, 3000, 4000, 5000, 6000]);
+        assert_eq!(per_second[3].heart_rate, Some(145));
+    }
+
+    #[test]
+    fn start_time_falls_back_to_first_record() {
+        let bytes = FitBuilder::new()
+            .definition(0, MESG_RECORD, &[field(253, 4, 0x86), field(3, 1, 0x02)])
+            .data(0, &[START.to_le_bytes().as_slice(), &[150]].concat())
+            .build();
+        let activity = parse_fit(&bytes).expect("parse");
+        assert!(activity.sessions.is_empty());
+        assert_eq!(activity.start_time(), Some(activity.records[0].timestamp));
+    }
+
+    #[test]
+    fn corrupted_activity_is_rejected() {
+        let mut bytes = easy_run_fit();
+        bytes[30] ^= 0x01;
+        assert!(matches!(parse_fit(&bytes), Err(FitError::CrcMismatch { .. })));
+    }
+
+    #[test]
+    fn empty_input_is_invalid_header() {
+        assert!(matches!(parse_fit(&[]), Err(FitError::InvalidHeader(_))));
+    }
+}
@@ -0,0 +1,646 @@
+use std::collections::HashMap;
+
+use nom::IResult;
+use nom::bytes::complete::{tag, take};
+use nom::number::Endianness;
+use nom::number::complete::{le_u8, le_u16, le_u32};
+
+use super::FitError;
+use super::types::{
+    BaseType, DataMessage, DeveloperFieldDefinition, DeveloperFieldDescription, DeveloperValue,
+    FieldDefinition, FieldValue, FitHeader, MESG_FIELD_DESCRIPTION, MessageDefinition,
+    TIMESTAMP_FIELD,
+};
+
+type Res<'a, T> = IResult<&'a [u8], T>;
+
+// ---------------------------------------------------------------------------
+// CRC
+// ---------------------------------------------------------------------------
+
+const CRC_TABLE: [u16; 16] = [
+    0x0000, 0xCC01, 0xD801, 0x1400, 0xF001, 0x3C00, 0x2800, 0xE401, 0xA001, 0x6C00, 0x7800,
+    0xB401, 0x5000, 0x9C01, 0x8801, 0x4400,
+];
+
+/// Feed one byte into a FIT CRC-16 (the SDK's nibble-table algorithm).
+pub fn crc16_update(crc: u16, byte: u8) -> u16 {
+    let mut tmp = CRC_TABLE[(crc & 0xF) as usize];
+    let mut crc = (crc >> 4) & 0x0FFF;
+    crc = crc ^ tmp ^ CRC_TABLE[(byte & 0xF) as usize];
+
+    tmp = CRC_TABLE[(crc & 0xF) as usize];
+    crc = (crc >> 4) & 0x0FFF;
+    crc ^ tmp ^ CRC_TABLE[((byte >> 4) & 0xF) as usize]
+}
+
+/// FIT CRC-16 over a byte slice.
+pub fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0, |crc, &b| crc16_update(crc, b))
+}
+
+// ---------------------------------------------------------------------------
+// Header
+// ---------------------------------------------------------------------------
+
+fn header(input: &[u8]) -> Res<'_, FitHeader> {
+    let (input, header_size) = le_u8(input)?;
+    let (input, protocol_version) = le_u8(input)?;
+    let (input, profile_version) = le_u16(input)?;
+    let (input, data_size) = le_u32(input)?;
+    let (input, _) = tag(".FIT")(input)?;
+
+    let (input, crc) = if header_size >= 14 {
+        let (input, crc) = le_u16(input)?;
+        (input, Some(crc))
+    } else {
+        (input, None)
+    };
+
+    Ok((
+        input,
+        FitHeader {
+            header_size,
+            protocol_version,
+            profile_version,
+            data_size,
+            crc,
+        },
+    ))
+}
+
+// ---------------------------------------------------------------------------
+// Definition messages
+// ---------------------------------------------------------------------------
+
+fn field_definition(input: &[u8]) -> Res<'_, (u8, u8, u8)> {
+    let (input, number) = le_u8(input)?;
+    let (input, size) = le_u8(input)?;
+    let (input, base_type) = le_u8(input)?;
+    Ok((input, (number, size, base_type)))
+}
+
+fn definition_message(input: &[u8], has_developer_fields: bool) -> Res<'_, MessageDefinition> {
+    let (input, _reserved) = le_u8(input)?;
+    let (input, architecture) = le_u8(input)?;
+    let big_endian = architecture == 1;
+    let endian = if big_endian { Endianness::Big } else { Endianness::Little };
+    let (input, global_message_number) =
+        nom::number::complete::u16::<_, nom::error::Error<&[u8]>>(endian)(input)?;
+    let (mut input, num_fields) = le_u8(input)?;
+
+    let mut fields = Vec::with_capacity(num_fields as usize);
+    for _ in 0..num_fields {
+        let (rest, (number, size, base_type)) = field_definition(input)?;
+        // Unknown base types are read as raw bytes, as the SDK does.
+        let base_type = BaseType::from_u8(base_type).unwrap_or(BaseType::Byte);
+        fields.push(FieldDefinition {
+            number,
+            size,
+            base_type,
+        });
+        input = rest;
+    }
+
+    let mut developer_fields = Vec::new();
+    if has_developer_fields {
+        let (rest, num_dev_fields) = le_u8(input)?;
+        input = rest;
+        for _ in 0..num_dev_fields {
+            let (rest, (number, size, developer_data_index)) = field_definition(input)?;
+            developer_fields.push(DeveloperFieldDefinition {
+                number,
+                size,
+                developer_data_index,
+            });
+            input = rest;
+        }
+    }
+
+    Ok((
+        input,
+        MessageDefinition {
+            big_endian,
+            global_message_number,
+            fields,
+            developer_fields,
+        },
+    ))
+}
+
+// ---------------------------------------------------------------------------
+// Field values
+// ---------------------------------------------------------------------------
+
+/// Read one element of a numeric base type. Returns the value and whether it
+/// differs from the type's invalid sentinel.
+fn element(input: &[u8], base_type: BaseType, endian: Endianness) -> Res<'_, (FieldValue, bool)> {
+    use nom::number::complete as num;
+    type E<'a> = nom::error::Error<&'a [u8]>;
+
+    match base_type {
+        BaseType::Enum | BaseType::UInt8 | BaseType::Byte | BaseType::String => {
+            let (input, v) = num::u8::<_, E>(input)?;
+            Ok((input, (FieldValue::UInt(v as u64), v != 0xFF)))
+        }
+        BaseType::UInt8z => {
+            let (input, v) = num::u8::<_, E>(input)?;
+            Ok((input, (FieldValue::UInt(v as u64), v != 0)))
+        }
+        BaseType::SInt8 => {
+            let (input, v) = num::i8::<_, E>(input)?;
+            Ok((input, (FieldValue::SInt(v as i64), v != i8::MAX)))
+        }
+        BaseType::UInt16 => {
+            let (input, v) = num::u16::<_, E>(endian)(input)?;
+            Ok((input, (FieldValue::UInt(v as u64), v != u16::MAX)))
+        }
+        BaseType::UInt16z => {
+            let (input, v) = num::u16::<_, E>(endian)(input)?;
+            Ok((input, (FieldValue::UInt(v as u64), v != 0)))
+        }
+        BaseType::SInt16 => {
+            let (input, v) = num::i16::<_, E>(endian)(input)?;
+            Ok((input, (FieldValue::SInt(v as i64), v != i16::MAX)))
+        }
+        BaseType::UInt32 => {
+            let (input, v) = num::u32::<_, E>(endian)(input)?;
+            Ok((input, (FieldValue::UInt(v as u64), v != u32::MAX)))
+        }
+        BaseType::UInt32z => {
+            let (input, v) = num::u32::<_, E>(endian)(input)?;
+            Ok((input, (FieldValue::UInt(v as u64), v != 0)))
+        }
+        BaseType::SInt32 => {
+            let (input, v) = num::i32::<_, E>(endian)(input)?;
+            Ok((input, (FieldValue::SInt(v as i64), v != i32::MAX)))
+        }
+        BaseType::Float32 => {
+            let (input, v) = num::u32::<_, E>(endian)(input)?;
+            let f = f32::from_bits(v);
+            Ok((input, (FieldValue::Float(f as f64), v != u32::MAX && !f.is_nan())))
+        }
+        BaseType::Float64 => {
+            let (input, v) = num::u64::<_, E>(endian)(input)?;
+            let f = f64::from_bits(v);
+            Ok((input, (FieldValue::Float(f), v != u64::MAX && !f.is_nan())))
+        }
+        BaseType::UInt64 => {
+            let (input, v) = num::u64::<_, E>(endian)(input)?;
+            Ok((input, (FieldValue::UInt(v), v != u64::MAX)))
+        }
+        BaseType::UInt64z => {
+            let (input, v) = num::u64::<_, E>(endian)(input)?;
+            Ok((input, (FieldValue::UInt(v), v != 0)))
+        }
+        BaseType::SInt64 => {
+            let (input, v) = num::i64::<_, E>(endian)(input)?;
+            Ok((input, (FieldValue::SInt(v), v != i64::MAX)))
+        }
+    }
+}
+
+/// Read a field of `size` bytes. Returns `None` when the field holds only
+/// invalid sentinels, i.e. the device did not record it for this sample.
+fn field_value(
+    input: &[u8],
+    base_type: BaseType,
+    size: u8,
+    big_endian: bool,
+) -> Res<'_, Option<FieldValue>> {
+    let (rest, raw) = take(size as usize)(input)?;
+
+    if base_type == BaseType::String {
+        let end = raw.iter().position(|&b| b == 0).unwrap_or(raw.len());
+        let text = String::from_utf8_lossy(&raw[..end]).into_owned();
+        let value = (!text.is_empty()).then_some(FieldValue::Text(text));
+        return Ok((rest, value));
+    }
+
+    let elem_size = base_type.size();
+    // A size that doesn't fit the base type (or a byte array) is kept raw.
+    if base_type == BaseType::Byte || raw.is_empty() || raw.len() % elem_size != 0 {
+        let value = (!raw.iter().all(|&b| b == 0xFF)).then(|| FieldValue::Bytes(raw.to_vec()));
+        return Ok((rest, value));
+    }
+
+    let endian = if big_endian { Endianness::Big } else { Endianness::Little };
+    let count = raw.len() / elem_size;
+    let mut values = Vec::with_capacity(count);
+    let mut any_valid = false;
+    let mut cursor = raw;
+    for _ in 0..count {
+        let (next, (value, valid)) = element(cursor, base_type, endian)?;
+        any_valid |= valid;
+        values.push(value);
+        cursor = next;
+    }
+
+    let value = if !any_valid {
+        None
+    } else if count == 1 {
+        values.pop()
+    } else {
+        Some(FieldValue::Array(values))
+    };
+    Ok((rest, value))
+}
+
+fn data_message<'a>(
+    input: &'a [u8],
+    definition: &MessageDefinition,
+    descriptions: &HashMap<(u8, u8), DeveloperFieldDescription>,
+) -> Res<'a, DataMessage> {
+    let mut input = input;
+
+    let mut fields = Vec::with_capacity(definition.fields.len());
+    for field in &definition.fields {
+        let (rest, value) = field_value(input, field.base_type, field.size, definition.big_endian)?;
+        if let Some(value) = value {
+            fields.push((field.number, value));
+        }
+        input = rest;
+    }
+
+    let mut developer_fields = Vec::with_capacity(definition.developer_fields.len());
+    for dev in &definition.developer_fields {
+        let description = descriptions
+            .get(&(dev.developer_data_index, dev.number))
+            .cloned();
+        let base_type = description
+            .as_ref()
+            .map(|d| d.base_type)
+            .unwrap_or(BaseType::Byte);
+        let (rest, value) = field_value(input, base_type, dev.size, definition.big_endian)?;
+        if let Some(value) = value {
+            developer_fields.push(DeveloperValue {
+                developer_data_index: dev.developer_data_index,
+                field_number: dev.number,
+                value,
+                description,
+            });
+        }
+        input = rest;
+    }
+
+    Ok((
+        input,
+        DataMessage {
+            global_message_number: definition.global_message_number,
+            fields,
+            developer_fields,
+        },
+    ))
+}
+
+/// Build a developer field description from a `field_description` message.
+fn developer_field_description(msg: &DataMessage) -> Option<DeveloperFieldDescription> {
+    let developer_data_index = msg.get_u64(0)? as u8;
+    let field_number = msg.get_u64(1)? as u8;
+    let base_type = BaseType::from_u8(msg.get_u64(2)? as u8)?;
+
+    Some(DeveloperFieldDescription {
+        developer_data_index,
+        field_number,
+        base_type,
+        name: msg.get(3).and_then(|v| v.as_str()).map(str::to_string),
+        units: msg.get(8).and_then(|v| v.as_str()).map(str::to_string),
+        scale: msg.get_f64(6),
+        offset: msg.get_f64(7),
+        native_message_number: msg.get_u64(14).map(|v| v as u16),
+        native_field_number: msg.get_u64(15).map(|v| v as u8),
+    })
+}
+
+/// Expand a compressed-timestamp header offset (5 bits) against the last
+/// full timestamp, accounting for rollover of the low bits.
+pub fn expand_compressed_timestamp(last_timestamp: u32, offset: u8) -> u32 {
+    let offset = (offset & 0x1F) as u32;
+    let base = last_timestamp & !0x1F;
+    if offset >= (last_timestamp & 0x1F) {
+        base + offset
+    } else {
+        base + offset + 0x20
+    }
+}
+
+// ---------------------------------------------------------------------------
+// File
+// ---------------------------------------------------------------------------
+
+fn malformed(context: &str) -> impl Fn(nom::Err<nom::error::Error<&[u8]>>) -> FitError + '_ {
+    move |_| FitError::Malformed(format!("truncated {context}"))
+}
+
+/// Validate the header and CRCs, then decode every data message in order.
+///
+/// Definition messages are consumed internally; compressed-timestamp data
+/// messages have their expanded timestamp inserted as field 253.
+pub fn parse_messages(bytes: &[u8]) -> Result<(FitHeader, Vec<DataMessage>), FitError> {
+    let (_, hdr) = header(bytes).map_err(|_| FitError::InvalidHeader("missing .FIT signature".into()))?;
+
+    if hdr.header_size != 12 && hdr.header_size != 14 {
+        return Err(FitError::InvalidHeader(format!(
+            "unsupported header size {}",
+            hdr.header_size
+        )));
+    }
+
+    if let Some(expected) = hdr.crc.filter(|&c| c != 0) {
+        let actual = crc16(&bytes[..12]);
+        if expected != actual {
+            return Err(FitError::CrcMismatch { expected, actual });
+        }
+    }
+
+    let data_start = hdr.header_size as usize;
+    let data_end = data_start + hdr.data_size as usize;
+    if bytes.len() < data_end + 2 {
+        return Err(FitError::Truncated {
+            expected: data_end + 2,
+            actual: bytes.len(),
+        });
+    }
+
+    let expected = u16::from_le_bytes([bytes[data_end], bytes[data_end + 1]]);
+    let actual = crc16(&bytes[..data_end]);
+    if expected != actual {
+        return Err(FitError::CrcMismatch { expected, actual });
+    }
+
+    let mut input = &bytes[data_start..data_end];
+    let mut definitions: HashMap<u8, MessageDefinition> = HashMap::new();
+    let mut descriptions: HashMap<(u8, u8), DeveloperFieldDescription> = HashMap::new();
+    let mut last_timestamp: Option<u32> = None;
+    let mut messages = Vec::new();
+
+    while !input.is_empty() {
+        let (rest, record_header) = le_u8::<_, nom::error::Error<&[u8]>>(input)
+            .map_err(malformed("record header"))?;
+
+        if record_header & 0x80 != 0 {
+            // Compressed timestamp header: local type in bits 5-6, offset in 0-4.
+            let local = (record_header >> 5) & 0x03;
+            let definition = definitions
+                .get(&local)
+                .ok_or(FitError::MissingDefinition(local))?;
+            let base = last_timestamp.ok_or_else(|| {
+                FitError::Malformed("compressed timestamp before any full timestamp".into())
+            })?;
+            let timestamp = expand_compressed_timestamp(base, record_header & 0x1F);
+
+            let (rest, mut msg) =
+                data_message(rest, definition, &descriptions).map_err(malformed("data message"))?;
+            msg.fields.retain(|(n, _)| *n != TIMESTAMP_FIELD);
+            msg.fields.push((TIMESTAMP_FIELD, FieldValue::UInt(timestamp as u64)));
+            last_timestamp = Some(timestamp);
+            messages.push(msg);
+            input = rest;
+        } else if record_header & 0x40 != 0 {
+            let local = record_header & 0x0F;
+            let has_developer_fields = record_header & 0x20 != 0;
+            let (rest, definition) = definition_message(rest, has_developer_fields)
+                .map_err(malformed("definition message"))?;
+            definitions.insert(local, definition);
+            input = rest;
+        } else {
+            let local = record_header & 0x0F;
+            let definition = definitions
+                .get(&local)
+                .ok_or(FitError::MissingDefinition(local))?;
+            let (rest, msg) =
+                data_message(rest, definition, &descriptions).map_err(malformed("data message"))?;
+
+            if let Some(ts) = msg.timestamp() {
+                last_timestamp = Some(ts);
+            }
+            if msg.global_message_number == MESG_FIELD_DESCRIPTION
+                && let Some(desc) = developer_field_description(&msg)
+            {
+                descriptions.insert((desc.developer_data_index, desc.field_number), desc);
+            }
+            messages.push(msg);
+            input = rest;
+        }
+    }
+
+    Ok((hdr, messages))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fit::test_support::{FitBuilder, field};
+    use crate::fit::types::{MESG_RECORD, MESG_SESSION};
+
+    #[test]
+    fn crc_of_empty_is_zero() {
+        assert_eq!(crc16(&[]), 0);
+    }
+
+    #[test]
+    fn crc_known_vector() {
+        // FIT CRC-16 is CRC-16/ARC; the standard check value for "123456789".
+        assert_eq!(crc16(b"123456789"), 0xBB3D);
+    }
+
+    #[test]
+    fn parses_header_fields() {
+        let bytes = FitBuilder::new().build();
+        let (hdr, messages) = parse_messages(&bytes).expect("parse");
+        assert_eq!(hdr.header_size, 14);
+        assert_eq!(hdr.protocol_version, 0x20);
+        assert_eq!(hdr.data_size, 0);
+        assert!(messages.is_empty());
+    }
+
+    #[test]
+    fn accepts_twelve_byte_header() {
+        let bytes = FitBuilder::new().short_header().build();
+        let (hdr, _) = parse_messages(&bytes).expect("parse");
+        assert_eq!(hdr.header_size, 12);
+        assert_eq!(hdr.crc, None);
+    }
+
+    #[test]
+    fn rejects_missing_signature() {
+        let mut bytes = FitBuilder::new().build();
+        bytes[8] = b'X';
+        assert!(matches!(
+            parse_messages(&bytes),
+            Err(FitError::InvalidHeader(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_bad_file_crc() {
+        let mut bytes = FitBuilder::new()
+            .definition(0, MESG_RECORD, &[field(3, 1, 0x02)])
+            .data(0, &[150])
+            .build();
+        let len = bytes.len();
+        bytes[len - 1] ^= 0xFF;
+        assert!(matches!(
+            parse_messages(&bytes),
+            Err(FitError::CrcMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_bad_header_crc() {
+        let mut bytes = FitBuilder::new().build();
+        bytes[12] ^= 0xFF;
+        assert!(matches!(
+            parse_messages(&bytes),
+            Err(FitError::CrcMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_truncated_file() {
+        let bytes = FitBuilder::new()
+            .definition(0, MESG_RECORD, &[field(3, 1, 0x02)])
+            .data(0, &[150])
+            .build();
+        assert!(matches!(
+            parse_messages(&bytes[..bytes.len() - 3]),
+            Err(FitError::Truncated { .. })
+        ));
+    }
+
+    #[test]
+    fn data_without_definition_is_error() {
+        let bytes = FitBuilder::new().data(3, &[150]).build();
+        assert!(matches!(
+            parse_messages(&bytes),
+            Err(FitError::MissingDefinition(3))
+        ));
+    }
+
+    #[test]
+    fn decodes_little_and_big_endian_fields() {
+        let bytes = FitBuilder::new()
+            .definition(0, MESG_RECORD, &[field(253, 4, 0x86), field(6, 2, 0x84)])
+            .data(0, &[0x10, 0x00, 0x00, 0x40, 0xB2, 0x0C])
+            .big_endian_definition(1, MESG_RECORD, &[field(253, 4, 0x86), field(6, 2, 0x84)])
+            .data(1, &[0x40, 0x00, 0x00, 0x11, 0x0C, 0xB2])
+            .build();
+
+        let (_, messages) = parse_messages(&bytes).expect("parse");
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].timestamp(), Some(0x4000_0010));
+        assert_eq!(messages[0].get_u64(6), Some(3250));
+        assert_eq!(messages[1].timestamp(), Some(0x4000_0011));
+        assert_eq!(messages[1].get_u64(6), Some(3250));
+    }
+
+    #[test]
+    fn invalid_sentinels_are_dropped() {
+        let bytes = FitBuilder::new()
+            .definition(0, MESG_RECORD, &[field(3, 1, 0x02), field(7, 2, 0x84), field(13, 1, 0x01)])
+            .data(0, &[0xFF, 0xFF, 0xFF, 0x7F])
+            .build();
+
+        let (_, messages) = parse_messages(&bytes).expect("parse");
+        assert!(messages[0].fields.is_empty());
+    }
+
+    #[test]
+    fn decodes_strings_and_arrays() {
+        let bytes = FitBuilder::new()
+            .definition(0, MESG_SESSION, &[field(3, 6, 0x07), field(10, 4, 0x84)])
+            .data(0, &[b'R', b'u', b'n', 0, 0, 0, 0x01, 0x00, 0xFF, 0xFF])
+            .build();
+
+        let (_, messages) = parse_messages(&bytes).expect("parse");
+        assert_eq!(messages[0].get(3), Some(&FieldValue::Text("Run".into())));
+        assert_eq!(
+            messages[0].get(10),
+            Some(&FieldValue::Array(vec![FieldValue::UInt(1), FieldValue::UInt(0xFFFF)]))
+        );
+    }
+
+    #[test]
+    fn compressed_timestamps_expand_from_last_full_timestamp() {
+        // Last full timestamp has low bits 0x1E; offsets 0x1F and 0x02 roll forward.
+        let bytes = FitBuilder::new()
+            .definition(0, MESG_RECORD, &[field(253, 4, 0x86), field(3, 1, 0x02)])
+            .data(0, &[0x1E, 0x00, 0x00, 0x40, 140])
+            .definition(1, MESG_RECORD, &[field(3, 1, 0x02)])
+            .compressed(1, 0x1F, &[141])
+            .compressed(1, 0x02, &[142])
+            .build();
+
+        let (_, messages) = parse_messages(&bytes).expect("parse");
+        let timestamps: Vec<u32> = messages.iter().filter_map(|m| m.timestamp()).collect();
+        assert_eq!(timestamps, vec![0x4000_001E, 0x4000_001F, 0x4000_0022]);
+        assert_eq!(messages[2].get_u64(3), Some(142));
+    }
+
+    #[test]
+    fn compressed_timestamp_without_base_is_error() {
+        let bytes = FitBuilder::new()
+            .definition(0, MESG_RECORD, &[field(3, 1, 0x02)])
+            .compressed(0, 0x05, &[141])
+            .build();
+        assert!(matches!(parse_messages(&bytes), Err(FitError::Malformed(_))));
+    }
+
+    #[test]
+    fn expand_compressed_timestamp_rollover() {
+        assert_eq!(expand_compressed_timestamp(100, 5), 101);
+        assert_eq!(expand_compressed_timestamp(0x3F, 0x01), 0x41);
+        assert_eq!(expand_compressed_timestamp(0x20, 0x00), 0x20);
+    }
+
+    #[test]
+    fn decodes_developer_fields_with_description() {
+        let bytes = FitBuilder::new()
+            // field_description: dev index 0, field 0, uint16, name "Power", units "W", native record.power
+            .definition(
+                0,
+                MESG_FIELD_DESCRIPTION,
+                &[
+                    field(0, 1, 0x02),
+                    field(1, 1, 0x02),
+                    field(2, 1, 0x02),
+                    field(3, 6, 0x07),
+                    field(8, 2, 0x07),
+                    field(14, 2, 0x84),
+                    field(15, 1, 0x02),
+                ],
+            )
+            .data(0, &[0, 0, 0x84, b'P', b'o', b'w', b'e', b'r', 0, b'W', 0, 20, 0, 7])
+            .developer_definition(1, MESG_RECORD, &[field(3, 1, 0x02)], &[field(0, 2, 0)])
+            .data(1, &[150, 0x2C, 0x01])
+            .build();
+
+        let (_, messages) = parse_messages(&bytes).expect("parse");
+        let record = &messages[1];
+        assert_eq!(record.developer_fields.len(), 1);
+        let dev = &record.developer_fields[0];
+        assert_eq!(dev.value, FieldValue::UInt(300));
+        let desc = dev.description.as_ref().expect("description");
+        assert_eq!(desc.name.as_deref(), Some("Power"));
+        assert_eq!(desc.units.as_deref(), Some("W"));
+        assert_eq!(desc.native_field_number, Some(7));
+    }
+
+    #[test]
+    fn undescribed_developer_fields_are_raw_bytes() {
+        let bytes = FitBuilder::new()
+            .developer_definition(0, MESG_RECORD, &[field(3, 1, 0x02)], &[field(5, 2, 1)])
+            .data(0, &[150, 0x01, 0x02])
+            .build();
+
+        let (_, messages) = parse_messages(&bytes).expect("parse");
+        let dev = &messages[0].developer_fields[0];
+        assert_eq!(dev.developer_data_index, 1);
+        assert_eq!(dev.value, FieldValue::Bytes(vec![0x01, 0x02]));
+        assert!(dev.description.is_none());
+    }
+}
@@ -0,0 +1,461 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::types::{DataMessage, FIT_EPOCH_OFFSET_S, MESG_RECORD};
+
+/// Convert a raw FIT timestamp (seconds since the FIT epoch) to UTC.
+pub fn fit_time(raw: u32) -> DateTime<Utc> {
+    DateTime::from_timestamp(raw as i64 + FIT_EPOCH_OFFSET_S, 0).unwrap_or_default()
+}
+
+/// Convert semicircles to degrees: degrees = semicircles * (180 / 2^31).
+pub fn semicircles_to_degrees(semicircles: i64) -> f64 {
+    semicircles as f64 * (180.0 / 2_147_483_648.0)
+}
+
+/// Gaps longer than this (seconds) are treated as pauses and not filled
+/// when resampling to 1 Hz.
+pub const MAX_FILL_GAP_S: i64 = 10;
+
+// ---------------------------------------------------------------------------
+// Record (global message 20)
+// ---------------------------------------------------------------------------
+
+/// A single time-series sample.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FitRecord {
+    pub timestamp: DateTime<Utc>,
+    /// Milliseconds since the first record of the activity.
+    pub timestamp_ms: u64,
+    pub heart_rate: Option<u16>,
+    pub speed_m_per_s: Option<f64>,
+    /// Cumulative distance.
+    pub distance_m: Option<f64>,
+    pub altitude_m: Option<f64>,
+    /// Cadence as recorded by the device (rpm, i.e. strides per minute for running).
+    pub cadence: Option<u16>,
+    pub power_watts: Option<u16>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub temperature_c: Option<i8>,
+}
+
+impl FitRecord {
+    /// Decode a `record` message. Returns `None` if it carries no timestamp.
+    pub fn from_message(msg: &DataMessage) -> Option<Self> {
+        let timestamp = fit_time(msg.timestamp()?);
+
+        // enhanced_* fields (32-bit) take precedence over the 16-bit originals.
+        let speed_m_per_s = msg
+            .get_scaled(73, 1000.0, 0.0)
+            .or_else(|| msg.get_scaled(6, 1000.0, 0.0));
+        let altitude_m = msg
+            .get_scaled(78, 5.0, 500.0)
+            .or_else(|| msg.get_scaled(2, 5.0, 500.0));
+
+        // Some devices (e.g. Stryd) deliver running power as a developer field
+        // mapped onto record.power.
+        let power_watts = msg.get_u64(7).map(|v| v as u16).or_else(|| {
+            msg.developer_fields
+                .iter()
+                .find(|d| {
+                    d.description.as_ref().is_some_and(|desc| {
+                        desc.native_message_number == Some(MESG_RECORD)
+                            && desc.native_field_number == Some(7)
+                    })
+                })
+                .and_then(|d| d.scaled_f64())
+                .map(|v| v.round() as u16)
+        });
+
+        Some(Self {
+            timestamp,
+            timestamp_ms: 0,
+            heart_rate: msg.get_u64(3).map(|v| v as u16),
+            speed_m_per_s,
+            distance_m: msg.get_scaled(5, 100.0, 0.0),
+            altitude_m,
+            cadence: msg.get_u64(4).map(|v| v as u16),
+            power_watts,
+            latitude: msg.get_i64(0).map(semicircles_to_degrees),
+            longitude: msg.get_i64(1).map(semicircles_to_degrees),
+            temperature_c: msg.get_i64(13).map(|v| v as i8),
+        })
+    }
+}
+
+/// Resample records onto a 1 Hz grid.
+///
+/// Multiple samples in the same second keep the last one. Short gaps (smart
+/// recording) are forward-filled from the previous sample; gaps longer than
+/// `MAX_FILL_GAP_S` are left as-is so pauses don't count as moving time.
+pub fn resample_per_second(records: &[FitRecord]) -> Vec<FitRecord> {
+    let mut out: Vec<FitRecord> = Vec::with_capacity(records.len());
+
+    for record in records {
+        let Some(prev) = out.last() else {
+            out.push(record.clone());
+            continue;
+        };
+
+        let gap = (record.timestamp - prev.timestamp).num_seconds();
+        if gap <= 0 {
+            *out.last_mut().unwrap() = record.clone();
+            continue;
+        }
+
+        if gap <= MAX_FILL_GAP_S {
+            let prev = prev.clone();
+            for s in 1..gap {
+                let mut filled = prev.clone();
+                filled.timestamp = prev.timestamp + chrono::Duration::seconds(s);
+                filled.timestamp_ms = prev.timestamp_ms + s as u64 * 1000;
+                out.push(filled);
+            }
+        }
+        out.push(record.clone());
+    }
+
+    out
+}
+
+// ---------------------------------------------------------------------------
+// Session (global message 18)
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FitSession {
+    pub start_time: DateTime<Utc>,
+    pub timestamp: Option<DateTime<Utc>>,
+    pub sport: Option<u8>,
+    pub sub_sport: Option<u8>,
+    pub total_elapsed_time_s: Option<f64>,
+    pub total_timer_time_s: Option<f64>,
+    pub total_distance_m: Option<f64>,
+    pub total_calories: Option<u16>,
+    pub avg_speed_m_per_s: Option<f64>,
+    pub max_speed_m_per_s: Option<f64>,
+    pub avg_heart_rate: Option<u16>,
+    pub max_heart_rate: Option<u16>,
+    pub avg_cadence: Option<u16>,
+    pub max_cadence: Option<u16>,
+    pub avg_power_watts: Option<u16>,
+    pub max_power_watts: Option<u16>,
+    pub total_ascent_m: Option<u16>,
+    pub total_descent_m: Option<u16>,
+}
+
+impl FitSession {
+    /// Decode a `session` message. Returns `None` without a start time.
+    pub fn from_message(msg: &DataMessage) -> Option<Self> {
+        let start_time = fit_time(msg.get_u64(2)? as u32);
+
+        Some(Self {
+            start_time,
+            timestamp: msg.timestamp().map(fit_time),
+            sport: msg.get_u64(5).map(|v| v as u8),
+            sub_sport: msg.get_u64(6).map(|v| v as u8),
+            total_elapsed_time_s: msg.get_scaled(7, 1000.0, 0.0),
+            total_timer_time_s: msg.get_scaled(8, 1000.0, 0.0),
+            total_distance_m: msg.get_scaled(9, 100.0, 0.0),
+            total_calories: msg.get_u64(11).map(|v| v as u16),
+            avg_speed_m_per_s: msg
+                .get_scaled(124, 1000.0, 0.0)
+                .or_else(|| msg.get_scaled(14, 1000.0, 0.0)),
+            max_speed_m_per_s: msg
+                .get_scaled(125, 1000.0, 0.0)
+                .or_else(|| msg.get_scaled(15, 1000.0, 0.0)),
+            avg_heart_rate: msg.get_u64(16).map(|v| v as u16),
+            max_heart_rate: msg.get_u64(17).map(|v| v as u16),
+            avg_cadence: msg.get_u64(18).map(|v| v as u16),
+            max_cadence: msg.get_u64(19).map(|v| v as u16),
+            avg_power_watts: msg.get_u64(20).map(|v| v as u16),
+            max_power_watts: msg.get_u64(21).map(|v| v as u16),
+            total_ascent_m: msg.get_u64(22).map(|v| v as u16),
+            total_descent_m: msg.get_u64(23).map(|v| v as u16),
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Lap (global message 19)
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FitLap {
+    pub start_time: DateTime<Utc>,
+    pub timestamp: Option<DateTime<Utc>>,
+    pub total_elapsed_time_s: Option<f64>,
+    pub total_timer_time_s: Option<f64>,
+    pub total_distance_m: Option<f64>,
+    pub avg_speed_m_per_s: Option<f64>,
+    pub max_speed_m_per_s: Option<f64>,
+    pub avg_heart_rate: Option<u16>,
+    pub max_heart_rate: Option<u16>,
+    pub avg_cadence: Option<u16>,
+    pub avg_power_watts: Option<u16>,
+    pub total_ascent_m: Option<u16>,
+    pub total_descent_m: Option<u16>,
+}
+
+impl FitLap {
+    /// Decode a `lap` message. Returns `None` without a start time.
+    pub fn from_message(msg: &DataMessage) -> Option<Self> {
+        let start_time = fit_time(msg.get_u64(2)? as u32);
+
+        Some(Self {
+            start_time,
+            timestamp: msg.timestamp().map(fit_time),
+            total_elapsed_time_s: msg.get_scaled(7, 1000.0, 0.0),
+            total_timer_time_s: msg.get_scaled(8, 1000.0, 0.0),
+            total_distance_m: msg.get_scaled(9, 100.0, 0.0),
+            avg_speed_m_per_s: msg
+                .get_scaled(110, 1000.0, 0.0)
+                .or_else(|| msg.get_scaled(13, 1000.0, 0.0)),
+            max_speed_m_per_s: msg
+                .get_scaled(111, 1000.0, 0.0)
+                .or_else(|| msg.get_scaled(14, 1000.0, 0.0)),
+            avg_heart_rate: msg.get_u64(15).map(|v| v as u16),
+            max_heart_rate: msg.get_u64(16).map(|v| v as u16),
+            avg_cadence: msg.get_u64(17).map(|v| v as u16),
+            avg_power_watts: msg.get_u64(19).map(|v| v as u16),
+            total_ascent_m: msg.get_u64(21).map(|v| v as u16),
+            total_descent_m: msg.get_u64(22).map(|v| v as u16),
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Event (global message 21)
+// ---------------------------------------------------------------------------
+
+/// `event` value for timer start/stop events.
+pub const EVENT_TIMER: u8 = 0;
+/// `event_type` values.
+pub const EVENT_TYPE_START: u8 = 0;
+pub const EVENT_TYPE_STOP: u8 = 1;
+pub const EVENT_TYPE_STOP_ALL: u8 = 4;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FitEvent {
+    pub timestamp: DateTime<Utc>,
+    pub event: u8,
+    pub event_type: u8,
+    pub data: Option<u32>,
+}
+
+impl FitEvent {
+    /// Decode an `event` message. Returns `None` without timestamp or event.
+    pub fn from_message(msg: &DataMessage) -> Option<Self> {
+        Some(Self {
+            timestamp: fit_time(msg.timestamp()?),
+            event: msg.get_u64(0)? as u8,
+            event_type: msg.get_u64(1).map(|v| v as u8).unwrap_or(EVENT_TYPE_START),
+            data: msg.get_u64(3).map(|v| v as u32),
+        })
+    }
+
+    /// Whether this event stops the activity timer.
+    pub fn is_timer_stop(&self) -> bool {
+        self.event == EVENT_TIMER
+            && (self.event_type == EVENT_TYPE_STOP || self.event_type == EVENT_TYPE_STOP_ALL)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fit::types::{
+        BaseType, DeveloperFieldDescription, DeveloperValue, FieldValue, MESG_SESSION,
+        TIMESTAMP_FIELD,
+    };
+
+    fn record_msg(fields: Vec<(u8, FieldValue)>) -> DataMessage {
+        DataMessage {
+            global_message_number: MESG_RECORD,
+            fields,
+            developer_fields: vec![],
+        }
+    }
+
+    fn sample(second: i64, hr: u16) -> FitRecord {
+        FitRecord {
+            timestamp: DateTime::from_timestamp(1_700_000_000 + second, 0).unwrap(),
+            timestamp_ms: second as u64 * 1000,
+            heart_rate: Some(hr),
+            speed_m_per_s: Some(3.0),
+            distance_m: Some(second as f64 * 3.0),
+            altitude_m: None,
+            cadence: None,
+            power_watts: None,
+            latitude: None,
+            longitude: None,
+            temperature_c: None,
+        }
+    }
+
+    #[test]
+    fn fit_epoch_conversion() {
+        assert_eq!(fit_time(0).to_rfc3339(), "1989-12-31T00:00:00+00:00");
+        // 2024-01-01T00:00:00Z = 1704067200 unix
+        assert_eq!(
+            fit_time((1_704_067_200 - FIT_EPOCH_OFFSET_S) as u32).to_rfc3339(),
+            "2024-01-01T00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn semicircle_conversion() {
+        assert!((semicircles_to_degrees(1 << 30) - 90.0).abs() < 1e-9);
+        assert!((semicircles_to_degrees(-(1 << 30)) + 90.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn record_applies_profile_scales() {
+        let msg = record_msg(vec![
+            (TIMESTAMP_FIELD, FieldValue::UInt(1_000_000_000)),
+            (0, FieldValue::SInt(1 << 29)),
+            (1, FieldValue::SInt(-(1 << 29))),
+            (2, FieldValue::UInt(3000)),
+            (3, FieldValue::UInt(152)),
+            (4, FieldValue::UInt(88)),
+            (5, FieldValue::UInt(123_456)),
+            (6, FieldValue::UInt(3250)),
+            (7, FieldValue::UInt(245)),
+            (13, FieldValue::SInt(-3)),
+        ]);
+
+        let r = FitRecord::from_message(&msg).expect("record");
+        assert_eq!(r.timestamp, fit_time(1_000_000_000));
+        assert!((r.latitude.unwrap() - 45.0).abs() < 1e-9);
+        assert!((r.longitude.unwrap() + 45.0).abs() < 1e-9);
+        assert_eq!(r.altitude_m, Some(100.0));
+        assert_eq!(r.heart_rate, Some(152));
+        assert_eq!(r.cadence, Some(88));
+        assert_eq!(r.distance_m, Some(1234.56));
+        assert_eq!(r.speed_m_per_s, Some(3.25));
+        assert_eq!(r.power_watts, Some(245));
+        assert_eq!(r.temperature_c, Some(-3));
+    }
+
+    #[test]
+    fn record_prefers_enhanced_fields() {
+        let msg = record_msg(vec![
+            (TIMESTAMP_FIELD, FieldValue::UInt(1)),
+            (2, FieldValue::UInt(3000)),
+            (6, FieldValue::UInt(3000)),
+            (73, FieldValue::UInt(3500)),
+            (78, FieldValue::UInt(3500)),
+        ]);
+        let r = FitRecord::from_message(&msg).unwrap();
+        assert_eq!(r.speed_m_per_s, Some(3.5));
+        assert_eq!(r.altitude_m, Some(200.0));
+    }
+
+    #[test]
+    fn record_without_timestamp_is_skipped() {
+        let msg = record_msg(vec![(3, FieldValue::UInt(150))]);
+        assert!(FitRecord::from_message(&msg).is_none());
+    }
+
+    #[test]
+    fn record_power_falls_back_to_native_mapped_developer_field() {
+        let mut msg = record_msg(vec![(TIMESTAMP_FIELD, FieldValue::UInt(1))]);
+        msg.developer_fields.push(DeveloperValue {
+            developer_data_index: 0,
+            field_number: 0,
+            value: FieldValue::UInt(281),
+            description: Some(DeveloperFieldDescription {
+                developer_data_index: 0,
+                field_number: 0,
+                base_type: BaseType::UInt16,
+                name: Some("Power".into()),
+                units: Some("Watts".into()),
+                scale: None,
+                offset: None,
+                native_message_number: Some(MESG_RECORD),
+                native_field_number: Some(7),
+            }),
+        });
+        let r = FitRecord::from_message(&msg).unwrap();
+        assert_eq!(r.power_watts, Some(281));
+    }
+
+    #[test]
+    fn session_decodes_summary() {
+        let msg = DataMessage {
+            global_message_number: MESG_SESSION,
+            fields: vec![
+                (TIMESTAMP_FIELD, FieldValue::UInt(1_000_003_600)),
+                (2, FieldValue::UInt(1_000_000_000)),
+                (5, FieldValue::UInt(1)),
+                (7, FieldValue::UInt(3_700_000)),
+                (8, FieldValue::UInt(3_600_000)),
+                (9, FieldValue::UInt(1_000_000)),
+                (14, FieldValue::UInt(2778)),
+                (16, FieldValue::UInt(145)),
+                (17, FieldValue::UInt(171)),
+                (22, FieldValue::UInt(120)),
+                (23, FieldValue::UInt(118)),
+            ],
+            developer_fields: vec![],
+        };
+        let s = FitSession::from_message(&msg).unwrap();
+        assert_eq!(s.start_time, fit_time(1_000_000_000));
+        assert_eq!(s.sport, Some(1));
+        assert_eq!(s.total_elapsed_time_s, Some(3700.0));
+        assert_eq!(s.total_timer_time_s, Some(3600.0));
+        assert_eq!(s.total_distance_m, Some(10_000.0));
+        assert_eq!(s.avg_speed_m_per_s, Some(2.778));
+        assert_eq!(s.avg_heart_rate, Some(145));
+        assert_eq!(s.max_heart_rate, Some(171));
+        assert_eq!(s.total_ascent_m, Some(120));
+        assert_eq!(s.total_descent_m, Some(118));
+        assert_eq!(s.max_speed_m_per_s, None);
+    }
+
+    #[test]
+    fn event_timer_stop_detection() {
+        let event = FitEvent {
+            timestamp: fit_time(1),
+            event: EVENT_TIMER,
+            event_type: EVENT_TYPE_STOP_ALL,
+            data: None,
+        };
+        assert!(event.is_timer_stop());
+        let start = FitEvent {
+            event_type: EVENT_TYPE_START,
+            ..event
+        };
+        assert!(!start.is_timer_stop());
+    }
+
+    #[test]
+    fn resample_fills_short_gaps() {
+        let records = vec![sample(0, 140), sample(3, 150)];
+        let out = resample_per_second(&records);
+        assert_eq!(out.len(), 4);
+        assert_eq!(out[1].heart_rate, Some(140));
+        assert_eq!(out[2].timestamp_ms, 2000);
+        assert_eq!(out[3].heart_rate, Some(150));
+    }
+
+    #[test]
+    fn resample_keeps_last_sample_in_same_second() {
+        let records = vec![sample(0, 140), sample(0, 142), sample(1, 143)];
+        let out = resample_per_second(&records);
+        assert_eq!(out.len(), 2);
+        assert_eq!(out[0].heart_rate, Some(142));
+    }
+
+    #[test]
+    fn resample_does_not_fill_pauses() {
+        let records = vec![sample(0, 140), sample(60, 150)];
+        let out = resample_per_second(&records);
+        assert_eq!(out.len(), 2);
+    }
+}
@@ -0,0 +1,331 @@
+// ---------------------------------------------------------------------------
+// Constants
+// ---------------------------------------------------------------------------
+
+/// Seconds between the Unix epoch and the FIT epoch (1989-12-31T00:00:00Z).
+pub const FIT_EPOCH_OFFSET_S: i64 = 631_065_600;
+
+/// Field number used for the `timestamp` field in every FIT message.
+pub const TIMESTAMP_FIELD: u8 = 253;
+
+/// Global message numbers we decode into typed structs.
+pub const MESG_SESSION: u16 = 18;
+pub const MESG_LAP: u16 = 19;
+pub const MESG_RECORD: u16 = 20;
+pub const MESG_EVENT: u16 = 21;
+pub const MESG_FIELD_DESCRIPTION: u16 = 206;
+
+// ---------------------------------------------------------------------------
+// File header
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FitHeader {
+    pub header_size: u8,
+    pub protocol_version: u8,
+    pub profile_version: u16,
+    /// Length of the data records section, excluding header and trailing CRC.
+    pub data_size: u32,
+    /// Header CRC (14-byte headers only). `None` or `Some(0)` means not computed.
+    pub crc: Option<u16>,
+}
+
+// ---------------------------------------------------------------------------
+// Base types
+// ---------------------------------------------------------------------------
+
+/// FIT base types as defined by the SDK profile.
+///
+/// Each has a fixed element size and an "invalid" sentinel that marks a
+/// field as absent for that sample.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BaseType {
+    Enum,
+    SInt8,
+    UInt8,
+    SInt16,
+    UInt16,
+    SInt32,
+    UInt32,
+    String,
+    Float32,
+    Float64,
+    UInt8z,
+    UInt16z,
+    UInt32z,
+    Byte,
+    SInt64,
+    UInt64,
+    UInt64z,
+}
+
+impl BaseType {
+    /// Decode a base type byte. Only the low 5 bits carry the type number;
+    /// the high bit is the endian-ability flag.
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value & 0x1F {
+            0 => Some(Self::Enum),
+            1 => Some(Self::SInt8),
+            2 => Some(Self::UInt8),
+            3 => Some(Self::SInt16),
+            4 => Some(Self::UInt16),
+            5 => Some(Self::SInt32),
+            6 => Some(Self::UInt32),
+            7 => Some(Self::String),
+            8 => Some(Self::Float32),
+            9 => Some(Self::Float64),
+            10 => Some(Self::UInt8z),
+            11 => Some(Self::UInt16z),
+            12 => Some(Self::UInt32z),
+            13 => Some(Self::Byte),
+            14 => Some(Self::SInt64),
+            15 => Some(Self::UInt64),
+            16 => Some(Self::UInt64z),
+            _ => None,
+        }
+    }
+
+    /// Size in bytes of a single element of this type.
+    pub fn size(&self) -> usize {
+        match self {
+            Self::Enum | Self::SInt8 | Self::UInt8 | Self::String | Self::UInt8z | Self::Byte => 1,
+            Self::SInt16 | Self::UInt16 | Self::UInt16z => 2,
+            Self::SInt32 | Self::UInt32 | Self::Float32 | Self::UInt32z => 4,
+            Self::Float64 | Self::SInt64 | Self::UInt64 | Self::UInt64z => 8,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Definitions
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDefinition {
+    pub number: u8,
+    pub size: u8,
+    pub base_type: BaseType,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeveloperFieldDefinition {
+    pub number: u8,
+    pub size: u8,
+    pub developer_data_index: u8,
+}
+
+/// Layout of the data messages that follow for one local message type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MessageDefinition {
+    pub big_endian: bool,
+    pub global_message_number: u16,
+    pub fields: Vec<FieldDefinition>,
+    pub developer_fields: Vec<DeveloperFieldDefinition>,
+}
+
+/// Describes a developer field, taken from a `field_description` message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeveloperFieldDescription {
+    pub developer_data_index: u8,
+    pub field_number: u8,
+    pub base_type: BaseType,
+    pub name: Option<String>,
+    pub units: Option<String>,
+    pub scale: Option<f64>,
+    pub offset: Option<f64>,
+    /// When set, the developer field carries the same data as this native
+    /// field of `native_message_number` (e.g. a Stryd power field).
+    pub native_message_number: Option<u16>,
+    pub native_field_number: Option<u8>,
+}
+
+// ---------------------------------------------------------------------------
+// Values
+// ---------------------------------------------------------------------------
+
+/// A decoded field value. Invalid sentinels are filtered out by the parser,
+/// so a field that is present in a message always has a meaningful value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    UInt(u64),
+    SInt(i64),
+    Float(f64),
+    Text(String),
+    Bytes(Vec<u8>),
+    Array(Vec<FieldValue>),
+}
+
+impl FieldValue {
+    /// Numeric view of the value. Arrays yield their first element.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Self::UInt(v) => Some(*v as f64),
+            Self::SInt(v) => Some(*v as f64),
+            Self::Float(v) => Some(*v),
+            Self::Array(values) => values.first().and_then(|v| v.as_f64()),
+            Self::Text(_) | Self::Bytes(_) => None,
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Self::UInt(v) => Some(*v),
+            Self::SInt(v) if *v >= 0 => Some(*v as u64),
+            Self::Array(values) => values.first().and_then(|v| v.as_u64()),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::SInt(v) => Some(*v),
+            Self::UInt(v) => i64::try_from(*v).ok(),
+            Self::Array(values) => values.first().and_then(|v| v.as_i64()),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Text(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+/// A developer field value attached to a data message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeveloperValue {
+    pub developer_data_index: u8,
+    pub field_number: u8,
+    pub value: FieldValue,
+    /// Description, if a matching `field_description` message preceded it.
+    pub description: Option<DeveloperFieldDescription>,
+}
+
+impl DeveloperValue {
+    /// Numeric value with the description's scale/offset applied.
+    pub fn scaled_f64(&self) -> Option<f64> {
+        let raw = self.value.as_f64()?;
+        let (scale, offset) = match &self.description {
+            Some(d) => (d.scale.unwrap_or(1.0), d.offset.unwrap_or(0.0)),
+            None => (1.0, 0.0),
+        };
+        if scale == 0.0 {
+            return None;
+        }
+        Some(raw / scale - offset)
+    }
+}
+
+/// A fully decoded data message, independent of the typed profile structs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataMessage {
+    pub global_message_number: u16,
+    pub fields: Vec<(u8, FieldValue)>,
+    pub developer_fields: Vec<DeveloperValue>,
+}
+
+impl DataMessage {
+    pub fn get(&self, number: u8) -> Option<&FieldValue> {
+        self.fields
+            .iter()
+            .find(|(n, _)| *n == number)
+            .map(|(_, v)| v)
+    }
+
+    pub fn get_f64(&self, number: u8) -> Option<f64> {
+        self.get(number).and_then(FieldValue::as_f64)
+    }
+
+    pub fn get_u64(&self, number: u8) -> Option<u64> {
+        self.get(number).and_then(FieldValue::as_u64)
+    }
+
+    pub fn get_i64(&self, number: u8) -> Option<i64> {
+        self.get(number).and_then(FieldValue::as_i64)
+    }
+
+    /// Numeric value with the profile's `scale` and `offset` applied.
+    pub fn get_scaled(&self, number: u8, scale: f64, offset: f64) -> Option<f64> {
+        self.get_f64(number).map(|v| v / scale - offset)
+    }
+
+    /// Raw FIT timestamp (seconds since the FIT epoch).
+    pub fn timestamp(&self) -> Option<u32> {
+        self.get_u64(TIMESTAMP_FIELD)
+            .and_then(|v| u32::try_from(v).ok())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base_type_from_u8_masks_endian_flag() {
+        assert_eq!(BaseType::from_u8(0x84), Some(BaseType::UInt16));
+        assert_eq!(BaseType::from_u8(0x04), Some(BaseType::UInt16));
+        assert_eq!(BaseType::from_u8(0x86), Some(BaseType::UInt32));
+        assert_eq!(BaseType::from_u8(0x1F), None);
+    }
+
+    #[test]
+    fn base_type_sizes() {
+        assert_eq!(BaseType::Enum.size(), 1);
+        assert_eq!(BaseType::UInt16.size(), 2);
+        assert_eq!(BaseType::SInt32.size(), 4);
+        assert_eq!(BaseType::Float64.size(), 8);
+    }
+
+    #[test]
+    fn field_value_numeric_views() {
+        assert_eq!(FieldValue::UInt(42).as_f64(), Some(42.0));
+        assert_eq!(FieldValue::SInt(-5).as_i64(), Some(-5));
+        assert_eq!(FieldValue::SInt(-5).as_u64(), None);
+        assert_eq!(
+            FieldValue::Array(vec![FieldValue::UInt(7), FieldValue::UInt(8)]).as_u64(),
+            Some(7)
+        );
+        assert_eq!(FieldValue::Text("x".into()).as_f64(), None);
+    }
+
+    #[test]
+    fn data_message_scaled_lookup() {
+        let msg = DataMessage {
+            global_message_number: MESG_RECORD,
+            fields: vec![(2, FieldValue::UInt(3000)), (6, FieldValue::UInt(3250))],
+            developer_fields: vec![],
+        };
+        // altitude: scale 5, offset 500 -> 3000 / 5 - 500 = 100 m
+        assert_eq!(msg.get_scaled(2, 5.0, 500.0), Some(100.0));
+        // speed: scale 1000 -> 3.25 m/s
+        assert_eq!(msg.get_scaled(6, 1000.0, 0.0), Some(3.25));
+        assert_eq!(msg.get_scaled(99, 1.0, 0.0), None);
+    }
+
+    #[test]
+    fn developer_value_applies_scale() {
+        let value = DeveloperValue {
+            developer_data_index: 0,
+            field_number: 1,
+            value: FieldValue::UInt(2500),
+            description: Some(DeveloperFieldDescription {
+                developer_data_index: 0,
+                field_number: 1,
+                base_type: BaseType::UInt16,
+                name: Some("Form Power".into()),
+                units: Some("Watts".into()),
+                scale: Some(10.0),
+                offset: None,
+                native_message_number: None,
+                native_field_number: None,
+            }),
+        };
+        assert_eq!(value.scaled_f64(), Some(250.0));
+    }
+}
@@ -5,6 +5,8 @@ pub mod db;
 pub mod domain;
 pub mod ai;
 pub mod error;
+pub mod fit;
+pub mod scheduler;
 
 use std::sync::Arc;
 
@@ -32,6 +34,9 @@ pub fn build_app(state: AppState) -> Router {
         .nest("/api/auth", api::auth::router())
         .nest("/api/athlete", api::athletes::router())
         .nest("/api/plan", api::plans::router())
+        .nest("/api/workouts", api::workouts::router())
+        .nest("/api/metrics", api::metrics::router())
+        .nest("/api/chat", api::chat::router())
         .layer(TraceLayer::new_for_http())
         .with_state(state)
 }
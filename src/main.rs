@@ -6,6 +6,8 @@ use std::sync::Arc;
 
 use coachjan::{AppState, build_app};
 use coachjan::config::Config;
+use coachjan::scheduler::Scheduler;
+use coachjan::scheduler::clock::SystemClock;
 
 #[tokio::main]
 async fn main() {
@@ -44,6 +46,9 @@ async fn main() {
         Arc::new(coachjan::ai::client::ClaudeClient::new(key.clone()))
     });
 
+    // Background jobs: session cleanup, daily metrics and mesocycle upkeep
+    Scheduler::new(db.clone(), Arc::new(SystemClock)).spawn();
+
     let state = AppState {
         db,
         config: config.clone(),
@@ -0,0 +1,135 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use sqlx::sqlite::SqlitePool;
+use tracing::{info, warn};
+
+use crate::db::plans::DueMesocycle;
+use crate::db::{drafts, metrics, plans, sessions};
+use crate::error::AppResult;
+
+fn date_key(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+// ---------------------------------------------------------------------------
+// Session and draft cleanup
+// ---------------------------------------------------------------------------
+
+/// Delete sessions that have expired as of `now`. Returns the number removed.
+pub async fn cleanup_sessions(pool: &SqlitePool, now: DateTime<Utc>) -> AppResult<u64> {
+    let removed = sessions::cleanup_sessions_expired_at(pool, now).await?;
+    if removed > 0 {
+        info!("Removed {removed} expired sessions");
+    }
+    Ok(removed)
+}
+
+/// Delete plan drafts that expired unconfirmed as of `now`. Returns the
+/// number removed.
+pub async fn cleanup_drafts(pool: &SqlitePool, now: DateTime<Utc>) -> AppResult<u64> {
+    let removed = drafts::delete_expired_drafts(pool, now).await?;
+    if removed > 0 {
+        info!("Removed {removed} expired plan drafts");
+    }
+    Ok(removed)
+}
+
+// ---------------------------------------------------------------------------
+// Daily metrics roll-forward
+// ---------------------------------------------------------------------------
+
+/// Extend every athlete's ATL/CTL/TSB series through `today`, so days without
+/// a workout get a zero-TSS row and fitness/fatigue keep decaying. Returns the
+/// number of athletes whose series was extended. One athlete's failure is
+/// logged and does not stop the others.
+pub async fn roll_forward_metrics(pool: &SqlitePool, today: NaiveDate) -> AppResult<usize> {
+    let mut rolled = 0;
+
+    for (user_id, latest) in metrics::get_latest_metrics_dates(pool).await? {
+        let latest = match NaiveDate::parse_from_str(&latest, "%Y-%m-%d") {
+            Ok(date) => date,
+            Err(e) => {
+                warn!(
+                    "Skipping metrics roll-forward for user {user_id}: invalid date '{latest}': {e}"
+                );
+                continue;
+            }
+        };
+        let Some(from) = latest.succ_opt().filter(|from| *from <= today) else {
+            continue;
+        };
+
+        match metrics::recompute_daily_metrics(pool, user_id, from, today).await {
+            Ok(()) => rolled += 1,
+            Err(e) => warn!("Metrics roll-forward failed for user {user_id}: {e}"),
+        }
+    }
+
+    Ok(rolled)
+}
+
+// ---------------------------------------------------------------------------
+// Mesocycle status transitions
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MesocycleStatusChanges {
+    pub completed: u64,
+    pub activated: u64,
+}
+
+/// Move mesocycle statuses along with the calendar: complete active blocks
+/// whose end date has passed and that need no transition evaluation, then
+/// activate pending blocks that have started and have workouts.
+pub async fn update_mesocycle_statuses(
+    pool: &SqlitePool,
+    today: NaiveDate,
+) -> AppResult<MesocycleStatusChanges> {
+    let today = date_key(today);
+    let completed = plans::complete_ended_mesocycles(pool, &today).await?;
+    let activated = plans::activate_started_mesocycles(pool, &today).await?;
+
+    if completed > 0 || activated > 0 {
+        info!("Mesocycle statuses updated: {completed} completed, {activated} activated");
+    }
+    Ok(MesocycleStatusChanges {
+        completed,
+        activated,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Mesocycle transition detection
+// ---------------------------------------------------------------------------
+
+/// Find active mesocycles that need a transition evaluation as of `today`.
+pub async fn detect_due_transitions(
+    pool: &SqlitePool,
+    today: NaiveDate,
+) -> AppResult<Vec<DueMesocycle>> {
+    plans::get_mesocycles_due_for_transition(pool, &date_key(today)).await
+}
+
+/// Flag each due mesocycle so the athlete is prompted to start its
+/// transition. Evaluating the block and generating the next one take Claude
+/// calls, so they only run when the athlete asks for them, never from here.
+/// Returns the IDs flagged for the first time.
+pub async fn flag_due_transitions(
+    pool: &SqlitePool,
+    due: &[DueMesocycle],
+    today: NaiveDate,
+) -> AppResult<Vec<i64>> {
+    let today = date_key(today);
+    let mut flagged = Vec::new();
+
+    for d in due {
+        if plans::mark_transition_due(pool, d.mesocycle.id, &today).await? {
+            info!(
+                "Mesocycle {} (user {}) is due for a transition evaluation",
+                d.mesocycle.id, d.user_id
+            );
+            flagged.push(d.mesocycle.id);
+        }
+    }
+
+    Ok(flagged)
+}
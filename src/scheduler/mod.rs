@@ -0,0 +1,457 @@
+//! Background jobs run on a timer from `main.rs`: hourly cleanup of expired
+//! sessions and plan drafts, and once per day the metrics roll-forward,
+//! mesocycle status transitions and flagging of due transitions. No job calls
+//! Claude; the athlete starts a flagged transition.
+
+pub mod clock;
+pub mod jobs;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+use sqlx::sqlite::SqlitePool;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tracing::warn;
+
+use self::clock::Clock;
+use self::jobs::MesocycleStatusChanges;
+
+/// How often the scheduler wakes up to check for due jobs.
+pub const TICK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Minimum time between cleanups of expired sessions and plan drafts.
+pub const SESSION_CLEANUP_INTERVAL: TimeDelta = TimeDelta::hours(1);
+
+/// What one tick ran. `None` fields were not due (or failed, which is logged).
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct TickReport {
+    pub sessions_removed: Option<u64>,
+    pub drafts_removed: Option<u64>,
+    pub daily: Option<DailyReport>,
+}
+
+/// Results of the once-per-day jobs.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct DailyReport {
+    pub date: NaiveDate,
+    /// Athletes whose ATL/CTL/TSB series was extended to today.
+    pub metrics_rolled_forward: usize,
+    pub mesocycles: MesocycleStatusChanges,
+    /// Mesocycles due for a transition evaluation.
+    pub transitions_due: Vec<i64>,
+    /// Due mesocycles flagged for the athlete on this run.
+    pub transitions_flagged: Vec<i64>,
+}
+
+pub struct Scheduler {
+    pool: SqlitePool,
+    clock: Arc<dyn Clock>,
+    last_session_cleanup: Option<DateTime<Utc>>,
+    last_daily_run: Option<NaiveDate>,
+}
+
+impl Scheduler {
+    pub fn new(pool: SqlitePool, clock: Arc<dyn Clock>) -> Self {
+        Self {
+            pool,
+            clock,
+            last_session_cleanup: None,
+            last_daily_run: None,
+        }
+    }
+
+    /// Run the scheduler in the background, ticking every `TICK_INTERVAL`.
+    /// The first tick fires immediately, so all jobs run at startup.
+    pub fn spawn(mut self) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(TICK_INTERVAL);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                self.tick().await;
+            }
+        })
+    }
+
+    /// Run every job that is due according to the clock. Expiry cleanup runs
+    /// at most once per `SESSION_CLEANUP_INTERVAL`; the daily jobs run once
+    /// per calendar day (UTC), on the first tick of the day.
+    pub async fn tick(&mut self) -> TickReport {
+        let now = self.clock.now();
+        let mut report = TickReport::default();
+
+        let cleanup_due = self
+            .last_session_cleanup
+            .is_none_or(|last| now - last >= SESSION_CLEANUP_INTERVAL);
+        if cleanup_due {
+            self.last_session_cleanup = Some(now);
+            match jobs::cleanup_sessions(&self.pool, now).await {
+                Ok(removed) => report.sessions_removed = Some(removed),
+                Err(e) => warn!("Session cleanup failed: {e}"),
+            }
+            match jobs::cleanup_drafts(&self.pool, now).await {
+                Ok(removed) => report.drafts_removed = Some(removed),
+                Err(e) => warn!("Plan draft cleanup failed: {e}"),
+            }
+        }
+
+        let today = now.date_naive();
+        if self.last_daily_run != Some(today) {
+            self.last_daily_run = Some(today);
+            report.daily = Some(self.run_daily(today).await);
+        }
+
+        report
+    }
+
+    async fn run_daily(&self, today: NaiveDate) -> DailyReport {
+        let mut report = DailyReport {
+            date: today,
+            ..Default::default()
+        };
+
+        match jobs::roll_forward_metrics(&self.pool, today).await {
+            Ok(rolled) => report.metrics_rolled_forward = rolled,
+            Err(e) => warn!("Metrics roll-forward failed: {e}"),
+        }
+
+        match jobs::update_mesocycle_statuses(&self.pool, today).await {
+            Ok(changes) => report.mesocycles = changes,
+            Err(e) => warn!("Mesocycle status update failed: {e}"),
+        }
+
+        match jobs::detect_due_transitions(&self.pool, today).await {
+            Ok(due) => {
+                report.transitions_due = due.iter().map(|d| d.mesocycle.id).collect();
+                match jobs::flag_due_transitions(&self.pool, &due, today).await {
+                    Ok(flagged) => report.transitions_flagged = flagged,
+                    Err(e) => warn!("Flagging due mesocycle transitions failed: {e}"),
+                }
+            }
+            Err(e) => warn!("Mesocycle transition detection failed: {e}"),
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{metrics, plans, profiles, sessions};
+    use crate::scheduler::clock::ManualClock;
+    use chrono::TimeZone;
+    use sqlx::Row;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+
+    async fn setup_pool() -> SqlitePool {
+        let opts = SqliteConnectOptions::new()
+            .filename(":memory:")
+            .create_if_missing(true)
+            .pragma("foreign_keys", "ON");
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(opts)
+            .await
+            .expect("Failed to create test pool");
+
+        sqlx::migrate!("./migrations")
+            .run(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    async fn create_test_user(pool: &SqlitePool) -> i64 {
+        let row = sqlx::query(
+            "INSERT INTO users (email, password_hash) VALUES ('test@example.com', 'hash') RETURNING id",
+        )
+        .fetch_one(pool)
+        .await
+        .expect("create test user");
+        row.get("id")
+    }
+
+    fn at(date: &str, hour: u32) -> DateTime<Utc> {
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        Utc.from_utc_datetime(&date.and_hms_opt(hour, 0, 0).unwrap())
+    }
+
+    fn scheduler(pool: &SqlitePool, clock: &Arc<ManualClock>) -> Scheduler {
+        Scheduler::new(pool.clone(), clock.clone())
+    }
+
+    /// A macrocycle with a 2+1 mesocycle (2026-06-01..06-21) that has a
+    /// workout per week, and an empty follow-up (06-22..07-12).
+    async fn create_test_plan(pool: &SqlitePool, user_id: i64) -> (i64, i64) {
+        let race_goal_id: i64 = sqlx::query(
+            r#"INSERT INTO race_goals (user_id, race_name, distance_m, race_date)
+               VALUES (?, 'Test Marathon', 42195.0, '2026-09-27') RETURNING id"#,
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .expect("create race goal")
+        .get("id");
+        let mc = plans::create_macrocycle(
+            pool,
+            &plans::CreateMacrocycle {
+                user_id,
+                race_goal_id,
+                start_date: "2026-06-01".to_string(),
+                end_date: "2026-09-27".to_string(),
+                target_ctl: Some(60.0),
+                coach_message: None,
+            },
+        )
+        .await
+        .expect("create macrocycle");
+
+        let mut ids = vec![];
+        for (seq, start, end) in [
+            (1, "2026-06-01", "2026-06-21"),
+            (2, "2026-06-22", "2026-07-12"),
+        ] {
+            let meso = plans::create_mesocycle(
+                pool,
+                &plans::CreateMesocycle {
+                    macrocycle_id: mc.id,
+                    sequence_number: seq,
+                    phase: "capacity".to_string(),
+                    focus: "aerobic_capacity".to_string(),
+                    load_weeks: 2,
+                    recovery_weeks: 1,
+                    target_volume_km: None,
+                    start_date: start.to_string(),
+                    end_date: end.to_string(),
+                },
+            )
+            .await
+            .expect("create mesocycle");
+            ids.push(meso.id);
+        }
+
+        for date in ["2026-06-02", "2026-06-09", "2026-06-16", "2026-06-21"] {
+            let workout_type = if date == "2026-06-21" {
+                "rest"
+            } else {
+                "easy_run"
+            };
+            create_workout(pool, ids[0], user_id, date, workout_type).await;
+        }
+
+        (ids[0], ids[1])
+    }
+
+    async fn create_workout(
+        pool: &SqlitePool,
+        mesocycle_id: i64,
+        user_id: i64,
+        date: &str,
+        workout_type: &str,
+    ) -> i64 {
+        plans::create_planned_workout(
+            pool,
+            &plans::CreatePlannedWorkout {
+                mesocycle_id,
+                user_id,
+                scheduled_date: date.to_string(),
+                workout_type: workout_type.to_string(),
+                duration_min: Some(45),
+                duration_category: None,
+                target_hr_zones: None,
+                target_pace_zones: None,
+                expected_tss: Some(40.0),
+                description: None,
+                coach_notes: None,
+                target_distance_km: None,
+            },
+        )
+        .await
+        .expect("create planned workout")
+        .id
+    }
+
+    async fn mesocycle_status(pool: &SqlitePool, id: i64) -> String {
+        sqlx::query_scalar("SELECT status FROM mesocycles WHERE id = ?")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_daily_jobs_roll_metrics_forward_once_per_day() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        profiles::create_daily_metrics(&pool, user_id, "2026-06-01", 0.0, 50.0, 50.0, 0.0)
+            .await
+            .unwrap();
+
+        let clock = Arc::new(ManualClock::new(at("2026-06-01", 8)));
+        let mut scheduler = scheduler(&pool, &clock);
+
+        let report = scheduler.tick().await;
+        assert_eq!(report.daily.as_ref().unwrap().metrics_rolled_forward, 0);
+
+        // Three days pass without training.
+        clock.advance(TimeDelta::days(3));
+        let report = scheduler.tick().await;
+        let daily = report.daily.expect("daily jobs run on a new day");
+        assert_eq!(daily.date, NaiveDate::from_ymd_opt(2026, 6, 4).unwrap());
+        assert_eq!(daily.metrics_rolled_forward, 1);
+
+        let rows = metrics::get_daily_metrics_range(&pool, user_id, "2026-06-02", "2026-06-04")
+            .await
+            .unwrap();
+        assert_eq!(rows.len(), 3);
+        assert!(rows.iter().all(|r| r.total_tss == 0.0));
+        assert!(
+            rows.windows(2)
+                .all(|w| w[1].ctl < w[0].ctl && w[1].atl < w[0].atl)
+        );
+        assert!(rows[0].ctl < 50.0);
+
+        // Later the same day: nothing daily is due, and no cleanup within the hour.
+        clock.advance(TimeDelta::minutes(30));
+        assert_eq!(scheduler.tick().await, TickReport::default());
+
+        clock.advance(TimeDelta::days(1));
+        let daily = scheduler.tick().await.daily.unwrap();
+        assert_eq!(daily.metrics_rolled_forward, 1);
+        let latest = metrics::get_latest_metrics_dates(&pool).await.unwrap();
+        assert_eq!(latest, vec![(user_id, "2026-06-05".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn test_session_cleanup_runs_hourly_against_the_clock() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        for (id, expires_at) in [
+            ("short", "2026-06-01T10:00:00+00:00"),
+            ("long", "2026-06-05T10:00:00+00:00"),
+        ] {
+            sqlx::query(
+                "INSERT INTO sessions (id, user_id, expires_at, created_at) VALUES (?, ?, ?, ?)",
+            )
+            .bind(id)
+            .bind(user_id)
+            .bind(expires_at)
+            .bind("2026-05-01T10:00:00+00:00")
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let clock = Arc::new(ManualClock::new(at("2026-06-01", 8)));
+        let mut scheduler = scheduler(&pool, &clock);
+        assert_eq!(scheduler.tick().await.sessions_removed, Some(0));
+
+        clock.advance(TimeDelta::minutes(59));
+        assert_eq!(scheduler.tick().await.sessions_removed, None);
+
+        clock.set(at("2026-06-01", 12));
+        assert_eq!(scheduler.tick().await.sessions_removed, Some(1));
+
+        clock.set(at("2026-06-06", 0));
+        assert_eq!(scheduler.tick().await.sessions_removed, Some(1));
+        assert_eq!(
+            sessions::cleanup_sessions_expired_at(&pool, clock.now())
+                .await
+                .unwrap(),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mesocycle_statuses_follow_the_calendar() {
+        let pool = setup_pool().await;
+        let user_id = create_test_user(&pool).await;
+        let (meso1, meso2) = create_test_plan(&pool, user_id).await;
+
+        let clock = Arc::new(ManualClock::new(at("2026-05-31", 6)));
+        let mut scheduler = scheduler(&pool, &clock);
+        let daily = scheduler.tick().await.daily.unwrap();
+        assert_eq!(daily.mesocycles, MesocycleStatusChanges::default());
+        assert_eq!(mesocycle_status(&pool, meso1).await, "pending");
+
+        // The block starts.
+        clock.advance(TimeDelta::days(1));
+        let daily = scheduler.tick().await.daily.unwrap();
+        assert_eq!(daily.mesocycles.activated, 1);
+        assert_eq!(mesocycle_status(&pool, meso1).await, "active");
+        // The empty follow-up is never activated by the calendar.
+        assert_eq!(mesocycle_status(&pool, meso2).await, "pending");
+
+        // Recovery week, last workout not done: not yet due.
+        clock.set(at("2026-06-16", 6));
+        assert!(
+            scheduler
+                .tick()
+                .await
+                .daily
+                .unwrap()
+                .transitions_due
+                .is_empty()
+        );
+
+        // The last non-rest workout is done: due before the end date.
+        sqlx::query(
+            "UPDATE planned_workouts SET is_completed = 1 WHERE scheduled_date = '2026-06-16'",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        clock.advance(TimeDelta::days(1));
+        let daily = scheduler.tick().await.daily.unwrap();
+        assert_eq!(daily.transitions_due, vec![meso1]);
+        assert_eq!(daily.transitions_flagged, vec![meso1]);
+        let flagged_at: Option<String> =
+            sqlx::query_scalar("SELECT transition_due_at FROM mesocycles WHERE id = ?")
+                .bind(meso1)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(flagged_at.as_deref(), Some("2026-06-17"));
+        // Flagging does not transition the block.
+        assert_eq!(mesocycle_status(&pool, meso1).await, "active");
+
+        // End date passed: still due, and not completed while the next block is empty.
+        sqlx::query("UPDATE planned_workouts SET is_completed = 0")
+            .execute(&pool)
+            .await
+            .unwrap();
+        clock.set(at("2026-06-22", 6));
+        let daily = scheduler.tick().await.daily.unwrap();
+        assert_eq!(daily.transitions_due, vec![meso1]);
+        // Already flagged; the athlete is not prompted again.
+        assert!(daily.transitions_flagged.is_empty());
+        assert_eq!(daily.mesocycles, MesocycleStatusChanges::default());
+        assert_eq!(mesocycle_status(&pool, meso1).await, "active");
+
+        // Once the next block has workouts, the calendar moves both along.
+        create_workout(&pool, meso2, user_id, "2026-06-23", "easy_run").await;
+        clock.advance(TimeDelta::days(1));
+        let daily = scheduler.tick().await.daily.unwrap();
+        assert!(daily.transitions_due.is_empty());
+        assert_eq!(
+            daily.mesocycles,
+            MesocycleStatusChanges {
+                completed: 1,
+                activated: 1
+            }
+        );
+        assert_eq!(mesocycle_status(&pool, meso1).await, "completed");
+        assert_eq!(mesocycle_status(&pool, meso2).await, "active");
+
+        // The final block completes when its end date passes.
+        clock.set(at("2026-07-13", 6));
+        let daily = scheduler.tick().await.daily.unwrap();
+        assert_eq!(daily.mesocycles.completed, 1);
+        assert_eq!(mesocycle_status(&pool, meso2).await, "completed");
+    }
+}
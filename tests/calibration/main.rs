@@ -0,0 +1,196 @@
+//! Calibration harness for the aerobic/anaerobic effect scores.
+//!
+//! Runs a synthetic workout of each reference type through
+//! `domain::effects` with the default coefficients and asserts the scores
+//! land in the ranges of PRODUCT_DESIGN §3.4. When tuning k1/k2/k3 or the
+//! scale factors, change the coefficients and keep these ranges green.
+//!
+//! | Workout                              | Aerobic   | Anaerobic |
+//! |--------------------------------------|-----------|-----------|
+//! | Easy run, 30 min Z1-2                | 1.0 - 1.9 | 0.0 - 0.5 |
+//! | Easy run, 60 min Z2                  | 2.0 - 2.9 | 0.0 - 0.5 |
+//! | Long run, 90 min Z2                  | 3.0 - 3.9 | 0.0 - 0.5 |
+//! | Long run + 6 x 20 s strides          | 3.0 - 3.9 | 0.5 - 1.5 |
+//! | Tempo run, 60 min Z3-4               | 3.5 - 4.5 | 1.0 - 2.0 |
+//! | VO2max, 5 x 4 min Z5                 | 4.0 - 4.9 | 2.5 - 3.5 |
+//! | Sprint repeats, 10 x 30 s            | 1.0 - 2.5 | 3.5 - 4.5 |
+//! | Race-pace intervals + sprint finish  | 3.0 - 4.5 | 4.0 - 5.0 |
+//!
+//! The spec gives no aerobic range for the last two; theirs only guard
+//! against regressions.
+
+mod synthetic;
+
+use std::ops::RangeInclusive;
+
+use coachjan::domain::effects::{
+    EffectCoefficients, calculate_aerobic_effect, calculate_anaerobic_effect,
+};
+use coachjan::fit::FitRecord;
+
+use synthetic::*;
+
+// ---------------------------------------------------------------------------
+// Harness
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Effects {
+    aerobic: f64,
+    anaerobic: f64,
+}
+
+fn score(records: &[FitRecord], ctl: f64) -> Effects {
+    let c = EffectCoefficients::default();
+    Effects {
+        aerobic: calculate_aerobic_effect(records, LTHR, RESTING_HR, MAX_HR, ctl, &c),
+        anaerobic: calculate_anaerobic_effect(
+            records,
+            LTHR,
+            RESTING_HR,
+            MAX_HR,
+            Some(FTPACE_M_PER_S),
+            ctl,
+            &c,
+        ),
+    }
+}
+
+fn assert_calibrated(
+    name: &str,
+    blocks: &[Block],
+    aerobic: RangeInclusive<f64>,
+    anaerobic: RangeInclusive<f64>,
+) {
+    let effects = score(&records(blocks), CTL);
+    assert!(
+        aerobic.contains(&effects.aerobic),
+        "{name}: aerobic effect {} outside {aerobic:?}",
+        effects.aerobic
+    );
+    assert!(
+        anaerobic.contains(&effects.anaerobic),
+        "{name}: anaerobic effect {} outside {anaerobic:?}",
+        effects.anaerobic
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Reference workouts
+// ---------------------------------------------------------------------------
+
+fn tempo_blocks() -> Vec<Block> {
+    vec![
+        block(10.0, 3.0, 135),
+        block(35.0, 4.65, 166),
+        block(15.0, 2.9, 130),
+    ]
+}
+
+fn vo2max_blocks() -> Vec<Block> {
+    let mut blocks = vec![WARM_UP];
+    blocks.extend(repeats(5, block(4.0, 5.0, 176), JOG));
+    blocks.push(COOL_DOWN);
+    blocks
+}
+
+#[test]
+fn easy_run_30_min() {
+    assert_calibrated("easy 30", &[block(30.0, 3.0, 138)], 1.0..=1.9, 0.0..=0.5);
+}
+
+#[test]
+fn easy_run_60_min() {
+    assert_calibrated(
+        "easy 60",
+        &[block(10.0, 2.9, 135), block(50.0, 3.2, 145)],
+        2.0..=2.9,
+        0.0..=0.5,
+    );
+}
+
+#[test]
+fn long_run_90_min() {
+    assert_calibrated(
+        "long run",
+        &[block(10.0, 2.9, 135), block(80.0, 3.3, 147)],
+        3.0..=3.9,
+        0.0..=0.5,
+    );
+}
+
+#[test]
+fn long_run_with_strides() {
+    let mut blocks = vec![block(10.0, 2.9, 135), block(70.0, 3.3, 147)];
+    blocks.extend(repeats(
+        6,
+        block(20.0 / 60.0, 5.5, 152),
+        block(100.0 / 60.0, 2.8, 140),
+    ));
+    blocks.push(block(5.0, 2.9, 135));
+    assert_calibrated("strides", &blocks, 3.0..=3.9, 0.5..=1.5);
+}
+
+#[test]
+fn tempo_run() {
+    assert_calibrated("tempo", &tempo_blocks(), 3.5..=4.5, 1.0..=2.0);
+}
+
+#[test]
+fn vo2max_intervals() {
+    assert_calibrated("vo2max", &vo2max_blocks(), 4.0..=4.9, 2.5..=3.5);
+}
+
+#[test]
+fn sprint_repeats() {
+    let mut blocks = vec![WARM_UP];
+    blocks.extend(repeats(10, block(0.5, 7.0, 175), block(3.0, 2.0, 120)));
+    blocks.push(COOL_DOWN);
+    assert_calibrated("sprints", &blocks, 1.0..=2.5, 3.5..=4.5);
+}
+
+#[test]
+fn race_pace_intervals_with_sprint_finish() {
+    let mut blocks = vec![WARM_UP];
+    blocks.extend(repeats(5, block(3.0, 5.1, 180), block(2.0, 2.8, 140)));
+    blocks.push(block(1.0, 6.5, 182));
+    blocks.push(COOL_DOWN);
+    assert_calibrated("race pace", &blocks, 3.0..=4.5, 4.0..=5.0);
+}
+
+// ---------------------------------------------------------------------------
+// Cross-workout properties
+// ---------------------------------------------------------------------------
+
+#[test]
+fn fitness_lowers_scores_for_the_same_session() {
+    let records = records(&vo2max_blocks());
+
+    let novice = score(&records, 25.0);
+    let trained = score(&records, 90.0);
+    assert!(
+        novice.aerobic > trained.aerobic,
+        "{novice:?} vs {trained:?}"
+    );
+    assert!(
+        novice.anaerobic > trained.anaerobic,
+        "{novice:?} vs {trained:?}"
+    );
+}
+
+#[test]
+fn harder_sessions_rank_above_easier_ones() {
+    let easy = score(
+        &records(&[block(10.0, 2.9, 135), block(50.0, 3.2, 145)]),
+        CTL,
+    );
+    let long = score(
+        &records(&[block(10.0, 2.9, 135), block(80.0, 3.3, 147)]),
+        CTL,
+    );
+    let tempo = score(&records(&tempo_blocks()), CTL);
+    let vo2max = score(&records(&vo2max_blocks()), CTL);
+
+    assert!(easy.aerobic < long.aerobic && long.aerobic < tempo.aerobic);
+    assert!(easy.anaerobic < tempo.anaerobic && tempo.anaerobic < vo2max.anaerobic);
+}
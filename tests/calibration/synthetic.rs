@@ -0,0 +1,80 @@
+//! Synthetic 1 Hz workouts for calibrating the effect scores.
+//!
+//! A workout is a list of blocks at a constant speed and target heart rate.
+//! Heart rate follows the target with a first-order lag, as it does on a real
+//! watch, so short sprints register in pace long before they show in HR.
+
+use chrono::DateTime;
+
+use coachjan::fit::FitRecord;
+
+/// Time constant of the HR response to a change in effort, in seconds.
+const HR_RESPONSE_S: f64 = 30.0;
+
+/// The reference athlete all calibration workouts are run for.
+pub const LTHR: u16 = 170;
+pub const RESTING_HR: u16 = 50;
+pub const MAX_HR: u16 = 185;
+pub const FTPACE_M_PER_S: f64 = 4.5;
+pub const CTL: f64 = 50.0;
+
+/// `seconds` at `speed_m_per_s` with HR settling towards `hr`.
+#[derive(Debug, Clone, Copy)]
+pub struct Block {
+    pub seconds: usize,
+    pub speed_m_per_s: f64,
+    pub hr: u16,
+}
+
+pub const fn block(minutes: f64, speed_m_per_s: f64, hr: u16) -> Block {
+    Block {
+        seconds: (minutes * 60.0) as usize,
+        speed_m_per_s,
+        hr,
+    }
+}
+
+/// `reps` repetitions of `work` separated by `recovery` (none after the last).
+pub fn repeats(reps: usize, work: Block, recovery: Block) -> Vec<Block> {
+    let mut blocks = Vec::with_capacity(reps * 2);
+    for rep in 0..reps {
+        if rep > 0 {
+            blocks.push(recovery);
+        }
+        blocks.push(work);
+    }
+    blocks
+}
+
+/// Expand blocks into a per-second record stream.
+pub fn records(blocks: &[Block]) -> Vec<FitRecord> {
+    let mut hr = f64::from(RESTING_HR) + 40.0;
+    let mut distance = 0.0;
+    blocks
+        .iter()
+        .flat_map(|b| std::iter::repeat_n(b, b.seconds))
+        .enumerate()
+        .map(|(i, b)| {
+            hr += (f64::from(b.hr) - hr) / HR_RESPONSE_S;
+            distance += b.speed_m_per_s;
+            FitRecord {
+                timestamp: DateTime::from_timestamp(1_772_521_200 + i as i64, 0).unwrap(),
+                timestamp_ms: i as u64 * 1000,
+                heart_rate: Some(hr.round() as u16),
+                speed_m_per_s: Some(b.speed_m_per_s),
+                distance_m: Some(distance),
+                altitude_m: Some(100.0),
+                cadence: Some(88),
+                power_watts: None,
+                latitude: None,
+                longitude: None,
+                temperature_c: None,
+            }
+        })
+        .collect()
+}
+
+// Common blocks for the reference athlete
+pub const WARM_UP: Block = block(15.0, 3.0, 135);
+pub const COOL_DOWN: Block = block(10.0, 2.9, 130);
+pub const JOG: Block = block(3.0, 2.8, 135);
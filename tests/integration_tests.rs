@@ -12,7 +12,7 @@ use http_body_util::BodyExt;
 use serde_json::{json, Value};
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
 use tower::ServiceExt;
-use wiremock::matchers::{method, path};
+use wiremock::matchers::{body_string_contains, method, path};
 use wiremock::{Mock, MockServer, ResponseTemplate};
 
 use coachjan::ai::client::ClaudeClient;
@@ -23,6 +23,16 @@ use coachjan::{build_app, AppState};
 // Test helpers
 // ---------------------------------------------------------------------------
 
+/// A fresh, per-app directory for uploaded FIT files so tests never share
+/// storage or write into the repository.
+fn test_fit_files_dir() -> String {
+    std::env::temp_dir()
+        .join("coachjan-tests")
+        .join(uuid::Uuid::new_v4().to_string())
+        .to_string_lossy()
+        .into_owned()
+}
+
 /// Build an Axum app backed by a fresh in-memory SQLite database.
 ///
 /// Each call creates a completely isolated database, so tests do not
@@ -49,6 +59,7 @@ async fn test_app() -> Router {
         host: "127.0.0.1".to_string(),
         port: 0,
         anthropic_api_key: None,
+        fit_files_dir: test_fit_files_dir(),
     };
 
     let state = AppState { db, config, claude_client: None };
@@ -126,6 +137,17 @@ fn put_json_authed(uri: &str, body: &Value, session_id: &str) -> Request<Body> {
         .unwrap()
 }
 
+/// Build an authenticated PATCH request with a JSON body.
+fn patch_json_authed(uri: &str, body: &Value, session_id: &str) -> Request<Body> {
+    Request::builder()
+        .method("PATCH")
+        .uri(uri)
+        .header("content-type", "application/json")
+        .header("cookie", format!("session_id={session_id}"))
+        .body(Body::from(serde_json::to_vec(body).unwrap()))
+        .unwrap()
+}
+
 /// Build a GET request (no auth).
 fn get_request(uri: &str) -> Request<Body> {
     Request::builder()
@@ -614,6 +636,158 @@ async fn update_profile_when_none_exists_returns_404() {
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
 
+#[tokio::test]
+async fn thresholds_record_history_and_update_zones() {
+    let app = test_app().await;
+    let (app, session_id) = register_user(app, "thresholds@example.com", "securepass123").await;
+
+    let response = send_request(
+        app.clone(),
+        get_authed("/api/athlete/thresholds", &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = send_request(
+        app.clone(),
+        post_json_authed("/api/athlete/profile", &valid_profile_body(), &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let original = body_json(response).await;
+
+    // A time trial sets both thresholds
+    let response = send_request(
+        app.clone(),
+        post_json_authed(
+            "/api/athlete/thresholds",
+            &json!({ "lthr": 174, "ftpace_m_per_s": 4.7, "source": "time_trial", "notes": "30 min TT" }),
+            &session_id,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let json = body_json(response).await;
+    assert_eq!(json["lthr"], 174);
+    assert_eq!(json["ftpace_m_per_s"], 4.7);
+    assert_ne!(json["hr_zones"], original["hr_zones"]);
+    assert_ne!(json["pace_zones"], original["pace_zones"]);
+    assert_eq!(json["lthr_history"].as_array().unwrap().len(), 2);
+    assert_eq!(json["lthr_history"][1]["source"], "time_trial");
+    assert_eq!(json["lthr_history"][1]["notes"], "30 min TT");
+
+    // Editing the profile directly is recorded as a manual change
+    let response = send_request(
+        app.clone(),
+        put_json_authed(
+            "/api/athlete/profile",
+            &json!({ "ftpace_m_per_s": 4.6 }),
+            &session_id,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = send_request(
+        app.clone(),
+        get_authed("/api/athlete/thresholds", &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = body_json(response).await;
+    let sources: Vec<&str> = json["ftpace_history"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["source"].as_str().unwrap())
+        .collect();
+    assert_eq!(sources, vec!["estimate", "time_trial", "manual"]);
+    assert_eq!(json["ftpace_m_per_s"], 4.6);
+
+    let response = send_request(
+        app,
+        post_json_authed(
+            "/api/athlete/thresholds",
+            &json!({ "lthr": 172, "source": "guess" }),
+            &session_id,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn race_result_proposes_ftpace_and_predicts_goal_time() {
+    let app = test_app().await;
+    let (app, session_id) = register_user(app, "raceresult@example.com", "securepass123").await;
+    let response = send_request(
+        app.clone(),
+        post_json_authed("/api/athlete/profile", &valid_profile_body(), &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    // 10K in 40:00
+    let response = send_request(
+        app.clone(),
+        post_json_authed(
+            "/api/athlete/race-results",
+            &json!({ "distance_m": 10000.0, "time_seconds": 2400, "race_date": "2026-09-20" }),
+            &session_id,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let json = body_json(response).await;
+    assert_eq!(json["race_result"]["time_seconds"], 2400);
+    assert_eq!(json["current_ftpace_m_per_s"], 4.5);
+    let proposal = json["proposed_thresholds"].clone();
+    let ftpace = proposal["ftpace_m_per_s"].as_f64().unwrap();
+    assert!((ftpace - 4.072).abs() < 0.001, "got {ftpace}");
+    assert_eq!(proposal["source"], "race");
+    assert_eq!(proposal["notes"], "10K in 40:00 on 2026-09-20");
+    assert_eq!(json["predictions"].as_array().unwrap().len(), 5);
+    // The goal race is a marathon
+    assert_eq!(json["goal_prediction"]["distance_m"], 42195.0);
+    assert!(json["goal_prediction"]["time_seconds"].as_i64().unwrap() > 3 * 3600);
+
+    // The proposal isn't applied until the athlete accepts it
+    let response = send_request(
+        app.clone(),
+        get_authed("/api/athlete/thresholds", &session_id),
+    )
+    .await;
+    assert_eq!(body_json(response).await["ftpace_m_per_s"], 4.5);
+
+    let response = send_request(
+        app.clone(),
+        post_json_authed("/api/athlete/thresholds", &proposal, &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let json = body_json(response).await;
+    assert_eq!(json["ftpace_m_per_s"].as_f64(), Some(ftpace));
+    let latest = json["ftpace_history"]
+        .as_array()
+        .unwrap()
+        .last()
+        .unwrap()
+        .clone();
+    assert_eq!(latest["source"], "race");
+    assert_eq!(latest["notes"], "10K in 40:00 on 2026-09-20");
+
+    let response = send_request(
+        app,
+        post_json_authed(
+            "/api/athlete/race-results",
+            &json!({ "distance_m": 10000.0, "time_seconds": 2400, "race_date": "2999-01-01" }),
+            &session_id,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
 #[tokio::test]
 async fn me_shows_has_profile_true_after_creation() {
     let app = test_app().await;
@@ -745,6 +919,12 @@ async fn root_returns_hello() {
 /// Build an Axum app backed by a fresh in-memory SQLite database with a
 /// ClaudeClient pointing at the given wiremock server URL.
 async fn test_app_with_claude(mock_server_uri: &str) -> Router {
+    test_app_with_claude_and_pool(mock_server_uri).await.0
+}
+
+/// Like `test_app_with_claude`, but also returns the underlying SQLite pool
+/// so tests can seed plan data directly.
+async fn test_app_with_claude_and_pool(mock_server_uri: &str) -> (Router, sqlx::SqlitePool) {
     let connect_options = SqliteConnectOptions::new()
         .filename(":memory:")
         .create_if_missing(true)
@@ -771,14 +951,16 @@ async fn test_app_with_claude(mock_server_uri: &str) -> Router {
         host: "127.0.0.1".to_string(),
         port: 0,
         anthropic_api_key: Some("test-api-key".to_string()),
+        fit_files_dir: test_fit_files_dir(),
     };
 
+    let pool = db.clone();
     let state = AppState {
         db,
         config,
         claude_client: Some(Arc::new(client)),
     };
-    build_app(state)
+    (build_app(state), pool)
 }
 
 /// Build a Claude API response wrapping a tool_use block.
@@ -805,7 +987,10 @@ async fn setup_user_with_profile(app: Router) -> (Router, String, i64) {
     let (app, session_id) =
         register_user(app, "planner@example.com", "securepass123").await;
 
-    let profile_body = valid_profile_body();
+    // Seven weeks out, so the plan flow's skeleton fits the race date
+    let race_date = chrono::Utc::now().date_naive() + chrono::Duration::weeks(7);
+    let mut profile_body = valid_profile_body();
+    profile_body["race_date"] = json!(race_date.format("%Y-%m-%d").to_string());
     let response = send_request(
         app.clone(),
         post_json_authed("/api/athlete/profile", &profile_body, &session_id),
@@ -880,6 +1065,12 @@ async fn generate_plan_returns_macrocycle_skeleton() {
     assert_eq!(response.status(), StatusCode::CREATED);
 
     let json = body_json(response).await;
+    // The skeleton is stored as a draft, returned with its id and expiry
+    assert!(json["id"].is_number());
+    assert_eq!(json["race_goal_id"], race_goal_id);
+    assert_eq!(json["version"], 1);
+    assert_eq!(json["status"], "draft");
+    assert!(json["expires_at"].is_string());
     assert_eq!(json["target_ctl"], 55.0);
     assert!(json["coach_message"]
         .as_str()
@@ -890,10 +1081,9 @@ async fn generate_plan_returns_macrocycle_skeleton() {
     assert_eq!(json["mesocycles"][1]["phase"], "utilization");
 }
 
-#[tokio::test]
-async fn full_plan_generation_and_confirm_flow() {
-    let mock_server = MockServer::start().await;
-
+/// Tool inputs for the generate + confirm flow: the macrocycle skeleton, a
+/// valid 4-week mesocycle plan starting today, and coach notes for it.
+fn plan_flow_inputs() -> (Value, Value, Value) {
     // We need to compute dates the same way the handler will so our mock
     // mesocycle plan returns matching dates.
     let today = chrono::Utc::now().date_naive();
@@ -1017,6 +1207,14 @@ async fn full_plan_generation_and_confirm_flow() {
         ]
     });
 
+    (skeleton_input, mesocycle_plan_input, coach_notes_input)
+}
+
+#[tokio::test]
+async fn full_plan_generation_and_confirm_flow() {
+    let mock_server = MockServer::start().await;
+    let (skeleton_input, mesocycle_plan_input, coach_notes_input) = plan_flow_inputs();
+
     // Mount mocks in sequence:
     // Call 1 (generate): returns skeleton
     // Call 2 (confirm - mesocycle plan): returns mesocycle plan
@@ -1083,10 +1281,11 @@ async fn full_plan_generation_and_confirm_flow() {
         2
     );
 
-    // Step 2: Confirm the plan (which generates mesocycle workouts + coach notes)
+    // Step 2: Confirm the draft (which generates mesocycle workouts + coach notes)
+    let confirm_body = json!({ "draft_id": skeleton_json["id"] });
     let response = send_request(
         app.clone(),
-        post_json_authed("/api/plan/confirm", &skeleton_json, &session_id),
+        post_json_authed("/api/plan/confirm", &confirm_body, &session_id),
     )
     .await;
     assert_eq!(
@@ -1106,6 +1305,8 @@ async fn full_plan_generation_and_confirm_flow() {
     assert_eq!(mesocycles.len(), 2);
     assert_eq!(mesocycles[0]["phase"], "capacity");
     assert_eq!(mesocycles[1]["phase"], "utilization");
+    assert_eq!(mesocycles[0]["status"], "active");
+    assert_eq!(mesocycles[1]["status"], "pending");
 
     let workouts = plan_json["workouts"].as_array().unwrap();
     // 4 weeks * 7 days = 28 workouts
@@ -1130,9 +1331,28 @@ async fn full_plan_generation_and_confirm_flow() {
         "Some non-rest workouts should have coach notes"
     );
 
+    // The recovery week's last easy run became a time trial
+    let time_trials: Vec<&Value> = workouts
+        .iter()
+        .filter(|w| w["workout_type"] == "time_trial")
+        .collect();
+    assert_eq!(time_trials.len(), 1);
+    let expected_date = chrono::Utc::now().date_naive() + chrono::Duration::days(25);
+    assert_eq!(
+        time_trials[0]["scheduled_date"],
+        expected_date.format("%Y-%m-%d").to_string()
+    );
+    assert_eq!(time_trials[0]["duration_category"], "long");
+    assert!(
+        time_trials[0]["coach_notes"]
+            .as_str()
+            .unwrap()
+            .contains("Changed from Easy Run to a 30-minute time trial")
+    );
+
     // Step 3: Verify GET /api/plan returns the persisted plan
     let response = send_request(
-        app,
+        app.clone(),
         get_authed("/api/plan", &session_id),
     )
     .await;
@@ -1148,6 +1368,14 @@ async fn full_plan_generation_and_confirm_flow() {
     // Workouts are now nested inside each mesocycle
     let first_meso_workouts = get_plan_json["mesocycles"][0]["workouts"].as_array().unwrap();
     assert_eq!(first_meso_workouts.len(), 28);
+
+    // A confirmed draft cannot be confirmed again
+    let response = send_request(
+        app,
+        post_json_authed("/api/plan/confirm", &confirm_body, &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
 
 #[tokio::test]
@@ -1197,307 +1425,2692 @@ async fn generate_plan_without_profile_returns_404() {
     assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
 
-#[tokio::test]
-async fn get_plan_without_plan_returns_404() {
-    let app = test_app().await;
-    let (app, session_id) =
-        register_user(app, "noplan@example.com", "securepass123").await;
+/// Register a user whose race is `weeks` weeks from today and generate a
+/// skeleton draft from `plan_flow_inputs` (7 weeks, 40 then 45 km).
+/// Returns (app, session_id, draft).
+async fn setup_draft(
+    mock_server: &MockServer,
+    app: Router,
+    email: &str,
+    weeks: i64,
+) -> (Router, String, Value) {
+    let (skeleton_input, _, _) = plan_flow_inputs();
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(claude_tool_use_response(
+                "generate_macrocycle_skeleton",
+                skeleton_input,
+            )),
+        )
+        .up_to_n_times(1)
+        .mount(mock_server)
+        .await;
 
+    let (app, session_id) = register_user(app, email, "securepass123").await;
+    let race_date = chrono::Utc::now().date_naive() + chrono::Duration::weeks(weeks);
+    let mut profile_body = valid_profile_body();
+    profile_body["race_date"] = json!(race_date.format("%Y-%m-%d").to_string());
     let response = send_request(
-        app,
-        get_authed("/api/plan", &session_id),
+        app.clone(),
+        post_json_authed("/api/athlete/profile", &profile_body, &session_id),
     )
     .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let race_goal_id = body_json(response).await["race_goal"]["id"].clone();
 
-    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = send_request(
+        app.clone(),
+        post_json_authed(
+            "/api/plan/generate",
+            &json!({ "race_goal_id": race_goal_id }),
+            &session_id,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let draft = body_json(response).await;
+    (app, session_id, draft)
 }
 
-// ===========================================================================
-// Phase 3 endpoint tests — GET /api/plan and GET /api/plan/workout/:id
-// ===========================================================================
-
-/// Build a test app *and* return a handle to the underlying SQLite pool so
-/// tests can insert seed data directly without needing Claude mocks.
-async fn test_app_with_pool() -> (Router, sqlx::SqlitePool) {
-    let connect_options = SqliteConnectOptions::new()
-        .filename(":memory:")
-        .create_if_missing(true)
-        .pragma("foreign_keys", "ON");
+#[tokio::test]
+async fn draft_edits_are_validated_and_versioned() {
+    let mock_server = MockServer::start().await;
+    let app = test_app_with_claude(&mock_server.uri()).await;
+    let (app, session_id, draft) = setup_draft(&mock_server, app, "drafts@example.com", 7).await;
+    let uri = format!("/api/plan/drafts/{}", draft["id"]);
 
-    let db = SqlitePoolOptions::new()
-        .max_connections(1)
-        .connect_with(connect_options)
-        .await
-        .expect("Failed to create in-memory SQLite pool");
+    let response = send_request(app.clone(), get_authed(&uri, &session_id)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body_json(response).await["mesocycles"][0]["load_weeks"], 3);
+
+    // Each edit that breaks a rule is rejected with the reason
+    for (patch, reason) in [
+        (
+            json!({"sequence_number": 1, "load_weeks": 40}),
+            "load_weeks",
+        ),
+        (
+            json!({"sequence_number": 2, "recovery_weeks": 5}),
+            "recovery_weeks",
+        ),
+        (
+            json!({"sequence_number": 2, "target_volume_km": 60.0}),
+            "volume rises",
+        ),
+        (
+            json!({"sequence_number": 1, "target_volume_km": -5.0}),
+            "target_volume_km",
+        ),
+        (
+            json!({"sequence_number": 1, "load_weeks": 4}),
+            "after the race",
+        ),
+        (
+            json!({"sequence_number": 1, "load_weeks": 1}),
+            "before the race",
+        ),
+        (
+            json!({"sequence_number": 9, "load_weeks": 2}),
+            "does not exist",
+        ),
+    ] {
+        let response = send_request(
+            app.clone(),
+            patch_json_authed(&uri, &json!({ "mesocycles": [patch] }), &session_id),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST, "{patch}");
+        let error = body_json(response).await["error"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        assert!(error.contains(reason), "{patch}: {error}");
+    }
 
-    sqlx::migrate!("./migrations")
-        .run(&db)
-        .await
-        .expect("Failed to run migrations");
+    // Moving a load week between mesocycles keeps the race date and volumes valid
+    let patch = json!({ "mesocycles": [
+        {"sequence_number": 1, "load_weeks": 2, "target_volume_km": 42.0},
+        {"sequence_number": 2, "load_weeks": 3},
+    ]});
+    let response = send_request(app.clone(), patch_json_authed(&uri, &patch, &session_id)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = body_json(response).await;
+    assert_eq!(json["version"], 2);
+    assert_eq!(json["mesocycles"][0]["load_weeks"], 2);
+    assert_eq!(json["mesocycles"][0]["target_volume_km"], 42.0);
+    assert_eq!(json["mesocycles"][1]["load_weeks"], 3);
+    assert_eq!(json["mesocycles"][1]["phase"], "utilization");
 
-    let config = Config {
-        database_url: String::new(),
-        host: "127.0.0.1".to_string(),
-        port: 0,
-        anthropic_api_key: None,
-    };
+    let response = send_request(app.clone(), get_authed(&uri, &session_id)).await;
+    assert_eq!(body_json(response).await["version"], 2);
 
-    let pool = db.clone();
-    let state = AppState { db, config, claude_client: None };
-    (build_app(state), pool)
+    // Drafts are private
+    let (app, other_session) =
+        register_user(app, "other-drafts@example.com", "securepass123").await;
+    let response = send_request(app.clone(), get_authed(&uri, &other_session)).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let response = send_request(app, patch_json_authed(&uri, &patch, &other_session)).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
 }
 
-/// Register a user and create an athlete profile (with race goal) using the
-/// HTTP API, then insert a macrocycle + mesocycle + workouts directly into the
-/// database. Returns (app, session_id, pool) so callers can do further queries.
-async fn setup_plan_data(
-    app: Router,
-    pool: &sqlx::SqlitePool,
-    email: &str,
-) -> (Router, String, i64, i64) {
-    // Register user & create profile through the API so auth session exists
-    let (app, session_id) = register_user(app, email, "securepass123").await;
+#[tokio::test]
+async fn expired_draft_cannot_be_confirmed() {
+    let mock_server = MockServer::start().await;
+    let (app, pool) = test_app_with_claude_and_pool(&mock_server.uri()).await;
+    let (app, session_id, draft) = setup_draft(&mock_server, app, "expired@example.com", 7).await;
 
-    let profile_body = valid_profile_body();
-    let resp = send_request(
+    sqlx::query("UPDATE plan_drafts SET expires_at = '2020-01-01T00:00:00+00:00' WHERE id = ?")
+        .bind(draft["id"].as_i64().unwrap())
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let response = send_request(
         app.clone(),
-        post_json_authed("/api/athlete/profile", &profile_body, &session_id),
+        post_json_authed(
+            "/api/plan/confirm",
+            &json!({ "draft_id": draft["id"] }),
+            &session_id,
+        ),
     )
     .await;
-    assert_eq!(resp.status(), StatusCode::CREATED);
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
 
-    let json = body_json(resp).await;
-    let race_goal_id = json["race_goal"]["id"]
-        .as_i64()
-        .expect("race_goal should have an id");
+    let response = send_request(
+        app,
+        get_authed(&format!("/api/plan/drafts/{}", draft["id"]), &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
 
-    // Look up the user_id from the DB (via session)
-    let user_row = sqlx::query("SELECT user_id FROM sessions WHERE id = ?")
-        .bind(&session_id)
-        .fetch_one(pool)
-        .await
-        .expect("should find session");
-    let user_id: i64 = sqlx::Row::get(&user_row, "user_id");
+    // Only the skeleton was generated; confirm never reached Claude
+    assert_eq!(mock_server.received_requests().await.unwrap().len(), 1);
+}
 
-    // Insert macrocycle directly
-    let mc_row = sqlx::query(
-        r#"INSERT INTO macrocycles (user_id, race_goal_id, start_date, end_date, target_ctl, coach_message)
-           VALUES (?, ?, '2026-03-01', '2026-09-27', 65.0, 'Building your aerobic base.')
-           RETURNING id"#,
-    )
-    .bind(user_id)
-    .bind(race_goal_id)
-    .fetch_one(pool)
-    .await
-    .expect("create macrocycle");
-    let macrocycle_id: i64 = sqlx::Row::get(&mc_row, "id");
+#[tokio::test]
+async fn confirm_rejects_a_stored_skeleton_that_no_longer_validates() {
+    let mock_server = MockServer::start().await;
+    let (app, pool) = test_app_with_claude_and_pool(&mock_server.uri()).await;
+    let (app, session_id, draft) = setup_draft(&mock_server, app, "stale@example.com", 7).await;
 
-    // Insert two mesocycles
-    let meso1_row = sqlx::query(
-        r#"INSERT INTO mesocycles (macrocycle_id, sequence_number, phase, focus, load_weeks, recovery_weeks, target_volume_km, start_date, end_date)
-           VALUES (?, 1, 'capacity', 'aerobic_capacity', 3, 1, 160.0, '2026-03-01', '2026-03-28')
-           RETURNING id"#,
+    sqlx::query(
+        "UPDATE plan_drafts SET skeleton = json_set(skeleton, '$.mesocycles[0].target_volume_km', 0) WHERE id = ?",
     )
-    .bind(macrocycle_id)
-    .fetch_one(pool)
+    .bind(draft["id"].as_i64().unwrap())
+    .execute(&pool)
     .await
-    .expect("create mesocycle 1");
-    let meso1_id: i64 = sqlx::Row::get(&meso1_row, "id");
+    .unwrap();
 
-    let meso2_row = sqlx::query(
-        r#"INSERT INTO mesocycles (macrocycle_id, sequence_number, phase, focus, load_weeks, recovery_weeks, target_volume_km, start_date, end_date)
-           VALUES (?, 2, 'utilization', 'race_specific', 2, 1, 140.0, '2026-03-29', '2026-04-18')
-           RETURNING id"#,
+    let response = send_request(
+        app.clone(),
+        post_json_authed(
+            "/api/plan/confirm",
+            &json!({ "draft_id": draft["id"] }),
+            &session_id,
+        ),
     )
-    .bind(macrocycle_id)
-    .fetch_one(pool)
-    .await
-    .expect("create mesocycle 2");
-    let meso2_id: i64 = sqlx::Row::get(&meso2_row, "id");
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 
-    // Insert workouts into mesocycle 1
-    for (i, (wtype, dur, tss, dist)) in [
-        ("easy_run",  Some(45i64), Some(35.0), Some(8.5)),
-        ("tempo_run", Some(50),    Some(70.0), Some(10.0)),
-        ("rest",      None,        None,       None),
-    ]
-    .iter()
-    .enumerate()
-    {
-        let date = format!("2026-03-{:02}", 3 + i);
-        sqlx::query(
-            r#"INSERT INTO planned_workouts
-                (mesocycle_id, user_id, scheduled_date, workout_type, duration_min, expected_tss, target_distance_km, coach_notes)
-               VALUES (?, ?, ?, ?, ?, ?, ?, 'Keep focused.')"#,
+    // The draft was not claimed and confirm never reached Claude
+    let response = send_request(
+        app,
+        get_authed(&format!("/api/plan/drafts/{}", draft["id"]), &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(mock_server.received_requests().await.unwrap().len(), 1);
+}
+
+/// Answer the next Claude call, once, with a `tool_name` call.
+async fn mount_tool_response_once(mock_server: &MockServer, tool_name: &str, input: Value) {
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(claude_tool_use_response(tool_name, input)),
         )
-        .bind(meso1_id)
-        .bind(user_id)
-        .bind(&date)
-        .bind(wtype)
-        .bind(dur)
-        .bind(tss)
-        .bind(dist)
-        .execute(pool)
-        .await
-        .expect("create workout in meso1");
-    }
+        .up_to_n_times(1)
+        .mount(mock_server)
+        .await;
+}
 
-    // Insert one workout into mesocycle 2
-    sqlx::query(
-        r#"INSERT INTO planned_workouts
-            (mesocycle_id, user_id, scheduled_date, workout_type, duration_min, expected_tss, target_distance_km)
-           VALUES (?, ?, '2026-04-01', 'long_run', 90, 120.0, 18.0)"#,
+/// The tool results sent to Claude with the `index`th request.
+async fn sent_tool_results(mock_server: &MockServer, index: usize) -> Vec<String> {
+    let requests = mock_server.received_requests().await.unwrap();
+    let body: Value = serde_json::from_slice(&requests[index].body).unwrap();
+    body["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .flat_map(|m| m["content"].as_array().unwrap())
+        .filter(|block| block["type"] == "tool_result")
+        .map(|block| block["content"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn confirm_retry_sends_validation_errors_to_claude() {
+    let mock_server = MockServer::start().await;
+    let app = test_app_with_claude(&mock_server.uri()).await;
+    let (app, session_id, draft) = setup_draft(&mock_server, app, "retry@example.com", 7).await;
+
+    // A second long run in week 1 is not something the repair pass fixes
+    let (_, mesocycle_plan_input, coach_notes_input) = plan_flow_inputs();
+    let mut invalid_plan = mesocycle_plan_input.clone();
+    invalid_plan["weeks"][0]["days"][0]["workout_type"] = json!("long_run");
+    let week_1 = &invalid_plan["weeks"][0]["days"];
+    let expected = format!(
+        "Week 1 ({} to {}): 2 long runs ({}, {}), at most 1 allowed",
+        week_1[0]["date"].as_str().unwrap(),
+        week_1[6]["date"].as_str().unwrap(),
+        week_1[0]["date"].as_str().unwrap(),
+        week_1[5]["date"].as_str().unwrap()
+    );
+    mount_tool_response_once(&mock_server, "generate_mesocycle_plan", invalid_plan).await;
+    mount_tool_response_once(
+        &mock_server,
+        "generate_mesocycle_plan",
+        mesocycle_plan_input,
     )
-    .bind(meso2_id)
-    .bind(user_id)
-    .execute(pool)
-    .await
-    .expect("create workout in meso2");
+    .await;
+    mount_tool_response_once(&mock_server, "add_coach_notes", coach_notes_input).await;
 
-    (app, session_id, macrocycle_id, user_id)
+    let response = send_request(
+        app,
+        post_json_authed(
+            "/api/plan/confirm",
+            &json!({ "draft_id": draft["id"] }),
+            &session_id,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    // skeleton, first plan, retry, coach notes
+    assert_eq!(mock_server.received_requests().await.unwrap().len(), 4);
+    assert!(sent_tool_results(&mock_server, 1).await.is_empty());
+    let feedback = sent_tool_results(&mock_server, 2).await;
+    assert_eq!(feedback.len(), 1);
+    assert!(feedback[0].contains(&expected), "{}", feedback[0]);
+
+    // The retry answers the first attempt's tool call
+    let requests = mock_server.received_requests().await.unwrap();
+    let body: Value = serde_json::from_slice(&requests[2].body).unwrap();
+    let messages = body["messages"].as_array().unwrap();
+    assert_eq!(messages.len(), 3);
+    assert_eq!(messages[1]["content"][0]["name"], "generate_mesocycle_plan");
+    assert_eq!(
+        messages[2]["content"][0]["tool_use_id"],
+        messages[1]["content"][0]["id"]
+    );
 }
 
 #[tokio::test]
-async fn get_plan_returns_mesocycles_with_nested_workouts() {
-    let (app, pool) = test_app_with_pool().await;
-    let (app, session_id, _mc_id, _user_id) =
-        setup_plan_data(app, &pool, "planview@example.com").await;
+async fn confirm_repairs_missing_rest_day_without_claude() {
+    let mock_server = MockServer::start().await;
+    let app = test_app_with_claude(&mock_server.uri()).await;
+    let (app, session_id, draft) = setup_draft(&mock_server, app, "repair@example.com", 7).await;
 
-    let response = send_request(app, get_authed("/api/plan", &session_id)).await;
-    assert_eq!(response.status(), StatusCode::OK);
+    // Week 1 loses its rest and recovery days
+    let (_, mut mesocycle_plan_input, coach_notes_input) = plan_flow_inputs();
+    for day in mesocycle_plan_input["weeks"][0]["days"]
+        .as_array_mut()
+        .unwrap()
+    {
+        if day["workout_type"] == "rest" || day["workout_type"] == "recovery_run" {
+            day["workout_type"] = json!("easy_run");
+            day["duration_category"] = json!("medium");
+        }
+    }
+    mount_tool_response_once(
+        &mock_server,
+        "generate_mesocycle_plan",
+        mesocycle_plan_input,
+    )
+    .await;
+    mount_tool_response_once(&mock_server, "add_coach_notes", coach_notes_input).await;
 
-    let json = body_json(response).await;
+    let response = send_request(
+        app,
+        post_json_authed(
+            "/api/plan/confirm",
+            &json!({ "draft_id": draft["id"] }),
+            &session_id,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
 
-    // Top-level structure
-    assert!(json["macrocycle"]["id"].is_number(), "macrocycle should have an id");
-    assert_eq!(json["macrocycle"]["status"], "active");
-    assert_eq!(json["macrocycle"]["target_ctl"], 65.0);
+    // skeleton, plan, coach notes: no retry
+    assert_eq!(mock_server.received_requests().await.unwrap().len(), 3);
 
-    // Mesocycles array
-    let mesocycles = json["mesocycles"]
+    let json = body_json(response).await;
+    let repaired: Vec<&Value> = json["workouts"]
         .as_array()
-        .expect("mesocycles should be an array");
-    assert_eq!(mesocycles.len(), 2);
+        .unwrap()
+        .iter()
+        .filter(|w| {
+            w["coach_notes"]
+                .as_str()
+                .is_some_and(|n| n.contains("to a rest day"))
+        })
+        .collect();
+    assert_eq!(repaired.len(), 1);
+    assert_eq!(repaired[0]["workout_type"], "rest");
+}
 
-    // First mesocycle
-    assert_eq!(mesocycles[0]["phase"], "capacity");
-    assert_eq!(mesocycles[0]["focus"], "aerobic_capacity");
-    assert_eq!(mesocycles[0]["sequence_number"], 1);
-    let meso1_workouts = mesocycles[0]["workouts"]
-        .as_array()
-        .expect("mesocycle 1 should have workouts array");
-    assert_eq!(meso1_workouts.len(), 3);
-    assert_eq!(meso1_workouts[0]["workout_type"], "easy_run");
-    assert_eq!(meso1_workouts[0]["target_distance_km"], 8.5);
-    assert_eq!(meso1_workouts[1]["workout_type"], "tempo_run");
-    assert_eq!(meso1_workouts[1]["target_distance_km"], 10.0);
-    assert_eq!(meso1_workouts[2]["workout_type"], "rest");
-    assert!(
-        meso1_workouts[2]["target_distance_km"].is_null(),
-        "rest workout should have null target_distance_km"
-    );
+#[tokio::test]
+async fn confirm_caps_first_week_volume_against_the_athletes_current_volume() {
+    let mock_server = MockServer::start().await;
+    let app = test_app_with_claude(&mock_server.uri()).await;
+    let (app, session_id, draft) = setup_draft(&mock_server, app, "baseline@example.com", 7).await;
 
-    // Second mesocycle
-    assert_eq!(mesocycles[1]["phase"], "utilization");
-    assert_eq!(mesocycles[1]["focus"], "race_specific");
-    assert_eq!(mesocycles[1]["sequence_number"], 2);
-    let meso2_workouts = mesocycles[1]["workouts"]
+    // The plan opens at 40 km, more than 10% above the athlete's 35 km
+    let response = send_request(
+        app.clone(),
+        put_json_authed(
+            "/api/athlete/profile",
+            &json!({ "current_weekly_volume_km": 35.0 }),
+            &session_id,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (_, mesocycle_plan_input, coach_notes_input) = plan_flow_inputs();
+    mount_tool_response_once(
+        &mock_server,
+        "generate_mesocycle_plan",
+        mesocycle_plan_input,
+    )
+    .await;
+    mount_tool_response_once(&mock_server, "add_coach_notes", coach_notes_input).await;
+
+    let response = send_request(
+        app,
+        post_json_authed(
+            "/api/plan/confirm",
+            &json!({ "draft_id": draft["id"] }),
+            &session_id,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert_eq!(mock_server.received_requests().await.unwrap().len(), 3);
+
+    // Only week 1 is capped; week 2 is compared with the capped 38.4 km
+    let json = body_json(response).await;
+    let capped: Vec<&str> = json["workouts"]
         .as_array()
-        .expect("mesocycle 2 should have workouts array");
-    assert_eq!(meso2_workouts.len(), 1);
-    assert_eq!(meso2_workouts[0]["workout_type"], "long_run");
-    assert_eq!(meso2_workouts[0]["target_distance_km"], 18.0);
-    assert_eq!(meso2_workouts[0]["duration_min"], 90);
+        .unwrap()
+        .iter()
+        .filter_map(|w| w["coach_notes"].as_str())
+        .filter(|n| n.contains("Weekly volume target lowered"))
+        .collect();
+    assert_eq!(capped.len(), 1);
+    assert!(capped[0].contains("from 40.0 km to 38.4 km"));
 }
 
 #[tokio::test]
-async fn get_workout_returns_workout_with_mesocycle_context() {
-    let (app, pool) = test_app_with_pool().await;
-    let (app, session_id, _mc_id, _user_id) =
-        setup_plan_data(app, &pool, "workoutview@example.com").await;
+async fn threshold_change_re_resolves_upcoming_workouts() {
+    let mock_server = MockServer::start().await;
+    let app = test_app_with_claude(&mock_server.uri()).await;
+    let (app, session_id, draft) = setup_draft(&mock_server, app, "rezone@example.com", 7).await;
+    let (_, mesocycle_plan_input, coach_notes_input) = plan_flow_inputs();
+    mount_tool_response_once(
+        &mock_server,
+        "generate_mesocycle_plan",
+        mesocycle_plan_input,
+    )
+    .await;
+    mount_tool_response_once(&mock_server, "add_coach_notes", coach_notes_input).await;
 
-    // Find the first workout id (easy_run on 2026-03-03)
-    let w_row = sqlx::query(
-        "SELECT id FROM planned_workouts WHERE scheduled_date = '2026-03-03' ORDER BY id LIMIT 1",
+    let response = send_request(
+        app.clone(),
+        post_json_authed(
+            "/api/plan/confirm",
+            &json!({ "draft_id": draft["id"] }),
+            &session_id,
+        ),
     )
-    .fetch_one(&pool)
-    .await
-    .expect("find workout");
-    let workout_id: i64 = sqlx::Row::get(&w_row, "id");
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let planned = body_json(response).await["workouts"].clone();
+    let planned = planned.as_array().unwrap();
+    assert!(planned[0]["description"].as_str().unwrap().contains("bpm"));
 
-    let uri = format!("/api/plan/workout/{workout_id}");
-    let response = send_request(app, get_authed(&uri, &session_id)).await;
+    // Today's run is done before the new LTHR comes in
+    let done_id = planned[0]["id"].as_i64().unwrap();
+    let response = send_request(
+        app.clone(),
+        post_json_authed(
+            &format!("/api/plan/workouts/{done_id}/complete"),
+            &json!({ "rpe": 4 }),
+            &session_id,
+        ),
+    )
+    .await;
     assert_eq!(response.status(), StatusCode::OK);
 
+    let response = send_request(
+        app.clone(),
+        post_json_authed(
+            "/api/athlete/thresholds",
+            &json!({ "lthr": 160, "source": "race" }),
+            &session_id,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
     let json = body_json(response).await;
+    let updated = json["updated_workouts"].as_array().unwrap();
 
-    // Workout fields
-    assert_eq!(json["workout"]["id"], workout_id);
-    assert_eq!(json["workout"]["workout_type"], "easy_run");
-    assert_eq!(json["workout"]["scheduled_date"], "2026-03-03");
-    assert_eq!(json["workout"]["duration_min"], 45);
-    assert_eq!(json["workout"]["expected_tss"], 35.0);
-    assert_eq!(json["workout"]["target_distance_km"], 8.5);
-    assert_eq!(json["workout"]["coach_notes"], "Keep focused.");
-    assert_eq!(json["workout"]["is_completed"], 0);
+    // Every open run is updated; the completed run, rest days and strength
+    // sessions are not
+    let expected: Vec<i64> = planned[1..]
+        .iter()
+        .filter(|w| !["rest", "strength_power"].contains(&w["workout_type"].as_str().unwrap()))
+        .map(|w| w["id"].as_i64().unwrap())
+        .collect();
+    let ids: Vec<i64> = updated.iter().map(|w| w["id"].as_i64().unwrap()).collect();
+    assert_eq!(ids, expected);
+    for workout in updated {
+        let before = planned.iter().find(|w| w["id"] == workout["id"]).unwrap();
+        assert_ne!(workout["description"], before["description"]);
+    }
 
-    // Mesocycle context
-    assert!(json["mesocycle"]["id"].is_number());
-    assert_eq!(json["mesocycle"]["phase"], "capacity");
-    assert_eq!(json["mesocycle"]["focus"], "aerobic_capacity");
-    assert_eq!(json["mesocycle"]["sequence_number"], 1);
+    let response = send_request(app, get_authed("/api/plan", &session_id)).await;
+    let plan = body_json(response).await;
+    let done = plan["mesocycles"][0]["workouts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|w| w["id"] == done_id)
+        .unwrap()
+        .clone();
+    assert_eq!(done["description"], planned[0]["description"]);
 }
 
 #[tokio::test]
-async fn get_workout_nonexistent_returns_404() {
-    let (app, _pool) = test_app_with_pool().await;
-    let (app, session_id) =
-        register_user(app, "noworkout@example.com", "securepass123").await;
+async fn confirm_retries_soft_errors_and_keeps_the_plan() {
+    let mock_server = MockServer::start().await;
+    let app = test_app_with_claude(&mock_server.uri()).await;
+    let (app, session_id, draft) = setup_draft(&mock_server, app, "soft@example.com", 7).await;
+
+    // An unrealistic TSS target in week 2 is a soft error that Claude never
+    // fixes
+    let (_, mut mesocycle_plan_input, coach_notes_input) = plan_flow_inputs();
+    mesocycle_plan_input["weeks"][1]["target_weekly_tss"] = json!(5000.0);
+    let week_2_start = mesocycle_plan_input["weeks"][1]["days"][0]["date"].clone();
+    for _ in 0..3 {
+        mount_tool_response_once(
+            &mock_server,
+            "generate_mesocycle_plan",
+            mesocycle_plan_input.clone(),
+        )
+        .await;
+    }
+    mount_tool_response_once(&mock_server, "add_coach_notes", coach_notes_input).await;
 
     let response = send_request(
         app,
-        get_authed("/api/plan/workout/99999", &session_id),
+        post_json_authed(
+            "/api/plan/confirm",
+            &json!({ "draft_id": draft["id"] }),
+            &session_id,
+        ),
     )
     .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
 
-    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    // skeleton, first plan, two retries, coach notes
+    assert_eq!(mock_server.received_requests().await.unwrap().len(), 5);
+    let feedback = sent_tool_results(&mock_server, 3).await;
+    assert_eq!(feedback.len(), 2);
+    for result in &feedback {
+        assert!(result.contains("weekly TSS 5000"), "{result}");
+        assert!(result.contains(week_2_start.as_str().unwrap()), "{result}");
+    }
 }
 
 #[tokio::test]
-async fn get_workout_for_other_user_returns_404() {
-    let (app, pool) = test_app_with_pool().await;
-
-    // User 1: create plan data
-    let (app, _session_user1, _mc_id, _user1_id) =
-        setup_plan_data(app, &pool, "owner@example.com").await;
+async fn confirm_failing_validation_after_retries_stores_nothing() {
+    let mock_server = MockServer::start().await;
+    let (app, pool) = test_app_with_claude_and_pool(&mock_server.uri()).await;
+    let (app, session_id, draft) = setup_draft(&mock_server, app, "nothing@example.com", 7).await;
+
+    // Every attempt leaves the recovery week without days, so it has no
+    // rest day and nothing the repair pass can turn into one
+    let (_, mut invalid_plan, _) = plan_flow_inputs();
+    invalid_plan["weeks"][3]["days"] = json!([]);
+    for _ in 0..3 {
+        mount_tool_response_once(
+            &mock_server,
+            "generate_mesocycle_plan",
+            invalid_plan.clone(),
+        )
+        .await;
+    }
 
-    // User 2: register with profile (but no plan data)
-    let (app, session_user2) =
-        register_user(app, "intruder@example.com", "securepass123").await;
-    let profile_body = valid_profile_body();
-    let resp = send_request(
-        app.clone(),
-        post_json_authed("/api/athlete/profile", &profile_body, &session_user2),
+    let response = send_request(
+        app,
+        post_json_authed(
+            "/api/plan/confirm",
+            &json!({ "draft_id": draft["id"] }),
+            &session_id,
+        ),
     )
     .await;
-    assert_eq!(resp.status(), StatusCode::CREATED);
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
 
-    // Find User 1's workout id
-    let w_row = sqlx::query(
-        "SELECT id FROM planned_workouts ORDER BY id LIMIT 1",
-    )
-    .fetch_one(&pool)
-    .await
+    // skeleton, first plan, two retries
+    assert_eq!(mock_server.received_requests().await.unwrap().len(), 4);
+
+    for table in ["macrocycles", "mesocycles", "planned_workouts"] {
+        let count: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {table}"))
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 0, "{table} should be empty");
+    }
+    let status: String = sqlx::query_scalar("SELECT status FROM plan_drafts WHERE id = ?")
+        .bind(draft["id"].as_i64().unwrap())
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(status, "draft");
+}
+
+#[tokio::test]
+async fn draft_revision_sends_history_and_records_feedback() {
+    let mock_server = MockServer::start().await;
+    let app = test_app_with_claude(&mock_server.uri()).await;
+    let (app, session_id, draft) = setup_draft(&mock_server, app, "revise@example.com", 7).await;
+    let uri = format!("/api/plan/drafts/{}", draft["id"]);
+
+    // A hand edit first, so the history has more than one version
+    let patch = json!({ "mesocycles": [{ "sequence_number": 2, "target_volume_km": 44.0 }] });
+    let response = send_request(app.clone(), patch_json_authed(&uri, &patch, &session_id)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let (mut revised, _, _) = plan_flow_inputs();
+    revised["coach_message"] = json!("Moved your key sessions off Tuesdays.");
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(claude_tool_use_response(
+                "generate_macrocycle_skeleton",
+                revised,
+            )),
+        )
+        .up_to_n_times(1)
+        .mount(&mock_server)
+        .await;
+
+    // Feedback is required
+    let response = send_request(
+        app.clone(),
+        post_json_authed(
+            &format!("{uri}/revise"),
+            &json!({ "feedback": "  " }),
+            &session_id,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = send_request(
+        app.clone(),
+        post_json_authed(
+            &format!("{uri}/revise"),
+            &json!({ "feedback": "I can't train on Tuesdays" }),
+            &session_id,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = body_json(response).await;
+    assert_eq!(json["id"], draft["id"]);
+    assert_eq!(json["version"], 3);
+    assert_eq!(
+        json["coach_message"],
+        "Moved your key sessions off Tuesdays."
+    );
+
+    // Claude saw both versions as tool calls, then the feedback
+    let requests = mock_server.received_requests().await.unwrap();
+    let body: Value = serde_json::from_slice(&requests[1].body).unwrap();
+    let messages = body["messages"].as_array().unwrap();
+    assert_eq!(messages.len(), 5);
+    assert_eq!(messages[1]["content"][0]["type"], "tool_use");
+    assert_eq!(messages[1]["content"][0]["id"], "skeleton_v1");
+    assert_eq!(messages[3]["content"][0]["type"], "tool_use");
+    assert_eq!(
+        messages[3]["content"][0]["input"]["mesocycles"][1]["target_volume_km"],
+        44.0
+    );
+    let last = &messages[4]["content"][0];
+    assert_eq!(last["type"], "tool_result");
+    assert_eq!(last["tool_use_id"], "skeleton_v2");
+    assert!(
+        last["content"]
+            .as_str()
+            .unwrap()
+            .contains("I can't train on Tuesdays")
+    );
+
+    let response = send_request(app, get_authed(&format!("{uri}/revisions"), &session_id)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let revisions = body_json(response).await;
+    let sources: Vec<_> = revisions
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["source"].as_str().unwrap())
+        .collect();
+    assert_eq!(sources, ["generated", "edited", "revised"]);
+    assert_eq!(revisions[2]["feedback"], "I can't train on Tuesdays");
+    assert_eq!(
+        revisions[0]["skeleton"]["coach_message"],
+        draft["coach_message"]
+    );
+}
+
+#[tokio::test]
+async fn draft_revision_retries_once_with_validation_errors() {
+    let mock_server = MockServer::start().await;
+    let app = test_app_with_claude(&mock_server.uri()).await;
+    let (app, session_id, draft) =
+        setup_draft(&mock_server, app, "revalidate@example.com", 7).await;
+    let uri = format!("/api/plan/drafts/{}/revise", draft["id"]);
+    let revise = json!({ "feedback": "Start with a much bigger block" });
+
+    let (valid, _, _) = plan_flow_inputs();
+    let mut invalid = valid.clone();
+    invalid["mesocycles"][0]["target_volume_km"] = json!(0.0);
+    let tool = "generate_macrocycle_skeleton";
+
+    // The first answer is invalid; the retry with its errors is accepted
+    mount_tool_response_once(&mock_server, tool, invalid.clone()).await;
+    mount_tool_response_once(&mock_server, tool, valid).await;
+    let response = send_request(app.clone(), post_json_authed(&uri, &revise, &session_id)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body_json(response).await["version"], 2);
+
+    let results = sent_tool_results(&mock_server, 2).await;
+    let feedback = results.last().unwrap();
+    assert!(feedback.contains("failed validation"), "{feedback}");
+    assert!(feedback.contains("Mesocycle 1"), "{feedback}");
+
+    // Invalid twice: the revision is rejected and the draft is unchanged
+    mount_tool_response_once(&mock_server, tool, invalid.clone()).await;
+    mount_tool_response_once(&mock_server, tool, invalid).await;
+    let response = send_request(app.clone(), post_json_authed(&uri, &revise, &session_id)).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let error = body_json(response).await["error"].clone();
+    assert!(
+        error
+            .as_str()
+            .unwrap()
+            .starts_with("Could not produce a revision"),
+        "{error}"
+    );
+    assert_eq!(mock_server.received_requests().await.unwrap().len(), 5);
+
+    let response = send_request(
+        app,
+        get_authed(&format!("/api/plan/drafts/{}", draft["id"]), &session_id),
+    )
+    .await;
+    assert_eq!(body_json(response).await["version"], 2);
+}
+
+#[tokio::test]
+async fn get_plan_without_plan_returns_404() {
+    let app = test_app().await;
+    let (app, session_id) =
+        register_user(app, "noplan@example.com", "securepass123").await;
+
+    let response = send_request(
+        app,
+        get_authed("/api/plan", &session_id),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+// ===========================================================================
+// Phase 3 endpoint tests — GET /api/plan and GET /api/plan/workout/:id
+// ===========================================================================
+
+/// Build a test app *and* return a handle to the underlying SQLite pool so
+/// tests can insert seed data directly without needing Claude mocks.
+async fn test_app_with_pool() -> (Router, sqlx::SqlitePool) {
+    let connect_options = SqliteConnectOptions::new()
+        .filename(":memory:")
+        .create_if_missing(true)
+        .pragma("foreign_keys", "ON");
+
+    let db = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(connect_options)
+        .await
+        .expect("Failed to create in-memory SQLite pool");
+
+    sqlx::migrate!("./migrations")
+        .run(&db)
+        .await
+        .expect("Failed to run migrations");
+
+    let config = Config {
+        database_url: String::new(),
+        host: "127.0.0.1".to_string(),
+        port: 0,
+        anthropic_api_key: None,
+        fit_files_dir: test_fit_files_dir(),
+    };
+
+    let pool = db.clone();
+    let state = AppState { db, config, claude_client: None };
+    (build_app(state), pool)
+}
+
+/// Register a user and create an athlete profile (with race goal) using the
+/// HTTP API, then insert a macrocycle + mesocycle + workouts directly into the
+/// database. Returns (app, session_id, pool) so callers can do further queries.
+async fn setup_plan_data(
+    app: Router,
+    pool: &sqlx::SqlitePool,
+    email: &str,
+) -> (Router, String, i64, i64) {
+    // Register user & create profile through the API so auth session exists
+    let (app, session_id) = register_user(app, email, "securepass123").await;
+
+    let profile_body = valid_profile_body();
+    let resp = send_request(
+        app.clone(),
+        post_json_authed("/api/athlete/profile", &profile_body, &session_id),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    let json = body_json(resp).await;
+    let race_goal_id = json["race_goal"]["id"]
+        .as_i64()
+        .expect("race_goal should have an id");
+
+    // Look up the user_id from the DB (via session)
+    let user_row = sqlx::query("SELECT user_id FROM sessions WHERE id = ?")
+        .bind(&session_id)
+        .fetch_one(pool)
+        .await
+        .expect("should find session");
+    let user_id: i64 = sqlx::Row::get(&user_row, "user_id");
+
+    // Insert macrocycle directly
+    let mc_row = sqlx::query(
+        r#"INSERT INTO macrocycles (user_id, race_goal_id, start_date, end_date, target_ctl, coach_message)
+           VALUES (?, ?, '2026-03-01', '2026-09-27', 65.0, 'Building your aerobic base.')
+           RETURNING id"#,
+    )
+    .bind(user_id)
+    .bind(race_goal_id)
+    .fetch_one(pool)
+    .await
+    .expect("create macrocycle");
+    let macrocycle_id: i64 = sqlx::Row::get(&mc_row, "id");
+
+    // Insert two mesocycles
+    let meso1_row = sqlx::query(
+        r#"INSERT INTO mesocycles (macrocycle_id, sequence_number, phase, focus, load_weeks, recovery_weeks, target_volume_km, start_date, end_date)
+           VALUES (?, 1, 'capacity', 'aerobic_capacity', 3, 1, 160.0, '2026-03-01', '2026-03-28')
+           RETURNING id"#,
+    )
+    .bind(macrocycle_id)
+    .fetch_one(pool)
+    .await
+    .expect("create mesocycle 1");
+    let meso1_id: i64 = sqlx::Row::get(&meso1_row, "id");
+
+    let meso2_row = sqlx::query(
+        r#"INSERT INTO mesocycles (macrocycle_id, sequence_number, phase, focus, load_weeks, recovery_weeks, target_volume_km, start_date, end_date)
+           VALUES (?, 2, 'utilization', 'race_specific', 2, 1, 140.0, '2026-03-29', '2026-04-18')
+           RETURNING id"#,
+    )
+    .bind(macrocycle_id)
+    .fetch_one(pool)
+    .await
+    .expect("create mesocycle 2");
+    let meso2_id: i64 = sqlx::Row::get(&meso2_row, "id");
+
+    // Insert workouts into mesocycle 1
+    for (i, (wtype, dur, tss, dist)) in [
+        ("easy_run",  Some(45i64), Some(35.0), Some(8.5)),
+        ("tempo_run", Some(50),    Some(70.0), Some(10.0)),
+        ("rest",      None,        None,       None),
+    ]
+    .iter()
+    .enumerate()
+    {
+        let date = format!("2026-03-{:02}", 3 + i);
+        sqlx::query(
+            r#"INSERT INTO planned_workouts
+                (mesocycle_id, user_id, scheduled_date, workout_type, duration_min, expected_tss, target_distance_km, coach_notes)
+               VALUES (?, ?, ?, ?, ?, ?, ?, 'Keep focused.')"#,
+        )
+        .bind(meso1_id)
+        .bind(user_id)
+        .bind(&date)
+        .bind(wtype)
+        .bind(dur)
+        .bind(tss)
+        .bind(dist)
+        .execute(pool)
+        .await
+        .expect("create workout in meso1");
+    }
+
+    // Insert one workout into mesocycle 2
+    sqlx::query(
+        r#"INSERT INTO planned_workouts
+            (mesocycle_id, user_id, scheduled_date, workout_type, duration_min, expected_tss, target_distance_km)
+           VALUES (?, ?, '2026-04-01', 'long_run', 90, 120.0, 18.0)"#,
+    )
+    .bind(meso2_id)
+    .bind(user_id)
+    .execute(pool)
+    .await
+    .expect("create workout in meso2");
+
+    (app, session_id, macrocycle_id, user_id)
+}
+
+#[tokio::test]
+async fn get_plan_returns_mesocycles_with_nested_workouts() {
+    let (app, pool) = test_app_with_pool().await;
+    let (app, session_id, _mc_id, _user_id) =
+        setup_plan_data(app, &pool, "planview@example.com").await;
+
+    let response = send_request(app, get_authed("/api/plan", &session_id)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let json = body_json(response).await;
+
+    // Top-level structure
+    assert!(json["macrocycle"]["id"].is_number(), "macrocycle should have an id");
+    assert_eq!(json["macrocycle"]["status"], "active");
+    assert_eq!(json["macrocycle"]["target_ctl"], 65.0);
+
+    // Mesocycles array
+    let mesocycles = json["mesocycles"]
+        .as_array()
+        .expect("mesocycles should be an array");
+    assert_eq!(mesocycles.len(), 2);
+
+    // First mesocycle
+    assert_eq!(mesocycles[0]["phase"], "capacity");
+    assert_eq!(mesocycles[0]["focus"], "aerobic_capacity");
+    assert_eq!(mesocycles[0]["sequence_number"], 1);
+    let meso1_workouts = mesocycles[0]["workouts"]
+        .as_array()
+        .expect("mesocycle 1 should have workouts array");
+    assert_eq!(meso1_workouts.len(), 3);
+    assert_eq!(meso1_workouts[0]["workout_type"], "easy_run");
+    assert_eq!(meso1_workouts[0]["target_distance_km"], 8.5);
+    assert_eq!(meso1_workouts[1]["workout_type"], "tempo_run");
+    assert_eq!(meso1_workouts[1]["target_distance_km"], 10.0);
+    assert_eq!(meso1_workouts[2]["workout_type"], "rest");
+    assert!(
+        meso1_workouts[2]["target_distance_km"].is_null(),
+        "rest workout should have null target_distance_km"
+    );
+
+    // Second mesocycle
+    assert_eq!(mesocycles[1]["phase"], "utilization");
+    assert_eq!(mesocycles[1]["focus"], "race_specific");
+    assert_eq!(mesocycles[1]["sequence_number"], 2);
+    let meso2_workouts = mesocycles[1]["workouts"]
+        .as_array()
+        .expect("mesocycle 2 should have workouts array");
+    assert_eq!(meso2_workouts.len(), 1);
+    assert_eq!(meso2_workouts[0]["workout_type"], "long_run");
+    assert_eq!(meso2_workouts[0]["target_distance_km"], 18.0);
+    assert_eq!(meso2_workouts[0]["duration_min"], 90);
+}
+
+#[tokio::test]
+async fn get_workout_returns_workout_with_mesocycle_context() {
+    let (app, pool) = test_app_with_pool().await;
+    let (app, session_id, _mc_id, _user_id) =
+        setup_plan_data(app, &pool, "workoutview@example.com").await;
+
+    // Find the first workout id (easy_run on 2026-03-03)
+    let w_row = sqlx::query(
+        "SELECT id FROM planned_workouts WHERE scheduled_date = '2026-03-03' ORDER BY id LIMIT 1",
+    )
+    .fetch_one(&pool)
+    .await
+    .expect("find workout");
+    let workout_id: i64 = sqlx::Row::get(&w_row, "id");
+
+    let uri = format!("/api/plan/workout/{workout_id}");
+    let response = send_request(app, get_authed(&uri, &session_id)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let json = body_json(response).await;
+
+    // Workout fields
+    assert_eq!(json["workout"]["id"], workout_id);
+    assert_eq!(json["workout"]["workout_type"], "easy_run");
+    assert_eq!(json["workout"]["scheduled_date"], "2026-03-03");
+    assert_eq!(json["workout"]["duration_min"], 45);
+    assert_eq!(json["workout"]["expected_tss"], 35.0);
+    assert_eq!(json["workout"]["target_distance_km"], 8.5);
+    assert_eq!(json["workout"]["coach_notes"], "Keep focused.");
+    assert_eq!(json["workout"]["is_completed"], 0);
+
+    // Mesocycle context
+    assert!(json["mesocycle"]["id"].is_number());
+    assert_eq!(json["mesocycle"]["phase"], "capacity");
+    assert_eq!(json["mesocycle"]["focus"], "aerobic_capacity");
+    assert_eq!(json["mesocycle"]["sequence_number"], 1);
+}
+
+#[tokio::test]
+async fn get_workout_nonexistent_returns_404() {
+    let (app, _pool) = test_app_with_pool().await;
+    let (app, session_id) =
+        register_user(app, "noworkout@example.com", "securepass123").await;
+
+    let response = send_request(
+        app,
+        get_authed("/api/plan/workout/99999", &session_id),
+    )
+    .await;
+
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn get_workout_for_other_user_returns_404() {
+    let (app, pool) = test_app_with_pool().await;
+
+    // User 1: create plan data
+    let (app, _session_user1, _mc_id, _user1_id) =
+        setup_plan_data(app, &pool, "owner@example.com").await;
+
+    // User 2: register with profile (but no plan data)
+    let (app, session_user2) =
+        register_user(app, "intruder@example.com", "securepass123").await;
+    let profile_body = valid_profile_body();
+    let resp = send_request(
+        app.clone(),
+        post_json_authed("/api/athlete/profile", &profile_body, &session_user2),
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+
+    // Find User 1's workout id
+    let w_row = sqlx::query(
+        "SELECT id FROM planned_workouts ORDER BY id LIMIT 1",
+    )
+    .fetch_one(&pool)
+    .await
     .expect("find workout");
     let workout_id: i64 = sqlx::Row::get(&w_row, "id");
 
-    // User 2 tries to access User 1's workout
-    let uri = format!("/api/plan/workout/{workout_id}");
-    let response = send_request(app, get_authed(&uri, &session_user2)).await;
+    // User 2 tries to access User 1's workout
+    let uri = format!("/api/plan/workout/{workout_id}");
+    let response = send_request(app, get_authed(&uri, &session_user2)).await;
+
+    assert_eq!(
+        response.status(),
+        StatusCode::NOT_FOUND,
+        "accessing another user's workout should return 404"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Workout upload tests — POST /api/workouts/upload
+// ---------------------------------------------------------------------------
+
+/// Seconds between the Unix epoch and the FIT epoch (1989-12-31T00:00:00Z).
+const FIT_EPOCH_OFFSET_S: i64 = 631_065_600;
+
+/// Build a minimal FIT activity: one record per second starting at
+/// `start_unix`, with timestamp, heart rate, distance and speed fields.
+fn build_fit_activity(start_unix: i64, seconds: u32, heart_rate: u8, speed_m_per_s: f64) -> Vec<u8> {
+    let mut data = vec![
+        0x40, 0, 0, 20, 0, 4, // definition: local 0 -> record (20), 4 fields
+        253, 4, 0x86, // timestamp
+        3, 1, 0x02, // heart_rate
+        5, 4, 0x86, // distance (m * 100)
+        6, 2, 0x84, // speed (m/s * 1000)
+    ];
+    let fit_start = (start_unix - FIT_EPOCH_OFFSET_S) as u32;
+    for s in 0..seconds {
+        data.push(0);
+        data.extend_from_slice(&(fit_start + s).to_le_bytes());
+        data.push(heart_rate);
+        data.extend_from_slice(&((s as f64 * speed_m_per_s * 100.0) as u32).to_le_bytes());
+        data.extend_from_slice(&((speed_m_per_s * 1000.0) as u16).to_le_bytes());
+    }
+
+    let mut out = vec![14, 0x20];
+    out.extend_from_slice(&2132u16.to_le_bytes());
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    out.extend_from_slice(b".FIT");
+    let header_crc = coachjan::fit::parser::crc16(&out);
+    out.extend_from_slice(&header_crc.to_le_bytes());
+    out.extend_from_slice(&data);
+    let file_crc = coachjan::fit::parser::crc16(&out);
+    out.extend_from_slice(&file_crc.to_le_bytes());
+    out
+}
+
+/// Build an authenticated multipart upload request with a single `file` field.
+fn upload_request(file_name: &str, bytes: &[u8], session_id: Option<&str>) -> Request<Body> {
+    let boundary = "coachjan-test-boundary";
+    let mut body = format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{file_name}\"\r\nContent-Type: application/octet-stream\r\n\r\n"
+    )
+    .into_bytes();
+    body.extend_from_slice(bytes);
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+
+    let mut builder = Request::builder()
+        .method("POST")
+        .uri("/api/workouts/upload")
+        .header(
+            "content-type",
+            format!("multipart/form-data; boundary={boundary}"),
+        );
+    if let Some(session_id) = session_id {
+        builder = builder.header("cookie", format!("session_id={session_id}"));
+    }
+    builder.body(Body::from(body)).unwrap()
+}
+
+/// 2026-03-03T07:00:00Z — the day of the seeded easy run in `setup_plan_data`.
+const EASY_RUN_START: i64 = 1_772_521_200;
+
+#[tokio::test]
+async fn upload_fit_returns_summary_and_stores_records() {
+    let app = test_app().await;
+    let (app, session_id) = register_user(app, "upload@example.com", "securepass123").await;
+
+    let fit = build_fit_activity(EASY_RUN_START, 600, 145, 3.0);
+    let response = send_request(app, upload_request("run.fit", &fit, Some(&session_id))).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let json = body_json(response).await;
+    let workout = &json["workout"];
+    assert_eq!(workout["source"], "fit_upload");
+    assert_eq!(workout["started_at"], "2026-03-03T07:00:00+00:00");
+    assert_eq!(workout["duration_seconds"], 599);
+    assert_eq!(workout["avg_hr"], 145);
+    assert_eq!(workout["hr_data_sufficient"], 1);
+    assert!(workout["distance_m"].as_f64().unwrap() > 1790.0);
+    assert_eq!(json["records_count"], 600);
+    assert!(json["planned_workout"].is_null(), "no plan, nothing to match");
+    assert!(workout["tss"].is_null(), "no profile, no thresholds to score against");
+
+    let path = workout["fit_file_path"].as_str().expect("fit file path");
+    assert!(path.ends_with(&format!("2026-03-03_{}.fit", workout["id"])));
+    assert_eq!(std::fs::read(path).expect("stored FIT file"), fit);
+}
+
+#[tokio::test]
+async fn upload_fit_matches_planned_workout_on_same_day() {
+    let (app, pool) = test_app_with_pool().await;
+    let (app, session_id, _macrocycle_id, _user_id) =
+        setup_plan_data(app, &pool, "match@example.com").await;
+
+    let fit = build_fit_activity(EASY_RUN_START, 2700, 140, 3.1);
+    let response = send_request(app, upload_request("run.fit", &fit, Some(&session_id))).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let json = body_json(response).await;
+    let planned = &json["planned_workout"];
+    assert_eq!(planned["workout_type"], "easy_run");
+    assert_eq!(planned["scheduled_date"], "2026-03-03");
+    assert_eq!(planned["is_completed"], 1);
+    assert_eq!(planned["completed_workout_id"], json["workout"]["id"]);
+    assert_eq!(planned["actual_duration_min"], 45);
+    assert_eq!(json["workout"]["planned_workout_id"], planned["id"]);
+
+    // Scored against the profile's FTPace (4.5 m/s)
+    let workout = &json["workout"];
+    let ngp = workout["ngp_m_per_s"].as_f64().unwrap();
+    assert!((ngp - 3.1).abs() < 0.01, "ngp was {ngp}");
+    assert!((workout["intensity_factor"].as_f64().unwrap() - ngp / 4.5).abs() < 1e-9);
+    assert!(workout["rtss"].as_f64().unwrap() > 30.0);
+    assert!(workout["hrtss"].as_f64().is_some());
+    assert_eq!(workout["tss"], workout["rtss"]);
+
+    // HR 140 sits in zone 2 for LTHR 170; 3.1 m/s is pace zone 1 for FTPace 4.5
+    let hr_z2 = workout["hr_zone_2_seconds"].as_i64().unwrap();
+    assert!(hr_z2 >= 2699, "hr zone 2 seconds was {hr_z2}");
+    assert_eq!(workout["hr_zone_3_seconds"], 0);
+    let pace_z1 = workout["pace_zone_1_seconds"].as_i64().unwrap();
+    assert!(pace_z1 >= 2699, "pace zone 1 seconds was {pace_z1}");
+    assert_eq!(workout["pace_zone_2_seconds"], 0);
+    assert!(workout["trimp"].as_f64().unwrap() > 0.0);
+
+    // Steady Z2 running: aerobic capacity, as the easy run prescribed
+    assert!(workout["aerobic_effect"].as_f64().unwrap() > 1.0);
+    assert_eq!(workout["anaerobic_effect"], 0.0);
+    assert_eq!(workout["classification"], "aerobic_capacity");
+    assert_eq!(json["classification_check"]["expected"], "aerobic_capacity");
+    assert_eq!(json["classification_check"]["matches"], true);
+    assert_eq!(workout["expected_classification"], "aerobic_capacity");
+    assert_eq!(workout["classification_matches"], 1);
+
+    // 8.4 km vs 8.5 km prescribed, TSS close to the expected 35
+    assert_eq!(workout["compliance"], "on_target");
+    assert_eq!(json["compliance"]["verdict"], "on_target");
+    assert!(json["compliance"]["distance_ratio"].as_f64().unwrap() > 0.95);
+    assert_eq!(json["off_target_streak"]["consecutive_off_target_count"], 0);
+    assert!(json["off_target_streak"]["off_target_direction"].is_null());
+}
+
+#[tokio::test]
+async fn upload_flags_easy_run_turned_into_tempo() {
+    let (app, pool) = test_app_with_pool().await;
+    let (app, session_id, _macrocycle_id, _user_id) =
+        setup_plan_data(app, &pool, "tempo@example.com").await;
+
+    // 45 min at HR 163 (Z4 for LTHR 170) on the easy-run day
+    let fit = build_fit_activity(EASY_RUN_START, 2700, 163, 4.1);
+    let response = send_request(app, upload_request("run.fit", &fit, Some(&session_id))).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let json = body_json(response).await;
+
+    assert_eq!(json["workout"]["classification"], "aerobic_utilization");
+    let check = &json["classification_check"];
+    assert_eq!(check["planned_workout_type"], "easy_run");
+    assert_eq!(check["expected"], "aerobic_capacity");
+    assert_eq!(check["actual"], "aerobic_utilization");
+    assert_eq!(check["matches"], false);
+    // Stored with the workout
+    assert_eq!(
+        json["workout"]["expected_classification"],
+        "aerobic_capacity"
+    );
+    assert_eq!(json["workout"]["classification_matches"], 0);
+
+    // Longer and far more load than prescribed
+    assert_eq!(json["workout"]["compliance"], "harder_than_prescribed");
+    assert_eq!(json["compliance"]["load"], "harder");
+    assert_eq!(json["compliance"]["volume"], "harder");
+    assert_eq!(json["off_target_streak"]["consecutive_off_target_count"], 1);
+    assert_eq!(json["off_target_streak"]["off_target_direction"], "harder");
+}
+
+#[tokio::test]
+async fn upload_time_trial_proposes_new_thresholds() {
+    let (app, pool) = test_app_with_pool().await;
+    let (app, session_id, _macrocycle_id, user_id) =
+        setup_plan_data(app, &pool, "timetrial@example.com").await;
+    sqlx::query(
+        "UPDATE planned_workouts SET workout_type = 'time_trial' WHERE user_id = ? AND scheduled_date = '2026-03-03'",
+    )
+    .bind(user_id)
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    // 35 minutes at 4.4 m/s and HR 174
+    let fit = build_fit_activity(EASY_RUN_START, 2100, 174, 4.4);
+    let response = send_request(
+        app.clone(),
+        upload_request("tt.fit", &fit, Some(&session_id)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let json = body_json(response).await;
+    assert_eq!(json["planned_workout"]["workout_type"], "time_trial");
+    let ftpace = json["time_trial"]["ftpace_m_per_s"].as_f64().unwrap();
+    assert!((ftpace - 4.4 * 0.95).abs() < 0.01, "ftpace was {ftpace}");
+    assert_eq!(json["time_trial"]["lthr"], 174);
+    let proposal = json["proposed_thresholds"].clone();
+    assert_eq!(proposal["lthr"], 174);
+    assert_eq!(proposal["ftpace_m_per_s"].as_f64(), Some(ftpace));
+    assert_eq!(proposal["source"], "time_trial");
+    assert_eq!(json["updated_workouts"], json!([]));
+
+    // Nothing changes until the athlete accepts the proposal
+    let response = send_request(
+        app.clone(),
+        get_authed("/api/athlete/thresholds", &session_id),
+    )
+    .await;
+    let json = body_json(response).await;
+    assert_ne!(json["lthr"], 174);
+    for history in ["lthr_history", "ftpace_history"] {
+        assert!(
+            json[history]
+                .as_array()
+                .unwrap()
+                .iter()
+                .all(|entry| entry["source"] != "time_trial")
+        );
+    }
+
+    let response = send_request(
+        app.clone(),
+        post_json_authed("/api/athlete/thresholds", &proposal, &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    assert!(body_json(response).await["updated_workouts"].is_array());
+
+    let response = send_request(app, get_authed("/api/athlete/thresholds", &session_id)).await;
+    let json = body_json(response).await;
+    assert_eq!(json["lthr"], 174);
+    assert_eq!(json["ftpace_m_per_s"].as_f64(), Some(ftpace));
+    for history in ["lthr_history", "ftpace_history"] {
+        let latest = json[history].as_array().unwrap().last().unwrap().clone();
+        assert_eq!(latest["source"], "time_trial");
+        assert!(
+            latest["notes"]
+                .as_str()
+                .unwrap()
+                .starts_with("30-min time trial on 2026-03-03")
+        );
+    }
+}
+
+#[tokio::test]
+async fn upload_easy_run_leaves_thresholds_alone() {
+    let (app, pool) = test_app_with_pool().await;
+    let (app, session_id, _macrocycle_id, _user_id) =
+        setup_plan_data(app, &pool, "notimetrial@example.com").await;
+
+    let fit = build_fit_activity(EASY_RUN_START, 2100, 174, 4.4);
+    let response = send_request(app, upload_request("run.fit", &fit, Some(&session_id))).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let json = body_json(response).await;
+    assert!(json["time_trial"].is_null());
+    assert!(json["proposed_thresholds"].is_null());
+    assert_eq!(json["updated_workouts"], json!([]));
+}
+
+/// Register an athlete with no FTPace and upload one 20-minute steady run per
+/// (HR, speed), a day apart and ending yesterday. Returns each upload's
+/// response.
+async fn upload_steady_runs(email: &str, runs: &[(u8, f64)]) -> (Router, String, Vec<Value>) {
+    let app = test_app().await;
+    let (app, session_id) = register_user(app, email, "securepass123").await;
+    let mut profile_body = valid_profile_body();
+    profile_body
+        .as_object_mut()
+        .unwrap()
+        .remove("ftpace_m_per_s");
+    let response = send_request(
+        app.clone(),
+        post_json_authed("/api/athlete/profile", &profile_body, &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let yesterday = chrono::Utc::now().timestamp() - 86_400;
+    let mut responses = Vec::new();
+    for (i, &(hr, speed)) in runs.iter().enumerate() {
+        let start = yesterday - (runs.len() - 1 - i) as i64 * 86_400;
+        let fit = build_fit_activity(start, 1200, hr, speed);
+        let response = send_request(
+            app.clone(),
+            upload_request("run.fit", &fit, Some(&session_id)),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        responses.push(body_json(response).await);
+    }
+    (app, session_id, responses)
+}
+
+#[tokio::test]
+async fn steady_runs_near_lthr_derive_ftpace() {
+    // speed = 0.04 * HR - 2.6 → 4.2 m/s at LTHR 170
+    let (app, session_id, uploads) =
+        upload_steady_runs("derived@example.com", &[(150, 3.4), (160, 3.8)]).await;
+
+    // One run is a single point on the line
+    assert!(uploads[0]["ftpace_estimate"].is_null());
+    assert_eq!(uploads[0]["ftpace_estimate_applied"], false);
+
+    let estimate = &uploads[1]["ftpace_estimate"];
+    assert_eq!(estimate["confidence"], "high");
+    assert_eq!(estimate["segments"], 8);
+    assert_eq!(estimate["workouts"], 2);
+    let ftpace = estimate["ftpace_m_per_s"].as_f64().unwrap();
+    assert!((ftpace - 4.2).abs() < 0.01, "ftpace was {ftpace}");
+    assert_eq!(uploads[1]["ftpace_estimate_applied"], true);
+
+    // The athlete now has pace zones
+    let response = send_request(
+        app.clone(),
+        get_authed("/api/athlete/thresholds", &session_id),
+    )
+    .await;
+    let json = body_json(response).await;
+    assert_eq!(json["ftpace_m_per_s"].as_f64(), Some(ftpace));
+    assert!(json["pace_zones"].is_object());
+    let latest = json["ftpace_history"]
+        .as_array()
+        .unwrap()
+        .last()
+        .unwrap()
+        .clone();
+    assert_eq!(latest["source"], "workout_derived");
+    assert!(latest["notes"].as_str().unwrap().contains("at LTHR 170"));
+
+    let response = send_request(app, get_authed("/api/athlete/ftpace-estimate", &session_id)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = body_json(response).await;
+    assert_eq!(json["estimate"]["confidence"], "high");
+    assert_eq!(json["current_ftpace_m_per_s"].as_f64(), Some(ftpace));
+}
+
+#[tokio::test]
+async fn manual_ftpace_takes_precedence_over_the_estimate() {
+    let (app, session_id, uploads) =
+        upload_steady_runs("manualpace@example.com", &[(150, 3.4), (160, 3.8)]).await;
+    assert_eq!(uploads[1]["ftpace_estimate_applied"], true);
+
+    let manual = json!({ "ftpace_m_per_s": 3.6, "source": "manual" });
+    let response = send_request(
+        app.clone(),
+        post_json_authed("/api/athlete/thresholds", &manual, &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    // Another run on the same line still estimates 4.2 m/s, but the athlete
+    // set FTPace themselves
+    let start = chrono::Utc::now().timestamp() - 2 * 3600;
+    let fit = build_fit_activity(start, 1200, 165, 4.0);
+    let response = send_request(
+        app.clone(),
+        upload_request("run.fit", &fit, Some(&session_id)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let json = body_json(response).await;
+    assert_eq!(json["ftpace_estimate"]["confidence"], "high");
+    assert_eq!(json["ftpace_estimate_applied"], false);
+
+    let response = send_request(app, get_authed("/api/athlete/thresholds", &session_id)).await;
+    assert_eq!(
+        body_json(response).await["ftpace_m_per_s"].as_f64(),
+        Some(3.6)
+    );
+}
+
+#[tokio::test]
+async fn easy_runs_only_give_a_low_confidence_estimate() {
+    let (app, session_id, uploads) =
+        upload_steady_runs("easyonly@example.com", &[(130, 2.6), (140, 3.0)]).await;
+
+    assert_eq!(uploads[1]["ftpace_estimate"]["confidence"], "low");
+    assert_eq!(uploads[1]["ftpace_estimate_applied"], false);
+
+    let response = send_request(app, get_authed("/api/athlete/thresholds", &session_id)).await;
+    let json = body_json(response).await;
+    assert!(json["ftpace_m_per_s"].is_null());
+    assert_eq!(json["ftpace_history"], json!([]));
+}
+
+#[tokio::test]
+async fn upload_without_ftpace_scores_hrtss() {
+    let app = test_app().await;
+    let (app, session_id) = register_user(app, "hrtss@example.com", "securepass123").await;
+    let mut profile_body = valid_profile_body();
+    profile_body.as_object_mut().unwrap().remove("ftpace_m_per_s");
+    let response = send_request(
+        app.clone(),
+        post_json_authed("/api/athlete/profile", &profile_body, &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    // One hour at LTHR (170) → hrTSS 100
+    let fit = build_fit_activity(EASY_RUN_START, 3601, 170, 3.5);
+    let response = send_request(app, upload_request("run.fit", &fit, Some(&session_id))).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let json = body_json(response).await;
+    let workout = &json["workout"];
+
+    assert!(workout["rtss"].is_null());
+    let hrtss = workout["hrtss"].as_f64().unwrap();
+    assert!((hrtss - 100.03).abs() < 0.01, "hrtss was {hrtss}");
+    assert_eq!(workout["tss"], workout["hrtss"]);
+    assert_eq!(workout["compliance"], "unplanned");
+
+    // No FTPace: HR zones and TRIMP only
+    assert!(workout["hr_zone_5_seconds"].as_i64().unwrap() >= 3600);
+    assert!(workout["trimp"].as_f64().is_some());
+    assert!((1..=6).all(|z| workout[format!("pace_zone_{z}_seconds")] == 0));
+}
+
+#[tokio::test]
+async fn upload_same_fit_twice_returns_409() {
+    let app = test_app().await;
+    let (app, session_id) = register_user(app, "dupe@example.com", "securepass123").await;
+
+    let fit = build_fit_activity(EASY_RUN_START, 60, 150, 3.0);
+    let first = send_request(app.clone(), upload_request("run.fit", &fit, Some(&session_id))).await;
+    assert_eq!(first.status(), StatusCode::CREATED);
+
+    let second = send_request(app, upload_request("run.fit", &fit, Some(&session_id))).await;
+    assert_eq!(second.status(), StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn upload_rejects_non_fit_and_corrupt_files() {
+    let app = test_app().await;
+    let (app, session_id) = register_user(app, "badfile@example.com", "securepass123").await;
+
+    let fit = build_fit_activity(EASY_RUN_START, 60, 150, 3.0);
+    let response = send_request(
+        app.clone(),
+        upload_request("run.gpx", &fit, Some(&session_id)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let mut corrupt = fit.clone();
+    let last = corrupt.len() - 1;
+    corrupt[last] ^= 0xFF;
+    let response = send_request(app, upload_request("run.fit", &corrupt, Some(&session_id))).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let json = body_json(response).await;
+    assert!(json["error"].as_str().unwrap().contains("Invalid FIT file"));
+}
+
+#[tokio::test]
+async fn upload_without_auth_returns_401() {
+    let app = test_app().await;
+    let fit = build_fit_activity(EASY_RUN_START, 60, 150, 3.0);
+    let response = send_request(app, upload_request("run.fit", &fit, None)).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+// ---------------------------------------------------------------------------
+// Training load tests — daily_metrics recomputation
+// ---------------------------------------------------------------------------
+
+/// Move the profile's bootstrap metrics row back to just before the seeded
+/// plan so workouts in March count towards load.
+async fn backdate_bootstrap(pool: &sqlx::SqlitePool, user_id: i64) {
+    sqlx::query("UPDATE daily_metrics SET date = '2026-03-01' WHERE user_id = ?")
+        .bind(user_id)
+        .execute(pool)
+        .await
+        .expect("backdate bootstrap");
+}
+
+async fn daily_tss(pool: &sqlx::SqlitePool, user_id: i64, date: &str) -> Option<f64> {
+    sqlx::query_scalar("SELECT total_tss FROM daily_metrics WHERE user_id = ? AND date = ?")
+        .bind(user_id)
+        .bind(date)
+        .fetch_optional(pool)
+        .await
+        .expect("query daily_metrics")
+}
+
+#[tokio::test]
+async fn upload_and_delete_recompute_daily_metrics() {
+    let (app, pool) = test_app_with_pool().await;
+    let (app, session_id, _macrocycle_id, user_id) =
+        setup_plan_data(app, &pool, "load@example.com").await;
+    backdate_bootstrap(&pool, user_id).await;
+
+    let fit = build_fit_activity(EASY_RUN_START, 2700, 140, 3.1);
+    let response = send_request(
+        app.clone(),
+        upload_request("run.fit", &fit, Some(&session_id)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let json = body_json(response).await;
+    let workout_id = json["workout"]["id"].as_i64().unwrap();
+
+    assert_eq!(daily_tss(&pool, user_id, "2026-03-02").await, Some(0.0));
+    assert_eq!(
+        daily_tss(&pool, user_id, "2026-03-03").await,
+        json["workout"]["tss"].as_f64()
+    );
+    let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    assert!(daily_tss(&pool, user_id, &today).await.is_some(), "rest days filled through today");
+
+    let request = Request::builder()
+        .method("DELETE")
+        .uri(format!("/api/workouts/{workout_id}"))
+        .header("cookie", format!("session_id={session_id}"))
+        .body(Body::empty())
+        .unwrap();
+    let response = send_request(app, request).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    assert_eq!(daily_tss(&pool, user_id, "2026-03-03").await, Some(0.0));
+    let is_completed: i64 = sqlx::query_scalar(
+        "SELECT is_completed FROM planned_workouts WHERE user_id = ? AND scheduled_date = '2026-03-03'",
+    )
+    .bind(user_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+    assert_eq!(is_completed, 0);
+}
+
+#[tokio::test]
+async fn completing_planned_workout_recomputes_daily_metrics() {
+    let (app, pool) = test_app_with_pool().await;
+    let (app, session_id, _macrocycle_id, user_id) =
+        setup_plan_data(app, &pool, "complete-load@example.com").await;
+    backdate_bootstrap(&pool, user_id).await;
+
+    let tempo_id: i64 = sqlx::query_scalar(
+        "SELECT id FROM planned_workouts WHERE user_id = ? AND workout_type = 'tempo_run'",
+    )
+    .bind(user_id)
+    .fetch_one(&pool)
+    .await
+    .unwrap();
+
+    // Ran 25 of the planned 50 minutes → half the expected 70 TSS
+    let response = send_request(
+        app,
+        post_json_authed(
+            &format!("/api/plan/workouts/{tempo_id}/complete"),
+            &json!({ "rpe": 6, "actual_duration_min": 25 }),
+            &session_id,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(daily_tss(&pool, user_id, "2026-03-04").await, Some(35.0));
+}
+
+// ---------------------------------------------------------------------------
+// PMC tests — GET /api/metrics/pmc
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn pmc_returns_actual_and_projected_series() {
+    let (app, pool) = test_app_with_pool().await;
+    let (app, session_id, macrocycle_id, user_id) =
+        setup_plan_data(app, &pool, "pmc@example.com").await;
+
+    // Move the race a few weeks into the future and plan a workout tomorrow
+    let today = chrono::Utc::now().date_naive();
+    let tomorrow = today.succ_opt().unwrap();
+    let race_day = today + chrono::Days::new(21);
+    sqlx::query("UPDATE macrocycles SET end_date = ? WHERE id = ?")
+        .bind(race_day.to_string())
+        .bind(macrocycle_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let meso_id: i64 = sqlx::query_scalar("SELECT id FROM mesocycles WHERE macrocycle_id = ? LIMIT 1")
+        .bind(macrocycle_id)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    sqlx::query(
+        r#"INSERT INTO planned_workouts (mesocycle_id, user_id, scheduled_date, workout_type, expected_tss)
+           VALUES (?, ?, ?, 'long_run', 120.0)"#,
+    )
+    .bind(meso_id)
+    .bind(user_id)
+    .bind(tomorrow.to_string())
+    .execute(&pool)
+    .await
+    .unwrap();
+
+    let response = send_request(app, get_authed("/api/metrics/pmc", &session_id)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = body_json(response).await;
+
+    let actual = json["actual"].as_array().unwrap();
+    assert_eq!(actual.len(), 1, "bootstrap row only");
+    assert_eq!(actual[0]["date"], today.to_string());
+
+    let projected = json["projected"].as_array().unwrap();
+    assert_eq!(projected.len(), 21);
+    assert_eq!(projected[0]["date"], tomorrow.to_string());
+    assert_eq!(projected[0]["total_tss"], 120.0);
+    assert_eq!(projected[20]["date"], race_day.to_string());
+
+    let race = &json["race_day"];
+    assert_eq!(race["date"], race_day.to_string());
+    assert_eq!(race["target_ctl"], 65.0);
+    assert_eq!(race["projected_ctl"], projected[20]["ctl"]);
+    assert_eq!(race["tsb_target_min"], 15.0);
+    assert_eq!(race["tsb_target_max"], 25.0);
+    assert!(race["ctl_gap"].as_f64().unwrap() < 0.0, "one long run won't reach CTL 65");
+    assert_eq!(race["tsb_on_target"], false);
+}
+
+#[tokio::test]
+async fn pmc_filters_by_date_range() {
+    let (app, pool) = test_app_with_pool().await;
+    let (app, session_id, macrocycle_id, _user_id) =
+        setup_plan_data(app, &pool, "pmc-range@example.com").await;
+
+    let today = chrono::Utc::now().date_naive();
+    let race_day = today + chrono::Days::new(30);
+    sqlx::query("UPDATE macrocycles SET end_date = ? WHERE id = ?")
+        .bind(race_day.to_string())
+        .bind(macrocycle_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let from = today + chrono::Days::new(5);
+    let to = today + chrono::Days::new(9);
+    let uri = format!("/api/metrics/pmc?from={from}&to={to}");
+    let response = send_request(app, get_authed(&uri, &session_id)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = body_json(response).await;
+
+    assert!(json["actual"].as_array().unwrap().is_empty());
+    assert_eq!(json["projected"].as_array().unwrap().len(), 5);
+    assert_eq!(json["race_day"]["date"], race_day.to_string());
+}
+
+#[tokio::test]
+async fn pmc_rejects_bad_dates_and_requires_auth() {
+    let app = test_app().await;
+    let response = send_request(app.clone(), get_request("/api/metrics/pmc")).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let (app, session_id) = register_user(app, "pmc-bad@example.com", "securepass123").await;
+    let response = send_request(
+        app.clone(),
+        get_authed("/api/metrics/pmc?from=03-01-2026", &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = send_request(
+        app.clone(),
+        get_authed("/api/metrics/pmc?from=2026-04-01&to=2026-03-01", &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // No profile or plan yet: empty series, no race-day projection
+    let response = send_request(app, get_authed("/api/metrics/pmc", &session_id)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = body_json(response).await;
+    assert!(json["actual"].as_array().unwrap().is_empty());
+    assert!(json["projected"].as_array().unwrap().is_empty());
+    assert!(json["race_day"].is_null());
+}
+
+// ---------------------------------------------------------------------------
+// Workout analysis tests — GET /api/workouts/:id/analysis
+// ---------------------------------------------------------------------------
+
+fn sample_workout_analysis(summary: &str) -> Value {
+    json!({
+        "summary": summary,
+        "training_effect_commentary": "Pure aerobic capacity work, exactly what the day called for.",
+        "compliance_commentary": "Right on the prescription for duration and intensity.",
+        "load_commentary": "CTL ticks up slightly; TSB stays positive.",
+        "coach_message": "Great start to the week. We keep the easy days easy."
+    })
+}
+
+#[tokio::test]
+async fn workout_analysis_is_generated_stored_and_regenerated() {
+    let mock_server = MockServer::start().await;
+    let (app, pool) = test_app_with_claude_and_pool(&mock_server.uri()).await;
+    let (app, session_id, _macrocycle_id, _user_id) =
+        setup_plan_data(app, &pool, "analysis@example.com").await;
+
+    let fit = build_fit_activity(EASY_RUN_START, 2700, 140, 3.1);
+    let response = send_request(
+        app.clone(),
+        upload_request("run.fit", &fit, Some(&session_id)),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let workout_id = body_json(response).await["workout"]["id"].as_i64().unwrap();
+    let uri = format!("/api/workouts/{workout_id}/analysis");
+
+    // First request generates the analysis with the workout and plan context
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .and(body_string_contains("analyze_workout"))
+        .and(body_string_contains("Prescribed workout"))
+        .and(body_string_contains("easy_run"))
+        .and(body_string_contains("Compliance: on_target"))
+        .and(body_string_contains("adjustment_eligible: false"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(claude_tool_use_response(
+            "analyze_workout",
+            sample_workout_analysis("Steady 45 minute easy run."),
+        )))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let response = send_request(app.clone(), get_authed(&uri, &session_id)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = body_json(response).await;
+    assert_eq!(json["workout_id"], workout_id);
+    assert_eq!(json["summary"], "Steady 45 minute easy run.");
+    assert_eq!(json["compliance"], "on_target");
+    assert_eq!(json["classification"], "aerobic_capacity");
+    assert!(json["commentary"].as_str().unwrap().contains("Pure aerobic capacity"));
+    assert!(json["commentary"].as_str().unwrap().contains("TSB stays positive"));
+    assert_eq!(
+        json["coach_message"],
+        "Great start to the week. We keep the easy days easy."
+    );
+    assert!(json["analyzed_at"].is_string());
+
+    // The coach message also lands in the chat timeline
+    let response = send_request(app.clone(), get_authed("/api/chat", &session_id)).await;
+    let messages = body_json(response).await["messages"].clone();
+    assert_eq!(messages.as_array().unwrap().len(), 1);
+    assert_eq!(messages[0]["role"], "coach");
+    assert_eq!(messages[0]["context_type"], "workout_analysis");
+    assert_eq!(messages[0]["related_workout_id"], workout_id);
+
+    // Second request returns the stored analysis without calling Claude
+    let response = send_request(app.clone(), get_authed(&uri, &session_id)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body_json(response).await["summary"], "Steady 45 minute easy run.");
+
+    // Regeneration replaces it. One on-target run is no reason to change the
+    // plan, so a proposed adjustment is dropped.
+    let mut regenerated = sample_workout_analysis("Relaxed aerobic run, well executed.");
+    regenerated["plan_adjustment"] = json!({
+        "trigger_reason": "sustained_harder_than_prescribed",
+        "operations": [{"type": "add_recovery_day", "date": "2026-03-05"}],
+        "explanation": "Rest"
+    });
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(claude_tool_use_response(
+            "analyze_workout",
+            regenerated,
+        )))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let response = send_request(
+        app.clone(),
+        get_authed(&format!("{uri}?regenerate=true"), &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        body_json(response).await["summary"],
+        "Relaxed aerobic run, well executed."
+    );
+
+    let response = send_request(app.clone(), get_authed(&uri, &session_id)).await;
+    let json = body_json(response).await;
+    assert_eq!(json["summary"], "Relaxed aerobic run, well executed.");
+    assert_eq!(json["plan_adjustments"], json!([]));
+
+    // The chat keeps one message for the workout's analysis
+    let response = send_request(app, get_authed("/api/chat", &session_id)).await;
+    let messages = body_json(response).await["messages"].clone();
+    assert_eq!(messages.as_array().unwrap().len(), 1);
+    assert_eq!(messages[0]["related_workout_id"], workout_id);
+}
+
+#[tokio::test]
+async fn workout_analysis_errors() {
+    let (app, pool) = test_app_with_pool().await;
+    let (app, session_id, _macrocycle_id, _user_id) =
+        setup_plan_data(app, &pool, "analysis-errors@example.com").await;
+
+    let response = send_request(
+        app.clone(),
+        get_authed("/api/workouts/999/analysis", &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // No Claude client and nothing stored yet
+    let fit = build_fit_activity(EASY_RUN_START, 600, 140, 3.1);
+    let response = send_request(
+        app.clone(),
+        upload_request("run.fit", &fit, Some(&session_id)),
+    )
+    .await;
+    let workout_id = body_json(response).await["workout"]["id"].as_i64().unwrap();
+    let response = send_request(
+        app,
+        get_authed(&format!("/api/workouts/{workout_id}/analysis"), &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+/// Add a mesocycle starting next Monday with easy/tempo/rest/easy in its
+/// first week, and return the planned workout IDs in that order.
+async fn setup_future_week(pool: &sqlx::SqlitePool, macrocycle_id: i64, user_id: i64) -> Vec<i64> {
+    use chrono::{Datelike, Duration, Utc};
+
+    let today = Utc::now().date_naive();
+    let start = today + Duration::days(7 - today.weekday().num_days_from_monday() as i64);
+    let day = |n: i64| (start + Duration::days(n)).format("%Y-%m-%d").to_string();
+
+    let meso_id: i64 = sqlx::query_scalar(
+        r#"INSERT INTO mesocycles (macrocycle_id, sequence_number, phase, focus, load_weeks, recovery_weeks, target_volume_km, start_date, end_date)
+           VALUES (?, 3, 'capacity', 'aerobic_capacity', 3, 1, 160.0, ?, ?)
+           RETURNING id"#,
+    )
+    .bind(macrocycle_id)
+    .bind(day(0))
+    .bind(day(27))
+    .fetch_one(pool)
+    .await
+    .expect("create future mesocycle");
+
+    let mut ids = Vec::new();
+    for (i, (wtype, dur, tss)) in [
+        ("easy_run",  Some(45i64), 35.0),
+        ("tempo_run", Some(50),    70.0),
+        ("rest",      None,        0.0),
+        ("easy_run",  Some(45),    35.0),
+    ]
+    .iter()
+    .enumerate()
+    {
+        let id: i64 = sqlx::query_scalar(
+            r#"INSERT INTO planned_workouts
+                (mesocycle_id, user_id, scheduled_date, workout_type, duration_min, duration_category, expected_tss)
+               VALUES (?, ?, ?, ?, ?, 'medium', ?)
+               RETURNING id"#,
+        )
+        .bind(meso_id)
+        .bind(user_id)
+        .bind(day(i as i64))
+        .bind(wtype)
+        .bind(dur)
+        .bind(tss)
+        .fetch_one(pool)
+        .await
+        .expect("create future workout");
+        ids.push(id);
+    }
+    ids
+}
+
+async fn insert_adjustment(pool: &sqlx::SqlitePool, user_id: i64, operation: Value) -> i64 {
+    let adjustment = json!({
+        "trigger_reason": "sustained_harder_than_prescribed",
+        "operations": [operation]
+    });
+    sqlx::query_scalar(
+        r#"INSERT INTO plan_adjustments (user_id, trigger_reason, adjustment, reason)
+           VALUES (?, ?, ?, 'Three hard days in a row')
+           RETURNING id"#,
+    )
+    .bind(user_id)
+    .bind(adjustment["trigger_reason"].as_str().unwrap())
+    .bind(adjustment.to_string())
+    .fetch_one(pool)
+    .await
+    .expect("insert plan adjustment")
+}
+
+#[tokio::test]
+async fn plan_adjustments_are_listed_accepted_and_rejected() {
+    let (app, pool) = test_app_with_pool().await;
+    let (app, session_id, macrocycle_id, user_id) =
+        setup_plan_data(app, &pool, "adjustments@example.com").await;
+    let ids = setup_future_week(&pool, macrocycle_id, user_id).await;
+    let (easy_id, tempo_id) = (ids[0], ids[1]);
+
+    let reduce = insert_adjustment(
+        &pool,
+        user_id,
+        json!({"type": "reduce_intensity", "workout_id": tempo_id, "new_workout_type": "easy_run"}),
+    )
+    .await;
+    let skip = insert_adjustment(
+        &pool,
+        user_id,
+        json!({"type": "skip_workout", "workout_id": easy_id}),
+    )
+    .await;
+
+    // Pending proposals come with the diff they would apply
+    let response = send_request(
+        app.clone(),
+        get_authed("/api/plan/adjustments?status=pending", &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = body_json(response).await;
+    let items = json.as_array().unwrap();
+    assert_eq!(items.len(), 2);
+    let reduce_item = items.iter().find(|i| i["id"] == reduce).unwrap();
+    assert_eq!(
+        reduce_item["adjustment"]["trigger_reason"],
+        "sustained_harder_than_prescribed"
+    );
+    assert_eq!(
+        reduce_item["adjustment"]["operations"][0]["type"],
+        "reduce_intensity"
+    );
+    assert_eq!(reduce_item["changes"][0]["change"], "workout_updated");
+    assert_eq!(reduce_item["changes"][0]["before"]["workout_type"], "tempo_run");
+    assert_eq!(reduce_item["changes"][0]["after"]["workout_type"], "easy_run");
+
+    // Listing did not change the plan
+    let response = send_request(
+        app.clone(),
+        get_authed(&format!("/api/plan/workout/{tempo_id}"), &session_id),
+    )
+    .await;
+    assert_eq!(body_json(response).await["workout"]["workout_type"], "tempo_run");
+
+    // Accepting applies it
+    let response = send_request(
+        app.clone(),
+        post_json_authed(&format!("/api/plan/adjustments/{reduce}/accept"), &json!({}), &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = body_json(response).await;
+    assert_eq!(json["adjustment"]["status"], "accepted");
+    assert_eq!(json["changes"][0]["after"]["workout_type"], "easy_run");
+
+    let response = send_request(
+        app.clone(),
+        get_authed(&format!("/api/plan/workout/{tempo_id}"), &session_id),
+    )
+    .await;
+    let workout = body_json(response).await["workout"].clone();
+    assert_eq!(workout["workout_type"], "easy_run");
+    assert!(workout["target_hr_zones"].is_string());
+
+    // Accepting twice is a conflict
+    let response = send_request(
+        app.clone(),
+        post_json_authed(&format!("/api/plan/adjustments/{reduce}/accept"), &json!({}), &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // Rejecting leaves the plan alone
+    let response = send_request(
+        app.clone(),
+        post_json_authed(&format!("/api/plan/adjustments/{skip}/reject"), &json!({}), &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(body_json(response).await["status"], "rejected");
+    let response = send_request(
+        app.clone(),
+        get_authed(&format!("/api/plan/workout/{easy_id}"), &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = send_request(
+        app.clone(),
+        post_json_authed("/api/plan/adjustments/999/reject", &json!({}), &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = send_request(app, get_authed("/api/plan/adjustments", &session_id)).await;
+    let json = body_json(response).await;
+    assert_eq!(json.as_array().unwrap().len(), 2);
+    assert!(json.as_array().unwrap().iter().all(|i| i["changes"].is_null()));
+}
+
+/// A valid mesocycle plan tool input: `load_weeks` load weeks then one
+/// recovery week, starting on `start`.
+fn simple_mesocycle_plan(start: chrono::NaiveDate, load_weeks: i64) -> Value {
+    let load = ["easy_run", "tempo_run", "rest", "easy_run", "rest", "long_run", "easy_run"];
+    let recovery = ["easy_run", "rest", "easy_run", "rest", "easy_run", "rest", "easy_run"];
+    let weeks: Vec<Value> = (0..=load_weeks)
+        .map(|week| {
+            let is_recovery = week == load_weeks;
+            let types = if is_recovery { recovery } else { load };
+            let days: Vec<Value> = types
+                .iter()
+                .enumerate()
+                .map(|(i, wt)| {
+                    let date = start + chrono::Duration::days(week * 7 + i as i64);
+                    let mut day = json!({ "date": date.format("%Y-%m-%d").to_string(), "workout_type": wt });
+                    if *wt != "rest" {
+                        day["duration_category"] = json!(if is_recovery { "short" } else { "medium" });
+                    }
+                    day
+                })
+                .collect();
+            json!({
+                "week_number": week + 1,
+                "week_type": if is_recovery { "recovery" } else { "load" },
+                "target_volume_km": if is_recovery { 25.0 } else { 40.0 },
+                "target_weekly_tss": if is_recovery { 150.0 } else { 250.0 },
+                "days": days
+            })
+        })
+        .collect();
+    json!({ "mesocycle_overview": "Sharpening for race day.", "weeks": weeks })
+}
+
+#[tokio::test]
+async fn mesocycle_transition_evaluates_and_generates_next_block() {
+    let mock_server = MockServer::start().await;
+    let (app, pool) = test_app_with_claude_and_pool(&mock_server.uri()).await;
+    let (app, session_id, _macrocycle_id, _user_id) =
+        setup_plan_data(app, &pool, "transition@example.com").await;
+
+    // Mesocycle 2 has not been generated yet
+    sqlx::query("DELETE FROM planned_workouts WHERE scheduled_date = '2026-04-01'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let plan = body_json(send_request(app.clone(), get_authed("/api/plan", &session_id)).await).await;
+    let meso1_id = plan["mesocycles"][0]["id"].as_i64().unwrap();
+    let meso2_id = plan["mesocycles"][1]["id"].as_i64().unwrap();
+
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .and(body_string_contains("evaluate_mesocycle"))
+        .and(body_string_contains("Evaluate mesocycle 1"))
+        .and(body_string_contains("Completion: 0/3"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(claude_tool_use_response(
+            "evaluate_mesocycle",
+            json!({
+                "assessment": "A short capacity block with no logged sessions.",
+                "ctl_vs_target": "behind",
+                "next_phase_recommendation": "utilization",
+                "next_focus": "Ease into tempo work.",
+                "volume_adjustment": "decrease",
+                "strength_level_progression": false,
+                "coach_message": "We start the next block fresh."
+            }),
+        )))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .and(body_string_contains("generate_mesocycle_plan"))
+        .and(body_string_contains("Ease into tempo work."))
+        .and(body_string_contains("Target volume: 126 km/week"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(claude_tool_use_response(
+            "generate_mesocycle_plan",
+            simple_mesocycle_plan(chrono::NaiveDate::from_ymd_opt(2026, 3, 29).unwrap(), 2),
+        )))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .and(body_string_contains("add_coach_notes"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(claude_tool_use_response(
+            "add_coach_notes",
+            json!({ "mesocycle_overview": "Sharpening.", "workout_notes": [] }),
+        )))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let uri = format!("/api/plan/mesocycles/{meso1_id}/transition");
+    let response = send_request(app.clone(), post_json_authed(&uri, &json!({}), &session_id)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = body_json(response).await;
+    assert_eq!(json["completed"]["id"], meso1_id);
+    assert_eq!(json["completed"]["status"], "completed");
+    assert!(json["completed"]["evaluation_summary"]
+        .as_str()
+        .unwrap()
+        .contains("Next block: Ease into tempo work."));
+    assert_eq!(json["evaluation"]["coach_message"], "We start the next block fresh.");
+    assert_eq!(json["next"]["id"], meso2_id);
+    assert_eq!(json["next"]["status"], "active");
+    assert_eq!(json["workouts"].as_array().unwrap().len(), 21);
+
+    let plan = body_json(send_request(app.clone(), get_authed("/api/plan", &session_id)).await).await;
+    assert_eq!(plan["mesocycles"][0]["status"], "completed");
+    assert_eq!(plan["mesocycles"][1]["workouts"].as_array().unwrap().len(), 21);
+    // The evaluation asked for less volume than the skeleton's 140 km
+    assert_eq!(plan["mesocycles"][1]["target_volume_km"], 126.0);
+
+    // Already completed
+    let response = send_request(app.clone(), post_json_authed(&uri, &json!({}), &session_id)).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response = send_request(
+        app,
+        post_json_authed("/api/plan/mesocycles/999/transition", &json!({}), &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn mesocycle_transition_is_claimed_and_released_on_failure() {
+    let mock_server = MockServer::start().await;
+    let (app, pool) = test_app_with_claude_and_pool(&mock_server.uri()).await;
+    let (app, session_id, _macrocycle_id, _user_id) =
+        setup_plan_data(app, &pool, "claimed@example.com").await;
+    sqlx::query("DELETE FROM planned_workouts WHERE scheduled_date = '2026-04-01'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let plan =
+        body_json(send_request(app.clone(), get_authed("/api/plan", &session_id)).await).await;
+    let meso1_id = plan["mesocycles"][0]["id"].as_i64().unwrap();
+    let uri = format!("/api/plan/mesocycles/{meso1_id}/transition");
+
+    // Another transition is running
+    sqlx::query("UPDATE mesocycles SET transition_started_at = datetime('now') WHERE id = ?")
+        .bind(meso1_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    let response = send_request(app.clone(), post_json_authed(&uri, &json!({}), &session_id)).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    assert!(mock_server.received_requests().await.unwrap().is_empty());
+
+    // A failed transition gives the claim back
+    sqlx::query("UPDATE mesocycles SET transition_started_at = NULL WHERE id = ?")
+        .bind(meso1_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+    mount_tool_response_once(
+        &mock_server,
+        "add_coach_notes",
+        json!({ "mesocycle_overview": "Wrong tool.", "workout_notes": [] }),
+    )
+    .await;
+    let response = send_request(app.clone(), post_json_authed(&uri, &json!({}), &session_id)).await;
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+    let (status, started_at): (String, Option<String>) =
+        sqlx::query_as("SELECT status, transition_started_at FROM mesocycles WHERE id = ?")
+            .bind(meso1_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+    assert_ne!(status, "completed");
+    assert!(started_at.is_none());
+    let plan = body_json(send_request(app, get_authed("/api/plan", &session_id)).await).await;
+    assert!(
+        plan["mesocycles"][1]["workouts"]
+            .as_array()
+            .unwrap()
+            .is_empty()
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Coach chat
+// ---------------------------------------------------------------------------
+
+fn claude_text_response(text: &str) -> Value {
+    json!({
+        "id": "msg_test_456",
+        "type": "message",
+        "role": "assistant",
+        "content": [{ "type": "text", "text": text }],
+        "model": "claude-haiku-4-5-20251001",
+        "stop_reason": "end_turn",
+        "usage": { "input_tokens": 100, "output_tokens": 50 }
+    })
+}
+
+#[tokio::test]
+async fn chat_replies_with_context_and_persists_history() {
+    let mock_server = MockServer::start().await;
+    let (app, pool) = test_app_with_claude_and_pool(&mock_server.uri()).await;
+    let (app, session_id, _macrocycle_id, _user_id) =
+        setup_plan_data(app, &pool, "chat@example.com").await;
+
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .and(body_string_contains("Chat Guidelines"))
+        .and(body_string_contains("## Athlete Context"))
+        .and(body_string_contains("HR zones (LTHR"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(claude_text_response("Keep Thursday easy, Zone 2.")),
+        )
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let response = send_request(
+        app.clone(),
+        post_json_authed(
+            "/api/chat",
+            &json!({"message": "How hard is Thursday?"}),
+            &session_id,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let json = body_json(response).await;
+    assert_eq!(json["message"]["role"], "athlete");
+    assert_eq!(json["message"]["content"], "How hard is Thursday?");
+    assert_eq!(json["reply"]["role"], "coach");
+    assert_eq!(json["reply"]["content"], "Keep Thursday easy, Zone 2.");
+
+    // The follow-up carries the earlier exchange as history
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(claude_text_response("I can't change the plan from chat.")),
+        )
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let response = send_request(
+        app.clone(),
+        post_json_authed(
+            "/api/chat",
+            &json!({"message": "Move it to Friday"}),
+            &session_id,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
 
+    let requests = mock_server.received_requests().await.unwrap();
+    let body: Value = serde_json::from_slice(&requests[1].body).unwrap();
+    assert_eq!(body["model"], "claude-haiku-4-5-20251001");
+    // No tools are offered, so chat can never change the plan
+    assert!(body.get("tools").is_none());
+    let turns: Vec<(&str, &str)> = body["messages"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| {
+            (
+                m["role"].as_str().unwrap(),
+                m["content"][0]["text"].as_str().unwrap(),
+            )
+        })
+        .collect();
     assert_eq!(
-        response.status(),
-        StatusCode::NOT_FOUND,
-        "accessing another user's workout should return 404"
+        turns,
+        [
+            ("user", "How hard is Thursday?"),
+            ("assistant", "Keep Thursday easy, Zone 2."),
+            ("user", "Move it to Friday"),
+        ]
+    );
+
+    // History, oldest first, with cursor pagination
+    let response = send_request(app.clone(), get_authed("/api/chat", &session_id)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let messages = body_json(response).await["messages"].clone();
+    let contents: Vec<&str> = messages
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| m["content"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        contents,
+        [
+            "How hard is Thursday?",
+            "Keep Thursday easy, Zone 2.",
+            "Move it to Friday",
+            "I can't change the plan from chat.",
+        ]
+    );
+
+    let response = send_request(app.clone(), get_authed("/api/chat?limit=2", &session_id)).await;
+    let page = body_json(response).await["messages"].clone();
+    assert_eq!(page.as_array().unwrap().len(), 2);
+    assert_eq!(page[0]["content"], "Move it to Friday");
+    let before = page[0]["id"].as_i64().unwrap();
+    let response = send_request(
+        app,
+        get_authed(&format!("/api/chat?limit=2&before={before}"), &session_id),
+    )
+    .await;
+    let page = body_json(response).await["messages"].clone();
+    assert_eq!(page[0]["content"], "How hard is Thursday?");
+    assert_eq!(page[1]["content"], "Keep Thursday easy, Zone 2.");
+}
+
+#[tokio::test]
+async fn chat_rejects_bad_input_and_requires_auth() {
+    let (app, pool) = test_app_with_pool().await;
+    let (app, session_id, _macrocycle_id, _user_id) =
+        setup_plan_data(app, &pool, "chat-errors@example.com").await;
+
+    let response = send_request(
+        app.clone(),
+        post_json_authed("/api/chat", &json!({"message": "   "}), &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let long = "x".repeat(4001);
+    let response = send_request(
+        app.clone(),
+        post_json_authed("/api/chat", &json!({"message": long}), &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = send_request(app.clone(), get_authed("/api/chat?limit=0", &session_id)).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // No Claude client configured
+    let response = send_request(
+        app.clone(),
+        post_json_authed("/api/chat", &json!({"message": "Hi"}), &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+
+    let response = send_request(
+        app,
+        Request::builder()
+            .uri("/api/chat")
+            .body(Body::empty())
+            .unwrap(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+// ---------------------------------------------------------------------------
+// Streaming (server-sent events)
+// ---------------------------------------------------------------------------
+
+/// A recorded Messages API event stream.
+fn recorded_stream(name: &str) -> String {
+    std::fs::read_to_string(format!(
+        "{}/tests/fixtures/{name}",
+        env!("CARGO_MANIFEST_DIR")
+    ))
+    .expect("read recorded stream")
+}
+
+/// Serve `body` as a Messages API event stream.
+fn event_stream(body: String) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_raw(body, "text/event-stream")
+}
+
+/// Re-encode a complete Messages API response as the event stream the API
+/// would have sent, splitting text and tool input into small deltas.
+fn claude_stream_body(response: &Value) -> String {
+    fn event(body: &mut String, data: Value) {
+        body.push_str(&format!(
+            "event: {}\ndata: {}\n\n",
+            data["type"].as_str().unwrap(),
+            data
+        ));
+    }
+    fn pieces(s: &str) -> Vec<String> {
+        let chars: Vec<char> = s.chars().collect();
+        chars.chunks(40).map(|c| c.iter().collect()).collect()
+    }
+
+    let mut body = String::new();
+    let mut start = response.clone();
+    start["content"] = json!([]);
+    start["stop_reason"] = Value::Null;
+    event(
+        &mut body,
+        json!({"type": "message_start", "message": start}),
+    );
+
+    for (index, block) in response["content"].as_array().unwrap().iter().enumerate() {
+        match block["type"].as_str().unwrap() {
+            "text" => {
+                event(
+                    &mut body,
+                    json!({"type": "content_block_start", "index": index,
+                           "content_block": {"type": "text", "text": ""}}),
+                );
+                for text in pieces(block["text"].as_str().unwrap()) {
+                    event(
+                        &mut body,
+                        json!({"type": "content_block_delta", "index": index,
+                               "delta": {"type": "text_delta", "text": text}}),
+                    );
+                }
+            }
+            _ => {
+                event(
+                    &mut body,
+                    json!({"type": "content_block_start", "index": index,
+                           "content_block": {"type": "tool_use", "id": block["id"],
+                                             "name": block["name"], "input": {}}}),
+                );
+                for partial_json in pieces(&block["input"].to_string()) {
+                    event(
+                        &mut body,
+                        json!({"type": "content_block_delta", "index": index,
+                               "delta": {"type": "input_json_delta", "partial_json": partial_json}}),
+                    );
+                }
+            }
+        }
+        event(
+            &mut body,
+            json!({"type": "content_block_stop", "index": index}),
+        );
+    }
+
+    event(
+        &mut body,
+        json!({"type": "message_delta", "delta": {"stop_reason": response["stop_reason"]},
+               "usage": {"output_tokens": response["usage"]["output_tokens"]}}),
+    );
+    event(&mut body, json!({"type": "message_stop"}));
+    body
+}
+
+/// Read a server-sent event response into (event name, JSON data) pairs.
+async fn sse_events(response: axum::response::Response) -> Vec<(String, Value)> {
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let body = String::from_utf8(bytes.to_vec()).unwrap();
+    body.split("\n\n")
+        .filter_map(|block| {
+            let mut name = None;
+            let mut data = None;
+            for line in block.lines() {
+                if let Some(value) = line.strip_prefix("event:") {
+                    name = Some(value.trim().to_string());
+                } else if let Some(value) = line.strip_prefix("data:") {
+                    data = Some(serde_json::from_str(value.trim()).unwrap());
+                }
+            }
+            Some((name?, data?))
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn chat_stream_relays_text_and_persists_the_exchange() {
+    let mock_server = MockServer::start().await;
+    let (app, pool) = test_app_with_claude_and_pool(&mock_server.uri()).await;
+    let (app, session_id, _macrocycle_id, _user_id) =
+        setup_plan_data(app, &pool, "chat-stream@example.com").await;
+
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .and(body_string_contains("\"stream\":true"))
+        .respond_with(event_stream(recorded_stream("claude_stream_text.sse")))
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let response = send_request(
+        app.clone(),
+        post_json_authed(
+            "/api/chat/stream",
+            &json!({"message": "Why so many easy runs?"}),
+            &session_id,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "text/event-stream");
+
+    let events = sse_events(response).await;
+    let text: String = events
+        .iter()
+        .filter(|(name, _)| name == "text")
+        .map(|(_, data)| data["text"].as_str().unwrap())
+        .collect();
+    let reply = "Easy runs build your aerobic base — keep them truly easy.";
+    assert_eq!(text, reply);
+
+    let (name, done) = events.last().unwrap();
+    assert_eq!(name, "done");
+    assert_eq!(
+        done["result"]["message"]["content"],
+        "Why so many easy runs?"
+    );
+    assert_eq!(done["result"]["reply"]["content"], reply);
+
+    let response = send_request(app, get_authed("/api/chat", &session_id)).await;
+    let messages = body_json(response).await["messages"].clone();
+    assert_eq!(messages.as_array().unwrap().len(), 2);
+    assert_eq!(messages[1]["content"], reply);
+}
+
+#[tokio::test]
+async fn chat_stream_reports_a_broken_stream_as_an_error_event() {
+    let mock_server = MockServer::start().await;
+    let (app, pool) = test_app_with_claude_and_pool(&mock_server.uri()).await;
+    let (app, session_id, _macrocycle_id, _user_id) =
+        setup_plan_data(app, &pool, "chat-stream-error@example.com").await;
+
+    // The connection drops before message_stop
+    let recorded = recorded_stream("claude_stream_text.sse");
+    let cut = recorded.find("event: message_delta").unwrap();
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .respond_with(event_stream(recorded[..cut].to_string()))
+        .mount(&mock_server)
+        .await;
+
+    let response = send_request(
+        app.clone(),
+        post_json_authed("/api/chat/stream", &json!({"message": "Hi"}), &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let events = sse_events(response).await;
+    let (name, error) = events.last().unwrap();
+    assert_eq!(name, "error");
+    assert_eq!(error["status"], 500);
+    assert!(!events.iter().any(|(name, _)| name == "done"));
+
+    // Without a reply, the athlete's message is not kept either
+    let response = send_request(app.clone(), get_authed("/api/chat", &session_id)).await;
+    assert_eq!(body_json(response).await["messages"], json!([]));
+
+    // Validation still fails fast with a plain status
+    let response = send_request(
+        app,
+        post_json_authed("/api/chat/stream", &json!({"message": " "}), &session_id),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn confirm_stream_reports_stages_and_returns_the_plan() {
+    let mock_server = MockServer::start().await;
+    let (skeleton_input, mesocycle_plan_input, coach_notes_input) = plan_flow_inputs();
+
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(claude_tool_use_response(
+            "generate_macrocycle_skeleton",
+            skeleton_input,
+        )))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .and(body_string_contains("\"stream\":true"))
+        .respond_with(event_stream(claude_stream_body(&claude_tool_use_response(
+            "generate_mesocycle_plan",
+            mesocycle_plan_input,
+        ))))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/v1/messages"))
+        .and(body_string_contains("\"stream\":true"))
+        .respond_with(event_stream(claude_stream_body(&claude_tool_use_response(
+            "add_coach_notes",
+            coach_notes_input,
+        ))))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&mock_server)
+        .await;
+
+    let app = test_app_with_claude(&mock_server.uri()).await;
+    let (app, session_id, race_goal_id) = setup_user_with_profile(app).await;
+    let response = send_request(
+        app.clone(),
+        post_json_authed(
+            "/api/plan/generate",
+            &json!({ "race_goal_id": race_goal_id }),
+            &session_id,
+        ),
+    )
+    .await;
+    let draft_id = body_json(response).await["id"].clone();
+
+    let response = send_request(
+        app.clone(),
+        post_json_authed(
+            "/api/plan/confirm/stream",
+            &json!({ "draft_id": draft_id }),
+            &session_id,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let events = sse_events(response).await;
+    let stages: Vec<&str> = events
+        .iter()
+        .filter(|(name, _)| name == "stage")
+        .map(|(_, data)| data["message"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        stages,
+        [
+            "Generating workouts for the capacity mesocycle",
+            "Writing coach notes",
+            "Saving workouts",
+        ]
     );
+    let tools: Vec<&str> = events
+        .iter()
+        .filter(|(name, _)| name == "tool_start")
+        .map(|(_, data)| data["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(tools, ["generate_mesocycle_plan", "add_coach_notes"]);
+    let tool_inputs = events.iter().filter(|(name, _)| name == "tool_input");
+    assert!(tool_inputs.count() > 10);
+
+    let (name, done) = events.last().unwrap();
+    assert_eq!(name, "done");
+    assert_eq!(done["result"]["macrocycle"]["status"], "active");
+    assert_eq!(done["result"]["workouts"].as_array().unwrap().len(), 28);
+
+    let response = send_request(app, get_authed("/api/plan", &session_id)).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn confirm_stream_checks_inputs_before_streaming() {
+    let mock_server = MockServer::start().await;
+    let app = test_app_with_claude(&mock_server.uri()).await;
+    let (app, session_id) =
+        register_user(app, "stream-nodraft@example.com", "securepass123").await;
+
+    let response = send_request(
+        app,
+        post_json_authed(
+            "/api/plan/confirm/stream",
+            &json!({ "draft_id": 1 }),
+            &session_id,
+        ),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert!(mock_server.received_requests().await.unwrap().is_empty());
 }